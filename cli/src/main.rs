@@ -0,0 +1,300 @@
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use anchor_client::anchor_lang::system_program;
+use anchor_client::anchor_lang::{AccountDeserialize, Discriminator};
+use anchor_client::solana_sdk::commitment_config::CommitmentConfig;
+use anchor_client::solana_sdk::pubkey::Pubkey;
+use anchor_client::solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use anchor_client::{Client, Cluster, Program};
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Parser, Subcommand};
+use serde::Deserialize;
+
+use multisig::client::{describe, describe_action_hash, describe_metadata, describe_protocol_fee};
+use multisig::compiled::signer_bits;
+use multisig::{
+    accounts, find_instruction_index_address, find_multisig_address, find_program_config_address,
+    find_proposal_address, find_spend_ledger_address, instruction, InstructionData, InstructionIndex, Multisig, Proposal,
+    ProposalAction, ProposalOptions, SerializableAccountMeta, SpendLedger,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "multisig-cli",
+    about = "Drive the multisig program from the command line"
+)]
+struct Cli {
+    /// RPC URL 或简写（localnet、devnet、mainnet）
+    #[arg(long, short = 'u', default_value = "localnet")]
+    url: String,
+    /// 付费并签名的 keypair 文件（创建者、提案人、批准人均为此账户）
+    #[arg(long, short = 'k', default_value = "~/.config/solana/id.json")]
+    keypair: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 创建多签，打印 multisig 地址
+    Create {
+        #[arg(long, value_delimiter = ',', required = true)]
+        members: Vec<Pubkey>,
+        #[arg(long)]
+        threshold: u8,
+        #[arg(long, default_value_t = 0)]
+        nonce: u8,
+        #[arg(long, default_value_t = 10)]
+        max_members: u8,
+    },
+    /// 从 JSON 文件提交 Raw 提案并登记内容索引，打印提案地址
+    Propose {
+        multisig: Pubkey,
+        #[arg(long)]
+        ix_file: PathBuf,
+        /// 已有相同内容的活跃提案时仍然提交
+        #[arg(long)]
+        allow_duplicate: bool,
+    },
+    /// 默认绑定到此刻读取的提案内容；--expected-hash 为 describe 打印的十六进制摘要
+    Approve {
+        proposal: Pubkey,
+        #[arg(long, value_parser = parse_hash)]
+        expected_hash: Option<[u8; 32]>,
+    },
+    /// 按提案内容自动推导 remaining accounts 后执行
+    Execute { proposal: Pubkey },
+    /// 取消原因写入 ProposalCancelled 事件
+    Cancel {
+        proposal: Pubkey,
+        /// 0 或自定义代码（>= 16），1-15 保留给程序
+        #[arg(long, default_value_t = 0)]
+        reason: u8,
+        #[arg(long)]
+        note: Option<String>,
+    },
+    /// 打印提案内容的可读描述与涉及的账户
+    Describe { proposal: Pubkey },
+    /// 以 JSON 打印 multisig 或提案账户
+    Show { address: Pubkey },
+}
+
+// ix.json：program_id 为 base58，data 为 base64；signers 位按签名账户自动推导
+#[derive(Deserialize)]
+struct IxFile {
+    #[serde(with = "multisig::serde_helpers::pubkey")]
+    program_id: Pubkey,
+    accounts: Vec<SerializableAccountMeta>,
+    #[serde(with = "multisig::serde_helpers::bytes")]
+    data: Vec<u8>,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let payer = Rc::new(load_keypair(&cli.keypair)?);
+    let cluster = Cluster::from_str(&cli.url)?;
+    let client = Client::new_with_options(cluster, payer.clone(), CommitmentConfig::confirmed());
+    let program = client.program(multisig::ID)?;
+
+    match cli.command {
+        Command::Create {
+            members,
+            threshold,
+            nonce,
+            max_members,
+        } => {
+            let (multisig, _) = find_multisig_address(&payer.pubkey(), nonce);
+            program
+                .request()
+                .accounts(accounts::CreateMultisig {
+                    multisig,
+                    creator: payer.pubkey(),
+                    system_program: system_program::ID,
+                })
+                .args(instruction::CreateMultisig {
+                    nonce,
+                    members,
+                    threshold,
+                    max_members,
+                })
+                .send()?;
+            println!("{}", multisig);
+        }
+        Command::Propose {
+            multisig,
+            ix_file,
+            allow_duplicate,
+        } => {
+            let file = std::fs::read_to_string(&ix_file)
+                .with_context(|| format!("reading {}", ix_file.display()))?;
+            let ix: IxFile = serde_json::from_str(&file)?;
+            let instruction_data = to_instruction_data(ix, &multisig);
+
+            let account: Multisig = program.account(multisig)?;
+            let (proposal, _) = find_proposal_address(&multisig, account.proposals_count);
+            let hash = ProposalAction::Raw(instruction_data.clone()).hash()?;
+            let (index, _) = find_instruction_index_address(&multisig, &hash);
+            let indexed_proposal = program
+                .account::<InstructionIndex>(index)
+                .ok()
+                .map(|i| i.proposal);
+            program
+                .request()
+                .accounts(accounts::ProposeTransaction {
+                    multisig,
+                    proposal,
+                    proposer: payer.pubkey(),
+                    system_program: system_program::ID,
+                    member_stats: None,
+                    program_config: find_program_config_address().0,
+                    instruction_index: Some(index),
+                    indexed_proposal,
+                })
+                .args(instruction::ProposeTransaction {
+                    instruction_data,
+                    options: ProposalOptions {
+                        allow_duplicate,
+                        ..Default::default()
+                    },
+                })
+                .send()?;
+            println!("{}", proposal);
+        }
+        Command::Approve {
+            proposal,
+            expected_hash,
+        } => {
+            let account: Proposal = program.account(proposal)?;
+            let expected_hash = match expected_hash {
+                Some(hash) => hash,
+                None => account.action.hash()?,
+            };
+            program
+                .request()
+                .accounts(accounts::ApproveTransaction {
+                    multisig: account.multisig,
+                    proposal,
+                    approver: payer.pubkey(),
+                    member_stats: None,
+                })
+                .args(instruction::ApproveTransaction {
+                    expected_hash: Some(expected_hash),
+                })
+                .send()?;
+        }
+        Command::Execute { proposal } => {
+            let account: Proposal = program.account(proposal)?;
+            // 已创建支出账本时一并传入，SPL 转账才会计入
+            let ledger = find_spend_ledger_address(&account.multisig).0;
+            let spend_ledger = program.account::<SpendLedger>(ledger).ok().map(|_| ledger);
+            let signature = program
+                .request()
+                .accounts(accounts::ExecuteTransaction {
+                    multisig: account.multisig,
+                    proposal,
+                    spend_ledger,
+                    executor: Some(payer.pubkey()),
+                    instruction_index: instruction_index(&program, &account, &proposal)?,
+                })
+                .accounts(account.execute_accounts())
+                .args(instruction::ExecuteTransaction {})
+                .send()?;
+            println!("{}", signature);
+        }
+        Command::Cancel {
+            proposal,
+            reason,
+            note,
+        } => {
+            let account: Proposal = program.account(proposal)?;
+            program
+                .request()
+                .accounts(accounts::CancelTransaction {
+                    multisig: account.multisig,
+                    proposal,
+                    canceller: payer.pubkey(),
+                    instruction_index: instruction_index(&program, &account, &proposal)?,
+                })
+                .args(instruction::CancelTransaction { reason, note })
+                .send()?;
+        }
+        Command::Describe { proposal } => {
+            let account: Proposal = program.account(proposal)?;
+            print!("{}", describe(&account));
+            println!("{}", describe_action_hash(&account)?);
+            if let Some(metadata) = describe_metadata(&account) {
+                println!("{}", metadata);
+            }
+            if let Some(fee) = describe_protocol_fee(&account) {
+                println!("{}", fee);
+            }
+        }
+        Command::Show { address } => show(&program, &address)?,
+    }
+    Ok(())
+}
+
+fn parse_hash(s: &str) -> Result<[u8; 32]> {
+    if s.len() != 64 || !s.is_ascii() {
+        bail!("expected 64 hex characters");
+    }
+    let mut hash = [0u8; 32];
+    for (i, byte) in hash.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[2 * i..2 * i + 2], 16)?;
+    }
+    Ok(hash)
+}
+
+// 指向该提案的内容索引，随执行或取消一并关闭
+fn instruction_index(
+    program: &Program<Rc<Keypair>>,
+    account: &Proposal,
+    proposal: &Pubkey,
+) -> Result<Option<Pubkey>> {
+    let (index, _) = find_instruction_index_address(&account.multisig, &account.action.hash()?);
+    Ok(program
+        .account::<InstructionIndex>(index)
+        .ok()
+        .filter(|i| i.proposal == *proposal)
+        .map(|_| index))
+}
+
+fn load_keypair(path: &str) -> Result<Keypair> {
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => PathBuf::from(std::env::var("HOME")?).join(rest),
+        None => PathBuf::from(path),
+    };
+    read_keypair_file(&path).map_err(|e| anyhow!("reading keypair {}: {}", path.display(), e))
+}
+
+// 与 TS 客户端一致：末尾附上目标程序账户（CPI 需要）
+fn to_instruction_data(ix: IxFile, multisig: &Pubkey) -> InstructionData {
+    let signers = signer_bits(&ix.accounts, multisig);
+    let mut accounts = ix.accounts;
+    accounts.push(SerializableAccountMeta {
+        pubkey: ix.program_id,
+        is_signer: false,
+        is_writable: false,
+    });
+    InstructionData {
+        program_id: ix.program_id,
+        accounts,
+        data: ix.data,
+        signers,
+    }
+}
+
+fn show(program: &Program<Rc<Keypair>>, address: &Pubkey) -> Result<()> {
+    let data = program.rpc().get_account_data(address)?;
+    let json = if data.starts_with(Multisig::DISCRIMINATOR) {
+        serde_json::to_string_pretty(&Multisig::try_deserialize(&mut data.as_slice())?)?
+    } else if data.starts_with(Proposal::DISCRIMINATOR) {
+        serde_json::to_string_pretty(&Proposal::try_deserialize(&mut data.as_slice())?)?
+    } else {
+        bail!("{} is not a multisig or proposal account", address);
+    };
+    println!("{}", json);
+    Ok(())
+}
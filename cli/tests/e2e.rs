@@ -0,0 +1,164 @@
+// 端到端：启动 solana-test-validator 加载 target/deploy/multisig.so，用 CLI 走完整个提案流程。
+// 需要本地安装 Solana 工具链并先执行 anchor build：
+//   cargo test -p multisig-cli -- --ignored
+
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::thread::sleep;
+use std::time::Duration;
+
+use anchor_client::solana_sdk::commitment_config::CommitmentConfig;
+use anchor_client::solana_sdk::pubkey::Pubkey;
+use anchor_client::solana_sdk::signature::{write_keypair_file, Keypair, Signer};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use multisig::find_vault_address;
+use solana_rpc_client::rpc_client::RpcClient;
+
+const RPC_PORT: u16 = 18899;
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+struct Validator(Child);
+
+impl Drop for Validator {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .to_path_buf()
+}
+
+fn start_validator(ledger: &Path) -> Validator {
+    let program = workspace_root().join("target/deploy/multisig.so");
+    assert!(program.exists(), "run `anchor build` first");
+    let child = Command::new("solana-test-validator")
+        .args(["--reset", "--quiet", "--rpc-port", &RPC_PORT.to_string()])
+        .arg("--ledger")
+        .arg(ledger)
+        .arg("--bpf-program")
+        .arg(multisig::ID.to_string())
+        .arg(&program)
+        .stdout(Stdio::null())
+        .spawn()
+        .expect("solana-test-validator not found");
+    let validator = Validator(child);
+    for _ in 0..60 {
+        if TcpStream::connect(("127.0.0.1", RPC_PORT)).is_ok() {
+            sleep(Duration::from_secs(2));
+            return validator;
+        }
+        sleep(Duration::from_secs(1));
+    }
+    panic!("validator did not start");
+}
+
+fn cli(keypair: &Path, args: &[&str]) -> String {
+    let url = format!("http://127.0.0.1:{}", RPC_PORT);
+    let output = Command::new(env!("CARGO_BIN_EXE_multisig-cli"))
+        .args(["--url", &url, "--keypair"])
+        .arg(keypair)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "multisig-cli {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+fn airdrop(rpc: &RpcClient, to: &Pubkey, lamports: u64) {
+    let signature = rpc.request_airdrop(to, lamports).unwrap();
+    while !rpc.confirm_transaction(&signature).unwrap() {
+        sleep(Duration::from_millis(200));
+    }
+}
+
+#[test]
+#[ignore = "requires solana-test-validator and target/deploy/multisig.so"]
+fn proposal_lifecycle() {
+    let dir = std::env::temp_dir().join(format!("multisig-cli-e2e-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let _validator = start_validator(&dir.join("ledger"));
+    let rpc = RpcClient::new_with_commitment(
+        format!("http://127.0.0.1:{}", RPC_PORT),
+        CommitmentConfig::confirmed(),
+    );
+
+    let member1 = Keypair::new();
+    let member2 = Keypair::new();
+    let key1 = dir.join("member1.json");
+    let key2 = dir.join("member2.json");
+    write_keypair_file(&member1, &key1).unwrap();
+    write_keypair_file(&member2, &key2).unwrap();
+    airdrop(&rpc, &member1.pubkey(), 2 * LAMPORTS_PER_SOL);
+    airdrop(&rpc, &member2.pubkey(), 2 * LAMPORTS_PER_SOL);
+
+    let members = format!("{},{}", member1.pubkey(), member2.pubkey());
+    let multisig: Pubkey = cli(
+        &key1,
+        &["create", "--members", &members, "--threshold", "2"],
+    )
+    .parse()
+    .unwrap();
+    let vault = find_vault_address(&multisig, 0).0;
+    airdrop(&rpc, &vault, LAMPORTS_PER_SOL);
+
+    // 金库向 receiver 转 0.1 SOL：System Transfer = [2u32, lamports u64]
+    let receiver = Pubkey::new_unique();
+    let mut data = 2u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&(LAMPORTS_PER_SOL / 10).to_le_bytes());
+    let ix = serde_json::json!({
+        "program_id": "11111111111111111111111111111111",
+        "accounts": [
+            { "pubkey": vault.to_string(), "is_signer": true, "is_writable": true },
+            { "pubkey": receiver.to_string(), "is_signer": false, "is_writable": true },
+        ],
+        "data": STANDARD.encode(&data),
+    });
+    let ix_file = dir.join("ix.json");
+    std::fs::write(&ix_file, ix.to_string()).unwrap();
+
+    let proposal = cli(
+        &key1,
+        &[
+            "propose",
+            &multisig.to_string(),
+            "--ix-file",
+            ix_file.to_str().unwrap(),
+        ],
+    );
+    let shown: serde_json::Value = serde_json::from_str(&cli(&key1, &["show", &proposal])).unwrap();
+    assert_eq!(shown["action"]["Raw"]["signers"], 2);
+
+    cli(&key1, &["approve", &proposal]);
+    cli(&key2, &["approve", &proposal]);
+    cli(&key2, &["execute", &proposal]);
+    assert_eq!(rpc.get_balance(&receiver).unwrap(), LAMPORTS_PER_SOL / 10);
+
+    // 取消：第二个提案由 proposer 撤回
+    let proposal = cli(
+        &key2,
+        &[
+            "propose",
+            &multisig.to_string(),
+            "--ix-file",
+            ix_file.to_str().unwrap(),
+        ],
+    );
+    cli(&key2, &["cancel", &proposal]);
+    assert!(rpc.get_account(&proposal.parse().unwrap()).is_err());
+
+    let shown: serde_json::Value =
+        serde_json::from_str(&cli(&key1, &["show", &multisig.to_string()])).unwrap();
+    assert_eq!(shown["proposals_count"], 2);
+}
@@ -0,0 +1,222 @@
+// 测试夹具：模拟把成员签名转手用于多签指令的第三方协议，
+// 以多签应用 authority 为管理员的外部协议，接收执行通知的记账程序，
+// 以及要求由 authority 自行创建配置账户的协议
+use anchor_lang::prelude::*;
+use multisig::cpi::accounts::{ApproveTransaction, GetProposalStatus, ProposeTransaction};
+use multisig::program::Multisig;
+use multisig::{InstructionData, ProposalStatusView};
+
+declare_id!("HUV9h1VQdgswZzFjK4EddYRW5ErE949gi5Du6uY6Dnmc");
+
+#[program]
+pub mod cpi_caller {
+    use super::*;
+
+    pub fn approve_via_cpi(ctx: Context<ApproveViaCpi>) -> Result<()> {
+        let accounts = ApproveTransaction {
+            multisig: ctx.accounts.multisig.to_account_info(),
+            proposal: ctx.accounts.proposal.to_account_info(),
+            approver: ctx.accounts.member.to_account_info(),
+            member_stats: None,
+        };
+        let program = ctx.accounts.multisig_program.to_account_info();
+        multisig::cpi::approve_transaction(CpiContext::new(program, accounts), None)
+    }
+
+    pub fn propose_via_cpi(
+        ctx: Context<ProposeViaCpi>,
+        instruction_data: InstructionData,
+    ) -> Result<()> {
+        let accounts = ProposeTransaction {
+            multisig: ctx.accounts.multisig.to_account_info(),
+            proposal: ctx.accounts.proposal.to_account_info(),
+            proposer: ctx.accounts.member.to_account_info(),
+            member_stats: None,
+            system_program: ctx.accounts.system_program.to_account_info(),
+            program_config: ctx.accounts.program_config.to_account_info(),
+            instruction_index: None,
+            indexed_proposal: None,
+        };
+        let program = ctx.accounts.multisig_program.to_account_info();
+        multisig::cpi::propose_transaction(
+            CpiContext::new(program, accounts),
+            instruction_data,
+            Default::default(),
+        )
+    }
+
+    // 模拟链上消费者：经 CPI 读取状态并原样返回
+    pub fn proposal_status_via_cpi<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ProposalStatusViaCpi<'info>>,
+    ) -> Result<ProposalStatusView> {
+        let accounts = GetProposalStatus {
+            multisig: ctx.accounts.multisig.to_account_info(),
+            proposal: ctx.accounts.proposal.to_account_info(),
+        };
+        let program = ctx.accounts.multisig_program.to_account_info();
+        let cpi = CpiContext::new(program, accounts)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+        Ok(multisig::cpi::get_proposal_status(cpi)?.get())
+    }
+
+    pub fn init_app_config(ctx: Context<InitAppConfig>, admin: Pubkey) -> Result<()> {
+        ctx.accounts.config.admin = admin;
+        ctx.accounts.config.value = 0;
+        Ok(())
+    }
+
+    // 仅管理员可调用
+    pub fn set_app_value(ctx: Context<SetAppValue>, value: u64) -> Result<()> {
+        ctx.accounts.config.value = value;
+        Ok(())
+    }
+
+    pub fn init_notification_log(ctx: Context<InitNotificationLog>) -> Result<()> {
+        ctx.accounts.log.set_inner(NotificationLog::default());
+        Ok(())
+    }
+
+    // 多签执行通知：记录最近一次收到的参数；fail 为 true 时拒绝，用于测试 required 模式
+    pub fn on_multisig_executed(
+        ctx: Context<OnMultisigExecuted>,
+        multisig: Pubkey,
+        index: u32,
+        instruction_hash: [u8; 32],
+    ) -> Result<()> {
+        let log = &mut ctx.accounts.log;
+        require!(!log.fail, ErrorCode::RequireViolated);
+        require_keys_eq!(multisig, ctx.accounts.multisig.key());
+        log.multisig = multisig;
+        log.index = index;
+        log.instruction_hash = instruction_hash;
+        log.count += 1;
+        Ok(())
+    }
+
+    // 配置账户须已由外部创建并归本程序所有（如多签金库的 create_account_with_seed）
+    pub fn init_seeded_config(ctx: Context<InitSeededConfig>, value: u64) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.authority.key();
+        config.value = value;
+        Ok(())
+    }
+
+    pub fn set_notification_failure(
+        ctx: Context<SetNotificationFailure>,
+        fail: bool,
+    ) -> Result<()> {
+        ctx.accounts.log.fail = fail;
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct NotificationLog {
+    pub multisig: Pubkey,
+    pub index: u32,
+    pub instruction_hash: [u8; 32],
+    pub count: u64,
+    pub fail: bool,
+}
+
+#[account]
+pub struct SeededConfig {
+    pub authority: Pubkey,
+    pub value: u64,
+}
+
+#[derive(Accounts)]
+pub struct InitSeededConfig<'info> {
+    #[account(zero)]
+    pub config: Account<'info, SeededConfig>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitNotificationLog<'info> {
+    #[account(
+        init,
+        seeds = [b"notifications"],
+        bump,
+        payer = payer,
+        space = 8 + 32 + 4 + 32 + 8 + 1
+    )]
+    pub log: Account<'info, NotificationLog>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OnMultisigExecuted<'info> {
+    /// CHECK: 仅与参数比对；通知不带多签签名
+    pub multisig: UncheckedAccount<'info>,
+    #[account(mut, seeds = [b"notifications"], bump)]
+    pub log: Account<'info, NotificationLog>,
+}
+
+#[derive(Accounts)]
+pub struct SetNotificationFailure<'info> {
+    #[account(mut, seeds = [b"notifications"], bump)]
+    pub log: Account<'info, NotificationLog>,
+}
+
+#[account]
+pub struct AppConfig {
+    pub admin: Pubkey,
+    pub value: u64,
+}
+
+#[derive(Accounts)]
+pub struct InitAppConfig<'info> {
+    #[account(init, seeds = [b"app_config"], bump, payer = payer, space = 8 + 32 + 8)]
+    pub config: Account<'info, AppConfig>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAppValue<'info> {
+    #[account(mut, seeds = [b"app_config"], bump, has_one = admin)]
+    pub config: Account<'info, AppConfig>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposalStatusViaCpi<'info> {
+    /// CHECK: 由多签程序校验
+    pub multisig: UncheckedAccount<'info>,
+    /// CHECK: 由多签程序校验
+    pub proposal: UncheckedAccount<'info>,
+    pub multisig_program: Program<'info, Multisig>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveViaCpi<'info> {
+    /// CHECK: 由多签程序校验
+    #[account(mut)]
+    pub multisig: UncheckedAccount<'info>,
+    /// CHECK: 由多签程序校验
+    #[account(mut)]
+    pub proposal: UncheckedAccount<'info>,
+    pub member: Signer<'info>,
+    pub multisig_program: Program<'info, Multisig>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeViaCpi<'info> {
+    /// CHECK: 由多签程序校验
+    #[account(mut)]
+    pub multisig: UncheckedAccount<'info>,
+    /// CHECK: 由多签程序创建
+    #[account(mut)]
+    pub proposal: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub member: Signer<'info>,
+    pub multisig_program: Program<'info, Multisig>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: 由多签程序校验
+    pub program_config: UncheckedAccount<'info>,
+}
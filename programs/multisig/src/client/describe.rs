@@ -0,0 +1,615 @@
+use std::fmt;
+
+use anchor_lang::prelude::*;
+
+pub use crate::decode::ASSOCIATED_TOKEN_PROGRAM_ID;
+use crate::decode::{is_token_program, read_u32, read_u64};
+use crate::{find_vault_address, CustodyKind, Proposal, ProposalAction, SerializableAccountMeta};
+
+pub const BPF_LOADER_UPGRADEABLE_ID: Pubkey =
+    pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+const LAMPORTS_PER_SOL_DECIMALS: u8 = 9;
+const SYSTEM_TRANSFER: u32 = 2;
+const TOKEN_TRANSFER: u8 = 3;
+const TOKEN_APPROVE: u8 = 4;
+const TOKEN_TRANSFER_CHECKED: u8 = 12;
+const TOKEN_APPROVE_CHECKED: u8 = 13;
+const LOADER_UPGRADE: u32 = 3;
+
+// 常见 mint 的符号，其余显示缩写地址
+const KNOWN_MINTS: &[(Pubkey, &str)] = &[
+    (
+        pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"),
+        "USDC",
+    ),
+    (
+        pubkey!("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB"),
+        "USDT",
+    ),
+    (
+        pubkey!("So11111111111111111111111111111111111111112"),
+        "wSOL",
+    ),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MultisigPda {
+    Multisig,
+    Vault(u8),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccountSummary {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+    pub pda: Option<MultisigPda>, // 该账户为本多签或其金库
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProposalSummary {
+    pub description: String,
+    pub program_id: Option<Pubkey>, // 配置类提案原生执行，无目标程序
+    pub accounts: Vec<AccountSummary>,
+}
+
+impl ProposalSummary {
+    pub fn touches_multisig(&self) -> bool {
+        self.accounts.iter().any(|a| a.pda.is_some())
+    }
+}
+
+impl fmt::Display for ProposalSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.description)?;
+        for account in &self.accounts {
+            let role = match (account.is_signer, account.is_writable) {
+                (true, true) => "signer, writable",
+                (true, false) => "signer",
+                (false, true) => "writable",
+                (false, false) => "readonly",
+            };
+            write!(f, "  {} ({})", account.pubkey, role)?;
+            match account.pda {
+                Some(MultisigPda::Multisig) => write!(f, " [multisig]")?,
+                Some(MultisigPda::Vault(i)) => write!(f, " [vault {}]", i)?,
+                None => {}
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+fn hex(bytes: &[u8; 32]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// 提案附带的链下文档：地址与期望的 sha256（十六进制），审批人下载后自行核对
+pub fn describe_metadata(proposal: &Proposal) -> Option<String> {
+    let uri = proposal.metadata_uri.as_ref()?;
+    Some(format!(
+        "Metadata: {} (sha256 {})",
+        uri,
+        hex(&proposal.metadata_hash)
+    ))
+}
+
+// approve_transaction 的 expected_hash，供硬件钱包等流程在链下核对
+pub fn describe_action_hash(proposal: &Proposal) -> Result<String> {
+    Ok(format!("Action hash: {}", hex(&proposal.action.hash()?)))
+}
+
+// 创建时报价的协议费，执行成功后从金库收取
+pub fn describe_protocol_fee(proposal: &Proposal) -> Option<String> {
+    let fee = proposal.protocol_fee.as_ref()?;
+    Some(format!(
+        "Protocol fee: up to {} SOL from {} to {}",
+        format_amount(fee.lamports, LAMPORTS_PER_SOL_DECIMALS),
+        fee.payer,
+        fee.recipient
+    ))
+}
+
+// 把提案内容转成审批人可读的描述；无法识别的指令退化为"目标程序 + 账户数"
+pub fn describe(proposal: &Proposal) -> ProposalSummary {
+    let multisig = &proposal.multisig;
+    let labels = Labels::new(multisig);
+    let config = |description: String, accounts: Vec<SerializableAccountMeta>| ProposalSummary {
+        description,
+        program_id: None,
+        accounts: labels.accounts(&accounts),
+    };
+    let self_meta = SerializableAccountMeta {
+        pubkey: *multisig,
+        is_signer: false,
+        is_writable: true,
+    };
+
+    match &proposal.action {
+        ProposalAction::Raw(ix) => {
+            // 客户端约定末尾附带目标程序账户，不计入指令账户
+            let accounts = match ix.accounts.split_last() {
+                Some((last, rest)) if last.pubkey == ix.program_id => rest,
+                _ => &ix.accounts[..],
+            };
+            let description = describe_instruction(&ix.program_id, accounts, &ix.data, &labels)
+                .unwrap_or_else(|| unknown(&ix.program_id, accounts.len(), &labels));
+            ProposalSummary {
+                description,
+                program_id: Some(ix.program_id),
+                accounts: labels.accounts(accounts),
+            }
+        }
+        // 逐条描述后合并；目标程序不止一个，program_id 留空
+        ProposalAction::Batch(ixs) => {
+            let mut descriptions = Vec::with_capacity(ixs.len());
+            let mut accounts = vec![];
+            for ix in ixs {
+                let ix_accounts = match ix.accounts.split_last() {
+                    Some((last, rest)) if last.pubkey == ix.program_id => rest,
+                    _ => &ix.accounts[..],
+                };
+                descriptions.push(
+                    describe_instruction(&ix.program_id, ix_accounts, &ix.data, &labels)
+                        .unwrap_or_else(|| unknown(&ix.program_id, ix_accounts.len(), &labels)),
+                );
+                accounts.extend_from_slice(ix_accounts);
+            }
+            ProposalSummary {
+                description: format!(
+                    "Batch of {} instructions: {}",
+                    ixs.len(),
+                    descriptions.join("; ")
+                ),
+                program_id: None,
+                accounts: labels.accounts(&accounts),
+            }
+        }
+        ProposalAction::Buffered(b) => {
+            let accounts = match b.accounts.split_last() {
+                Some((last, rest)) if last.pubkey == b.program_id => rest,
+                _ => &b.accounts[..],
+            };
+            ProposalSummary {
+                description: format!(
+                    "{} (data stored in buffer {})",
+                    unknown(&b.program_id, accounts.len(), &labels),
+                    short(&b.buffer)
+                ),
+                program_id: Some(b.program_id),
+                accounts: labels.accounts(accounts),
+            }
+        }
+        ProposalAction::AddMember(key) => {
+            config(format!("Invite {} as a member", key), vec![self_meta])
+        }
+        ProposalAction::RemoveMember(key) => {
+            config(format!("Remove member {}", key), vec![self_meta])
+        }
+        ProposalAction::ChangeThreshold(threshold) => config(
+            format!("Change threshold to {}", threshold),
+            vec![self_meta],
+        ),
+        ProposalAction::SetPaused(true) => config("Pause execution".to_string(), vec![self_meta]),
+        ProposalAction::SetPaused(false) => config("Resume execution".to_string(), vec![self_meta]),
+        ProposalAction::SweepVault { index, destination } => config(
+            format!(
+                "Sweep all SOL from vault {} to {}",
+                index,
+                labels.name(destination)
+            ),
+            vec![
+                SerializableAccountMeta {
+                    pubkey: find_vault_address(multisig, *index).0,
+                    is_signer: true,
+                    is_writable: true,
+                },
+                SerializableAccountMeta {
+                    pubkey: *destination,
+                    is_signer: false,
+                    is_writable: true,
+                },
+            ],
+        ),
+        // 提案不含 decimals，金额按最小单位显示
+        ProposalAction::Vesting(schedule) => config(
+            format!(
+                "Vest {} base units of {} to {} in {} tranches from vault 0",
+                schedule
+                    .tranches
+                    .iter()
+                    .map(|t| t.amount as u128)
+                    .sum::<u128>(),
+                token_symbol(&schedule.mint),
+                short(&schedule.recipient),
+                schedule.tranches.len()
+            ),
+            vec![SerializableAccountMeta {
+                pubkey: find_vault_address(multisig, 0).0,
+                is_signer: true,
+                is_writable: false,
+            }],
+        ),
+        ProposalAction::SetTokenAuthority {
+            target,
+            authority_type,
+            new_authority,
+        } => config(
+            format!(
+                "Set {} authority of {} to {}",
+                match authority_type {
+                    0 => "mint",
+                    1 => "freeze",
+                    2 => "owner",
+                    3 => "close",
+                    _ => "unknown",
+                },
+                short(target),
+                match new_authority {
+                    Some(key) => labels.name(key),
+                    None => "none (renounce)".to_string(),
+                }
+            ),
+            vec![SerializableAccountMeta {
+                pubkey: *target,
+                is_signer: false,
+                is_writable: true,
+            }],
+        ),
+        ProposalAction::Burn { mint, amount } => config(
+            format!(
+                "Burn {} base units of {} from vault 0",
+                amount,
+                token_symbol(mint)
+            ),
+            vec![SerializableAccountMeta {
+                pubkey: find_vault_address(multisig, 0).0,
+                is_signer: true,
+                is_writable: false,
+            }],
+        ),
+        ProposalAction::FreezeTokenAccount(token_account) => config(
+            format!("Freeze token account {}", short(token_account)),
+            vec![self_meta],
+        ),
+        ProposalAction::ThawTokenAccount(token_account) => config(
+            format!("Thaw token account {}", short(token_account)),
+            vec![self_meta],
+        ),
+        ProposalAction::MintTo {
+            mint,
+            destination_owner,
+            amount,
+        } => config(
+            format!(
+                "Mint {} base units of {} to {}",
+                amount,
+                token_symbol(mint),
+                labels.name(destination_owner)
+            ),
+            vec![self_meta],
+        ),
+        ProposalAction::VoteAuthorize {
+            vote_account,
+            new_authority,
+            authority_type,
+        } => config(
+            format!(
+                "Set {} authority of vote account {} to {}",
+                if *authority_type == 0 {
+                    "voter"
+                } else {
+                    "withdrawer"
+                },
+                short(vote_account),
+                labels.name(new_authority)
+            ),
+            vec![self_meta],
+        ),
+        ProposalAction::VoteWithdraw {
+            vote_account,
+            lamports,
+            destination,
+        } => config(
+            format!(
+                "Withdraw {} SOL from vote account {} to {}",
+                format_amount(*lamports, LAMPORTS_PER_SOL_DECIMALS),
+                short(vote_account),
+                labels.name(destination)
+            ),
+            vec![self_meta],
+        ),
+        ProposalAction::StakeSplit {
+            stake_account,
+            lamports,
+        } => config(
+            format!(
+                "Split {} SOL from stake account {} into a new stake account",
+                format_amount(*lamports, LAMPORTS_PER_SOL_DECIMALS),
+                short(stake_account)
+            ),
+            vec![self_meta],
+        ),
+        ProposalAction::StakeDeactivate(stake_account) => config(
+            format!("Deactivate stake account {}", short(stake_account)),
+            vec![self_meta],
+        ),
+        ProposalAction::StakeWithdraw {
+            stake_account,
+            lamports,
+            destination,
+        } => config(
+            format!(
+                "Withdraw {} SOL from stake account {} to {}",
+                format_amount(*lamports, LAMPORTS_PER_SOL_DECIMALS),
+                short(stake_account),
+                labels.name(destination)
+            ),
+            vec![self_meta],
+        ),
+        ProposalAction::TakeCustody { account, kind } => config(
+            format!(
+                "Take custody of the withdraw authority of {} account {}",
+                custody_kind(kind),
+                short(account)
+            ),
+            vec![self_meta],
+        ),
+        ProposalAction::ReleaseCustody {
+            account,
+            kind,
+            new_authority,
+        } => config(
+            format!(
+                "Hand the withdraw authority of {} account {} to {}",
+                custody_kind(kind),
+                short(account),
+                labels.name(new_authority)
+            ),
+            vec![self_meta],
+        ),
+        ProposalAction::TokenApprove {
+            token_account,
+            delegate,
+            amount,
+        } => config(
+            format!(
+                "Approve {} to spend {} base units from token account {}",
+                labels.name(delegate),
+                amount,
+                short(token_account)
+            ),
+            vec![SerializableAccountMeta {
+                pubkey: find_vault_address(multisig, 0).0,
+                is_signer: true,
+                is_writable: false,
+            }],
+        ),
+        ProposalAction::TokenRevoke(token_account) => config(
+            format!(
+                "Revoke the delegate of token account {}",
+                short(token_account)
+            ),
+            vec![SerializableAccountMeta {
+                pubkey: find_vault_address(multisig, 0).0,
+                is_signer: true,
+                is_writable: false,
+            }],
+        ),
+        ProposalAction::CreateMint {
+            decimals,
+            freeze_authority,
+        } => config(
+            format!(
+                "Create a mint with {} decimals, mint authority vault 0, {}",
+                decimals,
+                match freeze_authority {
+                    Some(key) => format!("freeze authority {}", labels.name(key)),
+                    None => "no freeze authority".to_string(),
+                }
+            ),
+            vec![SerializableAccountMeta {
+                pubkey: find_vault_address(multisig, 0).0,
+                is_signer: true,
+                is_writable: true,
+            }],
+        ),
+        ProposalAction::Recurring(payment) => config(
+            format!(
+                "Pay {} SOL from vault 0 to {} every {} seconds, up to {} times",
+                format_amount(payment.amount, LAMPORTS_PER_SOL_DECIMALS),
+                labels.name(&payment.destination),
+                payment.interval_seconds,
+                payment.max_payments
+            ),
+            vec![
+                SerializableAccountMeta {
+                    pubkey: find_vault_address(multisig, 0).0,
+                    is_signer: true,
+                    is_writable: true,
+                },
+                SerializableAccountMeta {
+                    pubkey: payment.destination,
+                    is_signer: false,
+                    is_writable: true,
+                },
+            ],
+        ),
+    }
+}
+
+fn describe_instruction(
+    program_id: &Pubkey,
+    accounts: &[SerializableAccountMeta],
+    data: &[u8],
+    labels: &Labels,
+) -> Option<String> {
+    let key = |i: usize| accounts.get(i).map(|a| labels.name(&a.pubkey));
+
+    if *program_id == anchor_lang::system_program::ID {
+        if data.len() != 12 || read_u32(data, 0)? != SYSTEM_TRANSFER {
+            return None;
+        }
+        return Some(format!(
+            "Transfer {} SOL from {} to {}",
+            format_amount(read_u64(data, 4)?, LAMPORTS_PER_SOL_DECIMALS),
+            key(0)?,
+            key(1)?
+        ));
+    }
+
+    if is_token_program(program_id) {
+        return match *data.first()? {
+            TOKEN_TRANSFER if data.len() == 9 => Some(format!(
+                "Transfer {} base units from {} to {} (authority {})",
+                read_u64(data, 1)?,
+                key(0)?,
+                key(1)?,
+                key(2)?
+            )),
+            TOKEN_TRANSFER_CHECKED if data.len() == 10 => Some(format!(
+                "Transfer {} {} from {} to {} (authority {})",
+                format_amount(read_u64(data, 1)?, data[9]),
+                token_symbol(&accounts.get(1)?.pubkey),
+                key(0)?,
+                key(2)?,
+                key(3)?
+            )),
+            TOKEN_APPROVE if data.len() == 9 => Some(format!(
+                "Approve {} to spend {} base units from {} (owner {})",
+                key(1)?,
+                read_u64(data, 1)?,
+                key(0)?,
+                key(2)?
+            )),
+            TOKEN_APPROVE_CHECKED if data.len() == 10 => Some(format!(
+                "Approve {} to spend {} {} from {} (owner {})",
+                key(2)?,
+                format_amount(read_u64(data, 1)?, data[9]),
+                token_symbol(&accounts.get(1)?.pubkey),
+                key(0)?,
+                key(3)?
+            )),
+            _ => None,
+        };
+    }
+
+    if *program_id == ASSOCIATED_TOKEN_PROGRAM_ID {
+        // 空数据 / 0 = Create，1 = CreateIdempotent
+        let idempotent = match data {
+            [] | [0] => false,
+            [1] => true,
+            _ => return None,
+        };
+        return Some(format!(
+            "Create {}associated token account {} for {} (mint {}, paid by {})",
+            if idempotent { "(if missing) " } else { "" },
+            key(1)?,
+            key(2)?,
+            token_symbol(&accounts.get(3)?.pubkey),
+            key(0)?
+        ));
+    }
+
+    if *program_id == BPF_LOADER_UPGRADEABLE_ID {
+        if data.len() != 4 || read_u32(data, 0)? != LOADER_UPGRADE {
+            return None;
+        }
+        return Some(format!(
+            "Upgrade program {} from buffer {} (authority {}, refund to {})",
+            key(1)?,
+            key(2)?,
+            key(6)?,
+            key(3)?
+        ));
+    }
+
+    None
+}
+
+fn unknown(program_id: &Pubkey, accounts: usize, labels: &Labels) -> String {
+    format!(
+        "Unknown instruction to {} with {} accounts",
+        labels.name(program_id),
+        accounts
+    )
+}
+
+// 本多签与金库地址，用于标注账户
+struct Labels {
+    multisig: Pubkey,
+    vaults: Vec<Pubkey>,
+}
+
+impl Labels {
+    fn new(multisig: &Pubkey) -> Self {
+        Self {
+            multisig: *multisig,
+            vaults: (0..7).map(|i| find_vault_address(multisig, i).0).collect(),
+        }
+    }
+
+    fn pda(&self, key: &Pubkey) -> Option<MultisigPda> {
+        if *key == self.multisig {
+            return Some(MultisigPda::Multisig);
+        }
+        self.vaults
+            .iter()
+            .position(|v| v == key)
+            .map(|i| MultisigPda::Vault(i as u8))
+    }
+
+    fn name(&self, key: &Pubkey) -> String {
+        match self.pda(key) {
+            Some(MultisigPda::Multisig) => "multisig".to_string(),
+            Some(MultisigPda::Vault(i)) => format!("vault {}", i),
+            None => short(key),
+        }
+    }
+
+    fn accounts(&self, accounts: &[SerializableAccountMeta]) -> Vec<AccountSummary> {
+        accounts
+            .iter()
+            .map(|a| AccountSummary {
+                pubkey: a.pubkey,
+                is_signer: a.is_signer,
+                is_writable: a.is_writable,
+                pda: self.pda(&a.pubkey),
+            })
+            .collect()
+    }
+}
+
+fn custody_kind(kind: &CustodyKind) -> &'static str {
+    match kind {
+        CustodyKind::Stake => "stake",
+        CustodyKind::Vote => "vote",
+    }
+}
+
+fn token_symbol(mint: &Pubkey) -> String {
+    KNOWN_MINTS
+        .iter()
+        .find(|(m, _)| m == mint)
+        .map(|(_, symbol)| symbol.to_string())
+        .unwrap_or_else(|| format!("tokens of mint {}", short(mint)))
+}
+
+fn short(key: &Pubkey) -> String {
+    let s = key.to_string();
+    format!("{}…", &s[..4])
+}
+
+// 按 decimals 插入小数点并去掉末尾的 0：12_500_000 / 6 -> "12.5"
+pub fn format_amount(amount: u64, decimals: u8) -> String {
+    let digits = format!("{:0>width$}", amount, width = decimals as usize + 1);
+    let (int, frac) = digits.split_at(digits.len() - decimals as usize);
+    let frac = frac.trim_end_matches('0');
+    if frac.is_empty() {
+        int.to_string()
+    } else {
+        format!("{}.{}", int, frac)
+    }
+}
@@ -0,0 +1,245 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use solana_transaction_status_client_types::EncodedConfirmedTransactionWithStatusMeta;
+
+use crate::{
+    AllowedDestinationsChanged, AppAuthoritySet, ApprovalAdded, ApprovalAlreadyPresent,
+    BalanceSnapshot, CommentPosted, ConfigActionExecuted, DepositReceived,
+    ExecutionFailureRecorded, MemberMarkedInactive, MemberMetadataClosed, MemberMetadataSet,
+    MigrationFinished, MigrationStarted, MigrationSwept, MintCreated, MintToProposed,
+    MultisigCreated, MultisigCreatedWithProposal, MultisigFrozen, MultisigImported,
+    MultisigUnfrozen, NotifyProgramSet, OptimisticConfigSet, PolicyProgramSet,
+    ProgramConfigUpdated, ProposalCancelled, ProposalCreated, ProposalExecuted,
+    ProposalMetadataSet, ProposalReady, ProposalRecorded, ProposalRejected, ProposalsCancelled,
+    ProtocolFeeCharged, RecurringPaymentCancelled, RecurringPaymentMade, StakeAccountSplit,
+    TokenDelegateApproved, VaultPauseSet, VestingCancelled, VestingTranchePaid, WindDownStarted,
+    WithdrawalRequestConverted, WithdrawalRequested, EVENT_SCHEMA_VERSION,
+};
+
+#[derive(Clone, Debug)]
+pub enum MultisigEvent {
+    MultisigCreated(MultisigCreated),
+    MultisigCreatedWithProposal(MultisigCreatedWithProposal),
+    ProposalCreated(ProposalCreated),
+    ProposalExecuted(ProposalExecuted),
+    ProposalRecorded(ProposalRecorded),
+    ConfigActionExecuted(ConfigActionExecuted),
+    CommentPosted(CommentPosted),
+    ProposalCancelled(ProposalCancelled),
+    ProposalRejected(ProposalRejected),
+    ApprovalAlreadyPresent(ApprovalAlreadyPresent),
+    ProposalMetadataSet(ProposalMetadataSet),
+    ProposalsCancelled(ProposalsCancelled),
+    MultisigFrozen(MultisigFrozen),
+    MultisigUnfrozen(MultisigUnfrozen),
+    WindDownStarted(WindDownStarted),
+    VaultPauseSet(VaultPauseSet),
+    ProtocolFeeCharged(ProtocolFeeCharged),
+    ProgramConfigUpdated(ProgramConfigUpdated),
+    AllowedDestinationsChanged(AllowedDestinationsChanged),
+    VestingTranchePaid(VestingTranchePaid),
+    VestingCancelled(VestingCancelled),
+    RecurringPaymentMade(RecurringPaymentMade),
+    RecurringPaymentCancelled(RecurringPaymentCancelled),
+    MintCreated(MintCreated),
+    TokenDelegateApproved(TokenDelegateApproved),
+    MintToProposed(MintToProposed),
+    StakeAccountSplit(StakeAccountSplit),
+    MemberMarkedInactive(MemberMarkedInactive),
+    DepositReceived(DepositReceived),
+    WithdrawalRequested(WithdrawalRequested),
+    WithdrawalRequestConverted(WithdrawalRequestConverted),
+    AppAuthoritySet(AppAuthoritySet),
+    MigrationStarted(MigrationStarted),
+    MigrationSwept(MigrationSwept),
+    MigrationFinished(MigrationFinished),
+    NotifyProgramSet(NotifyProgramSet),
+    PolicyProgramSet(PolicyProgramSet),
+    ApprovalAdded(ApprovalAdded),
+    ProposalReady(ProposalReady),
+    MultisigImported(MultisigImported),
+    BalanceSnapshot(BalanceSnapshot),
+    MemberMetadataSet(MemberMetadataSet),
+    MemberMetadataClosed(MemberMetadataClosed),
+    ExecutionFailureRecorded(ExecutionFailureRecorded),
+    OptimisticConfigSet(OptimisticConfigSet),
+}
+
+impl MultisigEvent {
+    // 判别符之后的第一个字节，未解码前即可读取
+    pub fn schema_version(data: &[u8]) -> Option<u8> {
+        data.get(8).copied()
+    }
+
+    // emit! 写出的数据：8 字节事件判别符 + borsh 编码的事件。
+    // schema_version 与本 crate 的 EVENT_SCHEMA_VERSION 不一致时布局未知，返回 None
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        fn event<T: Discriminator + AnchorDeserialize>(data: &[u8]) -> Option<T> {
+            let mut body = data.strip_prefix(T::DISCRIMINATOR)?;
+            T::deserialize(&mut body).ok()
+        }
+        if Self::schema_version(data)? != EVENT_SCHEMA_VERSION {
+            return None;
+        }
+        let disc = data.get(..8)?;
+        if disc == MultisigCreated::DISCRIMINATOR {
+            event(data).map(Self::MultisigCreated)
+        } else if disc == MultisigCreatedWithProposal::DISCRIMINATOR {
+            event(data).map(Self::MultisigCreatedWithProposal)
+        } else if disc == ProposalCreated::DISCRIMINATOR {
+            event(data).map(Self::ProposalCreated)
+        } else if disc == ProposalExecuted::DISCRIMINATOR {
+            event(data).map(Self::ProposalExecuted)
+        } else if disc == ProposalRecorded::DISCRIMINATOR {
+            event(data).map(Self::ProposalRecorded)
+        } else if disc == ConfigActionExecuted::DISCRIMINATOR {
+            event(data).map(Self::ConfigActionExecuted)
+        } else if disc == CommentPosted::DISCRIMINATOR {
+            event(data).map(Self::CommentPosted)
+        } else if disc == ProposalMetadataSet::DISCRIMINATOR {
+            event(data).map(Self::ProposalMetadataSet)
+        } else if disc == ProposalRejected::DISCRIMINATOR {
+            event(data).map(Self::ProposalRejected)
+        } else if disc == ApprovalAlreadyPresent::DISCRIMINATOR {
+            event(data).map(Self::ApprovalAlreadyPresent)
+        } else if disc == ProposalCancelled::DISCRIMINATOR {
+            event(data).map(Self::ProposalCancelled)
+        } else if disc == ProposalsCancelled::DISCRIMINATOR {
+            event(data).map(Self::ProposalsCancelled)
+        } else if disc == MultisigFrozen::DISCRIMINATOR {
+            event(data).map(Self::MultisigFrozen)
+        } else if disc == MultisigUnfrozen::DISCRIMINATOR {
+            event(data).map(Self::MultisigUnfrozen)
+        } else if disc == WindDownStarted::DISCRIMINATOR {
+            event(data).map(Self::WindDownStarted)
+        } else if disc == VaultPauseSet::DISCRIMINATOR {
+            event(data).map(Self::VaultPauseSet)
+        } else if disc == ProtocolFeeCharged::DISCRIMINATOR {
+            event(data).map(Self::ProtocolFeeCharged)
+        } else if disc == ProgramConfigUpdated::DISCRIMINATOR {
+            event(data).map(Self::ProgramConfigUpdated)
+        } else if disc == AllowedDestinationsChanged::DISCRIMINATOR {
+            event(data).map(Self::AllowedDestinationsChanged)
+        } else if disc == VestingTranchePaid::DISCRIMINATOR {
+            event(data).map(Self::VestingTranchePaid)
+        } else if disc == VestingCancelled::DISCRIMINATOR {
+            event(data).map(Self::VestingCancelled)
+        } else if disc == RecurringPaymentMade::DISCRIMINATOR {
+            event(data).map(Self::RecurringPaymentMade)
+        } else if disc == RecurringPaymentCancelled::DISCRIMINATOR {
+            event(data).map(Self::RecurringPaymentCancelled)
+        } else if disc == MintCreated::DISCRIMINATOR {
+            event(data).map(Self::MintCreated)
+        } else if disc == TokenDelegateApproved::DISCRIMINATOR {
+            event(data).map(Self::TokenDelegateApproved)
+        } else if disc == MintToProposed::DISCRIMINATOR {
+            event(data).map(Self::MintToProposed)
+        } else if disc == StakeAccountSplit::DISCRIMINATOR {
+            event(data).map(Self::StakeAccountSplit)
+        } else if disc == MemberMarkedInactive::DISCRIMINATOR {
+            event(data).map(Self::MemberMarkedInactive)
+        } else if disc == DepositReceived::DISCRIMINATOR {
+            event(data).map(Self::DepositReceived)
+        } else if disc == WithdrawalRequested::DISCRIMINATOR {
+            event(data).map(Self::WithdrawalRequested)
+        } else if disc == WithdrawalRequestConverted::DISCRIMINATOR {
+            event(data).map(Self::WithdrawalRequestConverted)
+        } else if disc == AppAuthoritySet::DISCRIMINATOR {
+            event(data).map(Self::AppAuthoritySet)
+        } else if disc == MigrationStarted::DISCRIMINATOR {
+            event(data).map(Self::MigrationStarted)
+        } else if disc == MigrationSwept::DISCRIMINATOR {
+            event(data).map(Self::MigrationSwept)
+        } else if disc == MigrationFinished::DISCRIMINATOR {
+            event(data).map(Self::MigrationFinished)
+        } else if disc == NotifyProgramSet::DISCRIMINATOR {
+            event(data).map(Self::NotifyProgramSet)
+        } else if disc == PolicyProgramSet::DISCRIMINATOR {
+            event(data).map(Self::PolicyProgramSet)
+        } else if disc == ApprovalAdded::DISCRIMINATOR {
+            event(data).map(Self::ApprovalAdded)
+        } else if disc == ProposalReady::DISCRIMINATOR {
+            event(data).map(Self::ProposalReady)
+        } else if disc == MultisigImported::DISCRIMINATOR {
+            event(data).map(Self::MultisigImported)
+        } else if disc == BalanceSnapshot::DISCRIMINATOR {
+            event(data).map(Self::BalanceSnapshot)
+        } else if disc == MemberMetadataSet::DISCRIMINATOR {
+            event(data).map(Self::MemberMetadataSet)
+        } else if disc == MemberMetadataClosed::DISCRIMINATOR {
+            event(data).map(Self::MemberMetadataClosed)
+        } else if disc == ExecutionFailureRecorded::DISCRIMINATOR {
+            event(data).map(Self::ExecutionFailureRecorded)
+        } else if disc == OptimisticConfigSet::DISCRIMINATOR {
+            event(data).map(Self::OptimisticConfigSet)
+        } else {
+            None
+        }
+    }
+}
+
+// 从交易日志中按顺序取出本程序发出的事件。
+// 按 "invoke [n]" / "success" / "failed" 维护调用栈，只解码栈顶为本程序时的 "Program data:"，
+// 因此被 CPI 的其他程序（或调用本程序的外层程序）发出的同格式数据不会混入。
+// 日志被截断时返回截断前已解析的事件；无法解码的行直接跳过。
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<MultisigEvent> {
+    let mut stack: Vec<Pubkey> = Vec::new();
+    let mut events = Vec::new();
+    for line in logs.iter().map(AsRef::as_ref) {
+        if line == "Log truncated" {
+            break;
+        }
+        if let Some(data) = line.strip_prefix("Program data: ") {
+            if stack.last() != Some(&crate::ID) {
+                continue;
+            }
+            // 一行可能含多段以空格分隔的数据（sol_log_data 多参数），emit! 只写一段
+            let event = data
+                .split(' ')
+                .next()
+                .and_then(|d| STANDARD.decode(d).ok())
+                .and_then(|d| MultisigEvent::decode(&d));
+            events.extend(event);
+            continue;
+        }
+        let Some(rest) = line.strip_prefix("Program ") else {
+            continue;
+        };
+        let Some((program, status)) = rest.split_once(' ') else {
+            continue;
+        };
+        let Ok(program) = program.parse::<Pubkey>() else {
+            continue;
+        };
+        if let Some(depth) = status
+            .strip_prefix("invoke [")
+            .and_then(|d| d.strip_suffix(']'))
+            .and_then(|d| d.parse::<usize>().ok())
+        {
+            // 以日志给出的深度为准，缺失的 success 行不会让栈错位
+            stack.truncate(depth.saturating_sub(1));
+            stack.push(program);
+        } else if (status == "success" || status.starts_with("failed"))
+            && stack.last() == Some(&program)
+        {
+            stack.pop();
+        }
+    }
+    events
+}
+
+// 失败的交易已回滚，其日志中的事件不代表链上状态，返回空
+pub fn events_from_transaction(
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Vec<MultisigEvent> {
+    let Some(meta) = &transaction.transaction.meta else {
+        return Vec::new();
+    };
+    if meta.err.is_some() {
+        return Vec::new();
+    }
+    let logs: Option<Vec<String>> = meta.log_messages.clone().into();
+    parse_logs(&logs.unwrap_or_default())
+}
@@ -0,0 +1,8 @@
+// 链下客户端辅助（`client` 特性）
+pub mod describe;
+pub mod events;
+
+pub use describe::{
+    describe, describe_action_hash, describe_metadata, describe_protocol_fee, ProposalSummary,
+};
+pub use events::{events_from_transaction, parse_logs, MultisigEvent};
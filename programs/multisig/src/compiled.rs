@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    find_vault_address, signer_vault, InstructionData, MultisigError, SerializableAccountMeta,
+    SIGNER_CONFIG,
+};
+
+// Squads VaultTransactionMessage 的序列化布局（长度前缀为 SmallVec：u8，指令数据为 u16）：
+//   num_signers: u8, num_writable_signers: u8, num_writable_non_signers: u8,
+//   account_keys: SmallVec<u8, Pubkey>,
+//   instructions: SmallVec<u8, { program_id_index: u8, account_indexes: SmallVec<u8, u8>, data: SmallVec<u16, u8> }>,
+//   address_table_lookups: SmallVec<u8, { account_key: Pubkey, writable_indexes: SmallVec<u8, u8>, readonly_indexes: SmallVec<u8, u8> }>
+//
+// 账户表按 [可写签名者, 只读签名者, 可写非签名者, 只读非签名者] 排列，标志位由头部三个计数推出。
+pub fn from_compiled_message(message: &[u8], multisig: &Pubkey) -> Result<Vec<InstructionData>> {
+    let mut reader = Reader(message);
+    let num_signers = reader.u8()? as usize;
+    let num_writable_signers = reader.u8()? as usize;
+    let num_writable_non_signers = reader.u8()? as usize;
+
+    let keys_len = reader.u8()? as usize;
+    let keys = (0..keys_len)
+        .map(|_| reader.pubkey())
+        .collect::<Result<Vec<_>>>()?;
+    require!(
+        num_writable_signers <= num_signers && num_signers + num_writable_non_signers <= keys.len(),
+        MultisigError::InvalidCompiledMessage
+    );
+
+    let meta = |index: u8| -> Result<SerializableAccountMeta> {
+        let index = index as usize;
+        // 超出静态账户表的索引指向地址查找表中的账户，这里无法解析
+        let pubkey = *keys
+            .get(index)
+            .ok_or(MultisigError::UnresolvedLookupTable)?;
+        let is_signer = index < num_signers;
+        let is_writable = if is_signer {
+            index < num_writable_signers
+        } else {
+            index < num_signers + num_writable_non_signers
+        };
+        Ok(SerializableAccountMeta {
+            pubkey,
+            is_signer,
+            is_writable,
+        })
+    };
+
+    let instructions_len = reader.u8()?;
+    let mut instructions = Vec::with_capacity(instructions_len as usize);
+    for _ in 0..instructions_len {
+        let program_id = meta(reader.u8()?)?.pubkey;
+        let indexes_len = reader.u8()? as usize;
+        let mut accounts = reader
+            .take(indexes_len)?
+            .iter()
+            .map(|i| meta(*i))
+            .collect::<Result<Vec<_>>>()?;
+        let data_len = reader.u16()? as usize;
+        let data = reader.take(data_len)?.to_vec();
+
+        let signers = signer_bits(&accounts, multisig);
+        // 与客户端约定一致：末尾附上目标程序账户，CPI 需要
+        accounts.push(SerializableAccountMeta {
+            pubkey: program_id,
+            is_signer: false,
+            is_writable: false,
+        });
+        instructions.push(InstructionData {
+            program_id,
+            accounts,
+            data,
+            signers,
+        });
+    }
+
+    require!(reader.u8()? == 0, MultisigError::UnresolvedLookupTable);
+    require!(reader.0.is_empty(), MultisigError::InvalidCompiledMessage);
+    Ok(instructions)
+}
+
+// 签名账户中的 multisig PDA 与金库 PDA 对应的 signers 位
+pub fn signer_bits(accounts: &[SerializableAccountMeta], multisig: &Pubkey) -> u8 {
+    let mut bits = 0;
+    for account in accounts.iter().filter(|a| a.is_signer) {
+        if account.pubkey == *multisig {
+            bits |= SIGNER_CONFIG;
+            continue;
+        }
+        if let Some(i) = (0..7).find(|i| find_vault_address(multisig, *i).0 == account.pubkey) {
+            bits |= signer_vault(i);
+        }
+    }
+    bits
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        require!(self.0.len() >= len, MultisigError::InvalidCompiledMessage);
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn pubkey(&mut self) -> Result<Pubkey> {
+        Ok(Pubkey::try_from(self.take(32)?).unwrap())
+    }
+}
@@ -0,0 +1,304 @@
+use anchor_lang::prelude::*;
+
+use crate::InstructionData;
+
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+pub const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+pub const VOTE_PROGRAM_ID: Pubkey = pubkey!("Vote111111111111111111111111111111111111111");
+pub const STAKE_PROGRAM_ID: Pubkey = pubkey!("Stake11111111111111111111111111111111111111");
+pub const SECP256K1_PROGRAM_ID: Pubkey = pubkey!("KeccakSecp256k11111111111111111111111111111");
+// StakeStateV2::size_of()
+pub const STAKE_STATE_SIZE: usize = 200;
+// spl_token::state::Mint::LEN
+pub const MINT_SIZE: usize = 82;
+// spl_token::state::Account::LEN / Multisig::LEN
+pub const TOKEN_ACCOUNT_SIZE: usize = 165;
+const TOKEN_MULTISIG_SIZE: usize = 355;
+
+// SystemInstruction 判别值
+const SYSTEM_CREATE_ACCOUNT: u32 = 0;
+const SYSTEM_ASSIGN: u32 = 1;
+const SYSTEM_TRANSFER: u32 = 2;
+const SYSTEM_CREATE_ACCOUNT_WITH_SEED: u32 = 3;
+const SYSTEM_ALLOCATE: u32 = 8;
+const SYSTEM_ALLOCATE_WITH_SEED: u32 = 9;
+const SYSTEM_ASSIGN_WITH_SEED: u32 = 10;
+const SYSTEM_TRANSFER_WITH_SEED: u32 = 11;
+// TokenInstruction::Transfer / CloseAccount / TransferChecked
+const TOKEN_TRANSFER: u8 = 3;
+const TOKEN_CLOSE_ACCOUNT: u8 = 9;
+const TOKEN_TRANSFER_CHECKED: u8 = 12;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transfer {
+    pub authority: Pubkey,
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub mint: Option<Pubkey>, // None = SOL
+    pub amount: u64,
+}
+
+pub fn is_token_program(program_id: &Pubkey) -> bool {
+    *program_id == TOKEN_PROGRAM_ID || *program_id == TOKEN_2022_PROGRAM_ID
+}
+
+pub fn find_associated_token_address(
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+// 严格解码：布局不完全匹配的一律视为无法识别
+pub fn decode_transfer(ix: &InstructionData, account_infos: &[AccountInfo]) -> Option<Transfer> {
+    let keys: Vec<Pubkey> = ix.accounts.iter().map(|m| m.pubkey).collect();
+
+    if ix.program_id == anchor_lang::system_program::ID {
+        if ix.data.len() != 12 || read_u32(&ix.data, 0)? != SYSTEM_TRANSFER {
+            return None;
+        }
+        return Some(Transfer {
+            authority: *keys.first()?,
+            source: *keys.first()?,
+            destination: *keys.get(1)?,
+            mint: None,
+            amount: read_u64(&ix.data, 4)?,
+        });
+    }
+
+    if is_token_program(&ix.program_id) {
+        match *ix.data.first()? {
+            TOKEN_TRANSFER if ix.data.len() == 9 => {
+                let source = *keys.first()?;
+                // 普通 transfer 不带 mint，需从源 token 账户读取
+                let mint = token_account_mint(source, &ix.program_id, account_infos)?;
+                Some(Transfer {
+                    authority: *keys.get(2)?,
+                    source,
+                    destination: *keys.get(1)?,
+                    mint: Some(mint),
+                    amount: read_u64(&ix.data, 1)?,
+                })
+            }
+            TOKEN_TRANSFER_CHECKED if ix.data.len() == 10 => Some(Transfer {
+                authority: *keys.get(3)?,
+                source: *keys.first()?,
+                destination: *keys.get(2)?,
+                mint: Some(*keys.get(1)?),
+                amount: read_u64(&ix.data, 1)?,
+            }),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+// SPL transfer / transfer_checked 的指令形状，不读取账户数据
+pub fn is_token_transfer(ix: &InstructionData) -> bool {
+    is_token_program(&ix.program_id)
+        && matches!(
+            (ix.data.first(), ix.data.len()),
+            (Some(&TOKEN_TRANSFER), 9) | (Some(&TOKEN_TRANSFER_CHECKED), 10)
+        )
+}
+
+// SOL/SPL 转账或关闭 token 账户时须签名的账户；只按指令形状判断，不读取账户数据
+pub fn withdrawal_authority(ix: &InstructionData) -> Option<Pubkey> {
+    let index = if ix.program_id == anchor_lang::system_program::ID {
+        if ix.data.len() != 12 || read_u32(&ix.data, 0)? != SYSTEM_TRANSFER {
+            return None;
+        }
+        0
+    } else if is_token_program(&ix.program_id) {
+        match (*ix.data.first()?, ix.data.len()) {
+            (TOKEN_TRANSFER, 9) | (TOKEN_CLOSE_ACCOUNT, 1) => 2,
+            (TOKEN_TRANSFER_CHECKED, 10) => 3,
+            _ => return None,
+        }
+    } else {
+        return None;
+    };
+    ix.accounts.get(index).map(|m| m.pubkey)
+}
+
+// 会改变账户 owner 或大小的 System 指令的目标账户。
+// 只看判别值前缀、不校验长度，填充过的指令同样会被识别。
+pub fn system_reshape_target(ix: &InstructionData) -> Option<Pubkey> {
+    if ix.program_id != anchor_lang::system_program::ID {
+        return None;
+    }
+    let index = match read_u32(&ix.data, 0)? {
+        SYSTEM_CREATE_ACCOUNT | SYSTEM_CREATE_ACCOUNT_WITH_SEED => 1,
+        SYSTEM_ASSIGN | SYSTEM_ALLOCATE | SYSTEM_ALLOCATE_WITH_SEED | SYSTEM_ASSIGN_WITH_SEED => 0,
+        _ => return None,
+    };
+    ix.accounts.get(index).map(|m| m.pubkey)
+}
+
+// System 指令从哪个账户扣除多少 lamports
+pub fn system_debit(ix: &InstructionData) -> Option<(Pubkey, u64)> {
+    if ix.program_id != anchor_lang::system_program::ID {
+        return None;
+    }
+    match read_u32(&ix.data, 0)? {
+        SYSTEM_CREATE_ACCOUNT | SYSTEM_TRANSFER | SYSTEM_TRANSFER_WITH_SEED => {
+            Some((ix.accounts.first()?.pubkey, read_u64(&ix.data, 4)?))
+        }
+        // base 32 字节后为 u64 长度前缀的 seed，其后才是 lamports
+        SYSTEM_CREATE_ACCOUNT_WITH_SEED => {
+            let seed_len = usize::try_from(read_u64(&ix.data, 36)?).ok()?;
+            let lamports = read_u64(&ix.data, 44usize.checked_add(seed_len)?)?;
+            Some((ix.accounts.first()?.pubkey, lamports))
+        }
+        _ => None,
+    }
+}
+
+// token 账户的 owner（数据 32..64）；账户不属于该 token 程序时返回 None
+pub fn token_account_owner(
+    account: &Pubkey,
+    token_program: &Pubkey,
+    account_infos: &[AccountInfo],
+) -> Option<Pubkey> {
+    let info = account_infos.iter().find(|i| i.key == account)?;
+    if info.owner != token_program {
+        return None;
+    }
+    let data = info.try_borrow_data().ok()?;
+    Pubkey::try_from(data.get(32..64)?).ok()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenAccount {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+// 严格读取 token 账户：owner 须为 SPL Token / Token-2022，且已初始化（state 1 或 2）。
+// SPL Token 账户恰为 165 字节；Token-2022 带扩展时更长，第 165 字节的 AccountType 须为 Account（2），
+// 以排除同样补齐到 165 字节以上的 mint，355 字节的 token multisig 账户也被排除
+pub fn read_token_account(info: &AccountInfo) -> Option<TokenAccount> {
+    let data = info.try_borrow_data().ok()?;
+    let valid_len = if *info.owner == TOKEN_PROGRAM_ID {
+        data.len() == TOKEN_ACCOUNT_SIZE
+    } else if *info.owner == TOKEN_2022_PROGRAM_ID {
+        data.len() == TOKEN_ACCOUNT_SIZE
+            || (data.len() > TOKEN_ACCOUNT_SIZE
+                && data.len() != TOKEN_MULTISIG_SIZE
+                && data[TOKEN_ACCOUNT_SIZE] == 2)
+    } else {
+        false
+    };
+    if !valid_len || !matches!(data[108], 1 | 2) {
+        return None;
+    }
+    Some(TokenAccount {
+        mint: Pubkey::try_from(&data[0..32]).ok()?,
+        owner: Pubkey::try_from(&data[32..64]).ok()?,
+        amount: read_u64(&data, 64)?,
+    })
+}
+
+fn token_account_mint(
+    account: Pubkey,
+    token_program: &Pubkey,
+    account_infos: &[AccountInfo],
+) -> Option<Pubkey> {
+    let info = account_infos.iter().find(|i| *i.key == account)?;
+    if info.owner != token_program {
+        return None;
+    }
+    let data = info.try_borrow_data().ok()?;
+    Pubkey::try_from(data.get(0..32)?).ok()
+}
+
+// spl_token 账户中的 COption<Pubkey>：4 字节标签 + 32 字节
+pub(crate) fn read_coption_pubkey(data: &[u8], offset: usize) -> Option<Pubkey> {
+    match data.get(offset..offset + 36)? {
+        [1, 0, 0, 0, key @ ..] => Pubkey::try_from(key).ok(),
+        _ => None,
+    }
+}
+
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+pub(crate) fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
+}
+
+// VoteStateVersions（bincode）：u32 版本标签，V1_14_11 = 1、Current = 2 时依次为
+// node_pubkey @4、authorized_withdrawer @36、commission @68、votes（u64 长度 + 每条 12 / 13 字节）、
+// root_slot Option<u64>、authorized_voters BTreeMap<Epoch, Pubkey>（u64 长度 + 每条 40 字节）
+pub fn read_vote_withdrawer(data: &[u8]) -> Option<Pubkey> {
+    match read_u32(data, 0)? {
+        1 | 2 => Pubkey::try_from(data.get(36..68)?).ok(),
+        _ => None,
+    }
+}
+
+// 在 epoch 生效的 authorized voter：键不大于 epoch 的最后一条
+pub fn read_vote_authorized_voter(data: &[u8], epoch: u64) -> Option<Pubkey> {
+    let vote_len = match read_u32(data, 0)? {
+        1 => 12, // Lockout
+        2 => 13, // LandedVote
+        _ => return None,
+    };
+    let votes = read_u64(data, 69)? as usize;
+    let mut offset = votes.checked_mul(vote_len)?.checked_add(77)?;
+    offset += match data.get(offset)? {
+        0 => 1,
+        1 => 9,
+        _ => return None,
+    };
+    let voters = read_u64(data, offset)?;
+    offset += 8;
+    let mut current = None;
+    for _ in 0..voters {
+        if read_u64(data, offset)? > epoch {
+            break;
+        }
+        current = Some(Pubkey::try_from(data.get(offset + 8..offset + 40)?).ok()?);
+        offset += 40;
+    }
+    current
+}
+
+// StakeStateV2（bincode）：u32 标签，Initialized = 1、Stake = 2 时 Meta 紧随其后：
+// rent_exempt_reserve @4、authorized.staker @12、authorized.withdrawer @44。返回 (staker, withdrawer)
+pub fn read_stake_authorities(data: &[u8]) -> Option<(Pubkey, Pubkey)> {
+    match read_u32(data, 0)? {
+        1 | 2 => Some((
+            Pubkey::try_from(data.get(12..44)?).ok()?,
+            Pubkey::try_from(data.get(44..76)?).ok()?,
+        )),
+        _ => None,
+    }
+}
+
+// secp256k1 预编译指令：1 字节签名数，随后每个签名 11 字节偏移：signature_offset u16、
+// signature_ix u8、eth_address_offset u16、eth_address_ix u8、message_offset u16、
+// message_size u16、message_ix u8。只接受单个签名且三段数据都在该指令自身（交易内下标 ix）
+pub fn read_secp256k1_signature(data: &[u8], ix: u8) -> Option<([u8; 20], &[u8])> {
+    if *data.first()? != 1 || [data.get(3)?, data.get(6)?, data.get(11)?] != [&ix; 3] {
+        return None;
+    }
+    let read_u16 = |offset: usize| -> Option<usize> {
+        Some(u16::from_le_bytes(data.get(offset..offset + 2)?.try_into().ok()?) as usize)
+    };
+    let address_offset = read_u16(4)?;
+    let (message_offset, message_size) = (read_u16(7)?, read_u16(9)?);
+    let address = data.get(address_offset..address_offset + 20)?.try_into().ok()?;
+    Some((address, data.get(message_offset..message_offset + message_size)?))
+}
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+
+use crate::MultisigError;
+
+// Multisig.extensions 的 TLV 布局：每项为 tag u16、len u16（均为小端序）后接 len 字节的值。
+// 新的可选配置放在这里，不改动 Multisig 的固定字段，未使用的多签也无需迁移。
+
+// 已分配的标签；新功能在此登记，标签一经发布不得复用或改变含义。0 保留为无效值
+pub const TAG_RESERVED: u16 = 0;
+// 1 字节，值为 1 时允许经 CPI 调用 propose / approve / cancel
+pub const TAG_ALLOW_CPI: u16 = 1;
+// 1 字节，值为 1 时不为该多签的提案报价协议费
+pub const TAG_NO_PROTOCOL_FEE: u16 = 2;
+// 每项 17 字节：app_id 16 字节 + bump，见 Multisig::app_authorities
+pub const TAG_APP_AUTHORITIES: u16 = 3;
+// 32 字节，迁移目标多签；设置后旧多签停止运作，见 start_migration
+pub const TAG_MIGRATION: u16 = 4;
+// 32 字节，记录在迁移创建的新多签上，指向旧多签
+pub const TAG_MIGRATED_FROM: u16 = 5;
+// 33 字节：通知程序 32 字节 + 通知失败时是否中止执行（1 为中止），见 set_notify_program
+pub const TAG_NOTIFY: u16 = 6;
+// 33 字节：策略程序 32 字节 + 执行时随其传入的账户数，见 set_policy_program
+pub const TAG_POLICY: u16 = 7;
+// 1 字节，提案必然失败的执行次数超过该值后可被关闭，见 set_max_execution_attempts
+pub const TAG_MAX_EXECUTION_ATTEMPTS: u16 = 8;
+// borsh 编码的 OptimisticConfig，见 set_optimistic_config
+pub const TAG_OPTIMISTIC: u16 = 9;
+
+const HEADER_LEN: usize = 4;
+
+// 返回该标签所在项的 (起点, 终点)；数据被截断时停止查找
+fn find(data: &[u8], tag: u16) -> Option<(usize, usize)> {
+    let mut offset = 0;
+    while offset + HEADER_LEN <= data.len() {
+        let entry_tag = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let len = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let end = offset + HEADER_LEN + len;
+        if end > data.len() {
+            return None;
+        }
+        if entry_tag == tag {
+            return Some((offset, end));
+        }
+        offset = end;
+    }
+    None
+}
+
+pub fn get(data: &[u8], tag: u16) -> Option<&[u8]> {
+    find(data, tag).map(|(start, end)| &data[start + HEADER_LEN..end])
+}
+
+pub fn remove(data: &mut Vec<u8>, tag: u16) -> bool {
+    match find(data, tag) {
+        Some((start, end)) => {
+            data.drain(start..end);
+            true
+        }
+        None => false,
+    }
+}
+
+// 覆盖写入同一标签时先移除旧值；capacity 为整个区域可用的字节数
+pub fn set(data: &mut Vec<u8>, tag: u16, value: &[u8], capacity: usize) -> Result<()> {
+    require!(
+        tag != TAG_RESERVED && value.len() <= u16::MAX as usize,
+        MultisigError::InvalidExtension
+    );
+    let old_len = find(data, tag).map_or(0, |(start, end)| end - start);
+    let new_len = data.len() - old_len + HEADER_LEN + value.len();
+    if new_len > capacity {
+        msg!("Extensions need {} bytes, {} available", new_len, capacity);
+        return err!(MultisigError::ExtensionSpaceExhausted);
+    }
+    remove(data, tag);
+    data.extend_from_slice(&tag.to_le_bytes());
+    data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+    data.extend_from_slice(value);
+    Ok(())
+}
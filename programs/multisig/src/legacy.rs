@@ -0,0 +1,43 @@
+// 旧 serum / coral multisig 程序的账户布局，仅用于 import_multisig 迁移成员与阈值
+use anchor_lang::prelude::*;
+
+pub const SERUM_MULTISIG_PROGRAM_ID: Pubkey =
+    pubkey!("msigmtwzgXJHj2ext4XJjCDmpbcMuufFb5cHuwg6Xdt");
+// sha256("account:Multisig")[..8]，与本程序 Multisig 的判别符相同，只能靠 owner 区分
+pub const LEGACY_MULTISIG_DISCRIMINATOR: [u8; 8] = [224, 116, 121, 186, 68, 161, 79, 236];
+// 旧程序不限制 owners 数量，这里只拒绝明显不合理的长度前缀
+const MAX_LEGACY_OWNERS: usize = 256;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LegacyMultisig {
+    pub owners: Vec<Pubkey>,
+    pub threshold: u64,
+    pub nonce: u8,
+    pub owner_set_seqno: u32,
+}
+
+// 判别符 | owners: u32 长度 + 32 * n | threshold: u64 | nonce: u8 | owner_set_seqno: u32，其后为分配时多留的空间。
+// 任何长度不足或前缀越界都返回 None，不会 panic
+pub fn parse_legacy_multisig(data: &[u8]) -> Option<LegacyMultisig> {
+    let rest = data.strip_prefix(&LEGACY_MULTISIG_DISCRIMINATOR)?;
+    let (len, rest) = rest.split_first_chunk::<4>()?;
+    let len = u32::from_le_bytes(*len) as usize;
+    if len > MAX_LEGACY_OWNERS {
+        return None;
+    }
+    let owners = rest.get(..len * 32)?;
+    let rest = &rest[len * 32..];
+    let owners = owners
+        .chunks_exact(32)
+        .map(|key| Pubkey::try_from(key).ok())
+        .collect::<Option<Vec<_>>>()?;
+    let (threshold, rest) = rest.split_first_chunk::<8>()?;
+    let (nonce, rest) = rest.split_first()?;
+    let (seqno, _) = rest.split_first_chunk::<4>()?;
+    Some(LegacyMultisig {
+        owners,
+        threshold: u64::from_le_bytes(*threshold),
+        nonce: *nonce,
+        owner_set_seqno: u32::from_le_bytes(*seqno),
+    })
+}
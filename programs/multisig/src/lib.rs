@@ -1,12 +1,111 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::instruction::Instruction;
-use anchor_lang::solana_program::program::invoke_signed;
-use std::collections::BTreeSet;
+use anchor_lang::solana_program::instruction::{
+    get_stack_height, Instruction, TRANSACTION_LEVEL_STACK_HEIGHT,
+};
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use solana_sha256_hasher::hashv;
+
+#[cfg(all(feature = "client", not(target_os = "solana")))]
+pub mod client;
+pub mod compiled;
+pub mod decode;
+pub mod extension;
+pub mod legacy;
+#[cfg(feature = "serde")]
+pub mod serde_helpers;
+#[cfg(all(feature = "testing", not(target_os = "solana")))]
+pub mod testing;
+
+use compiled::from_compiled_message;
+use decode::{
+    decode_transfer, find_associated_token_address, is_token_program, is_token_transfer,
+    read_secp256k1_signature, read_token_account, system_debit, system_reshape_target,
+    token_account_owner,
+    withdrawal_authority, Transfer, ASSOCIATED_TOKEN_PROGRAM_ID, MEMO_PROGRAM_ID, MINT_SIZE,
+    SECP256K1_PROGRAM_ID, STAKE_PROGRAM_ID, STAKE_STATE_SIZE, TOKEN_2022_PROGRAM_ID,
+    TOKEN_PROGRAM_ID, VOTE_PROGRAM_ID,
+};
 
 declare_id!("38tdFSkJASspVp8GvqdwjLiHTK2crbubsC75d1q31EPo");
 
+// approvals 位图为 u32，成员数上限由此决定；实际容量在创建时由 max_members 指定
+pub const MAX_MEMBERS: usize = 32;
+pub const MAX_THRESHOLD_OVERRIDES: usize = 4;
+pub const MAX_AMOUNT_RULES: usize = 4;
+pub const MAX_APPROVAL_GROUPS: usize = 4;
+pub const MAX_TRUSTED_DESTINATIONS: usize = 8;
+pub const MAX_DESTINATION_LIMITS: usize = 4;
+pub const MAX_ALLOWED_DESTINATIONS: usize = 8;
+pub const MAX_DENIED_DESTINATIONS: usize = 16;
+// signers 位图中金库占位 1..=7
+pub const MAX_VAULTS: usize = 7;
+pub const MAX_VAULT_NAME_LEN: usize = 24;
+pub const ETH_APPROVAL_DOMAIN: &[u8] = b"solana-multisig:eth-approval:v1";
+// 同一成员解冻后再次冻结需等待的秒数
+pub const FREEZE_COOLDOWN_SECONDS: i64 = 24 * 60 * 60;
+// 指定执行人独占执行的时长（自达到阈值起），之后任何人都可执行
+pub const DEFAULT_EXECUTOR_GRACE_SECONDS: u32 = 24 * 60 * 60;
+pub const MAX_PENDING_INVITES: usize = 4;
+pub const MAX_CONTROLLED_ACCOUNTS: usize = 8;
+pub const MAX_COMMENT_LEN: usize = 200;
+pub const MAX_EXECUTION_MEMO_LEN: usize = 128;
+pub const MAX_DEPOSIT_MEMO_LEN: usize = 64;
+pub const MAX_CANCEL_NOTE_LEN: usize = 64;
+pub const MAX_REJECTION_REASON_LEN: usize = 80;
+pub const MAX_METADATA_URI_LEN: usize = 200;
+pub const MAX_MEMBER_LABEL_LEN: usize = 32;
+pub const MAX_OPTIMISTIC_SHAPES: usize = 4;
+pub const MAX_SHAPE_DISCRIMINATOR_LEN: usize = 8;
+pub const MAX_SPEND_MINTS: usize = 8;
+pub const MAX_APP_AUTHORITIES: usize = 8;
+pub const MAX_BATCH_INSTRUCTIONS: usize = 8;
+// ProposalCancelled.reason：1..CANCEL_REASON_CUSTOM 保留给程序自身的取消路径，
+// cancel_transaction 只能使用 0 或自定义代码
+pub const CANCEL_REASON_UNSPECIFIED: u8 = 0;
+pub const CANCEL_REASON_EXPIRED: u8 = 1;
+pub const CANCEL_REASON_REJECTED: u8 = 2;
+pub const CANCEL_REASON_BULK_CANCELLED: u8 = 3; // cancel_all 紧急批量取消
+pub const CANCEL_REASON_MEMBER_REMOVED: u8 = 4;
+pub const CANCEL_REASON_VOTING_CLOSED: u8 = 5;
+pub const CANCEL_REASON_EXECUTION_FAILED: u8 = 6;
+pub const CANCEL_REASON_VETOED: u8 = 7; // 乐观通道内被否决
+pub const CANCEL_REASON_CUSTOM: u8 = 16;
+// recent_executed 位图覆盖的最近提案数，depends_on 只能引用这个窗口内的提案
+pub const EXECUTED_WINDOW: u32 = 64;
+// approvals 位图版本
+pub const PROPOSAL_VERSION: u8 = 2;
+// 协议费上限：1% 加 0.01 SOL
+pub const MAX_PROTOCOL_FEE_BPS: u16 = 100;
+pub const MAX_FLAT_FEE_LAMPORTS: u64 = 10_000_000;
+// 所有事件的首个字段（紧随判别符），解析方可先读出再决定如何解码；任一事件布局变化时递增
+#[constant]
+pub const EVENT_SCHEMA_VERSION: u8 = 4;
+pub const PROGRAM_VERSION: &str = env!("CARGO_PKG_VERSION");
+// 单次 init 最多分配 10240 字节，缓冲区数据需留出账户头部空间
+pub const MAX_BUFFER_SIZE: usize = 9 * 1024;
+pub const MAX_BUFFER_REFS: usize = 8;
+// 超过此时长且无提案引用的缓冲区可由任何人关闭，租金仍退还给创建者
+pub const BUFFER_EXPIRY_SECONDS: i64 = 7 * 24 * 60 * 60;
+// 超过此时长的提款请求不能再转为提案，任何人可关闭，租金退还给请求者
+pub const WITHDRAWAL_REQUEST_EXPIRY_SECONDS: i64 = 30 * 24 * 60 * 60;
+// 已支付分期记录在 u32 位图中
+pub const MAX_VESTING_TRANCHES: usize = 32;
+// 单次余额快照最多读取的 token 账户数，限制事件大小
+pub const MAX_SNAPSHOT_ACCOUNTS: usize = 64;
+// 通知程序须实现的固定指令 on_multisig_executed(multisig, index, instruction_hash)，
+// 即 Anchor 的 sha256("global:on_multisig_executed")[..8]
+pub const NOTIFY_DISCRIMINATOR: [u8; 8] = [239, 155, 110, 39, 250, 245, 13, 36];
+// 策略程序须实现的固定指令 check_execution(proposal, instruction_hash, accounts)，
+// 即 Anchor 的 sha256("global:check_execution")[..8]
+pub const POLICY_DISCRIMINATOR: [u8; 8] = [166, 38, 237, 233, 75, 183, 114, 212];
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SerializableAccountMeta {
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
     pub pubkey: Pubkey,
     pub is_signer: bool,
     pub is_writable: bool,
@@ -22,6 +121,16 @@ impl From<AccountMeta> for SerializableAccountMeta {
     }
 }
 
+impl From<&SerializableAccountMeta> for AccountMeta {
+    fn from(s: &SerializableAccountMeta) -> Self {
+        Self {
+            pubkey: s.pubkey,
+            is_signer: s.is_signer,
+            is_writable: s.is_writable,
+        }
+    }
+}
+
 impl From<SerializableAccountMeta> for AccountMeta {
     fn from(s: SerializableAccountMeta) -> Self {
         Self {
@@ -32,253 +141,9446 @@ impl From<SerializableAccountMeta> for AccountMeta {
     }
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InstructionData {
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
     pub program_id: Pubkey,
     pub accounts: Vec<SerializableAccountMeta>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::bytes"))]
     pub data: Vec<u8>,
+    pub signers: u8, // 位 0 = multisig PDA，位 i+1 = i 号金库；为 0 时不带签名调用
 }
 
-#[account]
-pub struct Multisig {
-    pub creator: Pubkey,      
-    pub nonce: u8,  
-    pub members: Vec<Pubkey>,
-    pub threshold: u8,
-    pub proposals_count: u32, // 用于生成唯一 Proposal PDA
-    pub bump: u8,
+pub const SIGNER_CONFIG: u8 = 1;
+
+pub const fn signer_vault(index: u8) -> u8 {
+    1 << (index + 1)
 }
 
-#[account]
-pub struct Proposal {
-    pub multisig: Pubkey,
-    pub proposer: Pubkey,
-    pub instruction: InstructionData,
-    pub approvals: Vec<Pubkey>,
-    pub executed: bool,
-    pub cancelled: bool,
-    pub bump: u8,
+// 提案内容：Raw 为任意 CPI，其余为原生执行的配置变更（无需 CPI）
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ProposalAction {
+    Raw(InstructionData),
+    // 执行时发出邀请，受邀者仍需 accept_membership
+    AddMember(#[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))] Pubkey),
+    RemoveMember(#[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))] Pubkey),
+    ChangeThreshold(u8),
+    SetPaused(bool),
+    // 显式清空金库：执行时转出全部余额（remaining_accounts 需含金库、收款方与 System Program）
+    SweepVault {
+        index: u8,
+        #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+        destination: Pubkey,
+    },
+    // 指令数据存放在 InstructionBuffer 中，执行时 remaining_accounts 首个账户须为该缓冲区
+    Buffered(BufferedInstruction),
+    // 由 activate_proposal 激活，之后各期到期即可由任何人调用 execute_vesting_tranche 从 0 号金库的 ATA 支付
+    Vesting(VestingSchedule),
+    // 由 activate_proposal 激活，之后每个周期任何人可调用 execute_recurring_payment 从 0 号金库转出 SOL
+    Recurring(RecurringPayment),
+    // 以 0 号金库付租金并作为 mint authority 创建 SPL mint。新 mint 为执行时提供的临时密钥对，
+    // 须作为 remaining_accounts 首个账户并在 execute 交易中签名，地址见 MintCreated 事件
+    CreateMint {
+        decimals: u8,
+        #[cfg_attr(feature = "serde", serde(with = "serde_helpers::option_pubkey"))]
+        freeze_authority: Option<Pubkey>,
+    },
+    // spl_token::set_authority，由持有该权限的 multisig PDA 或金库签名；执行时读取 target 校验当前权限
+    SetTokenAuthority {
+        #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+        target: Pubkey,
+        authority_type: u8, // 0 MintTokens, 1 FreezeAccount, 2 AccountOwner, 3 CloseAccount
+        #[cfg_attr(feature = "serde", serde(with = "serde_helpers::option_pubkey"))]
+        new_authority: Option<Pubkey>,
+    },
+    // 从 0 号金库的 ATA 销毁（burn_checked），decimals 在执行时从 mint 读取；支持 Token-2022
+    Burn {
+        #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+        mint: Pubkey,
+        amount: u64,
+    },
+    // 以 multisig PDA 作为 freeze authority 冻结 / 解冻 token 账户
+    FreezeTokenAccount(#[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))] Pubkey),
+    ThawTokenAccount(#[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))] Pubkey),
+    // 以 0 号金库为 owner 授权 / 撤销 delegate（approve_checked / revoke），支持 Token-2022
+    TokenApprove {
+        #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+        token_account: Pubkey,
+        #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+        delegate: Pubkey,
+        amount: u64,
+    },
+    TokenRevoke(#[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))] Pubkey),
+    // 以 multisig PDA 作为 mint authority 增发到收款方的 ATA（mint_to_checked），ATA 不存在时由 0 号金库付费创建
+    MintTo {
+        #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+        mint: Pubkey,
+        #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+        destination_owner: Pubkey,
+        amount: u64,
+    },
+    // 以 multisig PDA 作为当前权限更换 vote 账户的 voter / withdrawer，或从中提取 lamports
+    VoteAuthorize {
+        #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+        vote_account: Pubkey,
+        #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+        new_authority: Pubkey,
+        authority_type: u8, // 0 Voter, 1 Withdrawer
+    },
+    VoteWithdraw {
+        #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+        vote_account: Pubkey,
+        lamports: u64,
+        #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+        destination: Pubkey,
+    },
+    // 以 multisig PDA 作为 staker / withdrawer 管理 stake 账户。
+    // 拆分出的新 stake 账户由临时密钥对创建（同 CreateMint），租金由 0 号金库支付，地址见 StakeAccountSplit 事件
+    StakeSplit {
+        #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+        stake_account: Pubkey,
+        lamports: u64,
+    },
+    StakeDeactivate(#[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))] Pubkey),
+    StakeWithdraw {
+        #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+        stake_account: Pubkey,
+        lamports: u64,
+        #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+        destination: Pubkey,
+    },
+    // 把 stake / vote 账户的 withdraw authority 移交给 multisig PDA：当前 withdrawer 须在 execute 交易中签名。
+    // 执行后记入 multisig.controlled_accounts
+    TakeCustody {
+        #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+        account: Pubkey,
+        kind: CustodyKind,
+    },
+    // 以 multisig PDA 签名把 withdraw authority 交给 new_authority，并移出登记表
+    ReleaseCustody {
+        #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+        account: Pubkey,
+        kind: CustodyKind,
+        #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+        new_authority: Pubkey,
+    },
+    // 多条指令在同一次执行中依次调用，任一失败则整体回滚；
+    // remaining_accounts 依次为各指令的账户，之后才是策略、协议费等账户
+    Batch(Vec<InstructionData>),
 }
 
-#[error_code]
-pub enum MultisigError {
-    #[msg("Members must be sorted and unique")]
-    InvalidMembers,
-    #[msg("Threshold out of range")]
-    InvalidThreshold,
-    #[msg("Only members can interact")]
-    NotMember,
-    #[msg("Already approved")]
-    AlreadyApproved,
-    #[msg("Not enough approvals")]
-    NotExecutable,
-    #[msg("Only proposer can cancel")]
-    NotProposer,
-    #[msg("Already processed")]
-    AlreadyProcessed,
-    #[msg("CPI account mismatch")]
-    AccountMismatch,
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CustodyKind {
+    Stake,
+    Vote,
 }
 
-// ===== Accounts =====
+impl CustodyKind {
+    pub fn program_id(&self) -> Pubkey {
+        match self {
+            CustodyKind::Stake => STAKE_PROGRAM_ID,
+            CustodyKind::Vote => VOTE_PROGRAM_ID,
+        }
+    }
+}
 
-#[derive(Accounts)]
-#[instruction(nonce: u8, members: Vec<Pubkey>, threshold: u8)]
-pub struct CreateMultisig<'info> {
-    #[account(
-        init,
-        seeds = [b"multisig", creator.key().as_ref(), &[nonce]],
-        bump,
-        payer = creator,
-        space = 8 + 32 + 1 + (32 * 10) + 1 + 4 + 1
-        //       ^   ^    ^     ^        ^    ^    ^
-        //       |   |    |     |        |    |    |
-        //       |   |    |     |        |    |    bump
-        //       |   |    |     |        |    proposals_count (u32)
-        //       |   |    |     |        threshold (u8)
-        //       |   |    |     members (max 10)
-        //       |   |    nonce (u8)
-        //       |   creator (Pubkey = 32)
-        //       discriminator (8)
-    )]
-    pub multisig: Account<'info, Multisig>,
-    #[account(mut)]
-    pub creator: Signer<'info>,
-    pub system_program: Program<'info, System>,
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecurringPayment {
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+    pub destination: Pubkey,
+    pub amount: u64, // lamports
+    pub interval_seconds: i64,
+    pub max_payments: u32,
 }
 
-#[derive(Accounts)]
-#[instruction(instruction_data: InstructionData)]
-pub struct ProposeTransaction<'info> {
-    #[account(mut)]
-    pub multisig: Account<'info, Multisig>,
-    #[account(
-        init,
-        seeds = [b"proposal", multisig.key().as_ref(), &multisig.proposals_count.to_le_bytes()],
-        bump,
-        payer = proposer,
-        space = 8 + 32 + 32 + 1000 + (32 * 10) + 1 + 1 + 1
-    )]
-    pub proposal: Account<'info, Proposal>,
-    #[account(mut)]
-    pub proposer: Signer<'info>,
-    pub system_program: Program<'info, System>,
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VestingSchedule {
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+    pub mint: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+    pub recipient: Pubkey, // 收款 token 账户的 owner
+    pub tranches: Vec<VestingTranche>,
 }
 
-#[derive(Accounts)]
-pub struct ApproveTransaction<'info> {
-    #[account(mut)]
-    pub multisig: Account<'info, Multisig>,
-    #[account(
-        mut,
-        constraint = proposal.multisig == multisig.key(),
-        constraint = !proposal.executed && !proposal.cancelled @ MultisigError::AlreadyProcessed,
-    )]
-    pub proposal: Account<'info, Proposal>,
-    #[account(
-        constraint = multisig.members.contains(&approver.key()) @ MultisigError::NotMember,
-    )]
-    pub approver: Signer<'info>,
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VestingTranche {
+    pub unlock_time: i64,
+    pub amount: u64,
 }
 
-#[derive(Accounts)]
-pub struct ExecuteTransaction<'info> {
-    #[account(mut)]
-    pub multisig: Account<'info, Multisig>,
-    #[account(
-        mut,
-        close = multisig,
-        constraint = proposal.multisig == multisig.key(),
-        constraint = !proposal.executed && !proposal.cancelled @ MultisigError::AlreadyProcessed,
-        constraint = {
-            let approval_set: BTreeSet<_> = proposal.approvals.iter().collect();
-            approval_set.len() >= multisig.threshold as usize
-        } @ MultisigError::NotExecutable,
-    )]
-    pub proposal: Account<'info, Proposal>,
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BufferedInstruction {
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+    pub program_id: Pubkey,
+    pub accounts: Vec<SerializableAccountMeta>,
+    pub signers: u8,
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+    pub buffer: Pubkey,
+    pub hash: [u8; 32], // 提案时缓冲区内容的 sha256
 }
 
-#[derive(Accounts)]
-pub struct CancelTransaction<'info> {
-    #[account(mut)]
-    pub multisig: Account<'info, Multisig>,
-    #[account(
-        mut,
-        close = multisig,
-        constraint = proposal.multisig == multisig.key(),
-        constraint = !proposal.executed && !proposal.cancelled @ MultisigError::AlreadyProcessed,
-        constraint = proposal.proposer == canceller.key() @ MultisigError::NotProposer,
-    )]
-    pub proposal: Account<'info, Proposal>,
-    pub canceller: Signer<'info>,
+// 提案创建时可选的执行约束
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct ProposalOptions {
+    pub depends_on: Option<u32>,
+    pub not_before: Option<i64>,
+    pub not_after: Option<i64>,
+    pub urgent: bool,
+    pub allow_dangerous: bool, // 允许改动 multisig/金库 PDA，需要全体未到期成员批准
+    pub no_sign: bool, // 仅记录：执行时不调用、不签名，只发出事件
+    pub execution_memo: Option<String>, // 执行时以 0 号金库签名写入 SPL Memo，供对账
+    pub delay_override: Option<u32>, // 比默认 timelock 更长的等待时间，不可更短
+    pub voting_ends_at: Option<i64>, // 之后不再接受投票；不得晚于 not_after
+    pub metadata_uri: Option<String>, // 链下说明文档（理由、发票、审计报告）
+    pub metadata_hash: [u8; 32], // 文档内容的 sha256，供审批人核对下载的文件
+    pub designated_executor: Option<Pubkey>, // 只有该签名者可执行，直到 executor_grace_seconds 过后
+    pub allow_duplicate: bool, // 传入内容索引且已有相同内容的活跃提案时仍然创建，索引继续指向原提案
+    pub optimistic: bool, // 走乐观通道：无需批准，延迟期内未被否决即可执行；指令须符合白名单形状
 }
 
-// ===== Program Logic =====
+// 乐观通道允许的指令形状，各项须完全一致
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct InstructionShape {
+    pub program_id: Pubkey,
+    pub discriminator: Vec<u8>, // data 须以此开头，1..=8 字节
+    pub data_len: u16,
+    pub accounts: u8, // 账户数，含末尾的程序账户
+    pub signers: u8, // 与 InstructionData.signers 相同的位图
+}
 
-#[program]
-pub mod multisig {
-    use super::*;
+impl InstructionShape {
+    pub fn matches(&self, ix: &InstructionData) -> bool {
+        ix.program_id == self.program_id
+            && ix.data.len() == self.data_len as usize
+            && ix.data.starts_with(&self.discriminator)
+            && ix.accounts.len() == self.accounts as usize
+            && ix.signers == self.signers
+    }
+}
 
-    pub fn create_multisig(
-        ctx: Context<CreateMultisig>,
-        nonce: u8, // used in seeds, not in logic
-        members: Vec<Pubkey>,
-        threshold: u8,
-    ) -> Result<()> {
-        // 验证成员：排序 + 唯一 + 非空
-        let mut members = members;
-        members.sort();
-        members.dedup();
-        require!(!members.is_empty(), MultisigError::InvalidMembers);
-        require!(threshold > 0 && threshold <= members.len() as u8, MultisigError::InvalidThreshold);
+// 乐观执行：符合形状的提案自创建起等待 delay_seconds，期间否决数达到 veto_threshold 即作废
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OptimisticConfig {
+    pub enabled_for: Vec<InstructionShape>,
+    pub delay_seconds: u32,
+    pub veto_threshold: u8,
+}
 
-        let multisig = &mut ctx.accounts.multisig;
-        multisig.creator = ctx.accounts.creator.key(); 
-        multisig.nonce = nonce;  
-        multisig.members = members;
-        multisig.threshold = threshold;
-        multisig.proposals_count = 0;
-        multisig.bump = ctx.bumps.multisig;
-        Ok(())
+impl OptimisticConfig {
+    // 只接受单条 Raw 指令，缓冲区与类型化的配置提案一律不符合
+    pub fn allows(&self, action: &ProposalAction) -> bool {
+        match action {
+            ProposalAction::Raw(ix) => self.enabled_for.iter().any(|s| s.matches(ix)),
+            _ => false,
+        }
     }
+}
 
-    pub fn propose_transaction(
-        ctx: Context<ProposeTransaction>,
-        instruction_data: InstructionData,
-    ) -> Result<()> {
-        let proposer = ctx.accounts.proposer.key();
-        let multisig = &ctx.accounts.multisig;
-        require!(multisig.members.contains(&proposer), MultisigError::NotMember);
+impl ProposalAction {
+    pub fn instructions(&self) -> &[InstructionData] {
+        match self {
+            ProposalAction::Raw(ix) => std::slice::from_ref(ix),
+            ProposalAction::Batch(ixs) => ixs,
+            _ => &[],
+        }
+    }
 
-        let proposal = &mut ctx.accounts.proposal;
-        proposal.multisig = multisig.key();
-        proposal.proposer = proposer;
-        proposal.instruction = instruction_data;
-        proposal.approvals = vec![];
-        proposal.executed = false;
-        proposal.cancelled = false;
-        proposal.bump = ctx.bumps.proposal;
+    pub fn instructions_mut(&mut self) -> &mut [InstructionData] {
+        match self {
+            ProposalAction::Raw(ix) => std::slice::from_mut(ix),
+            ProposalAction::Batch(ixs) => ixs,
+            _ => &mut [],
+        }
+    }
 
-        // 递增计数器（防重放）
-        ctx.accounts.multisig.proposals_count += 1;
-        Ok(())
+    // 多指令提案须每条都是解冻，冻结期间不能夹带其他指令
+    pub fn is_unfreeze(&self) -> bool {
+        let ixs = self.instructions();
+        !ixs.is_empty() && ixs.iter().all(InstructionData::is_unfreeze)
     }
 
-    pub fn approve_transaction(ctx: Context<ApproveTransaction>) -> Result<()> {
-        let approver = ctx.accounts.approver.key();
-        let proposal = &mut ctx.accounts.proposal;
+    // 执行时会以哪些金库签名：Raw 按 signers 位图，原生执行的动作按其资金来源
+    pub fn signing_vaults(&self) -> Vec<u8> {
+        match self {
+            ProposalAction::Raw(ix) => ix.signer_vaults().collect(),
+            ProposalAction::Batch(ixs) => (0..7)
+                .filter(|i| ixs.iter().any(|ix| ix.signers & signer_vault(*i) != 0))
+                .collect(),
+            ProposalAction::Buffered(b) => {
+                (0..7).filter(|i| b.signers & signer_vault(*i) != 0).collect()
+            }
+            ProposalAction::SweepVault { index, .. } => vec![*index],
+            ProposalAction::Vesting(_)
+            | ProposalAction::Recurring(_)
+            | ProposalAction::CreateMint { .. }
+            | ProposalAction::Burn { .. }
+            | ProposalAction::TokenApprove { .. }
+            | ProposalAction::TokenRevoke(_)
+            | ProposalAction::MintTo { .. }
+            | ProposalAction::StakeSplit { .. } => vec![0],
+            _ => vec![],
+        }
+    }
+
+    // 提案会转出的 (目的地, mint, 数量)，目的地为 token 账户时取其 owner；mint = None 为 SOL。
+    // None 表示会动用多签资产但去向无法确定
+    pub fn outflows(
+        &self,
+        multisig: &Pubkey,
+        account_infos: &[AccountInfo],
+    ) -> Vec<Option<(Pubkey, Option<Pubkey>, u64)>> {
+        match self {
+            ProposalAction::Raw(_) | ProposalAction::Batch(_) => self
+                .instructions()
+                .iter()
+                .flat_map(|ix| ix.outflows(multisig, account_infos))
+                .collect(),
+            ProposalAction::SweepVault { index, destination } => {
+                let vault = find_vault_address(multisig, *index).0;
+                let balance = account_infos.iter().find(|i| *i.key == vault).map(|i| i.lamports());
+                vec![balance.map(|amount| (*destination, None, amount))]
+            }
+            ProposalAction::StakeWithdraw {
+                lamports,
+                destination,
+                ..
+            }
+            | ProposalAction::VoteWithdraw {
+                lamports,
+                destination,
+                ..
+            } => vec![Some((*destination, None, *lamports))],
+            ProposalAction::MintTo {
+                mint,
+                destination_owner,
+                amount,
+            } => vec![Some((*destination_owner, Some(*mint), *amount))],
+            // 之后的付款、委托额度或权限移交，金额与去向在此无法约束
+            ProposalAction::Vesting(_)
+            | ProposalAction::Recurring(_)
+            | ProposalAction::TokenApprove { .. }
+            | ProposalAction::SetTokenAuthority { .. }
+            | ProposalAction::VoteAuthorize { .. }
+            | ProposalAction::ReleaseCustody { .. } => vec![None],
+            _ => vec![],
+        }
+    }
 
-        if proposal.approvals.contains(&approver) {
-            return err!(MultisigError::AlreadyApproved);
+    // 修改本多签配置的提案：类型化配置动作，或以 multisig PDA 签名调用本程序（自治指令只接受该签名）
+    pub fn is_config(&self) -> bool {
+        match self {
+            ProposalAction::AddMember(_)
+            | ProposalAction::RemoveMember(_)
+            | ProposalAction::ChangeThreshold(_)
+            | ProposalAction::SetPaused(_) => true,
+            _ => self
+                .instructions()
+                .iter()
+                .any(|ix| ix.program_id == crate::ID && ix.signs_as_config()),
         }
+    }
 
-        proposal.approvals.push(approver);
-        Ok(())
+    // 暂停期间不执行的动作：会动用金库或外部账户的，配置变更不受影响
+    pub fn respects_pause(&self) -> bool {
+        matches!(
+            self,
+            ProposalAction::Raw(_)
+                | ProposalAction::Batch(_)
+                | ProposalAction::Buffered(_)
+                | ProposalAction::SweepVault { .. }
+                | ProposalAction::SetTokenAuthority { .. }
+                | ProposalAction::Burn { .. }
+                | ProposalAction::FreezeTokenAccount(_)
+                | ProposalAction::ThawTokenAccount(_)
+                | ProposalAction::TokenApprove { .. }
+                | ProposalAction::TokenRevoke(_)
+                | ProposalAction::MintTo { .. }
+                | ProposalAction::VoteAuthorize { .. }
+                | ProposalAction::VoteWithdraw { .. }
+                | ProposalAction::StakeSplit { .. }
+                | ProposalAction::StakeDeactivate(_)
+                | ProposalAction::StakeWithdraw { .. }
+                | ProposalAction::TakeCustody { .. }
+                | ProposalAction::ReleaseCustody { .. }
+                | ProposalAction::CreateMint { .. }
+        )
     }
 
-    pub fn execute_transaction(ctx: Context<ExecuteTransaction>) -> Result<()> {
-        let proposal = &mut ctx.accounts.proposal;
-        proposal.executed = true;
+    // 清退模式下只允许把资产转出；缓冲区提案在创建时无法检查内容，一律拒绝
+    pub fn is_wind_down_allowed(&self, multisig: &Pubkey) -> bool {
+        match self {
+            ProposalAction::Raw(ix) => ix.is_wind_down_allowed(multisig),
+            ProposalAction::Batch(ixs) => ixs.iter().all(|ix| ix.is_wind_down_allowed(multisig)),
+            ProposalAction::SweepVault { .. }
+            | ProposalAction::StakeDeactivate(_)
+            | ProposalAction::StakeWithdraw { .. }
+            | ProposalAction::VoteWithdraw { .. } => true,
+            _ => false,
+        }
+    }
 
-        let ix = &proposal.instruction;
-        let account_infos = ctx.remaining_accounts;
-        let accounts: Vec<AccountMeta> = ix.accounts.iter().map(|s| s.clone().into()).collect();
+    // 提案内容的摘要（borsh 序列化后 sha256）
+    pub fn hash(&self) -> Result<[u8; 32]> {
+        Ok(hashv(&[&self.try_to_vec()?]).to_bytes())
+    }
+
+    // 供客户端构造 execute 的 remaining_accounts；PDA 由程序 invoke_signed 签名，不标记为签名者。
+    // 应用 authority 无法仅凭提案判断，其签名标记需由调用方清除
+    pub fn execute_accounts(&self, multisig: &Pubkey) -> Vec<AccountMeta> {
+        let metas = |accounts: &[SerializableAccountMeta], pdas: Vec<Pubkey>| {
+            accounts
+                .iter()
+                .map(|a| AccountMeta {
+                    pubkey: a.pubkey,
+                    is_signer: a.is_signer && !pdas.contains(&a.pubkey),
+                    is_writable: a.is_writable,
+                })
+                .collect::<Vec<_>>()
+        };
+        match self {
+            ProposalAction::Raw(ix) => metas(&ix.accounts, ix.signer_pdas(multisig)),
+            ProposalAction::Batch(ixs) => ixs
+                .iter()
+                .flat_map(|ix| metas(&ix.accounts, ix.signer_pdas(multisig)))
+                .collect(),
+            ProposalAction::Buffered(b) => {
+                let mut accounts = vec![AccountMeta::new(b.buffer, false)];
+                accounts.extend(metas(&b.accounts, signer_pdas(b.signers, multisig)));
+                accounts
+            }
+            ProposalAction::SweepVault { index, destination } => vec![
+                AccountMeta::new(find_vault_address(multisig, *index).0, false),
+                AccountMeta::new(*destination, false),
+                AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+            ],
+            // 当前权限默认为 0 号金库；由 multisig PDA 或其他金库持有时替换第二个账户
+            ProposalAction::SetTokenAuthority { target, .. } => vec![
+                AccountMeta::new(*target, false),
+                AccountMeta::new_readonly(find_vault_address(multisig, 0).0, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            ],
+            // Token-2022 mint 需替换 ATA 与 Token Program
+            ProposalAction::Burn { mint, .. } => {
+                let vault = find_vault_address(multisig, 0).0;
+                vec![
+                    AccountMeta::new(
+                        find_associated_token_address(&vault, mint, &TOKEN_PROGRAM_ID),
+                        false,
+                    ),
+                    AccountMeta::new(*mint, false),
+                    AccountMeta::new_readonly(vault, false),
+                    AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                ]
+            }
+            // 调用方还需追加 token 账户的 mint 与所属 Token Program
+            ProposalAction::FreezeTokenAccount(token_account)
+            | ProposalAction::ThawTokenAccount(token_account) => {
+                vec![AccountMeta::new(*token_account, false)]
+            }
+            // 调用方还需在 token 账户之后插入其 mint，并追加所属 Token Program
+            ProposalAction::TokenApprove { token_account, .. } => vec![
+                AccountMeta::new(*token_account, false),
+                AccountMeta::new_readonly(find_vault_address(multisig, 0).0, false),
+            ],
+            // 调用方还需追加所属 Token Program
+            ProposalAction::TokenRevoke(token_account) => vec![
+                AccountMeta::new(*token_account, false),
+                AccountMeta::new_readonly(find_vault_address(multisig, 0).0, false),
+            ],
+            // Token-2022 mint 需替换 ATA 与 Token Program
+            ProposalAction::MintTo {
+                mint,
+                destination_owner,
+                ..
+            } => vec![
+                AccountMeta::new(
+                    find_associated_token_address(destination_owner, mint, &TOKEN_PROGRAM_ID),
+                    false,
+                ),
+                AccountMeta::new(*mint, false),
+                AccountMeta::new_readonly(*destination_owner, false),
+                AccountMeta::new(find_vault_address(multisig, 0).0, false),
+                AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+            ],
+            ProposalAction::VoteAuthorize { vote_account, .. } => vec![
+                AccountMeta::new(*vote_account, false),
+                AccountMeta::new_readonly(anchor_lang::solana_program::sysvar::clock::ID, false),
+                AccountMeta::new_readonly(VOTE_PROGRAM_ID, false),
+            ],
+            ProposalAction::VoteWithdraw {
+                vote_account,
+                destination,
+                ..
+            } => vec![
+                AccountMeta::new(*vote_account, false),
+                AccountMeta::new(*destination, false),
+                AccountMeta::new_readonly(VOTE_PROGRAM_ID, false),
+            ],
+            // 调用方还需在最前面插入新 stake 账户（签名者）
+            ProposalAction::StakeSplit { stake_account, .. } => vec![
+                AccountMeta::new(*stake_account, false),
+                AccountMeta::new(find_vault_address(multisig, 0).0, false),
+                AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+                AccountMeta::new_readonly(STAKE_PROGRAM_ID, false),
+            ],
+            ProposalAction::StakeDeactivate(stake_account) => vec![
+                AccountMeta::new(*stake_account, false),
+                AccountMeta::new_readonly(anchor_lang::solana_program::sysvar::clock::ID, false),
+                AccountMeta::new_readonly(STAKE_PROGRAM_ID, false),
+            ],
+            ProposalAction::StakeWithdraw {
+                stake_account,
+                destination,
+                ..
+            } => vec![
+                AccountMeta::new(*stake_account, false),
+                AccountMeta::new(*destination, false),
+                AccountMeta::new_readonly(anchor_lang::solana_program::sysvar::clock::ID, false),
+                AccountMeta::new_readonly(
+                    anchor_lang::solana_program::sysvar::stake_history::ID,
+                    false,
+                ),
+                AccountMeta::new_readonly(STAKE_PROGRAM_ID, false),
+            ],
+            // 调用方还需在 Clock sysvar 之后插入当前 withdrawer（签名者）
+            ProposalAction::TakeCustody { account, kind } => vec![
+                AccountMeta::new(*account, false),
+                AccountMeta::new_readonly(anchor_lang::solana_program::sysvar::clock::ID, false),
+                AccountMeta::new_readonly(kind.program_id(), false),
+            ],
+            ProposalAction::ReleaseCustody { account, kind, .. } => vec![
+                AccountMeta::new(*account, false),
+                AccountMeta::new_readonly(anchor_lang::solana_program::sysvar::clock::ID, false),
+                AccountMeta::new_readonly(kind.program_id(), false),
+            ],
+            // 调用方还需在最前面插入新 mint（签名者）
+            ProposalAction::CreateMint { .. } => vec![
+                AccountMeta::new(find_vault_address(multisig, 0).0, false),
+                AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            ],
+            // 配置类提案原生执行
+            _ => vec![],
+        }
+    }
+}
 
-        msg!("Accounts len: {}, AccountInfos len: {}", accounts.len(), account_infos.len());
+impl InstructionData {
+    pub fn is_self_call(&self, discriminator: &[u8]) -> bool {
+        self.program_id == crate::ID && self.data.starts_with(discriminator)
+    }
 
-        // 安全验证 remaining_accounts
-        require!(accounts.len() == account_infos.len(), MultisigError::AccountMismatch);
-        for (meta, info) in accounts.iter().zip(account_infos.iter()) {
-            msg!("meta key: {}, info key: {}", meta.pubkey, *info.key);
-            msg!("meta writable: {}, info writable: {}", meta.is_writable, info.is_writable);
+    pub fn is_unfreeze(&self) -> bool {
+        self.is_self_call(instruction::Unfreeze::DISCRIMINATOR)
+    }
 
-            require!(meta.pubkey == *info.key, MultisigError::AccountMismatch);
-            //require!(meta.is_writable == info.is_writable, MultisigError::AccountMismatch);
-            //require!(meta.is_signer == info.is_signer, MultisigError::AccountMismatch);
+    // 由本指令签名 PDA 发出的转账、关闭 token 账户，以及回收支出账本租金；
+    // 解冻不带来新的义务，也允许，以免冻结后资产无法转出
+    pub fn is_wind_down_allowed(&self, multisig: &Pubkey) -> bool {
+        if self.is_unfreeze() || self.is_self_call(instruction::CloseSpendLedger::DISCRIMINATOR) {
+            return true;
         }
+        withdrawal_authority(self).is_some_and(|a| self.signer_pdas(multisig).contains(&a))
+    }
 
-        let instruction = Instruction {
-            program_id: ix.program_id,
-            accounts,
-            data: ix.data.clone(),
+    pub fn signs_as_config(&self) -> bool {
+        self.signers & SIGNER_CONFIG != 0
+    }
+
+    // 见 ProposalAction::outflows；每条指令至多一笔
+    pub fn outflows(
+        &self,
+        multisig: &Pubkey,
+        account_infos: &[AccountInfo],
+    ) -> Vec<Option<(Pubkey, Option<Pubkey>, u64)>> {
+        // 不签名的指令动不了多签资产；调用本程序的是自治指令
+        if self.signers == 0 || self.program_id == crate::ID {
+            return vec![];
+        }
+        let outflow = decode_transfer(self, account_infos)
+            .filter(|t| self.signer_pdas(multisig).contains(&t.authority))
+            .and_then(|t| {
+                let destination = match t.mint {
+                    None => t.destination,
+                    Some(_) => token_account_owner(&t.destination, &self.program_id, account_infos)?,
+                };
+                Some((destination, t.mint, t.amount))
+            });
+        vec![outflow]
+    }
+
+    // 选中签名的金库编号
+    pub fn signer_vaults(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..7).filter(|i| self.signers & signer_vault(*i) != 0)
+    }
+
+    // 本指令中以 PDA 身份签名的账户
+    pub fn signer_pdas(&self, multisig: &Pubkey) -> Vec<Pubkey> {
+        signer_pdas(self.signers, multisig)
+    }
+
+    // System CreateAccountWithSeed：由金库出资并作为 base，新地址为 create_with_seed(金库, seed, owner)。
+    // base 与出资方为同一账户，只需列出一次，执行时该金库的 signers 位同时满足两处签名要求。
+    // lamports 通常为 Rent::minimum_balance(space)，从金库扣除，须为金库留足免租余额
+    pub fn create_account_with_seed(
+        multisig: &Pubkey,
+        vault_index: u8,
+        seed: &str,
+        lamports: u64,
+        space: u64,
+        owner: &Pubkey,
+    ) -> Result<(Pubkey, Self)> {
+        let vault = find_vault_address(multisig, vault_index).0;
+        let address = Pubkey::create_with_seed(&vault, seed, owner)
+            .map_err(|_| MultisigError::InvalidAccountSeed)?;
+
+        let mut data = 3u32.to_le_bytes().to_vec(); // SystemInstruction::CreateAccountWithSeed
+        data.extend_from_slice(vault.as_ref());
+        data.extend_from_slice(&(seed.len() as u64).to_le_bytes());
+        data.extend_from_slice(seed.as_bytes());
+        data.extend_from_slice(&lamports.to_le_bytes());
+        data.extend_from_slice(&space.to_le_bytes());
+        data.extend_from_slice(owner.as_ref());
+        let ix = Self {
+            program_id: anchor_lang::system_program::ID,
+            accounts: vec![
+                AccountMeta::new(vault, true).into(),
+                AccountMeta::new(address, false).into(),
+                AccountMeta::new_readonly(anchor_lang::system_program::ID, false).into(),
+            ],
+            data,
+            signers: signer_vault(vault_index),
         };
+        Ok((address, ix))
+    }
+}
 
-        // 构造 seeds 并调用 invoke_signed
-        let seeds = &[
-            b"multisig",
-            ctx.accounts.multisig.creator.as_ref(),
-            &[ctx.accounts.multisig.nonce],
-            &[ctx.accounts.multisig.bump],
-        ];
-        let signer_seeds = &[&seeds[..]];
+fn signer_pdas(signers: u8, multisig: &Pubkey) -> Vec<Pubkey> {
+    let config = (signers & SIGNER_CONFIG != 0).then_some(*multisig);
+    config
+        .into_iter()
+        .chain(
+            (0..7)
+                .filter(|i| signers & signer_vault(*i) != 0)
+                .map(|i| find_vault_address(multisig, i).0),
+        )
+        .collect()
+}
 
-        invoke_signed(&instruction, account_infos, signer_seeds)?;
-        
-        Ok(())
+#[account]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Multisig {
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+    pub creator: Pubkey,      
+    pub nonce: u8,  
+    pub members: Vec<Member>, // 按 key 排序
+    pub threshold: u8,
+    pub proposals_count: u32, // 用于生成唯一 Proposal PDA
+    pub bump: u8,
+    pub config_seqno: u32, // 成员变更时递增，使旧提案的位图失效
+    pub threshold_overrides: Vec<ThresholdOverride>,
+    pub amount_rules: Vec<AmountRule>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::option_pubkey"))]
+    pub frozen_by: Option<Pubkey>,
+    pub frozen_at: i64,
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::option_pubkey"))]
+    pub last_freezer: Option<Pubkey>,
+    pub unfrozen_at: i64,
+    pub pending_invites: Vec<Invitation>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::option_pubkey"))]
+    pub config_authority: Option<Pubkey>, // 可代替 multisig PDA 签署自治指令（如上级多签）
+    pub paused: bool, // 暂停期间不执行 Raw 提案
+    pub quorum: Option<u8>, // 执行前至少需要的投票（赞成、反对、弃权）人数
+    pub recent_executed: u64, // 第 i 位 = 提案 proposals_count - 1 - i 已执行
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::option_pubkey"))]
+    pub guardian: Option<Pubkey>, // 可直接调用 cancel_all
+    pub execute_against_snapshot: bool, // 基础阈值取提案创建时的值，而非当前配置
+    pub timelock_seconds: u32, // 达到阈值后需等待的时间
+    pub urgent_timelock_seconds: u32,
+    pub urgent_threshold: Option<u8>, // None = 全体未到期成员
+    pub min_execution_delay_slots: u16, // 达到阈值与执行之间至少间隔的 slot 数
+    pub max_members: u8, // 账户空间按此容量分配
+    // proposals_count = executed_count + cancelled_count + 未关闭的提案数
+    pub executed_count: u32,
+    pub cancelled_count: u32, // 含主动取消、cancel_all、过期关闭与被移除成员的提案
+    // 最近一次创建、提案、投票、执行或取消的时间；无需许可的清理（close_expired）不计入
+    pub last_activity: i64,
+    pub controlled_accounts: Vec<ControlledAccount>, // 由本多签持有 withdraw authority 的外部账户
+    pub inactivity_timeout: Option<u32>, // None = 不启用成员在线检测
+    pub liveness_floor: u8, // 因成员失联而降低的阈值不得低于此值
+    pub wound_down_at: Option<i64>, // 进入清退模式的时间；之后只能提出转出资产的提案，不可撤销
+    // 执行时识别出的、由 multisig/金库 PDA 转出的 SOL 累计；无法解码的指令不计入，仅供参考
+    pub total_lamports_out: u64,
+    pub approval_groups: Vec<ApprovalGroup>, // 执行时每组都须达到各自的最低批准数
+    pub config_requires_unanimity: bool, // 配置类提案需全体未到期成员批准
+    pub trusted_destinations: Vec<Pubkey>, // 钱包地址；转入其 token 账户同样视为可信
+    pub destination_limits: Vec<DestinationLimit>,
+    pub allowed_destinations: Vec<Pubkey>, // 非空时只能转给这些地址，无法识别的指令一律拒绝
+    pub allowlist_delay_seconds: u32, // 启用后修改 allowed_destinations 需等待的时间
+    pub pending_allowlist: Option<PendingAllowlist>,
+    pub denied_destinations: Vec<Pubkey>, // 即使全体批准也不能转给这些地址
+    pub vaults: Vec<VaultConfig>, // 已登记的金库，按 index 排序；未登记的金库使用默认设置
+    pub retain_executed: bool, // 执行后保留提案账户作为记录，而不是立即关闭
+    pub retention_seconds: u32, // 保留的提案至少过这么久才能由 close_executed_proposal 关闭
+    pub executor_grace_seconds: u32,
+    pub approval_ttl_seconds: Option<u32>, // 超过该时长的批准不再计入，需重新确认
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::bytes"))]
+    pub extensions: Vec<u8>, // TLV 扩展区，须保持为最后一个字段，见 extension.rs
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VaultConfig {
+    pub index: u8,
+    pub threshold_override: Option<u8>, // 请求该金库签名的指令至少需要的批准数
+    pub name: String, // 如 "payroll"，供审批人辨认用途
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::option_pubkey"))]
+    pub mint_hint: Option<Pubkey>, // 主要持有的 token，仅作展示
+    pub paused: bool, // 暂停期间不以该金库签名，请求其签名的提案无法执行
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PendingAllowlist {
+    pub destinations: Vec<Pubkey>,
+    pub delay_seconds: u32,
+    pub effective_at: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ControlledAccount {
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+    pub account: Pubkey,
+    pub kind: CustodyKind,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Member {
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+    pub key: Pubkey,
+    pub expires_at: Option<i64>, // 到期后不再计入
+    pub last_seen: i64, // 最近一次签名（提案、投票、心跳）的时间
+    pub inactive: bool, // 被 mark_inactive 标记后不计入在线成员，下次签名时自动恢复
+    pub key_type: KeyType,
+}
+
+// Secp256k1 成员的 key 为以太坊地址（20 字节）后补 12 个 0，见 eth_member_key
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KeyType {
+    Ed25519,
+    Secp256k1, // 只能通过 approve_with_eth_signature 批准，不能提案或签 Solana 交易
+}
+
+impl Member {
+    pub fn is_active(&self, now: i64) -> bool {
+        self.expires_at.is_none_or(|t| now < t)
     }
 
-    pub fn cancel_transaction(_ctx: Context<CancelTransaction>) -> Result<()> {
-        // 提案账户已在 #[account(close = multisig)] 中自动关闭
-        Ok(())
+    // 未到期且未被标记为失联
+    pub fn is_live(&self, now: i64) -> bool {
+        self.is_active(now) && !self.inactive
+    }
+}
+
+// 待受邀者签名 accept_membership 后才加入 members
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Invitation {
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+    pub key: Pubkey,
+    pub membership_expires_at: Option<i64>,
+    pub invite_expires_at: Option<i64>,
+}
+
+impl Invitation {
+    pub fn is_active(&self, now: i64) -> bool {
+        self.invite_expires_at.is_none_or(|t| now < t)
+    }
+}
+
+// 只读视图：当前可用成员数，以及到期会使阈值不可达的时间点
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ConfigStatus {
+    pub active_members: u8,
+    pub required_threshold: u8,
+    pub unreachable_at: Option<i64>,
+}
+
+// 针对特定目标程序提高阈值（如升级、配置类程序）
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThresholdOverride {
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+    pub program_id: Pubkey,
+    pub min_threshold: u8,
+}
+
+// 金额不超过 max_amount 的 SOL（mint = None）或指定 mint 转账使用该阈值
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AmountRule {
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::option_pubkey"))]
+    pub mint: Option<Pubkey>,
+    pub max_amount: u64,
+    pub threshold: u8,
+}
+
+// 单个提案转给某类目的地的总额上限；未配置的 mint 不受限
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DestinationLimit {
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::option_pubkey"))]
+    pub mint: Option<Pubkey>, // None = SOL
+    pub max_to_trusted: Option<u64>, // None = 不限
+    pub max_to_others: u64,
+}
+
+// 位 i = members[i]；成员增删时按 key 重新映射，下标变化不会让分组指向别人
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ApprovalGroup {
+    pub members: u32,
+    pub min_approvals: u8,
+}
+
+// set_approval_groups 的参数：按 key 指定分组成员
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ApprovalGroupSpec {
+    pub members: Vec<Pubkey>,
+    pub min_approvals: u8,
+}
+
+pub fn hash_members<'a>(keys: impl Iterator<Item = &'a Pubkey>) -> [u8; 32] {
+    let keys: Vec<&[u8]> = keys.map(|k| k.as_ref()).collect();
+    hashv(&keys).to_bytes()
+}
+
+pub fn eth_member_key(address: &[u8; 20]) -> Pubkey {
+    let mut key = [0u8; 32];
+    key[..20].copy_from_slice(address);
+    Pubkey::new_from_array(key)
+}
+
+// 以太坊成员签名的内容：EIP-191 personal_sign 前缀 + 域标签 + 程序、多签、提案地址、
+// action.hash() 与过期时间。提案地址不复用、内容由哈希绑定，重放只会得到 AlreadyApproved
+pub fn eth_approval_message(
+    multisig: &Pubkey,
+    proposal: &Pubkey,
+    action_hash: &[u8; 32],
+    expiry: i64,
+) -> Vec<u8> {
+    let mut payload = ETH_APPROVAL_DOMAIN.to_vec();
+    for part in [crate::ID.as_ref(), multisig.as_ref(), proposal.as_ref(), action_hash] {
+        payload.extend_from_slice(part);
     }
-}
\ No newline at end of file
+    payload.extend_from_slice(&expiry.to_le_bytes());
+    let mut message = format!("\x19Ethereum Signed Message:\n{}", payload.len()).into_bytes();
+    message.extend(payload);
+    message
+}
+
+// PDA 种子前缀；账户约束、invoke_signed 与以下 find_* 共用，客户端应直接调用 find_*
+pub const MULTISIG_SEED: &[u8] = b"multisig";
+pub const PROPOSAL_SEED: &[u8] = b"proposal";
+pub const VAULT_SEED: &[u8] = b"vault";
+pub const BUFFER_SEED: &[u8] = b"buffer";
+pub const COMMENT_SEED: &[u8] = b"comment";
+pub const MEMBER_STATS_SEED: &[u8] = b"member_stats";
+pub const MEMBER_METADATA_SEED: &[u8] = b"member-meta";
+pub const SPEND_LEDGER_SEED: &[u8] = b"spend_ledger";
+pub const PROGRAM_CONFIG_SEED: &[u8] = b"program_config";
+pub const WITHDRAWAL_REQUEST_SEED: &[u8] = b"withdrawal_request";
+pub const APP_AUTHORITY_SEED: &[u8] = b"authority";
+pub const INSTRUCTION_INDEX_SEED: &[u8] = b"ix-index";
+pub const SNAPSHOT_SEED: &[u8] = b"snapshot";
+
+pub fn find_multisig_address(creator: &Pubkey, nonce: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MULTISIG_SEED, creator.as_ref(), &[nonce]], &crate::ID)
+}
+
+// index 即创建时的 multisig.proposals_count，按小端序编码
+pub fn find_proposal_address(multisig: &Pubkey, index: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PROPOSAL_SEED, multisig.as_ref(), &index.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+pub fn find_vault_address(multisig: &Pubkey, index: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_SEED, multisig.as_ref(), &[index]], &crate::ID)
+}
+
+pub fn find_buffer_address(multisig: &Pubkey, creator: &Pubkey, id: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[BUFFER_SEED, multisig.as_ref(), creator.as_ref(), &id.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+pub fn find_snapshot_address(multisig: &Pubkey, vault_index: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SNAPSHOT_SEED, multisig.as_ref(), &[vault_index]], &crate::ID)
+}
+
+// hash 即 ProposalAction::hash()
+pub fn find_instruction_index_address(multisig: &Pubkey, hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[INSTRUCTION_INDEX_SEED, multisig.as_ref(), hash], &crate::ID)
+}
+
+pub fn find_withdrawal_request_address(
+    multisig: &Pubkey,
+    requester: &Pubkey,
+    id: u32,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[WITHDRAWAL_REQUEST_SEED, multisig.as_ref(), requester.as_ref(), &id.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+// 各外部协议各自的管理员地址，登记后才可由提案签名
+pub fn find_app_authority_address(multisig: &Pubkey, app_id: &[u8; 16]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[APP_AUTHORITY_SEED, multisig.as_ref(), app_id], &crate::ID)
+}
+
+// seq 即发表时的 proposal.comments_count
+pub fn find_comment_address(proposal: &Pubkey, author: &Pubkey, seq: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[COMMENT_SEED, proposal.as_ref(), author.as_ref(), &seq.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+pub fn find_member_stats_address(multisig: &Pubkey, member: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[MEMBER_STATS_SEED, multisig.as_ref(), member.as_ref()],
+        &crate::ID,
+    )
+}
+
+pub fn find_member_metadata_address(multisig: &Pubkey, member: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[MEMBER_METADATA_SEED, multisig.as_ref(), member.as_ref()],
+        &crate::ID,
+    )
+}
+
+pub fn find_spend_ledger_address(multisig: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SPEND_LEDGER_SEED, multisig.as_ref()], &crate::ID)
+}
+
+pub fn find_program_config_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROGRAM_CONFIG_SEED], &crate::ID)
+}
+
+impl Multisig {
+    // 除 members 外的固定部分
+    pub const BASE_SPACE: usize = 8 // discriminator
+        + 32 // creator
+        + 1 // nonce
+        + 4 // members（长度前缀）
+        + 1 // threshold
+        + 4 // proposals_count
+        + 1 // bump
+        + 4 // config_seqno
+        + 4 + 33 * MAX_THRESHOLD_OVERRIDES // threshold_overrides
+        + 4 + 42 * MAX_AMOUNT_RULES // amount_rules
+        + 33 // frozen_by
+        + 8 // frozen_at
+        + 33 // last_freezer
+        + 8 // unfrozen_at
+        + 4 + 50 * MAX_PENDING_INVITES // pending_invites
+        + 33 // config_authority
+        + 1 // paused
+        + 2 // quorum
+        + 8 // recent_executed
+        + 33 // guardian
+        + 1 // execute_against_snapshot
+        + 4 // timelock_seconds
+        + 4 // urgent_timelock_seconds
+        + 2 // urgent_threshold
+        + 2 // min_execution_delay_slots
+        + 1 // max_members
+        + 4 // executed_count
+        + 4 // cancelled_count
+        + 8 // last_activity
+        + 4 + 33 * MAX_CONTROLLED_ACCOUNTS // controlled_accounts
+        + 5 // inactivity_timeout
+        + 1 // liveness_floor
+        + 9 // wound_down_at
+        + 8 // total_lamports_out
+        + 4 + 5 * MAX_APPROVAL_GROUPS // approval_groups
+        + 1 // config_requires_unanimity
+        + 4 + 32 * MAX_TRUSTED_DESTINATIONS // trusted_destinations
+        + 4 + 50 * MAX_DESTINATION_LIMITS // destination_limits
+        + 4 + 32 * MAX_ALLOWED_DESTINATIONS // allowed_destinations
+        + 4 // allowlist_delay_seconds
+        + 1 + 4 + 32 * MAX_ALLOWED_DESTINATIONS + 4 + 8 // pending_allowlist
+        + 4 + 32 * MAX_DENIED_DESTINATIONS // denied_destinations
+        + 4 + (3 + 4 + MAX_VAULT_NAME_LEN + 33 + 1) * MAX_VAULTS // vaults
+        + 1 // retain_executed
+        + 4 // retention_seconds
+        + 4 // executor_grace_seconds
+        + 5 // approval_ttl_seconds
+        + 4 + Self::EXTENSION_SPACE; // extensions
+
+    pub const MEMBER_SPACE: usize = 51;
+    // 创建时为扩展区预留的字节数；用满后可经 resize_multisig 扩容，与成员槽共用新增空间
+    pub const EXTENSION_SPACE: usize = 256;
+
+    pub const fn space(max_members: u8) -> usize {
+        Self::BASE_SPACE + Self::MEMBER_SPACE * max_members as usize
+    }
+
+    // 成员集合的摘要，用于校验客户端提供的历史成员列表
+    pub fn members_hash(&self) -> [u8; 32] {
+        hash_members(self.members.iter().map(|m| &m.key))
+    }
+
+    // 所有成员检查都走二分查找，依赖 members 按 key 严格递增
+    pub fn member_index(&self, key: &Pubkey) -> Option<usize> {
+        self.members.binary_search_by_key(key, |m| m.key).ok()
+    }
+
+    pub fn members_sorted(&self) -> bool {
+        self.members.windows(2).all(|w| w[0].key < w[1].key)
+    }
+
+    // 以 Solana 签名者身份出现的成员且未到期
+    pub fn active_member_index(&self, key: &Pubkey, now: i64) -> Result<usize> {
+        self.active_member_of_type(key, KeyType::Ed25519, now)
+    }
+
+    pub fn active_member_of_type(
+        &self,
+        key: &Pubkey,
+        key_type: KeyType,
+        now: i64,
+    ) -> Result<usize> {
+        let index = self.member_index(key).ok_or(MultisigError::NotMember)?;
+        require!(self.members[index].is_active(now), MultisigError::MembershipExpired);
+        require!(self.members[index].key_type == key_type, MultisigError::WrongKeyType);
+        Ok(index)
+    }
+
+    // 超出预留区的扩展占用的字节
+    pub fn extension_overflow(&self) -> usize {
+        self.extensions.len().saturating_sub(Self::EXTENSION_SPACE)
+    }
+
+    // 账户大小为 data_len 时扩展区可用的字节：预留区加上未被成员占用的空间
+    pub fn extension_capacity(&self, data_len: usize) -> usize {
+        let members = Self::MEMBER_SPACE * self.members.len();
+        (data_len + Self::EXTENSION_SPACE).saturating_sub(Self::BASE_SPACE + members)
+    }
+
+    pub fn get_extension(&self, tag: u16) -> Option<&[u8]> {
+        extension::get(&self.extensions, tag)
+    }
+
+    pub fn set_extension(&mut self, tag: u16, value: &[u8], data_len: usize) -> Result<()> {
+        let capacity = self.extension_capacity(data_len);
+        extension::set(&mut self.extensions, tag, value, capacity)
+    }
+
+    pub fn remove_extension(&mut self, tag: u16) -> bool {
+        extension::remove(&mut self.extensions, tag)
+    }
+
+    pub fn allow_cpi(&self) -> bool {
+        self.get_extension(extension::TAG_ALLOW_CPI) == Some(&[1])
+    }
+
+    pub fn protocol_fee_opted_out(&self) -> bool {
+        self.get_extension(extension::TAG_NO_PROTOCOL_FEE) == Some(&[1])
+    }
+
+    pub fn migrating_to(&self) -> Option<Pubkey> {
+        self.get_extension(extension::TAG_MIGRATION)
+            .and_then(|v| Pubkey::try_from(v).ok())
+    }
+
+    // (通知程序, 通知失败时是否中止执行)
+    pub fn notify_program(&self) -> Option<(Pubkey, bool)> {
+        let value = self.get_extension(extension::TAG_NOTIFY)?;
+        let program = Pubkey::try_from(value.get(..32)?).ok()?;
+        Some((program, value.get(32..) == Some(&[1])))
+    }
+
+    // (策略程序, 执行时随其传入的账户数)
+    pub fn policy_program(&self) -> Option<(Pubkey, u8)> {
+        let value = self.get_extension(extension::TAG_POLICY)?;
+        let program = Pubkey::try_from(value.get(..32)?).ok()?;
+        Some((program, *value.get(32)?))
+    }
+
+    // 提案的 execution_attempts 超过该值后任何人可以关闭它；None = 不启用
+    pub fn max_execution_attempts(&self) -> Option<u8> {
+        match self.get_extension(extension::TAG_MAX_EXECUTION_ATTEMPTS)? {
+            [max] => Some(*max),
+            _ => None,
+        }
+    }
+
+    pub fn optimistic_config(&self) -> Option<OptimisticConfig> {
+        let value = self.get_extension(extension::TAG_OPTIMISTIC)?;
+        OptimisticConfig::try_from_slice(value).ok()
+    }
+
+    // 提案当前是否走乐观通道：创建时选择，且配置仍允许其指令形状
+    pub fn optimistic_lane(&self, proposal: &Proposal) -> Option<OptimisticConfig> {
+        if !proposal.optimistic {
+            return None;
+        }
+        self.optimistic_config().filter(|c| c.allows(&proposal.action))
+    }
+
+    pub fn is_vetoed(&self, proposal: &Proposal, now: i64) -> bool {
+        self.optimistic_lane(proposal).is_some_and(|c| {
+            self.tally(proposal, proposal.rejections, now) >= c.veto_threshold as u32
+        })
+    }
+
+    // 迁移开始后旧多签只允许 migrate_sweep 与 finish_migration
+    pub fn require_not_migrating(&self) -> Result<()> {
+        require!(self.migrating_to().is_none(), MultisigError::MigrationInProgress);
+        Ok(())
+    }
+
+    // 已登记的应用 authority：(app_id, bump)
+    pub fn app_authorities(&self) -> Vec<([u8; 16], u8)> {
+        self.get_extension(extension::TAG_APP_AUTHORITIES)
+            .unwrap_or_default()
+            .chunks_exact(17)
+            .map(|c| (c[..16].try_into().unwrap(), c[16]))
+            .collect()
+    }
+
+    fn set_app_authorities(&mut self, entries: &[([u8; 16], u8)], data_len: usize) -> Result<()> {
+        if entries.is_empty() {
+            self.remove_extension(extension::TAG_APP_AUTHORITIES);
+            return Ok(());
+        }
+        let value: Vec<u8> = entries
+            .iter()
+            .flat_map(|(app_id, bump)| app_id.iter().chain(std::iter::once(bump)))
+            .copied()
+            .collect();
+        self.set_extension(extension::TAG_APP_AUTHORITIES, &value, data_len)
+    }
+
+    // 防止成员在别的协议的交易里被间接调用 propose / approve / cancel 而不自知
+    pub fn require_top_level(&self) -> Result<()> {
+        require!(
+            self.allow_cpi() || get_stack_height() == TRANSACTION_LEVEL_STACK_HEIGHT,
+            MultisigError::CpiNotAllowed
+        );
+        Ok(())
+    }
+
+    // 插入后保持有序，下标变化 -> 旧提案位图失效；扩展区占用的空间不能再分给成员
+    pub fn insert_member(&mut self, member: Member, data_len: usize) -> Result<()> {
+        let pos = match self.members.binary_search_by_key(&member.key, |m| m.key) {
+            Ok(_) => return err!(MultisigError::AlreadyMember),
+            Err(pos) => pos,
+        };
+        require!(
+            self.members.len() < self.max_members as usize
+                && self.extensions.len() + Self::MEMBER_SPACE <= self.extension_capacity(data_len),
+            MultisigError::TooManyMembers
+        );
+        let old_members: Vec<Pubkey> = self.members.iter().map(|m| m.key).collect();
+        self.members.insert(pos, member);
+        debug_assert!(self.members_sorted());
+        self.rebase_groups(&old_members);
+        self.config_seqno += 1;
+        Ok(())
+    }
+
+    pub fn active_members(&self, now: i64) -> usize {
+        self.members.iter().filter(|m| m.is_active(now)).count()
+    }
+
+    pub fn live_members(&self, now: i64) -> usize {
+        self.members.iter().filter(|m| m.is_live(now)).count()
+    }
+
+    // 启用在线检测时，阈值最多降到在线成员数，但不低于 liveness_floor；从不抬高阈值
+    pub fn liveness_cap(&self, threshold: u8, now: i64) -> u8 {
+        if self.inactivity_timeout.is_none() {
+            return threshold;
+        }
+        let live = self.live_members(now) as u8;
+        threshold.min(live.max(self.liveness_floor))
+    }
+
+    // 成员的任何签名都算一次心跳，并自动解除 inactive 标记
+    pub fn touch_member(&mut self, index: usize, now: i64) {
+        let member = &mut self.members[index];
+        member.last_seen = now;
+        if member.inactive {
+            member.inactive = false;
+            msg!("Member {} reactivated", member.key);
+        }
+    }
+
+    pub fn mark_inactive(&mut self, member: &Pubkey, now: i64) -> Result<()> {
+        let timeout = self.inactivity_timeout.ok_or(MultisigError::LivenessDisabled)?;
+        let index = self.member_index(member).ok_or(MultisigError::NotMember)?;
+        let m = &self.members[index];
+        require!(
+            !m.inactive && now >= m.last_seen.saturating_add(timeout as i64),
+            MultisigError::MemberNotIdle
+        );
+        self.members[index].inactive = true;
+        // 在线成员少于下限时拒绝，阈值因此永远不会低于 liveness_floor 且仍可达
+        require!(
+            self.live_members(now) >= self.liveness_floor as usize,
+            MultisigError::LivenessFloorReached
+        );
+        Ok(())
+    }
+
+    // 只统计当前未到期且未失联成员对应的位
+    pub fn count_votes(&self, votes: u32, now: i64) -> u32 {
+        let mask = self
+            .members
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.is_live(now))
+            .fold(0u32, |mask, (i, _)| mask | (1 << i));
+        (votes & mask).count_ones()
+    }
+
+    // 分组中未到期的成员数
+    pub fn group_size(&self, group: &ApprovalGroup, now: i64) -> usize {
+        self.members
+            .iter()
+            .enumerate()
+            .filter(|(i, m)| group.members & (1 << i) != 0 && m.is_active(now))
+            .count()
+    }
+
+    // 第一个未达到最低批准数的分组下标
+    pub fn unmet_group(&self, approvals: u32, now: i64) -> Option<usize> {
+        self.approval_groups
+            .iter()
+            .position(|g| self.count_votes(approvals & g.members, now) < g.min_approvals as u32)
+    }
+
+    // 成员列表变化后按 key 重建分组位图，被移除的成员随之退出分组
+    fn rebase_groups(&mut self, old_members: &[Pubkey]) {
+        for i in 0..self.approval_groups.len() {
+            let old = self.approval_groups[i].members;
+            let rebased = old_members
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| old & (1 << j) != 0)
+                .filter_map(|(_, key)| self.member_index(key))
+                .fold(0u32, |mask, index| mask | (1 << index));
+            self.approval_groups[i].members = rebased;
+        }
+    }
+
+    // 成员变更后旧位图无法确认投票人仍是有效成员，须先 prune_approvals 才计票
+    pub fn tally(&self, proposal: &Proposal, votes: u32, now: i64) -> u32 {
+        if proposal.config_seqno == self.config_seqno {
+            self.count_votes(votes, now)
+        } else {
+            0
+        }
+    }
+
+    // 紧急提案以更高阈值换取更短的 timelock，上限为未到期成员数
+    pub fn urgent_threshold(&self, now: i64) -> u8 {
+        let active = self.active_members(now) as u8;
+        self.urgent_threshold.unwrap_or(active).max(self.threshold).min(active)
+    }
+
+    // 执行所需的批准数及抬高阈值的 override；judge 与 record_vote 共用，timelock 自达到该值起算。
+    // 投票时不读取缓冲区，缓冲提案按不含数据的指令计（金额规则不生效，只会偏严）
+    pub fn proposal_threshold(
+        &self,
+        multisig: &Pubkey,
+        proposal: &Proposal,
+        account_infos: &[AccountInfo],
+        now: i64,
+    ) -> (u8, Option<Pubkey>) {
+        // 乐观通道不要求批准，改为检查否决
+        if self.optimistic_lane(proposal).is_some() {
+            return (0, None);
+        }
+        let unbuffered;
+        let action = match &proposal.action {
+            ProposalAction::Buffered(b) => {
+                unbuffered = ProposalAction::Raw(InstructionData {
+                    program_id: b.program_id,
+                    accounts: b.accounts.clone(),
+                    data: vec![],
+                    signers: b.signers,
+                });
+                &unbuffered
+            }
+            action => action,
+        };
+        // 快照模式下基础阈值取创建时的值，override 与金额规则仍按当前配置
+        let base = if self.execute_against_snapshot {
+            proposal.threshold_at_creation
+        } else {
+            self.threshold
+        };
+        // 在线检测只降低基础阈值，金额规则、金库 override 及以下抬高的要求不受影响
+        let base = self.liveness_cap(base, now);
+        let (threshold, tier) = self.effective_threshold(base, action, multisig, account_infos);
+        // 紧急提案即使满足基础阈值也必须达到 urgent_threshold
+        let threshold = if proposal.urgent {
+            threshold.max(self.urgent_threshold(now))
+        } else {
+            threshold
+        };
+        let threshold = if proposal.allow_dangerous {
+            threshold.max(self.active_members(now) as u8)
+        } else {
+            threshold
+        };
+        let threshold = if self.config_requires_unanimity
+            && !proposal.no_sign
+            && proposal.action.is_config()
+        {
+            threshold.max(self.active_members(now) as u8)
+        } else {
+            threshold
+        };
+        (threshold, tier)
+    }
+
+    // 新提案的默认 timelock；创建时写入 proposal.delay_seconds
+    pub fn default_delay(&self, urgent: bool) -> u32 {
+        if urgent {
+            self.urgent_timelock_seconds
+        } else {
+            self.timelock_seconds
+        }
+    }
+
+    // 新提案占据窗口最低位，更早的提案依次左移
+    pub fn record_proposal(&mut self) {
+        self.recent_executed <<= 1;
+        self.proposals_count += 1;
+    }
+
+    pub fn mark_executed(&mut self, index: u32) {
+        self.executed_count += 1;
+        let offset = self.proposals_count - 1 - index;
+        if offset < EXECUTED_WINDOW {
+            self.recent_executed |= 1 << offset;
+        }
+    }
+
+    // None 表示提案不存在或已滑出窗口；index 来自用户输入，不能直接加一
+    pub fn is_executed(&self, index: u32) -> Option<bool> {
+        let offset = self.proposals_count.checked_sub(index.checked_add(1)?)?;
+        (offset < EXECUTED_WINDOW).then(|| self.recent_executed & (1 << offset) != 0)
+    }
+
+    // 所有配置中要求的最高阈值
+    pub fn required_threshold(&self) -> u8 {
+        self.threshold_overrides
+            .iter()
+            .map(|o| o.min_threshold)
+            .chain(self.amount_rules.iter().map(|r| r.threshold))
+            .chain(self.vaults.iter().filter_map(|v| v.threshold_override))
+            .fold(self.threshold, u8::max)
+    }
+
+    pub fn vault_mut(&mut self, index: u8) -> Result<&mut VaultConfig> {
+        self.vaults
+            .iter_mut()
+            .find(|v| v.index == index)
+            .ok_or(error!(MultisigError::VaultNotRegistered))
+    }
+
+    pub fn is_vault_paused(&self, index: u8) -> bool {
+        self.vaults.iter().any(|v| v.index == index && v.paused)
+    }
+
+    // 动作执行时会签名的已登记金库，随提案事件一并发出
+    pub fn signing_vaults(&self, action: &ProposalAction) -> Vec<VaultConfig> {
+        let indexes = action.signing_vaults();
+        self.vaults.iter().filter(|v| indexes.contains(&v.index)).cloned().collect()
+    }
+
+    // 所请求金库的 threshold_override 中的最大值
+    pub fn vault_threshold(&self, indexes: impl IntoIterator<Item = u8>) -> u8 {
+        indexes
+            .into_iter()
+            .filter_map(|i| self.vaults.iter().find(|v| v.index == i)?.threshold_override)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn config_status(&self, now: i64) -> ConfigStatus {
+        let required = self.required_threshold() as usize;
+        let mut active = self.active_members(now);
+        let mut expiries: Vec<i64> = self
+            .members
+            .iter()
+            .filter(|m| m.is_active(now))
+            .filter_map(|m| m.expires_at)
+            .collect();
+        expiries.sort_unstable();
+
+        let mut unreachable_at = (active < required).then_some(now);
+        for t in expiries {
+            if unreachable_at.is_some() {
+                break;
+            }
+            active -= 1;
+            if active < required {
+                unreachable_at = Some(t);
+            }
+        }
+        ConfigStatus {
+            active_members: self.active_members(now) as u8,
+            required_threshold: required as u8,
+            unreachable_at,
+        }
+    }
+
+    pub fn invite(
+        &mut self,
+        invitee: Pubkey,
+        membership_expires_at: Option<i64>,
+        invite_expires_at: Option<i64>,
+        now: i64,
+    ) -> Result<()> {
+        require!(self.member_index(&invitee).is_none(), MultisigError::AlreadyMember);
+        require!(
+            self.members.len() < self.max_members as usize,
+            MultisigError::TooManyMembers
+        );
+
+        // 顺便清理过期邀请，释放名额
+        self.pending_invites.retain(|i| i.is_active(now));
+        require!(
+            self.pending_invites.iter().all(|i| i.key != invitee),
+            MultisigError::AlreadyInvited
+        );
+        require!(
+            self.pending_invites.len() < MAX_PENDING_INVITES,
+            MultisigError::TooManyInvites
+        );
+
+        self.pending_invites.push(Invitation {
+            key: invitee,
+            membership_expires_at,
+            invite_expires_at,
+        });
+        Ok(())
+    }
+
+    pub fn remove_member(&mut self, member: &Pubkey, now: i64) -> Result<()> {
+        let pos = self.member_index(member).ok_or(MultisigError::NotMember)?;
+        let old_members: Vec<Pubkey> = self.members.iter().map(|m| m.key).collect();
+        self.members.remove(pos);
+        debug_assert!(self.members_sorted());
+        self.rebase_groups(&old_members);
+        // 移除后阈值及各规则仍须可达
+        self.validate_thresholds(now)?;
+        self.config_seqno += 1;
+        Ok(())
+    }
+
+    pub fn record_custody(&mut self, account: Pubkey, kind: CustodyKind) -> Result<()> {
+        if self.controlled_accounts.iter().any(|c| c.account == account) {
+            return Ok(());
+        }
+        require!(
+            self.controlled_accounts.len() < MAX_CONTROLLED_ACCOUNTS,
+            MultisigError::TooManyControlledAccounts
+        );
+        self.controlled_accounts.push(ControlledAccount { account, kind });
+        Ok(())
+    }
+
+    pub fn release_custody(&mut self, account: &Pubkey) {
+        self.controlled_accounts.retain(|c| c.account != *account);
+    }
+
+    pub fn set_threshold(&mut self, threshold: u8, now: i64) -> Result<()> {
+        self.threshold = threshold;
+        self.validate_thresholds(now)
+    }
+
+    // 原生执行类型化的配置提案
+    pub fn apply_action(&mut self, action: &ProposalAction, now: i64) -> Result<()> {
+        match action {
+            ProposalAction::Raw(_) | ProposalAction::Batch(_) => Ok(()),
+            ProposalAction::AddMember(key) => self.invite(*key, None, None, now),
+            ProposalAction::RemoveMember(key) => self.remove_member(key, now),
+            ProposalAction::ChangeThreshold(threshold) => self.set_threshold(*threshold, now),
+            ProposalAction::SetPaused(paused) => {
+                self.paused = *paused;
+                Ok(())
+            }
+            // 资金转移在 execute_proposal 中完成，不改动配置
+            ProposalAction::SweepVault { .. } => Ok(()),
+            ProposalAction::Buffered(_) => Ok(()),
+            // 执行即激活，分期由 execute_vesting_tranche 支付
+            ProposalAction::Vesting(_) => Ok(()),
+            ProposalAction::Recurring(_) => Ok(()),
+            ProposalAction::CreateMint { .. } => Ok(()),
+            ProposalAction::SetTokenAuthority { .. } => Ok(()),
+            ProposalAction::Burn { .. } => Ok(()),
+            ProposalAction::FreezeTokenAccount(_) | ProposalAction::ThawTokenAccount(_) => Ok(()),
+            ProposalAction::TokenApprove { .. } | ProposalAction::TokenRevoke(_) => Ok(()),
+            ProposalAction::MintTo { .. } => Ok(()),
+            ProposalAction::VoteAuthorize { .. } | ProposalAction::VoteWithdraw { .. } => Ok(()),
+            ProposalAction::StakeSplit { .. }
+            | ProposalAction::StakeDeactivate(_)
+            | ProposalAction::StakeWithdraw { .. } => Ok(()),
+            // 权限移交在 execute_proposal 中完成，这里只维护登记表
+            ProposalAction::TakeCustody { account, kind } => self.record_custody(*account, *kind),
+            ProposalAction::ReleaseCustody { account, .. } => {
+                self.release_custody(account);
+                Ok(())
+            }
+        }
+    }
+
+    // 提案时即按当前配置试运行一次，尽早拒绝无效的配置变更
+    pub fn validate_action(&self, action: &ProposalAction, now: i64) -> Result<()> {
+        self.clone().apply_action(action, now)
+    }
+
+    // 阈值、override、金额规则在未到期成员数下都必须可达
+    pub fn validate_thresholds(&self, now: i64) -> Result<()> {
+        let n = self.active_members(now);
+        require!(
+            self.threshold > 0 && self.threshold as usize <= n,
+            MultisigError::InvalidThreshold
+        );
+        require!(
+            self.threshold_overrides
+                .iter()
+                .all(|o| o.min_threshold > 0 && o.min_threshold as usize <= n),
+            MultisigError::InvalidThreshold
+        );
+        require!(
+            self.amount_rules
+                .iter()
+                .all(|r| r.threshold > 0 && r.threshold as usize <= n),
+            MultisigError::InvalidThreshold
+        );
+        require!(
+            self.vaults
+                .iter()
+                .filter_map(|v| v.threshold_override)
+                .all(|t| t > 0 && t as usize <= n),
+            MultisigError::InvalidThreshold
+        );
+        require!(
+            self.quorum.is_none_or(|q| q > 0 && q as usize <= n),
+            MultisigError::InvalidQuorum
+        );
+        require!(
+            self.inactivity_timeout.is_none_or(|t| {
+                t > 0 && self.liveness_floor > 0 && self.liveness_floor as usize <= n
+            }),
+            MultisigError::InvalidLivenessFloor
+        );
+        require!(
+            self.approval_groups.iter().all(|g| {
+                g.min_approvals > 0 && g.min_approvals as usize <= self.group_size(g, now)
+            }),
+            MultisigError::InvalidApprovalGroup
+        );
+        Ok(())
+    }
+
+    // 按 (mint, 是否可信) 汇总提案的转出并对照 destination_limits。
+    // 配置了任何上限时，去向无法确定的转出按最严格处理：须全体未到期成员批准（unanimous）
+    pub fn check_destination_limits(
+        &self,
+        action: &ProposalAction,
+        multisig: &Pubkey,
+        account_infos: &[AccountInfo],
+        unanimous: bool,
+    ) -> Result<()> {
+        if self.destination_limits.is_empty() {
+            return Ok(());
+        }
+        let mut totals: Vec<(Option<Pubkey>, bool, u64)> = vec![];
+        for outflow in action.outflows(multisig, account_infos) {
+            let Some((destination, mint, amount)) = outflow else {
+                if unanimous {
+                    continue;
+                }
+                msg!("Outflow destination cannot be classified; all members must approve");
+                return err!(MultisigError::DestinationLimitExceeded);
+            };
+            let trusted = self.trusted_destinations.contains(&destination);
+            match totals.iter_mut().find(|t| t.0 == mint && t.1 == trusted) {
+                Some(total) => total.2 = total.2.saturating_add(amount),
+                None => totals.push((mint, trusted, amount)),
+            }
+        }
+        for (mint, trusted, amount) in totals {
+            let Some(limit) = self.destination_limits.iter().find(|l| l.mint == mint) else {
+                continue;
+            };
+            let max = if trusted {
+                limit.max_to_trusted
+            } else {
+                Some(limit.max_to_others)
+            };
+            if let Some(max) = max.filter(|max| amount > *max) {
+                msg!(
+                    "Sends {} of {:?} to {} destinations, limit {}",
+                    amount,
+                    mint,
+                    if trusted { "trusted" } else { "other" },
+                    max
+                );
+                return err!(MultisigError::DestinationLimitExceeded);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_allowed_destination(&self, destination: &Pubkey) -> bool {
+        self.allowed_destinations.is_empty() || self.allowed_destinations.contains(destination)
+    }
+
+    // allowed_destinations 非空时，提案的每笔转出都须去往列表中的地址；
+    // 会动用多签资产但去向无法识别的指令一律拒绝。account_infos 为 None 表示提案时，
+    // 此时 SPL 转账还读不到目的 token 账户的 owner，留到执行时核对
+    pub fn check_allowed_destinations(
+        &self,
+        action: &ProposalAction,
+        multisig: &Pubkey,
+        account_infos: Option<&[AccountInfo]>,
+    ) -> Result<()> {
+        if self.allowed_destinations.is_empty() {
+            return Ok(());
+        }
+        let destinations = match action {
+            // 收款方固定，每次付款时再核对一次
+            ProposalAction::Vesting(schedule) => vec![Some(schedule.recipient)],
+            ProposalAction::Recurring(payment) => vec![Some(payment.destination)],
+            ProposalAction::SweepVault { destination, .. } => vec![Some(*destination)],
+            // 缓冲区在执行时还原为 Raw 后再检查
+            ProposalAction::Buffered(_) => vec![],
+            ProposalAction::Raw(_) | ProposalAction::Batch(_) if account_infos.is_none() => action
+                .instructions()
+                .iter()
+                .filter(|ix| !is_token_transfer(ix))
+                .flat_map(|ix| ix.outflows(multisig, &[]))
+                .map(|outflow| outflow.map(|(destination, ..)| destination))
+                .collect(),
+            _ => action
+                .outflows(multisig, account_infos.unwrap_or(&[]))
+                .into_iter()
+                .map(|outflow| outflow.map(|(destination, ..)| destination))
+                .collect(),
+        };
+        for destination in destinations {
+            let Some(destination) = destination else {
+                msg!("Unrecognized outflow while the destination allowlist is set");
+                return err!(MultisigError::DestinationNotAllowed);
+            };
+            if !self.allowed_destinations.contains(&destination) {
+                msg!("{} is not an allowed destination", destination);
+                return err!(MultisigError::DestinationNotAllowed);
+            }
+        }
+        Ok(())
+    }
+
+    // 只拦截识别出的转出；与白名单不同，无法识别的指令照常放行
+    pub fn check_denied_destinations(
+        &self,
+        action: &ProposalAction,
+        multisig: &Pubkey,
+        account_infos: &[AccountInfo],
+    ) -> Result<()> {
+        if self.denied_destinations.is_empty() {
+            return Ok(());
+        }
+        for (destination, ..) in action.outflows(multisig, account_infos).into_iter().flatten() {
+            if self.denied_destinations.contains(&destination) {
+                msg!("{} is a denied destination", destination);
+                return err!(MultisigError::DestinationDenied);
+            }
+        }
+        Ok(())
+    }
+
+    // 拒绝会改变 multisig/金库 PDA owner、大小的 System 指令，
+    // 以及使其余额低于免租下限的转账（WouldBreakRentExemption）。
+    // 只有本指令选中签名的 PDA 会受影响。balance 返回账户的 (lamports, data_len)，未知时跳过余额检查。
+    pub fn check_protected(
+        &self,
+        action: &ProposalAction,
+        multisig: &Pubkey,
+        balance: impl Fn(&Pubkey) -> Option<(u64, usize)>,
+    ) -> Result<()> {
+        let rent = Rent::get()?;
+        // 多指令提案按同一账户的累计转出计，拆成几笔不能绕过免租下限
+        let mut debited: Vec<(Pubkey, u64)> = vec![];
+        for ix in action.instructions() {
+            let authorities = ix.signer_pdas(multisig);
+            if let Some(target) = system_reshape_target(ix) {
+                if authorities.contains(&target) {
+                    msg!("System instruction reshapes protected account {}", target);
+                    return err!(MultisigError::ForbiddenConfigMutation);
+                }
+            }
+            if let Some((from, amount)) = system_debit(ix) {
+                if !authorities.contains(&from) {
+                    continue;
+                }
+                let amount = match debited.iter_mut().find(|(key, _)| *key == from) {
+                    Some((_, total)) => {
+                        *total = total.saturating_add(amount);
+                        *total
+                    }
+                    None => {
+                        debited.push((from, amount));
+                        amount
+                    }
+                };
+                if let Some((lamports, data_len)) = balance(&from) {
+                    let floor = rent.minimum_balance(data_len);
+                    if lamports.saturating_sub(amount) < floor {
+                        msg!(
+                            "Transfer of {} from {} breaks rent exemption; max transferable: {} (use SweepVault to empty it)",
+                            amount,
+                            from,
+                            lamports.saturating_sub(floor)
+                        );
+                        return err!(MultisigError::WouldBreakRentExemption);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // 单条指令的基础阈值：由本指令签名 PDA 发起、识别出的小额转账可使用金额规则，否则为 threshold
+    fn base_threshold(
+        &self,
+        threshold: u8,
+        ix: &InstructionData,
+        authorities: &[Pubkey],
+        account_infos: &[AccountInfo],
+    ) -> u8 {
+        let Some(transfer) = decode_transfer(ix, account_infos) else {
+            return threshold;
+        };
+        if !authorities.contains(&transfer.authority) {
+            return threshold;
+        }
+        self.amount_rules
+            .iter()
+            .filter(|r| r.mint == transfer.mint && transfer.amount <= r.max_amount)
+            .map(|r| r.threshold)
+            .min()
+            .unwrap_or(threshold)
+    }
+
+    // 返回生效阈值（多指令取最严格者），以及抬高阈值的 override（若有）
+    // 类型化配置提案视为对本程序的调用；threshold 为基础阈值
+    pub fn effective_threshold(
+        &self,
+        threshold: u8,
+        action: &ProposalAction,
+        multisig: &Pubkey,
+        account_infos: &[AccountInfo],
+    ) -> (u8, Option<Pubkey>) {
+        let targets: Vec<(u8, Pubkey)> = match action {
+            ProposalAction::Raw(_) => action
+                .instructions()
+                .iter()
+                .map(|ix| {
+                    let authorities = ix.signer_pdas(multisig);
+                    let base = self.base_threshold(threshold, ix, &authorities, account_infos);
+                    (base.max(self.vault_threshold(ix.signer_vaults())), ix.program_id)
+                })
+                .collect(),
+            // 金额规则不适用于多指令提案：拆成几笔小额转账即可降低阈值
+            ProposalAction::Batch(ixs) => ixs
+                .iter()
+                .map(|ix| (threshold.max(self.vault_threshold(ix.signer_vaults())), ix.program_id))
+                .collect(),
+            // 增发按所传 Token Program 的档位计
+            // 未传入 Token Program 时（如投票时）按两种程序中较严的档位计
+            ProposalAction::MintTo { .. } => {
+                let base = threshold.max(self.vault_threshold([0]));
+                match account_infos.iter().map(|i| *i.key).find(is_token_program) {
+                    Some(token_program) => vec![(base, token_program)],
+                    None => vec![(base, TOKEN_PROGRAM_ID), (base, TOKEN_2022_PROGRAM_ID)],
+                }
+            }
+            _ => vec![(
+                threshold.max(self.vault_threshold(action.signing_vaults())),
+                crate::ID,
+            )],
+        };
+
+        let mut strictest = 0;
+        let mut tier = None;
+        for (base, program_id) in targets {
+            let mut ix_threshold = base;
+            let mut ix_tier = None;
+            for o in self.threshold_overrides.iter().filter(|o| o.program_id == program_id) {
+                if o.min_threshold > ix_threshold {
+                    ix_threshold = o.min_threshold;
+                    ix_tier = Some(o.program_id);
+                }
+            }
+            if ix_threshold > strictest {
+                strictest = ix_threshold;
+                tier = ix_tier;
+            }
+        }
+        (strictest, tier)
+    }
+}
+
+#[account]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Proposal {
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+    pub multisig: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+    pub approvals: u32, // 按 multisig.members 下标的位图
+    pub rejections: u32,
+    pub abstentions: u32,
+    pub executed: bool,
+    pub cancelled: bool,
+    pub bump: u8,
+    pub version: u8,
+    pub config_seqno: u32,
+    pub members_hash: [u8; 32], // approvals 位图所对应的成员列表
+    pub comments_count: u32, // 用于生成唯一 Comment PDA
+    pub index: u32,
+    pub depends_on: Option<u32>, // 该索引的提案执行后才能执行
+    pub threshold_at_creation: u8,
+    pub not_before: Option<i64>, // 绝对执行窗口
+    pub not_after: Option<i64>,
+    pub urgent: bool,
+    pub ready_at: Option<i64>, // 批准数达到阈值的时间，timelock 自此起算
+    pub allow_dangerous: bool,
+    pub no_sign: bool, // 创建后不可修改
+    pub ready_slot: Option<u64>,
+    pub tranches_paid: u32, // Vesting 提案已支付分期的位图
+    pub payments_made: u32, // Recurring 提案已支付次数
+    pub last_paid_at: Option<i64>,
+    pub execution_memo: Option<String>,
+    // 创建时确定的 timelock（默认值或 delay_override），之后修改 timelock 不影响已有提案
+    pub delay_seconds: u32,
+    pub rejection_reasons: Vec<RejectionReason>, // 每位投反对票的成员一条，改投时清除
+    pub voting_ends_at: Option<i64>,
+    // 出现第一个赞成票后不可再修改
+    pub metadata_uri: Option<String>,
+    pub metadata_hash: [u8; 32],
+    pub executed_at: Option<i64>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::option_pubkey"))]
+    pub executed_by: Option<Pubkey>, // 执行时未传入 executor 则为 None
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::option_pubkey"))]
+    pub designated_executor: Option<Pubkey>,
+    pub approved_at: Vec<i64>, // 与 approvals 同下标的批准时间，用于 approval_ttl_seconds
+    pub protocol_fee: Option<ProtocolFee>, // 创建时按全局配置报价，执行成功后收取
+    pub created_at: i64,
+    pub execution_attempts: u8, // record_failed_execution 记录的必然失败的执行次数
+    pub last_failed_slot: u64, // 最近一次记录失败的 slot，同一 slot 内只计一次
+    pub optimistic: bool, // 创建时选择了乐观通道；配置移除或形状不再允许时按普通提案处理
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProtocolFee {
+    pub lamports: u64, // 上限；收取时不会让 payer 低于免租金额
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+    pub payer: Pubkey, // 转出资金的金库
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+    pub recipient: Pubkey,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ProposalStatus {
+    Pending, // 批准、分组或 quorum 未满足
+    Blocked, // 票数已够，但冻结、暂停、目的地限制、依赖等使其暂不能执行
+    Timelocked, // 等待 not_before、timelock 或最小 slot 间隔
+    Ready,
+    Expired, // 已过 not_after
+    Executed,
+    Cancelled,
+}
+
+// record_failed_execution 识别出的、与调用方无关的失败原因
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExecutionFailure {
+    MissingAccount, // token 指令引用的账户已被关闭
+    InsufficientBalance, // 转出账户的 lamports 或 token 余额不足
+    MetaMismatch, // 要求多签或金库签名，但 signers 中未选中
+}
+
+// get_proposal_status 的返回值
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProposalStatusView {
+    pub status: ProposalStatus,
+    pub approvals: u8, // 计入阈值的有效批准
+    pub threshold: u8, // 执行时实际要求的阈值
+    pub ready_at: Option<i64>, // timelock 届满（且不早于 not_before）的时间；未达到阈值时为 None
+    pub expires_at: Option<i64>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RejectionReason {
+    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::pubkey"))]
+    pub member: Pubkey,
+    pub code: u8, // 由客户端约定，0 = 未分类
+    pub reason: String,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Vote {
+    Approve,
+    Reject,
+    Abstain,
+}
+
+impl Proposal {
+    // 每位成员只保留一票：改投时清除原来的位
+    pub fn cast_vote(&mut self, index: usize, vote: Vote) -> Result<()> {
+        let bit = 1u32 << index;
+        let target = match vote {
+            Vote::Approve => self.approvals,
+            Vote::Reject => self.rejections,
+            Vote::Abstain => self.abstentions,
+        };
+        if target & bit != 0 {
+            return match vote {
+                Vote::Approve => err!(MultisigError::AlreadyApproved),
+                _ => err!(MultisigError::AlreadyVoted),
+            };
+        }
+
+        self.approvals &= !bit;
+        self.rejections &= !bit;
+        self.abstentions &= !bit;
+        match vote {
+            Vote::Approve => self.approvals |= bit,
+            Vote::Reject => self.rejections |= bit,
+            Vote::Abstain => self.abstentions |= bit,
+        }
+        Ok(())
+    }
+
+    pub fn is_past_window(&self, now: i64) -> bool {
+        self.not_after.is_some_and(|t| now > t)
+    }
+
+    // 指定执行人独占期：自达到阈值起 executor_grace_seconds 内只有该签名者可执行
+    pub fn check_executor(
+        &self,
+        executor: Option<Pubkey>,
+        multisig: &Multisig,
+        now: i64,
+    ) -> Result<()> {
+        let Some(designated) = self.designated_executor else {
+            return Ok(());
+        };
+        let grace = multisig.executor_grace_seconds as i64;
+        let opened = self.ready_at.is_some_and(|t| now >= t.saturating_add(grace));
+        require!(
+            executor == Some(designated) || opened,
+            MultisigError::NotDesignatedExecutor
+        );
+        Ok(())
+    }
+
+    pub fn is_voting_closed(&self, now: i64) -> bool {
+        self.voting_ends_at.is_some_and(|t| now > t)
+    }
+
+    // 投票截止时仍未达到阈值：之后不可能再变为可执行
+    // 乐观提案只在延迟期内接受投票
+    pub fn veto_window_ends_at(&self) -> i64 {
+        self.created_at.saturating_add(self.delay_seconds as i64)
+    }
+
+    pub fn is_abandoned(&self, now: i64) -> bool {
+        self.is_voting_closed(now) && self.ready_at.is_none()
+    }
+
+    pub fn voted(&self) -> u32 {
+        self.approvals | self.rejections | self.abstentions
+    }
+
+    pub fn is_vesting(&self) -> bool {
+        matches!(self.action, ProposalAction::Vesting(_))
+    }
+
+    pub fn is_recurring(&self) -> bool {
+        matches!(self.action, ProposalAction::Recurring(_))
+    }
+
+    // 执行（激活）后账户保留，直到支付完毕或被治理取消
+    pub fn stays_open(&self) -> bool {
+        self.is_vesting() || self.is_recurring()
+    }
+}
+
+impl Proposal {
+    pub const BASE_SPACE: usize = 8 // discriminator
+        + 32 // multisig
+        + 32 // proposer
+        + 1 + 1000 // action
+        + 4 // approvals
+        + 4 // rejections
+        + 4 // abstentions
+        + 1 // executed
+        + 1 // cancelled
+        + 1 // bump
+        + 1 // version
+        + 4 // config_seqno
+        + 32 // members_hash
+        + 4 // comments_count
+        + 4 // index
+        + 5 // depends_on
+        + 1 // threshold_at_creation
+        + 9 // not_before
+        + 9 // not_after
+        + 1 // urgent
+        + 9 // ready_at
+        + 1 // allow_dangerous
+        + 1 // no_sign
+        + 9 // ready_slot
+        + 4 // tranches_paid
+        + 4 // payments_made
+        + 9 // last_paid_at
+        + 1 + 4 + MAX_EXECUTION_MEMO_LEN // execution_memo
+        + 4 // delay_seconds
+        + 4 // rejection_reasons
+        + 9 // voting_ends_at
+        + 1 + 4 + MAX_METADATA_URI_LEN // metadata_uri
+        + 32 // metadata_hash
+        + 9 // executed_at
+        + 33 // executed_by
+        + 33 // designated_executor
+        + 4 // approved_at
+        + 1 + 8 + 32 + 32 // protocol_fee
+        + 8 // created_at
+        + 1 // execution_attempts
+        + 8 // last_failed_slot
+        + 1; // optimistic
+
+    pub const REJECTION_SPACE: usize = 32 + 1 + 4 + MAX_REJECTION_REASON_LEN;
+
+    // 按成员容量为每位成员预留一条拒绝理由和一个批准时间
+    pub const fn space(max_members: u8) -> usize {
+        Self::BASE_SPACE + (Self::REJECTION_SPACE + 8) * max_members as usize
+    }
+
+    // 未超过 ttl 的批准；未设置 ttl 时即 approvals
+    pub fn fresh_approvals(&self, ttl: Option<u32>, now: i64) -> u32 {
+        let Some(ttl) = ttl else {
+            return self.approvals;
+        };
+        let fresh = self
+            .approved_at
+            .iter()
+            .enumerate()
+            .filter(|(_, at)| now < at.saturating_add(ttl as i64))
+            .fold(0u32, |mask, (i, _)| mask | 1 << i);
+        self.approvals & fresh
+    }
+
+    pub fn set_approved_at(&mut self, index: usize, now: i64) {
+        if self.approved_at.len() <= index {
+            self.approved_at.resize(index + 1, 0);
+        }
+        self.approved_at[index] = now;
+    }
+
+    // 在动作所需账户之后依次追加协议费所需的 [金库, 收款方, System Program]
+    // 与 memo 所需的 [0 号金库, Memo Program]
+    pub fn execute_accounts(&self) -> Vec<AccountMeta> {
+        let mut accounts = self.action.execute_accounts(&self.multisig);
+        if let Some(fee) = &self.protocol_fee {
+            accounts.push(AccountMeta::new(fee.payer, false));
+            accounts.push(AccountMeta::new(fee.recipient, false));
+            accounts.push(AccountMeta::new_readonly(anchor_lang::system_program::ID, false));
+        }
+        if self.execution_memo.is_some() {
+            let vault = find_vault_address(&self.multisig, 0).0;
+            accounts.push(AccountMeta::new_readonly(vault, false));
+            accounts.push(AccountMeta::new_readonly(MEMO_PROGRAM_ID, false));
+        }
+        accounts
+    }
+}
+
+// 提案关闭后评论成为孤儿账户，任何人可调用 close_comment 把租金退还给作者
+#[account]
+pub struct Comment {
+    pub proposal: Pubkey,
+    pub author: Pubkey,
+    pub seq: u32,
+    pub posted_at: i64,
+    pub text: String,
+    pub bump: u8,
+}
+
+impl Comment {
+    pub const SPACE: usize = 8 // discriminator
+        + 32 // proposal
+        + 32 // author
+        + 4 // seq
+        + 8 // posted_at
+        + 4 + MAX_COMMENT_LEN // text
+        + 1; // bump
+}
+
+// 成员参与统计：由 create_member_stats 按需创建，提案与投票时作为可选账户传入才会累计
+#[account]
+pub struct MemberStats {
+    pub multisig: Pubkey,
+    pub member: Pubkey,
+    pub proposals_created: u32,
+    pub approvals_given: u32,
+    pub rejections_given: u32,
+    pub last_vote_at: i64, // 含弃权；0 = 从未投票
+    pub bump: u8,
+}
+
+impl MemberStats {
+    pub const SPACE: usize = 8 // discriminator
+        + 32 // multisig
+        + 32 // member
+        + 4 // proposals_created
+        + 4 // approvals_given
+        + 4 // rejections_given
+        + 8 // last_vote_at
+        + 1; // bump
+}
+
+// 成员的展示名与通知地址，供钱包界面与链下通知服务读取；只有成员本人或多签自身可写
+#[account]
+pub struct MemberMetadata {
+    pub multisig: Pubkey,
+    pub member: Pubkey,
+    pub payer: Pubkey, // 关闭时退还租金
+    pub label: String,
+    pub notification_key: Option<Pubkey>,
+    pub contact_hash: [u8; 32], // 联系方式的哈希，明文只保存在链下
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl MemberMetadata {
+    pub const SPACE: usize = 8 // discriminator
+        + 32 // multisig
+        + 32 // member
+        + 32 // payer
+        + 4 + MAX_MEMBER_LABEL_LEN // label
+        + 1 + 32 // notification_key
+        + 32 // contact_hash
+        + 8 // updated_at
+        + 1; // bump
+}
+
+// 各 mint 的累计转出：由 create_spend_ledger 按需创建，执行时作为可选账户传入才会累计。
+// 与 total_lamports_out 一样只统计能识别的 SPL 转账；mint 数达到上限后新 mint 不再记录
+#[account]
+pub struct SpendLedger {
+    pub multisig: Pubkey,
+    pub payer: Pubkey, // 关闭时退还租金
+    pub mints: Vec<MintSpend>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MintSpend {
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+impl SpendLedger {
+    pub const SPACE: usize = 8 // discriminator
+        + 32 // multisig
+        + 32 // payer
+        + 4 + 40 * MAX_SPEND_MINTS // mints
+        + 1; // bump
+
+    pub fn spent(&self, mint: &Pubkey) -> u64 {
+        self.mints
+            .iter()
+            .find(|m| m.mint == *mint)
+            .map_or(0, |m| m.amount)
+    }
+
+    pub fn record(&mut self, mint: Pubkey, amount: u64) -> Result<()> {
+        if let Some(entry) = self.mints.iter_mut().find(|m| m.mint == mint) {
+            entry.amount = entry
+                .amount
+                .checked_add(amount)
+                .ok_or(MultisigError::SpendCounterOverflow)?;
+        } else if self.mints.len() < MAX_SPEND_MINTS {
+            self.mints.push(MintSpend { mint, amount });
+        } else {
+            msg!("Spend ledger full, {} not recorded", mint);
+        }
+        Ok(())
+    }
+}
+
+// 全局协议费配置：由程序的升级权限初始化一次，之后由 authority 维护，费用初始为零。
+// 初始化后创建的提案都按其报价，报价记入提案，执行成功后才收取
+#[account]
+#[derive(Debug)]
+pub struct ProgramConfig {
+    pub authority: Pubkey,
+    pub fee_bps: u16, // 按转出的 lamports 计；token 转账只收固定部分
+    pub flat_fee_lamports: u64,
+    pub fee_recipient: Pubkey,
+    pub bump: u8,
+}
+
+impl ProgramConfig {
+    pub const SPACE: usize = 8 // discriminator
+        + 32 // authority
+        + 2 // fee_bps
+        + 8 // flat_fee_lamports
+        + 32 // fee_recipient
+        + 1; // bump
+
+    // 对动用金库资金的提案报价：指令中由金库转出的 SOL 与类型化动作的 SOL 流出按 bps 计，
+    // token 转账与金额未知的流出只收固定部分；不转出资金或费用为零时返回 None
+    pub fn quote(&self, action: &ProposalAction, multisig: &Pubkey) -> Option<ProtocolFee> {
+        let (payer, variable) = match action.instructions() {
+            [] => {
+                let outflows = action.outflows(multisig, &[]);
+                if outflows.is_empty() {
+                    return None;
+                }
+                let lamports: u128 = outflows
+                    .iter()
+                    .flatten()
+                    .filter(|(_, mint, _)| mint.is_none())
+                    .map(|(_, _, amount)| *amount as u128)
+                    .sum();
+                let vault = action.signing_vaults().first().copied().unwrap_or(0);
+                (find_vault_address(multisig, vault).0, lamports)
+            }
+            ixs => {
+                let mut payer = None;
+                let mut lamports = 0u128;
+                for ix in ixs {
+                    let vaults: Vec<Pubkey> = ix
+                        .signer_pdas(multisig)
+                        .into_iter()
+                        .filter(|k| k != multisig)
+                        .collect();
+                    match system_debit(ix) {
+                        Some((from, amount)) if vaults.contains(&from) => {
+                            payer = payer.or(Some(from));
+                            lamports += amount as u128;
+                        }
+                        _ if is_token_transfer(ix) => payer = payer.or(vaults.first().copied()),
+                        _ => {}
+                    }
+                }
+                (payer?, lamports)
+            }
+        };
+        let variable = variable * self.fee_bps as u128 / 10_000;
+        let lamports = self.flat_fee_lamports.saturating_add(variable as u64);
+        (lamports > 0).then_some(ProtocolFee {
+            lamports,
+            payer,
+            recipient: self.fee_recipient,
+        })
+    }
+}
+
+// 每个金库最近一次保留的余额快照；明细见同一交易的 BalanceSnapshot 事件，
+// digest 为事件中 balances 的 borsh 序列化的 sha256
+#[account]
+pub struct BalanceSnapshotRecord {
+    pub multisig: Pubkey,
+    pub vault_index: u8,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub lamports: u64,
+    pub mints: u8,
+    pub digest: [u8; 32],
+    pub bump: u8,
+}
+
+impl BalanceSnapshotRecord {
+    pub const SPACE: usize = 8 + 32 + 1 + 8 + 8 + 8 + 1 + 32 + 1;
+}
+
+// 按提案内容哈希查找活跃提案：提案创建时可选地登记，执行、取消或过期关闭时一并关闭；
+// 错过关闭的索引在提案结束后由任何人清理，或在下次登记相同内容时改指向新提案
+#[account]
+pub struct InstructionIndex {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub hash: [u8; 32],
+    pub bump: u8,
+}
+
+impl InstructionIndex {
+    pub const SPACE: usize = 8 + 32 + 32 + 32 + 1;
+}
+
+// 大指令数据分多笔交易写入，finalize 后不可再改；refs 记录引用它的未关闭提案，
+// 为空时才能关闭。提案被取消或过期关闭后，任何人可调用 release_buffer 解除引用。
+#[account]
+pub struct InstructionBuffer {
+    pub multisig: Pubkey,
+    pub creator: Pubkey,
+    pub id: u32,
+    pub created_at: i64,
+    pub bump: u8,
+    pub finalized: bool,
+    pub hash: [u8; 32],
+    pub refs: Vec<Pubkey>,
+    pub data: Vec<u8>,
+}
+
+impl InstructionBuffer {
+    pub const BASE_SPACE: usize = 8 // discriminator
+        + 32 // multisig
+        + 32 // creator
+        + 4 // id
+        + 8 // created_at
+        + 1 // bump
+        + 1 // finalized
+        + 32 // hash
+        + 4 + 32 * MAX_BUFFER_REFS // refs
+        + 4; // data 长度前缀
+
+    pub const fn space(size: u32) -> usize {
+        Self::BASE_SPACE + size as usize
+    }
+
+    pub fn release(&mut self, proposal: &Pubkey) -> Result<()> {
+        let position = self
+            .refs
+            .iter()
+            .position(|r| r == proposal)
+            .ok_or(MultisigError::AccountMismatch)?;
+        self.refs.swap_remove(position);
+        Ok(())
+    }
+}
+
+// 非成员提交的提款请求；成员调用 convert_request_to_proposal 原样转为从 0 号金库转出的提案
+#[account]
+pub struct WithdrawalRequest {
+    pub multisig: Pubkey,
+    pub requester: Pubkey,
+    pub id: u32,
+    pub mint: Option<Pubkey>, // None 为 SOL
+    pub amount: u64,
+    pub recipient: Pubkey, // 代币转入其 ATA
+    pub memo: Option<String>, // 转为提案的 execution_memo
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl WithdrawalRequest {
+    pub const SPACE: usize = 8 // discriminator
+        + 32 // multisig
+        + 32 // requester
+        + 4 // id
+        + 1 + 32 // mint
+        + 8 // amount
+        + 32 // recipient
+        + 1 + 4 + MAX_EXECUTION_MEMO_LEN // memo
+        + 8 // created_at
+        + 1; // bump
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.created_at + WITHDRAWAL_REQUEST_EXPIRY_SECONDS
+    }
+
+    // 与客户端手工构造的转账相同；代币的 decimals 与 Token Program 取自 mint 账户
+    pub fn instruction(
+        &self,
+        multisig: &Pubkey,
+        mint: Option<&AccountInfo>,
+    ) -> Result<InstructionData> {
+        let vault = find_vault_address(multisig, 0).0;
+        let Some(mint_key) = self.mint else {
+            // SystemInstruction::Transfer
+            let mut data = 2u32.to_le_bytes().to_vec();
+            data.extend_from_slice(&self.amount.to_le_bytes());
+            return Ok(InstructionData {
+                program_id: anchor_lang::system_program::ID,
+                accounts: vec![
+                    AccountMeta::new(vault, true).into(),
+                    AccountMeta::new(self.recipient, false).into(),
+                    AccountMeta::new_readonly(anchor_lang::system_program::ID, false).into(),
+                ],
+                data,
+                signers: signer_vault(0),
+            });
+        };
+        let mint = mint.ok_or(MultisigError::AccountMismatch)?;
+        require_keys_eq!(*mint.key, mint_key, MultisigError::AccountMismatch);
+        let token_program = *mint.owner;
+        require!(is_token_program(&token_program), MultisigError::AccountMismatch);
+        let decimals = *mint
+            .try_borrow_data()?
+            .get(44)
+            .ok_or(MultisigError::AccountMismatch)?;
+
+        let mut data = vec![12]; // TransferChecked
+        data.extend_from_slice(&self.amount.to_le_bytes());
+        data.push(decimals);
+        Ok(InstructionData {
+            program_id: token_program,
+            accounts: vec![
+                AccountMeta::new(
+                    find_associated_token_address(&vault, &mint_key, &token_program),
+                    false,
+                )
+                .into(),
+                AccountMeta::new_readonly(mint_key, false).into(),
+                AccountMeta::new(
+                    find_associated_token_address(&self.recipient, &mint_key, &token_program),
+                    false,
+                )
+                .into(),
+                AccountMeta::new_readonly(vault, true).into(),
+                AccountMeta::new_readonly(token_program, false).into(),
+            ],
+            data,
+            signers: signer_vault(0),
+        })
+    }
+}
+
+#[error_code]
+pub enum MultisigError {
+    #[msg("Members must be sorted and unique")]
+    InvalidMembers,
+    #[msg("Threshold out of range")]
+    InvalidThreshold,
+    #[msg("Only members can interact")]
+    NotMember,
+    #[msg("Already approved")]
+    AlreadyApproved,
+    #[msg("Not enough approvals")]
+    NotExecutable,
+    #[msg("Only proposer can cancel")]
+    NotProposer,
+    #[msg("Already processed")]
+    AlreadyProcessed,
+    #[msg("CPI account mismatch")]
+    AccountMismatch,
+    #[msg("Member set changed since the proposal was created")]
+    StaleProposal,
+    #[msg("Too many members")]
+    TooManyMembers,
+    #[msg("Already a member")]
+    AlreadyMember,
+    #[msg("Not enough approvals for the target program's threshold tier")]
+    TierThresholdNotMet,
+    #[msg("Too many threshold overrides")]
+    TooManyOverrides,
+    #[msg("Too many amount rules")]
+    TooManyAmountRules,
+    #[msg("Multisig is frozen")]
+    Frozen,
+    #[msg("Multisig is not frozen")]
+    NotFrozen,
+    #[msg("Freeze cooldown has not elapsed")]
+    FreezeCooldown,
+    #[msg("Membership has expired")]
+    MembershipExpired,
+    #[msg("Already invited")]
+    AlreadyInvited,
+    #[msg("No pending invitation")]
+    NotInvited,
+    #[msg("Invitation has expired")]
+    InvitationExpired,
+    #[msg("Too many pending invitations")]
+    TooManyInvites,
+    #[msg("Member list does not match the proposal's snapshot")]
+    MembersMismatch,
+    #[msg("Signer is not authorized to change the configuration")]
+    Unauthorized,
+    #[msg("Multisig is paused")]
+    Paused,
+    #[msg("Member has already cast this vote")]
+    AlreadyVoted,
+    #[msg("Quorum must be between 1 and the number of active members")]
+    InvalidQuorum,
+    #[msg("Not enough members have voted to reach quorum")]
+    QuorumNotReached,
+    #[msg("Comment text is empty or too long")]
+    InvalidComment,
+    #[msg("Proposal is still open")]
+    ProposalStillOpen,
+    #[msg("Dependency must be an earlier proposal within the tracked window")]
+    InvalidDependency,
+    #[msg("Dependency has not been executed")]
+    DependencyNotExecuted,
+    #[msg("Execution window must satisfy not_before < not_after")]
+    InvalidWindow,
+    #[msg("Proposal cannot be executed before not_before")]
+    TooEarly,
+    #[msg("Proposal execution window has passed")]
+    TooLate,
+    #[msg("Proposal has not expired")]
+    NotExpired,
+    #[msg("Timelock has not elapsed since the proposal reached its threshold")]
+    TimelockNotElapsed,
+    #[msg("Urgent timelock cannot exceed the regular timelock")]
+    InvalidTimelock,
+    #[msg("Instruction would reassign, reallocate, or drain the multisig or vault PDA")]
+    ForbiddenConfigMutation,
+    #[msg("Transfer would leave the account below the rent-exempt minimum")]
+    WouldBreakRentExemption,
+    #[msg("Minimum slot delay since reaching threshold has not passed")]
+    ExecutionTooSoon,
+    #[msg("Instruction buffer size out of range")]
+    InvalidBufferSize,
+    #[msg("Write exceeds the instruction buffer size")]
+    BufferOverflow,
+    #[msg("Instruction buffer is finalized")]
+    BufferFinalized,
+    #[msg("Instruction buffer is not finalized")]
+    BufferNotFinalized,
+    #[msg("Instruction buffer content does not match the proposal hash")]
+    BufferHashMismatch,
+    #[msg("Instruction buffer is referenced by too many proposals")]
+    TooManyBufferRefs,
+    #[msg("Instruction buffer is still referenced by an open proposal")]
+    BufferInUse,
+    #[msg("Buffered proposals must be proposed with propose_buffered")]
+    BufferedReclone,
+    #[msg("Only the creator can close an instruction buffer before it expires")]
+    BufferNotExpired,
+    #[msg("Bootstrap window closed: a proposal has already been created")]
+    BootstrapClosed,
+    #[msg("Malformed compiled transaction message")]
+    InvalidCompiledMessage,
+    #[msg("Compiled message references address lookup tables")]
+    UnresolvedLookupTable,
+    #[msg("Compiled message must contain exactly one instruction")]
+    CompiledInstructionCount,
+    #[msg("Resize would shrink the multisig account")]
+    InvalidResize,
+    #[msg("Vesting schedule must have 1 to 32 tranches with non-zero amounts")]
+    InvalidVestingSchedule,
+    #[msg("Vesting and recurring proposals are executed with activate_proposal")]
+    StandingProposal,
+    #[msg("Only vesting and recurring proposals can be activated")]
+    NotStandingProposal,
+    #[msg("Proposal is not an active vesting schedule")]
+    VestingNotActive,
+    #[msg("Vesting tranche index out of range")]
+    InvalidTranche,
+    #[msg("Vesting tranche is still locked")]
+    TrancheLocked,
+    #[msg("Vesting tranche has already been paid")]
+    TranchePaid,
+    #[msg("Recurring payment needs a non-zero amount, interval and payment count")]
+    InvalidRecurringPayment,
+    #[msg("Proposal is not an active recurring payment")]
+    RecurringNotActive,
+    #[msg("Recurring payment is not due yet")]
+    PaymentNotDue,
+    #[msg("New mint account must sign the execute transaction")]
+    MintNotSigner,
+    #[msg("Invalid token authority type or new authority")]
+    InvalidAuthorityType,
+    #[msg("Current token authority is not the multisig or one of its vaults")]
+    AuthorityMismatch,
+    #[msg("Burn amount must be non-zero")]
+    InvalidBurnAmount,
+    #[msg("Vault token balance is lower than the burn amount")]
+    InsufficientBalance,
+    #[msg("Mint's freeze authority is not the multisig")]
+    FreezeAuthorityMismatch,
+    #[msg("Mint amount must be non-zero and keep the supply within u64")]
+    InvalidMintAmount,
+    #[msg("Mint's mint authority is not the multisig")]
+    MintAuthorityMismatch,
+    #[msg("Execution memo must be non-empty, within the length limit, and on a proposal that executes once")]
+    InvalidExecutionMemo,
+    #[msg("Proposal has an execution memo: pass vault 0 and the Memo program last")]
+    MemoProgramMissing,
+    #[msg("Multisig is not the vote account's authority for this action")]
+    VoteAuthorityMismatch,
+    #[msg("Multisig is not the stake account's authority for this action")]
+    StakeAuthorityMismatch,
+    #[msg("Stake amount must be non-zero")]
+    InvalidStakeAmount,
+    #[msg("New stake account must sign the execute transaction")]
+    StakeAccountNotSigner,
+    #[msg("Too many controlled accounts")]
+    TooManyControlledAccounts,
+    #[msg("Current withdraw authority must sign the execute transaction")]
+    CustodySignerMismatch,
+    #[msg("Member liveness tracking is not enabled")]
+    LivenessDisabled,
+    #[msg("Member has signed within the inactivity timeout or is already inactive")]
+    MemberNotIdle,
+    #[msg("Marking the member inactive would leave fewer live members than the liveness floor")]
+    LivenessFloorReached,
+    #[msg("Liveness floor must be between 1 and the number of members")]
+    InvalidLivenessFloor,
+    #[msg("Delay override cannot be shorter than the default timelock")]
+    DelayOverrideTooShort,
+    #[msg("Cancel reason is reserved for program-initiated cancellations")]
+    ReservedCancelReason,
+    #[msg("Cancel note must be 1-64 bytes")]
+    InvalidCancelNote,
+    #[msg("Rejection reason must be at most 80 bytes")]
+    InvalidRejectionReason,
+    #[msg("Proposal has no space left for another rejection reason")]
+    TooManyRejectionReasons,
+    #[msg("Voting on this proposal has closed")]
+    VotingClosed,
+    #[msg("Metadata URI must be 1-200 bytes")]
+    UriTooLong,
+    #[msg("Metadata cannot change once the proposal has approvals")]
+    MetadataLocked,
+    #[msg("Spend counter overflow")]
+    SpendCounterOverflow,
+    #[msg("Multisig is winding down; only withdrawals are allowed")]
+    WindDownRestricted,
+    #[msg("Too many approval groups")]
+    TooManyApprovalGroups,
+    #[msg("Approval group minimum must be between 1 and the number of active members in the group")]
+    InvalidApprovalGroup,
+    #[msg("An approval group has not reached its minimum approvals")]
+    GroupApprovalsNotMet,
+    #[msg("Proposal exceeds the spending limit for its destination")]
+    DestinationLimitExceeded,
+    #[msg("Too many trusted destinations")]
+    TooManyTrustedDestinations,
+    #[msg("Destination limits must be at most 4 with one entry per mint")]
+    InvalidDestinationLimits,
+    #[msg("Batch proposals must have 1 to 8 instructions")]
+    InvalidBatch,
+    #[msg("Destination is not on the allowlist")]
+    DestinationNotAllowed,
+    #[msg("Too many allowed destinations")]
+    TooManyAllowedDestinations,
+    #[msg("No pending allowlist change")]
+    NoPendingAllowlistChange,
+    #[msg("Allowlist change is still in its delay period")]
+    AllowlistChangeNotReady,
+    #[msg("Destination is on the denylist")]
+    DestinationDenied,
+    #[msg("Too many denied destinations")]
+    TooManyDeniedDestinations,
+    #[msg("Vault index out of range")]
+    InvalidVaultIndex,
+    #[msg("Vault is already registered")]
+    VaultAlreadyRegistered,
+    #[msg("Vault is not registered")]
+    VaultNotRegistered,
+    #[msg("Vault name must be non-empty and at most 24 bytes")]
+    InvalidVaultName,
+    #[msg("Vault is paused")]
+    VaultPaused,
+    #[msg("Proposal has not been executed")]
+    NotExecuted,
+    #[msg("Executed proposal is still within its retention period")]
+    RetentionNotElapsed,
+    #[msg("Proposal content does not match the expected hash")]
+    InstructionHashMismatch,
+    #[msg("Member key type cannot be used here")]
+    WrongKeyType,
+    #[msg("Ethereum approval signature has expired")]
+    EthSignatureExpired,
+    #[msg("Missing or invalid secp256k1 signature instruction")]
+    InvalidEthSignature,
+    #[msg("Only the designated executor may execute this proposal yet")]
+    NotDesignatedExecutor,
+    #[msg("Approval TTL must be positive")]
+    InvalidApprovalTtl,
+    #[msg("Invalid extension tag or value")]
+    InvalidExtension,
+    #[msg("Not enough space for extensions; resize the multisig account")]
+    ExtensionSpaceExhausted,
+    #[msg("This instruction must be invoked at the top level of the transaction")]
+    CpiNotAllowed,
+    #[msg("Protocol fee exceeds the maximum")]
+    InvalidProtocolFee,
+    #[msg("Protocol fee accounts are missing")]
+    ProtocolFeeAccountMissing,
+    #[msg("Protocol fee opt-out is only possible before the first proposal")]
+    FeeOptOutClosed,
+    #[msg("Deposit amount must be non-zero")]
+    InvalidDepositAmount,
+    #[msg("Deposit memo must be non-empty and within the length limit")]
+    InvalidDepositMemo,
+    #[msg("Withdrawal request needs a non-zero amount and a valid memo")]
+    InvalidWithdrawalRequest,
+    #[msg("Withdrawal request has expired")]
+    WithdrawalRequestExpired,
+    #[msg("Only the requester can close a withdrawal request before it expires")]
+    WithdrawalRequestNotExpired,
+    #[msg("App authority is already registered")]
+    AppAuthorityRegistered,
+    #[msg("App authority is not registered")]
+    AppAuthorityNotRegistered,
+    #[msg("Too many app authorities")]
+    TooManyAppAuthorities,
+    #[msg("Multisig is migrating to a new address")]
+    MigrationInProgress,
+    #[msg("Multisig is not migrating to this address")]
+    NotMigrating,
+    #[msg("Old vaults still hold lamports")]
+    MigrationIncomplete,
+    #[msg("Notify program account missing or not executable")]
+    NotifyProgramMissing,
+    #[msg("Policy program accounts missing or out of order")]
+    PolicyAccountsMismatch,
+    #[msg("Seed too long or owner is not a valid program id")]
+    InvalidAccountSeed,
+    #[msg("Source is not a legacy multisig account")]
+    InvalidLegacyMultisig,
+    #[msg("An active proposal with the same content already exists")]
+    DuplicateProposal,
+    #[msg("Snapshot account is not a token account owned by the vault, or is repeated")]
+    InvalidSnapshotAccount,
+    #[msg("Too many token accounts for one snapshot")]
+    TooManySnapshotAccounts,
+    #[msg("Member label is too long")]
+    InvalidMemberLabel,
+    #[msg("Proposer is still a member of the multisig")]
+    ProposerStillMember,
+    #[msg("Execution would not fail for a reason that can be recorded")]
+    ExecutionNotFailing,
+    #[msg("A failed execution was already recorded in this slot")]
+    FailureAlreadyRecorded,
+    #[msg("Proposal is not eligible for optimistic execution")]
+    NotOptimisticEligible,
+    #[msg("Invalid optimistic execution config")]
+    InvalidOptimisticConfig,
+    #[msg("Optimistic proposal was vetoed")]
+    ProposalVetoed,
+}
+
+// ===== Events =====
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct MultisigCreated {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub creator: Pubkey,
+    pub members: u8,
+    pub threshold: u8,
+    pub program_version: String, // 创建时部署的 PROGRAM_VERSION
+    pub timestamp: i64,
+}
+
+// create_multisig_with_proposal 只发出这一条，代替 MultisigCreated 与 ProposalCreated
+#[event]
+#[derive(Clone, Debug)]
+pub struct MultisigCreatedWithProposal {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub creator: Pubkey,
+    pub members: u8,
+    pub threshold: u8,
+    pub program_version: String,
+    pub timestamp: i64,
+    pub proposal: ProposalCreated, // 0 号提案，proposer 即 creator
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct ProposalCreated {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub index: u32,
+    pub proposer: Pubkey,
+    pub urgent: bool,
+    pub timestamp: i64, // 即更新后的 multisig.last_activity
+    pub vaults: Vec<VaultConfig>, // 执行时会签名的已登记金库
+    pub protocol_fee: Option<ProtocolFee>, // 审批前即可看到执行时将收取的费用
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct ProposalExecuted {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub index: u32,
+    pub urgent: bool,
+    pub timestamp: i64,
+    pub vaults: Vec<VaultConfig>,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct ProposalRecorded {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub index: u32,
+    pub action_hash: [u8; 32],
+    pub action: ProposalAction,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct ConfigActionExecuted {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub action: ProposalAction,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct CommentPosted {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub author: Pubkey,
+    pub seq: u32,
+    pub text: String,
+}
+
+// 提案账户随即关闭，此事件是取消原因唯一的持久记录
+#[event]
+#[derive(Clone, Debug)]
+pub struct ProposalCancelled {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub index: u32,
+    pub canceller: Option<Pubkey>, // None = 无需许可的清理
+    pub reason: u8,
+    pub note: Option<String>,
+    pub action_hash: [u8; 32], // 与 ProposalAction::hash 一致，供关联链下记录
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct ProposalsCancelled {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub cancelled: u32,
+    pub skipped: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct VestingTranchePaid {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub tranche: u8,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub remaining: u8,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct VestingCancelled {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub tranches_cancelled: u8, // 尚未支付的分期，已支付的不受影响
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct RecurringPaymentMade {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub payments_made: u32, // 含本次
+    pub max_payments: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct RecurringPaymentCancelled {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub payments_made: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct MintCreated {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub mint: Pubkey,
+    pub mint_authority: Pubkey, // 0 号金库
+    pub freeze_authority: Option<Pubkey>,
+    pub decimals: u8,
+}
+
+// 创建增发提案时发出，供审批者核对；estimated_supply 为创建时供应量加上增发量
+#[event]
+#[derive(Clone, Debug)]
+pub struct MintToProposed {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+    pub destination: Pubkey, // 收款方的 ATA
+    pub amount: u64,
+    pub estimated_supply: u64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct StakeAccountSplit {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub stake_account: Pubkey,
+    pub new_stake_account: Pubkey,
+    pub lamports: u64,
+}
+
+// 创建时附带或之后由发起人修改元数据时发出
+#[event]
+#[derive(Clone, Debug)]
+pub struct ProposalMetadataSet {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub metadata_uri: Option<String>,
+    pub metadata_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct ProposalRejected {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub member: Pubkey,
+    pub code: u8,
+    pub reason: String,
+    pub rejections: u32, // 本次投票后计入的反对票数
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct ProtocolFeeCharged {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub payer: Pubkey,
+    pub recipient: Pubkey,
+    pub lamports: u64, // 实际收取，可能因免租金额而低于报价
+    pub timestamp: i64,
+}
+
+// 初始化与每次修改后的完整配置
+#[event]
+#[derive(Clone, Debug)]
+pub struct ProgramConfigUpdated {
+    pub schema_version: u8,
+    pub authority: Pubkey,
+    pub fee_bps: u16,
+    pub flat_fee_lamports: u64,
+    pub fee_recipient: Pubkey,
+    pub timestamp: i64,
+}
+
+// approve_idempotent 遇到已存在的批准时发出，提案不变
+#[event]
+#[derive(Clone, Debug)]
+pub struct ApprovalAlreadyPresent {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub member: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct MemberMarkedInactive {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub member: Pubkey,
+    pub last_seen: i64,
+    pub live_members: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct TokenDelegateApproved {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub token_account: Pubkey,
+    pub mint: Pubkey,
+    pub delegate: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct WindDownStarted {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct VaultPauseSet {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub index: u8,
+    pub paused: bool,
+    pub authority: Pubkey, // multisig PDA、config_authority 或 guardian
+    pub timestamp: i64,
+}
+
+// 修改被排期时 effective_at 为生效时间；启用或落地时等于当前时间
+#[event]
+#[derive(Clone, Debug)]
+pub struct AllowedDestinationsChanged {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub destinations: Vec<Pubkey>,
+    pub delay_seconds: u32,
+    pub effective_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct MultisigFrozen {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub frozen_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct MultisigUnfrozen {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub frozen_by: Pubkey,
+    pub frozen_at: i64,
+    pub timestamp: i64,
+}
+
+// 通过 deposit / deposit_token 转入金库的资金；直接转账不会产生此事件
+#[event]
+#[derive(Clone, Debug)]
+pub struct DepositReceived {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub vault: Pubkey,
+    pub depositor: Pubkey,
+    pub mint: Option<Pubkey>, // None 为 SOL
+    pub amount: u64,
+    pub memo: Option<String>,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct WithdrawalRequested {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub request: Pubkey,
+    pub requester: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub recipient: Pubkey,
+    pub timestamp: i64,
+}
+
+// 紧随同一指令中的 ProposalCreated
+#[event]
+#[derive(Clone, Debug)]
+pub struct WithdrawalRequestConverted {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub request: Pubkey,
+    pub proposal: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct AppAuthoritySet {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub app_id: [u8; 16],
+    pub authority: Pubkey,
+    pub registered: bool, // false 为注销
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct MigrationStarted {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub new_multisig: Pubkey,
+    pub new_creator: Pubkey,
+    pub timestamp: i64,
+}
+
+// 每次 migrate_sweep 一条；可多次调用直至旧金库清空
+#[event]
+#[derive(Clone, Debug)]
+pub struct MigrationSwept {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub new_multisig: Pubkey,
+    pub vault_index: u8,
+    pub lamports: u64,
+    pub token_accounts: u8, // 本次清空并关闭的 token 账户数
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct MigrationFinished {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub new_multisig: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct NotifyProgramSet {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub program: Option<Pubkey>, // None 为移除
+    pub required: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct ApprovalAdded {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub member: Pubkey,
+    pub approvals: u8, // 计入阈值的有效批准，含本次
+    pub seconds_since_creation: i64,
+    pub timestamp: i64,
+}
+
+// 批准数首次（或批准过期后再次）达到阈值
+#[event]
+#[derive(Clone, Debug)]
+pub struct ProposalReady {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub index: u32,
+    pub approvals: u8,
+    pub time_to_ready: i64, // 自创建起的秒数
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct PolicyProgramSet {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub program: Option<Pubkey>, // None 为移除
+    pub account_count: u8,
+    pub timestamp: i64,
+}
+
+// 由旧 serum multisig 导入成员与阈值后新建
+#[event]
+#[derive(Clone, Debug)]
+pub struct MultisigImported {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub source: Pubkey, // 旧多签账户
+    pub creator: Pubkey,
+    pub members: u8,
+    pub threshold: u8,
+    pub owner_set_seqno: u32, // 导入时旧多签的成员集版本
+    pub timestamp: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct MintBalance {
+    pub mint: Pubkey,
+    pub amount: u64, // 同一 mint 下各 token 账户之和
+    pub accounts: u8,
+}
+
+// 金库持仓证明：lamports 与按 mint 汇总的 token 余额（按 mint 排序）
+#[event]
+#[derive(Clone, Debug)]
+pub struct BalanceSnapshot {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub vault: Pubkey,
+    pub vault_index: u8,
+    pub lamports: u64,
+    pub balances: Vec<MintBalance>,
+    pub retained: bool,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct MemberMetadataSet {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub member: Pubkey,
+    pub label: String,
+    pub notification_key: Option<Pubkey>,
+    pub contact_hash: [u8; 32],
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct MemberMetadataClosed {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub member: Pubkey,
+    pub still_member: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct ExecutionFailureRecorded {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub index: u32,
+    pub reason: ExecutionFailure,
+    pub attempts: u8,
+    pub cancellable: bool, // 已超过 max_execution_attempts，可由 close_expired 关闭
+    pub reporter: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct OptimisticConfigSet {
+    pub schema_version: u8,
+    pub multisig: Pubkey,
+    pub config: Option<OptimisticConfig>,
+    pub timestamp: i64,
+}
+
+// ===== Accounts =====
+
+#[derive(Accounts)]
+#[instruction(nonce: u8, members: Vec<Pubkey>, threshold: u8, max_members: u8)]
+pub struct CreateMultisig<'info> {
+    #[account(
+        init,
+        seeds = [MULTISIG_SEED, creator.key().as_ref(), &[nonce]],
+        bump,
+        payer = creator,
+        space = Multisig::space(max_members)
+    )]
+    pub multisig: Account<'info, Multisig>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce: u8, max_members: u8)]
+pub struct ImportMultisig<'info> {
+    #[account(
+        init,
+        seeds = [MULTISIG_SEED, creator.key().as_ref(), &[nonce]],
+        bump,
+        payer = creator,
+        space = Multisig::space(max_members)
+    )]
+    pub multisig: Account<'info, Multisig>,
+    /// CHECK: 只读取数据，owner 必须是旧 serum multisig 程序，布局由 parse_legacy_multisig 校验
+    #[account(owner = legacy::SERUM_MULTISIG_PROGRAM_ID @ MultisigError::InvalidLegacyMultisig)]
+    pub source: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// 提案 PDA 的种子引用同一指令中刚创建的 multisig；序号固定为 0
+#[derive(Accounts)]
+#[instruction(nonce: u8, members: Vec<Pubkey>, threshold: u8, max_members: u8)]
+pub struct CreateMultisigWithProposal<'info> {
+    #[account(
+        init,
+        seeds = [MULTISIG_SEED, creator.key().as_ref(), &[nonce]],
+        bump,
+        payer = creator,
+        space = Multisig::space(max_members)
+    )]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        init,
+        seeds = [PROPOSAL_SEED, multisig.key().as_ref(), &0u32.to_le_bytes()],
+        bump,
+        payer = creator,
+        space = Proposal::space(max_members)
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: 地址已约束；初始化后必须据此为提案报价协议费，未初始化时为空账户
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfigBootstrap<'info> {
+    #[account(mut, has_one = creator)]
+    pub multisig: Account<'info, Multisig>,
+    pub creator: Signer<'info>,
+}
+
+// 第一个提案前由创建者、之后只能由 multisig PDA（自治提案）扩容；任何人可出资
+#[derive(Accounts)]
+#[instruction(new_size: u16)]
+pub struct ResizeMultisig<'info> {
+    #[account(
+        mut,
+        // realloc 先于其他约束执行：取较大值使缩容成为空操作，由处理函数报错
+        realloc = (new_size as usize).max(multisig.to_account_info().data_len()),
+        realloc::payer = payer,
+        realloc::zero = true,
+    )]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        constraint = authority.key() == multisig.key()
+            || (authority.key() == multisig.creator && multisig.proposals_count == 0)
+            @ MultisigError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeTransaction<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        init,
+        seeds = [PROPOSAL_SEED, multisig.key().as_ref(), &multisig.proposals_count.to_le_bytes()],
+        bump,
+        payer = proposer,
+        space = Proposal::space(multisig.max_members)
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        mut,
+        seeds = [MEMBER_STATS_SEED, multisig.key().as_ref(), proposer.key().as_ref()],
+        bump = member_stats.bump,
+    )]
+    pub member_stats: Option<Account<'info, MemberStats>>,
+    /// CHECK: 地址已约束；初始化后必须据此为提案报价协议费，未初始化时为空账户
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+    /// CHECK: 内容索引 PDA，地址与状态在 index_proposal 中校验；不传则不登记
+    #[account(mut)]
+    pub instruction_index: Option<UncheckedAccount<'info>>,
+    /// CHECK: 索引当前指向的提案，仅在索引已存在时需要，用于判断其是否仍活跃
+    pub indexed_proposal: Option<UncheckedAccount<'info>>,
+}
+
+// 只接受未关闭的源提案：已执行或取消的提案账户已被关闭，无数据可复制
+#[derive(Accounts)]
+pub struct RecloneProposal<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        constraint = source.multisig == multisig.key(),
+        constraint = !source.executed && !source.cancelled @ MultisigError::AlreadyProcessed,
+    )]
+    pub source: Account<'info, Proposal>,
+    #[account(
+        init,
+        seeds = [PROPOSAL_SEED, multisig.key().as_ref(), &multisig.proposals_count.to_le_bytes()],
+        bump,
+        payer = proposer,
+        space = Proposal::space(multisig.max_members)
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        mut,
+        seeds = [MEMBER_STATS_SEED, multisig.key().as_ref(), proposer.key().as_ref()],
+        bump = member_stats.bump,
+    )]
+    pub member_stats: Option<Account<'info, MemberStats>>,
+    /// CHECK: 地址已约束；初始化后必须据此为提案报价协议费，未初始化时为空账户
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveTransaction<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        constraint = proposal.multisig == multisig.key(),
+        constraint = !proposal.executed && !proposal.cancelled @ MultisigError::AlreadyProcessed,
+        constraint = proposal.config_seqno == multisig.config_seqno @ MultisigError::StaleProposal,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    pub approver: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [MEMBER_STATS_SEED, multisig.key().as_ref(), approver.key().as_ref()],
+        bump = member_stats.bump,
+    )]
+    pub member_stats: Option<Account<'info, MemberStats>>,
+}
+
+// 无需许可：签名由 secp256k1 预编译指令验证，提交者只付手续费
+#[derive(Accounts)]
+pub struct ApproveWithEthSignature<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        constraint = proposal.multisig == multisig.key(),
+        constraint = !proposal.executed && !proposal.cancelled @ MultisigError::AlreadyProcessed,
+        constraint = proposal.config_seqno == multisig.config_seqno @ MultisigError::StaleProposal,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    /// CHECK: 指令 sysvar，地址已约束
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+// 无需许可：任何人可为现任成员付费创建统计账户
+#[derive(Accounts)]
+#[instruction(member: Pubkey)]
+pub struct CreateMemberStats<'info> {
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        init,
+        seeds = [MEMBER_STATS_SEED, multisig.key().as_ref(), member.as_ref()],
+        bump,
+        payer = payer,
+        space = MemberStats::SPACE
+    )]
+    pub member_stats: Account<'info, MemberStats>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// 成员本人或多签自身（治理提案，或 config_authority）可写；治理创建时可由金库付租金
+#[derive(Accounts)]
+#[instruction(member: Pubkey)]
+pub struct CreateMemberMetadata<'info> {
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        init,
+        seeds = [MEMBER_METADATA_SEED, multisig.key().as_ref(), member.as_ref()],
+        bump,
+        payer = payer,
+        space = MemberMetadata::SPACE
+    )]
+    pub metadata: Account<'info, MemberMetadata>,
+    #[account(
+        constraint = authority.key() == member
+            || authority.key() == multisig.key()
+            || multisig.config_authority == Some(authority.key()) @ MultisigError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMemberMetadata<'info> {
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        has_one = multisig,
+        seeds = [MEMBER_METADATA_SEED, multisig.key().as_ref(), metadata.member.as_ref()],
+        bump = metadata.bump,
+    )]
+    pub metadata: Account<'info, MemberMetadata>,
+    #[account(
+        constraint = authority.key() == metadata.member
+            || authority.key() == multisig.key()
+            || multisig.config_authority == Some(authority.key()) @ MultisigError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+}
+
+// 成员移除后任何人可清理，否则须由成员本人或多签自身关闭
+#[derive(Accounts)]
+pub struct CloseMemberMetadata<'info> {
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        close = payer,
+        has_one = multisig,
+        has_one = payer,
+        seeds = [MEMBER_METADATA_SEED, multisig.key().as_ref(), metadata.member.as_ref()],
+        bump = metadata.bump,
+    )]
+    pub metadata: Account<'info, MemberMetadata>,
+    /// CHECK: 账户创建者，接收退还的租金
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+    pub closer: Signer<'info>,
+}
+
+// 只有程序的升级权限能初始化，且只能一次
+#[derive(Accounts)]
+pub struct InitProgramConfig<'info> {
+    #[account(
+        init,
+        seeds = [PROGRAM_CONFIG_SEED],
+        bump,
+        payer = authority,
+        space = ProgramConfig::SPACE
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, program::Multisig>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ MultisigError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateProgramConfig<'info> {
+    #[account(
+        mut,
+        seeds = [PROGRAM_CONFIG_SEED],
+        bump = program_config.bump,
+        has_one = authority @ MultisigError::Unauthorized,
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    pub authority: Signer<'info>,
+}
+
+// 创建者在首个提案之前可选择退出，之后不可撤销
+#[derive(Accounts)]
+pub struct OptOutProtocolFee<'info> {
+    #[account(
+        mut,
+        constraint = multisig.creator == creator.key() @ MultisigError::Unauthorized,
+        constraint = multisig.proposals_count == 0 @ MultisigError::FeeOptOutClosed,
+    )]
+    pub multisig: Account<'info, Multisig>,
+    pub creator: Signer<'info>,
+}
+
+// 无需许可：任何人可付费创建；关闭须经自治指令，租金退还给创建者
+#[derive(Accounts)]
+pub struct CreateSpendLedger<'info> {
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        init,
+        seeds = [SPEND_LEDGER_SEED, multisig.key().as_ref()],
+        bump,
+        payer = payer,
+        space = SpendLedger::SPACE
+    )]
+    pub spend_ledger: Account<'info, SpendLedger>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseSpendLedger<'info> {
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        constraint = authority.key() == multisig.key()
+            || multisig.config_authority == Some(authority.key()) @ MultisigError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        close = payer,
+        has_one = payer,
+        seeds = [SPEND_LEDGER_SEED, multisig.key().as_ref()],
+        bump = spend_ledger.bump,
+    )]
+    pub spend_ledger: Account<'info, SpendLedger>,
+    /// CHECK: 账户创建者，接收退还的租金
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+// 提案在执行后关闭，或在 retain_executed 时保留为记录
+#[derive(Accounts)]
+pub struct ExecuteTransaction<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        constraint = proposal.multisig == multisig.key(),
+        constraint = !proposal.executed && !proposal.cancelled @ MultisigError::AlreadyProcessed,
+        constraint = proposal.config_seqno == multisig.config_seqno @ MultisigError::StaleProposal,
+        constraint = !proposal.stays_open() @ MultisigError::StandingProposal,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        mut,
+        seeds = [SPEND_LEDGER_SEED, multisig.key().as_ref()],
+        bump = spend_ledger.bump,
+    )]
+    pub spend_ledger: Option<Account<'info, SpendLedger>>,
+    pub executor: Option<Signer<'info>>, // 记入保留的提案
+    #[account(
+        mut,
+        close = multisig,
+        has_one = multisig,
+        constraint = instruction_index.proposal == proposal.key() @ MultisigError::AccountMismatch,
+    )]
+    pub instruction_index: Option<Account<'info, InstructionIndex>>,
+}
+
+// 只读；remaining_accounts 与 execute_transaction 相同，缓冲区账户无需可写
+#[derive(Accounts)]
+pub struct GetProposalStatus<'info> {
+    pub multisig: Account<'info, Multisig>,
+    #[account(constraint = proposal.multisig == multisig.key())]
+    pub proposal: Account<'info, Proposal>,
+}
+
+// 无需许可；remaining_accounts 与 execute_transaction 相同
+#[derive(Accounts)]
+pub struct RecordFailedExecution<'info> {
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        constraint = proposal.multisig == multisig.key(),
+        constraint = !proposal.executed && !proposal.cancelled @ MultisigError::AlreadyProcessed,
+        constraint = proposal.config_seqno == multisig.config_seqno @ MultisigError::StaleProposal,
+        constraint = !proposal.stays_open() @ MultisigError::StandingProposal,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    pub reporter: Signer<'info>,
+}
+
+// 无需许可；金库由种子推导，记账不会落到其他多签名下
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    // 迁移开始后应存入新多签
+    #[account(constraint = multisig.migrating_to().is_none() @ MultisigError::MigrationInProgress)]
+    pub multisig: Account<'info, Multisig>,
+    /// CHECK: 0 号金库，仅接收 lamports
+    #[account(mut, seeds = [VAULT_SEED, multisig.key().as_ref(), &[0]], bump)]
+    pub vault: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// 无需许可；remaining_accounts 为金库的 token 账户，逐个校验 owner
+#[derive(Accounts)]
+#[instruction(vault_index: u8)]
+pub struct SnapshotBalances<'info> {
+    pub multisig: Account<'info, Multisig>,
+    /// CHECK: 由种子推导，只读取 lamports
+    #[account(seeds = [VAULT_SEED, multisig.key().as_ref(), &[vault_index]], bump)]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: retain 时写入的快照 PDA，首次写入时创建
+    #[account(mut, seeds = [SNAPSHOT_SEED, multisig.key().as_ref(), &[vault_index]], bump)]
+    pub snapshot: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    pub payer: Option<Signer<'info>>, // retain 时须为成员，首次创建快照 PDA 时付租金
+    pub system_program: Option<Program<'info, System>>,
+}
+
+#[derive(Accounts)]
+pub struct DepositToken<'info> {
+    #[account(constraint = multisig.migrating_to().is_none() @ MultisigError::MigrationInProgress)]
+    pub multisig: Account<'info, Multisig>,
+    /// CHECK: 0 号金库，用于推导收款 ATA
+    #[account(seeds = [VAULT_SEED, multisig.key().as_ref(), &[0]], bump)]
+    pub vault: UncheckedAccount<'info>,
+    pub depositor: Signer<'info>,
+    /// CHECK: 存入者的 token 账户，权限由 token 程序校验
+    #[account(mut)]
+    pub source: UncheckedAccount<'info>,
+    /// CHECK: 须为金库在该 mint 下的 ATA，在处理函数中推导校验
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+    /// CHECK: decimals 由 token 程序在 TransferChecked 中校验
+    pub mint: UncheckedAccount<'info>,
+    /// CHECK: SPL Token 或 Token-2022
+    #[account(constraint = is_token_program(token_program.key) @ MultisigError::AccountMismatch)]
+    pub token_program: UncheckedAccount<'info>,
+}
+
+// 与 ExecuteTransaction 相同的检查，但提案账户保留到全部分期支付或被取消
+#[derive(Accounts)]
+pub struct ActivateProposal<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        constraint = proposal.multisig == multisig.key(),
+        constraint = !proposal.executed && !proposal.cancelled @ MultisigError::AlreadyProcessed,
+        constraint = proposal.config_seqno == multisig.config_seqno @ MultisigError::StaleProposal,
+        constraint = proposal.stays_open() @ MultisigError::NotStandingProposal,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    pub executor: Option<Signer<'info>>,
+}
+
+// 无需许可：金额、收款方与解锁时间均已由提案固定
+#[derive(Accounts)]
+pub struct ExecuteVestingTranche<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        constraint = proposal.multisig == multisig.key(),
+        constraint = proposal.is_vesting() && proposal.executed @ MultisigError::VestingNotActive,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    /// CHECK: 0 号金库，仅作为转账签名者
+    #[account(seeds = [VAULT_SEED, multisig.key().as_ref(), &[0]], bump)]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: 金库的 ATA，在处理函数中按 mint 与 token_program 推导校验
+    #[account(mut)]
+    pub source: UncheckedAccount<'info>,
+    /// CHECK: 收款 token 账户，在处理函数中校验 mint 与 owner
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+    /// CHECK: 须为提案中的 mint，decimals 由 token 程序在 TransferChecked 中校验
+    pub mint: UncheckedAccount<'info>,
+    /// CHECK: SPL Token 或 Token-2022
+    #[account(constraint = is_token_program(token_program.key) @ MultisigError::AccountMismatch)]
+    pub token_program: UncheckedAccount<'info>,
+}
+
+// 取消剩余分期：关闭提案账户，租金退还给 multisig
+#[derive(Accounts)]
+pub struct CancelVesting<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        close = multisig,
+        constraint = proposal.multisig == multisig.key(),
+        constraint = proposal.is_vesting() && proposal.executed @ MultisigError::VestingNotActive,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        constraint = authority.key() == multisig.key()
+            || multisig.config_authority == Some(authority.key()) @ MultisigError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+}
+
+// 无需许可：收款方、金额与周期均已由提案固定
+#[derive(Accounts)]
+pub struct ExecuteRecurringPayment<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        constraint = proposal.multisig == multisig.key(),
+        constraint = proposal.is_recurring() && proposal.executed @ MultisigError::RecurringNotActive,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    /// CHECK: 0 号金库，付款来源
+    #[account(mut, seeds = [VAULT_SEED, multisig.key().as_ref(), &[0]], bump)]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: 须为提案中的 destination，在处理函数中校验
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// 停止之后的付款：关闭提案账户，租金退还给 multisig
+#[derive(Accounts)]
+pub struct CancelRecurringPayment<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        close = multisig,
+        constraint = proposal.multisig == multisig.key(),
+        constraint = proposal.is_recurring() && proposal.executed @ MultisigError::RecurringNotActive,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        constraint = authority.key() == multisig.key()
+            || multisig.config_authority == Some(authority.key()) @ MultisigError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteMany<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        seeds = [SPEND_LEDGER_SEED, multisig.key().as_ref()],
+        bump = spend_ledger.bump,
+    )]
+    pub spend_ledger: Option<Account<'info, SpendLedger>>,
+    pub executor: Option<Signer<'info>>,
+}
+
+// 无需许可：保留期满后关闭已执行的提案，租金退还给 multisig
+#[derive(Accounts)]
+pub struct CloseExecutedProposal<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        close = multisig,
+        constraint = proposal.multisig == multisig.key(),
+        constraint = proposal.executed && !proposal.stays_open() @ MultisigError::NotExecuted,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+#[derive(Accounts)]
+pub struct SetProposalMetadata<'info> {
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        constraint = proposal.multisig == multisig.key(),
+        constraint = !proposal.executed && !proposal.cancelled @ MultisigError::AlreadyProcessed,
+        constraint = proposal.proposer == proposer.key() @ MultisigError::NotProposer,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    pub proposer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelTransaction<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        close = multisig,
+        constraint = proposal.multisig == multisig.key(),
+        constraint = !proposal.executed && !proposal.cancelled @ MultisigError::AlreadyProcessed,
+        constraint = proposal.proposer == canceller.key() @ MultisigError::NotProposer,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    pub canceller: Signer<'info>,
+    #[account(
+        mut,
+        close = multisig,
+        has_one = multisig,
+        constraint = instruction_index.proposal == proposal.key() @ MultisigError::AccountMismatch,
+    )]
+    pub instruction_index: Option<Account<'info, InstructionIndex>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseExpired<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        close = proposer,
+        has_one = proposer,
+        constraint = proposal.multisig == multisig.key(),
+        constraint = !proposal.executed && !proposal.cancelled @ MultisigError::AlreadyProcessed,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    /// CHECK: 提案发起人，接收退还的租金
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = multisig,
+        has_one = multisig,
+        constraint = instruction_index.proposal == proposal.key() @ MultisigError::AccountMismatch,
+    )]
+    pub instruction_index: Option<Account<'info, InstructionIndex>>,
+}
+
+// 与 CloseExpired 相同，只是条件改为 proposer 已不在成员列表中
+#[derive(Accounts)]
+pub struct CancelOrphanedProposal<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        close = proposer,
+        has_one = proposer,
+        constraint = proposal.multisig == multisig.key(),
+        constraint = !proposal.executed && !proposal.cancelled @ MultisigError::AlreadyProcessed,
+        constraint = multisig.member_index(&proposal.proposer).is_none()
+            @ MultisigError::ProposerStillMember,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    /// CHECK: 提案发起人，接收退还的租金
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = multisig,
+        has_one = multisig,
+        constraint = instruction_index.proposal == proposal.key() @ MultisigError::AccountMismatch,
+    )]
+    pub instruction_index: Option<Account<'info, InstructionIndex>>,
+}
+
+#[derive(Accounts)]
+pub struct PruneApprovals<'info> {
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        constraint = proposal.multisig == multisig.key(),
+        constraint = !proposal.executed && !proposal.cancelled @ MultisigError::AlreadyProcessed,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+#[derive(Accounts)]
+pub struct PostComment<'info> {
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        constraint = proposal.multisig == multisig.key(),
+        constraint = !proposal.executed && !proposal.cancelled @ MultisigError::AlreadyProcessed,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        init,
+        seeds = [
+            COMMENT_SEED,
+            proposal.key().as_ref(),
+            author.key().as_ref(),
+            &proposal.comments_count.to_le_bytes(),
+        ],
+        bump,
+        payer = author,
+        space = Comment::SPACE
+    )]
+    pub comment: Account<'info, Comment>,
+    #[account(mut)]
+    pub author: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseComment<'info> {
+    #[account(mut, close = author, has_one = proposal, has_one = author)]
+    pub comment: Account<'info, Comment>,
+    /// CHECK: 只要求提案账户已被关闭（执行或取消）
+    #[account(constraint = proposal.data_is_empty() @ MultisigError::ProposalStillOpen)]
+    pub proposal: UncheckedAccount<'info>,
+    /// CHECK: 评论作者，接收退还的租金
+    #[account(mut)]
+    pub author: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u32, size: u32)]
+pub struct CreateBuffer<'info> {
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        init,
+        seeds = [BUFFER_SEED, multisig.key().as_ref(), creator.key().as_ref(), &id.to_le_bytes()],
+        bump,
+        payer = creator,
+        space = InstructionBuffer::space(size)
+    )]
+    pub buffer: Account<'info, InstructionBuffer>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WriteBuffer<'info> {
+    #[account(
+        mut,
+        has_one = creator,
+        constraint = !buffer.finalized @ MultisigError::BufferFinalized,
+    )]
+    pub buffer: Account<'info, InstructionBuffer>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeBuffered<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        constraint = buffer.multisig == multisig.key(),
+        constraint = buffer.finalized @ MultisigError::BufferNotFinalized,
+    )]
+    pub buffer: Account<'info, InstructionBuffer>,
+    #[account(
+        init,
+        seeds = [PROPOSAL_SEED, multisig.key().as_ref(), &multisig.proposals_count.to_le_bytes()],
+        bump,
+        payer = proposer,
+        space = Proposal::space(multisig.max_members)
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        mut,
+        seeds = [MEMBER_STATS_SEED, multisig.key().as_ref(), proposer.key().as_ref()],
+        bump = member_stats.bump,
+    )]
+    pub member_stats: Option<Account<'info, MemberStats>>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseBuffer<'info> {
+    #[account(mut)]
+    pub buffer: Account<'info, InstructionBuffer>,
+    /// CHECK: 只要求提案账户已被关闭（取消或过期）
+    #[account(constraint = proposal.data_is_empty() @ MultisigError::ProposalStillOpen)]
+    pub proposal: UncheckedAccount<'info>,
+}
+
+// 无需许可；租金退还给 multisig
+#[derive(Accounts)]
+pub struct CloseInstructionIndex<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(mut, close = multisig, has_one = multisig, has_one = proposal)]
+    pub instruction_index: Account<'info, InstructionIndex>,
+    /// CHECK: 索引指向的提案；已关闭，或已执行/取消（保留的提案）时才可清理
+    #[account(constraint = !proposal_is_active(&proposal) @ MultisigError::ProposalStillOpen)]
+    pub proposal: UncheckedAccount<'info>,
+}
+
+// propose_buffered 与关闭都写 buffer 账户，运行时按写锁串行执行：
+// 同一 slot 内先关闭则提案失败，先提案则 refs 非空、关闭失败
+#[derive(Accounts)]
+pub struct CloseBuffer<'info> {
+    #[account(
+        mut,
+        close = creator,
+        has_one = creator,
+        constraint = buffer.refs.is_empty() @ MultisigError::BufferInUse,
+    )]
+    pub buffer: Account<'info, InstructionBuffer>,
+    /// CHECK: 缓冲区创建者（租金支付方），接收退还的租金
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+    pub closer: Signer<'info>,
+}
+
+// 无需许可，租金由请求者支付
+#[derive(Accounts)]
+#[instruction(id: u32)]
+pub struct RequestWithdrawal<'info> {
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        init,
+        seeds = [
+            WITHDRAWAL_REQUEST_SEED,
+            multisig.key().as_ref(),
+            requester.key().as_ref(),
+            &id.to_le_bytes(),
+        ],
+        bump,
+        payer = requester,
+        space = WithdrawalRequest::SPACE
+    )]
+    pub request: Account<'info, WithdrawalRequest>,
+    #[account(mut)]
+    pub requester: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// 提案账户与 ProposeTransaction 相同；请求随之关闭，租金退还给请求者
+#[derive(Accounts)]
+pub struct ConvertWithdrawalRequest<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        close = requester,
+        has_one = requester,
+        constraint = request.multisig == multisig.key(),
+    )]
+    pub request: Account<'info, WithdrawalRequest>,
+    /// CHECK: 请求者，接收退还的租金
+    #[account(mut)]
+    pub requester: UncheckedAccount<'info>,
+    #[account(
+        init,
+        seeds = [PROPOSAL_SEED, multisig.key().as_ref(), &multisig.proposals_count.to_le_bytes()],
+        bump,
+        payer = proposer,
+        space = Proposal::space(multisig.max_members)
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        mut,
+        seeds = [MEMBER_STATS_SEED, multisig.key().as_ref(), proposer.key().as_ref()],
+        bump = member_stats.bump,
+    )]
+    pub member_stats: Option<Account<'info, MemberStats>>,
+    /// CHECK: 地址已约束；初始化后必须据此为提案报价协议费，未初始化时为空账户
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump)]
+    pub program_config: UncheckedAccount<'info>,
+    /// CHECK: 代币请求须传入其 mint，在 WithdrawalRequest::instruction 中校验
+    pub mint: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseWithdrawalRequest<'info> {
+    #[account(mut, close = requester, has_one = requester)]
+    pub request: Account<'info, WithdrawalRequest>,
+    /// CHECK: 请求者，接收退还的租金
+    #[account(mut)]
+    pub requester: UncheckedAccount<'info>,
+    pub closer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptMembership<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    pub invitee: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ViewMultisig<'info> {
+    pub multisig: Account<'info, Multisig>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyFreeze<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    pub member: Signer<'info>,
+}
+
+// 无需许可：生效时间在链上校验
+#[derive(Accounts)]
+pub struct ApplyAllowedDestinations<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+}
+
+#[derive(Accounts)]
+pub struct Heartbeat<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    pub member: Signer<'info>,
+}
+
+// 无需许可：超时与下限均在链上校验
+#[derive(Accounts)]
+pub struct MarkInactive<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+}
+
+// 自治指令：由 multisig PDA 通过 execute_transaction 的 invoke_signed 调用，
+// 或由 config_authority 签名
+#[derive(Accounts)]
+pub struct Auth<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    // 清退后 multisig PDA 签名的调用已在提案中按白名单过滤，config_authority 不再可用
+    #[account(
+        constraint = authority.key() == multisig.key()
+            || multisig.config_authority == Some(authority.key()) @ MultisigError::Unauthorized,
+        constraint = authority.key() == multisig.key()
+            || multisig.wound_down_at.is_none() @ MultisigError::WindDownRestricted,
+        constraint = multisig.migrating_to().is_none() @ MultisigError::MigrationInProgress,
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAll<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        constraint = authority.key() == multisig.key()
+            || multisig.config_authority == Some(authority.key())
+            || multisig.guardian == Some(authority.key()) @ MultisigError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+}
+
+// guardian 只能暂停，恢复须经自治
+#[derive(Accounts)]
+pub struct SetVaultPaused<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        constraint = authority.key() == multisig.key()
+            || multisig.config_authority == Some(authority.key())
+            || multisig.guardian == Some(authority.key()) @ MultisigError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+}
+
+// 只能由 multisig PDA 自身签名（config_authority 不可用）
+#[derive(Accounts)]
+pub struct SelfAuth<'info> {
+    #[account(mut, signer)]
+    pub multisig: Account<'info, Multisig>,
+}
+
+// 新多签由新 creator 与 nonce 推导，空间与旧多签相同；payer 通常为提案选中签名的 0 号金库
+#[derive(Accounts)]
+#[instruction(new_creator: Pubkey, nonce: u8)]
+pub struct StartMigration<'info> {
+    #[account(mut, signer)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        init,
+        seeds = [MULTISIG_SEED, new_creator.as_ref(), &[nonce]],
+        bump,
+        payer = payer,
+        space = multisig.to_account_info().data_len()
+    )]
+    pub new_multisig: Account<'info, Multisig>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// 无需许可：资金只能转入新多签同编号的金库
+// remaining_accounts: [Token Program, (token 账户, mint, 新金库的 ATA)...]，无 token 账户时可省略
+#[derive(Accounts)]
+#[instruction(vault_index: u8)]
+pub struct MigrateSweep<'info> {
+    #[account(
+        constraint = multisig.migrating_to() == Some(new_multisig.key())
+            @ MultisigError::NotMigrating,
+    )]
+    pub multisig: Account<'info, Multisig>,
+    pub new_multisig: Account<'info, Multisig>,
+    /// CHECK: 旧多签的金库
+    #[account(mut, seeds = [VAULT_SEED, multisig.key().as_ref(), &[vault_index]], bump)]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: 新多签同编号的金库
+    #[account(mut, seeds = [VAULT_SEED, new_multisig.key().as_ref(), &[vault_index]], bump)]
+    pub new_vault: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// remaining_accounts: 旧多签的全部金库（0..MAX_VAULTS 依次）
+#[derive(Accounts)]
+pub struct FinishMigration<'info> {
+    #[account(
+        mut,
+        constraint = multisig.migrating_to().is_some() @ MultisigError::NotMigrating,
+        constraint = multisig.wound_down_at.is_none() @ MultisigError::AlreadyProcessed,
+    )]
+    pub multisig: Account<'info, Multisig>,
+}
+
+// create_multisig 与 create_multisig_with_proposal 共用的初始化，不发事件
+fn init_multisig(
+    multisig: &mut Account<Multisig>,
+    creator: Pubkey,
+    nonce: u8,
+    members: Vec<Pubkey>,
+    threshold: u8,
+    max_members: u8,
+    bump: u8,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    multisig.creator = creator;
+    multisig.nonce = nonce;
+    multisig.members = initial_members(members, threshold, max_members, now)?;
+    multisig.threshold = threshold;
+    multisig.proposals_count = 0;
+    multisig.bump = bump;
+    multisig.config_seqno = 0;
+    multisig.threshold_overrides = vec![];
+    multisig.amount_rules = vec![];
+    multisig.frozen_by = None;
+    multisig.frozen_at = 0;
+    multisig.last_freezer = None;
+    multisig.unfrozen_at = 0;
+    multisig.pending_invites = vec![];
+    multisig.config_authority = None;
+    multisig.paused = false;
+    multisig.quorum = None;
+    multisig.recent_executed = 0;
+    multisig.guardian = None;
+    multisig.execute_against_snapshot = false;
+    multisig.timelock_seconds = 0;
+    multisig.urgent_timelock_seconds = 0;
+    multisig.urgent_threshold = None;
+    multisig.min_execution_delay_slots = 0;
+    multisig.max_members = max_members;
+    multisig.executed_count = 0;
+    multisig.cancelled_count = 0;
+    multisig.last_activity = now;
+    multisig.controlled_accounts = vec![];
+    multisig.inactivity_timeout = None;
+    multisig.liveness_floor = 0;
+    multisig.wound_down_at = None;
+    multisig.total_lamports_out = 0;
+    multisig.approval_groups = vec![];
+    multisig.config_requires_unanimity = false;
+    multisig.trusted_destinations = vec![];
+    multisig.destination_limits = vec![];
+    multisig.allowed_destinations = vec![];
+    multisig.allowlist_delay_seconds = 0;
+    multisig.pending_allowlist = None;
+    multisig.denied_destinations = vec![];
+    multisig.vaults = vec![];
+    multisig.retain_executed = false;
+    multisig.retention_seconds = 0;
+    multisig.executor_grace_seconds = DEFAULT_EXECUTOR_GRACE_SECONDS;
+    multisig.approval_ttl_seconds = None;
+    multisig.extensions = vec![];
+    Ok(())
+}
+
+// ===== Program Logic =====
+
+#[program]
+pub mod multisig {
+    use super::*;
+
+    pub fn create_multisig(
+        ctx: Context<CreateMultisig>,
+        nonce: u8, // used in seeds, not in logic
+        members: Vec<Pubkey>,
+        threshold: u8,
+        max_members: u8, // 决定账户空间，之后的成员变更不可超过
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let creator = ctx.accounts.creator.key();
+        let bump = ctx.bumps.multisig;
+        init_multisig(multisig, creator, nonce, members, threshold, max_members, bump)?;
+
+        emit!(MultisigCreated {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig.key(),
+            creator,
+            members: multisig.members.len() as u8,
+            threshold,
+            program_version: PROGRAM_VERSION.to_string(),
+            timestamp: multisig.last_activity,
+        });
+        Ok(())
+    }
+
+    // 按旧 serum multisig 的 owners 与 threshold 新建多签，其余设置取默认值；旧账户不受影响
+    pub fn import_multisig(ctx: Context<ImportMultisig>, nonce: u8, max_members: u8) -> Result<()> {
+        let legacy = legacy::parse_legacy_multisig(&ctx.accounts.source.try_borrow_data()?)
+            .ok_or(MultisigError::InvalidLegacyMultisig)?;
+        let threshold =
+            u8::try_from(legacy.threshold).map_err(|_| MultisigError::InvalidThreshold)?;
+        let multisig = &mut ctx.accounts.multisig;
+        let creator = ctx.accounts.creator.key();
+        let bump = ctx.bumps.multisig;
+        init_multisig(multisig, creator, nonce, legacy.owners, threshold, max_members, bump)?;
+
+        emit!(MultisigImported {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig.key(),
+            source: ctx.accounts.source.key(),
+            creator,
+            members: multisig.members.len() as u8,
+            threshold,
+            owner_set_seqno: legacy.owner_set_seqno,
+            timestamp: multisig.last_activity,
+        });
+        Ok(())
+    }
+
+    // 创建多签并由创建者提交 0 号提案，两者在同一条指令内要么都成功要么都不存在
+    pub fn create_multisig_with_proposal(
+        ctx: Context<CreateMultisigWithProposal>,
+        nonce: u8,
+        members: Vec<Pubkey>,
+        threshold: u8,
+        max_members: u8,
+        instruction_data: InstructionData,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        let creator = accounts.creator.key();
+        let bump = ctx.bumps.multisig;
+        let multisig = &mut accounts.multisig;
+        init_multisig(multisig, creator, nonce, members, threshold, max_members, bump)?;
+        let proposal = init_proposal(
+            &mut accounts.multisig,
+            &mut accounts.proposal,
+            None,
+            load_program_config(&accounts.program_config)?.as_ref(),
+            creator,
+            ctx.bumps.proposal,
+            ProposalAction::Raw(instruction_data),
+            options,
+        )?;
+
+        let multisig = &accounts.multisig;
+        emit!(MultisigCreatedWithProposal {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig.key(),
+            creator,
+            members: multisig.members.len() as u8,
+            threshold,
+            program_version: PROGRAM_VERSION.to_string(),
+            timestamp: proposal.timestamp,
+            proposal,
+        });
+        if accounts.proposal.metadata_uri.is_some() {
+            emit_metadata_set(&accounts.proposal, multisig.last_activity);
+        }
+        Ok(())
+    }
+
+    // 第一个提案创建前，创建者可修正初始成员与阈值；之后窗口永久关闭
+    pub fn update_config_bootstrap(
+        ctx: Context<UpdateConfigBootstrap>,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let now = Clock::get()?.unix_timestamp;
+        require!(multisig.proposals_count == 0, MultisigError::BootstrapClosed);
+        let old_members: Vec<Pubkey> = multisig.members.iter().map(|m| m.key).collect();
+        multisig.members = initial_members(members, threshold, multisig.max_members, now)?;
+        multisig.threshold = threshold;
+        multisig.rebase_groups(&old_members);
+        multisig.validate_thresholds(now)?;
+        multisig.config_seqno += 1;
+        multisig.last_activity = now;
+        Ok(())
+    }
+
+    // 只能扩容，新增区域清零（之后追加的字段反序列化为零值）；
+    // 多出的空间同时提高成员容量，上限为 MAX_MEMBERS
+    pub fn resize_multisig(ctx: Context<ResizeMultisig>, new_size: u16) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        require!(
+            multisig.to_account_info().data_len() == new_size as usize,
+            MultisigError::InvalidResize
+        );
+        let capacity = (new_size as usize)
+            .saturating_sub(Multisig::BASE_SPACE + multisig.extension_overflow())
+            / Multisig::MEMBER_SPACE;
+        let capacity = capacity.min(MAX_MEMBERS) as u8;
+        if capacity > multisig.max_members {
+            msg!("max_members {} -> {}", multisig.max_members, capacity);
+            multisig.max_members = capacity;
+        }
+        Ok(())
+    }
+
+    pub fn propose_transaction(
+        ctx: Context<ProposeTransaction>,
+        instruction_data: InstructionData,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        create_indexed_proposal(
+            accounts,
+            ctx.bumps.proposal,
+            ProposalAction::Raw(instruction_data),
+            options,
+        )
+    }
+
+    pub fn propose_action(
+        ctx: Context<ProposeTransaction>,
+        action: ProposalAction,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        ctx.accounts
+            .multisig
+            .validate_action(&action, Clock::get()?.unix_timestamp)?;
+        let accounts = ctx.accounts;
+        create_indexed_proposal(accounts, ctx.bumps.proposal, action, options)
+    }
+
+    // 接受 Squads VaultTransactionMessage 格式的编译消息，还原为单条指令；
+    // 多指令消息需在客户端拆分后逐条提案
+    pub fn propose_compiled(
+        ctx: Context<ProposeTransaction>,
+        message: Vec<u8>,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        let mut instructions = from_compiled_message(&message, &ctx.accounts.multisig.key())?;
+        require!(instructions.len() == 1, MultisigError::CompiledInstructionCount);
+
+        let accounts = ctx.accounts;
+        create_indexed_proposal(
+            accounts,
+            ctx.bumps.proposal,
+            ProposalAction::Raw(instructions.remove(0)),
+            options,
+        )
+    }
+
+    // 一次批准整个分期计划；激活后各期到期即可无需投票地领取
+    pub fn propose_vesting(
+        ctx: Context<ProposeTransaction>,
+        mint: Pubkey,
+        recipient: Pubkey,
+        tranches: Vec<VestingTranche>,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        require!(
+            !tranches.is_empty()
+                && tranches.len() <= MAX_VESTING_TRANCHES
+                && tranches.iter().all(|t| t.amount > 0),
+            MultisigError::InvalidVestingSchedule
+        );
+        // 仅记录的提案激活后不应放款
+        require!(!options.no_sign, MultisigError::InvalidVestingSchedule);
+
+        let accounts = ctx.accounts;
+        create_indexed_proposal(
+            accounts,
+            ctx.bumps.proposal,
+            ProposalAction::Vesting(VestingSchedule {
+                mint,
+                recipient,
+                tranches,
+            }),
+            options,
+        )
+    }
+
+    // 新 mint 地址在执行时才确定，见 ProposalAction::CreateMint
+    pub fn propose_create_mint(
+        ctx: Context<ProposeTransaction>,
+        decimals: u8,
+        freeze_authority: Option<Pubkey>,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        create_indexed_proposal(
+            accounts,
+            ctx.bumps.proposal,
+            ProposalAction::CreateMint {
+                decimals,
+                freeze_authority,
+            },
+            options,
+        )
+    }
+
+    pub fn propose_set_token_authority(
+        ctx: Context<ProposeTransaction>,
+        target: Pubkey,
+        authority_type: u8,
+        new_authority: Option<Pubkey>,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        require!(authority_type <= 3, MultisigError::InvalidAuthorityType);
+        // token 账户必须有 owner
+        require!(
+            authority_type != 2 || new_authority.is_some(),
+            MultisigError::InvalidAuthorityType
+        );
+
+        let accounts = ctx.accounts;
+        create_indexed_proposal(
+            accounts,
+            ctx.bumps.proposal,
+            ProposalAction::SetTokenAuthority {
+                target,
+                authority_type,
+                new_authority,
+            },
+            options,
+        )
+    }
+
+    pub fn propose_burn(
+        ctx: Context<ProposeTransaction>,
+        mint: Pubkey,
+        amount: u64,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        require!(amount > 0, MultisigError::InvalidBurnAmount);
+
+        let accounts = ctx.accounts;
+        create_indexed_proposal(
+            accounts,
+            ctx.bumps.proposal,
+            ProposalAction::Burn { mint, amount },
+            options,
+        )
+    }
+
+    pub fn propose_vote_authorize(
+        ctx: Context<ProposeTransaction>,
+        vote_account: Pubkey,
+        new_authority: Pubkey,
+        authority_type: u8,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        require!(authority_type <= 1, MultisigError::InvalidAuthorityType);
+
+        let accounts = ctx.accounts;
+        create_indexed_proposal(
+            accounts,
+            ctx.bumps.proposal,
+            ProposalAction::VoteAuthorize {
+                vote_account,
+                new_authority,
+                authority_type,
+            },
+            options,
+        )
+    }
+
+    pub fn propose_vote_withdraw(
+        ctx: Context<ProposeTransaction>,
+        vote_account: Pubkey,
+        lamports: u64,
+        destination: Pubkey,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        create_indexed_proposal(
+            accounts,
+            ctx.bumps.proposal,
+            ProposalAction::VoteWithdraw {
+                vote_account,
+                lamports,
+                destination,
+            },
+            options,
+        )
+    }
+
+    pub fn propose_stake_split(
+        ctx: Context<ProposeTransaction>,
+        stake_account: Pubkey,
+        lamports: u64,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        require!(lamports > 0, MultisigError::InvalidStakeAmount);
+
+        let accounts = ctx.accounts;
+        create_indexed_proposal(
+            accounts,
+            ctx.bumps.proposal,
+            ProposalAction::StakeSplit {
+                stake_account,
+                lamports,
+            },
+            options,
+        )
+    }
+
+    pub fn propose_stake_deactivate(
+        ctx: Context<ProposeTransaction>,
+        stake_account: Pubkey,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        create_indexed_proposal(
+            accounts,
+            ctx.bumps.proposal,
+            ProposalAction::StakeDeactivate(stake_account),
+            options,
+        )
+    }
+
+    pub fn propose_stake_withdraw(
+        ctx: Context<ProposeTransaction>,
+        stake_account: Pubkey,
+        lamports: u64,
+        destination: Pubkey,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        require!(lamports > 0, MultisigError::InvalidStakeAmount);
+
+        let accounts = ctx.accounts;
+        create_indexed_proposal(
+            accounts,
+            ctx.bumps.proposal,
+            ProposalAction::StakeWithdraw {
+                stake_account,
+                lamports,
+                destination,
+            },
+            options,
+        )
+    }
+
+    pub fn propose_take_custody(
+        ctx: Context<ProposeTransaction>,
+        account: Pubkey,
+        kind: CustodyKind,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        let action = ProposalAction::TakeCustody { account, kind };
+        ctx.accounts
+            .multisig
+            .validate_action(&action, Clock::get()?.unix_timestamp)?;
+        let accounts = ctx.accounts;
+        create_indexed_proposal(accounts, ctx.bumps.proposal, action, options)
+    }
+
+    pub fn propose_release_custody(
+        ctx: Context<ProposeTransaction>,
+        account: Pubkey,
+        kind: CustodyKind,
+        new_authority: Pubkey,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        create_indexed_proposal(
+            accounts,
+            ctx.bumps.proposal,
+            ProposalAction::ReleaseCustody {
+                account,
+                kind,
+                new_authority,
+            },
+            options,
+        )
+    }
+
+    // remaining_accounts[0] 须为 mint：创建时即核对 mint authority 并估算增发后的供应量
+    pub fn propose_mint_to(
+        ctx: Context<ProposeTransaction>,
+        mint: Pubkey,
+        destination_owner: Pubkey,
+        amount: u64,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        require!(amount > 0, MultisigError::InvalidMintAmount);
+        let mint_info = ctx
+            .remaining_accounts
+            .first()
+            .ok_or(MultisigError::AccountMismatch)?;
+        require_keys_eq!(*mint_info.key, mint, MultisigError::AccountMismatch);
+        require!(is_token_program(mint_info.owner), MultisigError::AccountMismatch);
+
+        let accounts = ctx.accounts;
+        let multisig_key = accounts.multisig.key();
+        // Mint.mint_authority COption @0，supply @36
+        let (authority, supply) = {
+            let data = mint_info.try_borrow_data()?;
+            (decode::read_coption_pubkey(&data, 0), decode::read_u64(&data, 36))
+        };
+        if authority != Some(multisig_key) {
+            msg!("Mint {} is not mintable by {}", mint, multisig_key);
+            return err!(MultisigError::MintAuthorityMismatch);
+        }
+        let estimated_supply = supply
+            .ok_or(MultisigError::AccountMismatch)?
+            .checked_add(amount)
+            .ok_or(MultisigError::InvalidMintAmount)?;
+
+        create_indexed_proposal(
+            accounts,
+            ctx.bumps.proposal,
+            ProposalAction::MintTo {
+                mint,
+                destination_owner,
+                amount,
+            },
+            options,
+        )?;
+        emit!(MintToProposed {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig_key,
+            proposal: accounts.proposal.key(),
+            mint,
+            recipient: destination_owner,
+            destination: find_associated_token_address(&destination_owner, &mint, mint_info.owner),
+            amount,
+            estimated_supply,
+        });
+        Ok(())
+    }
+
+    pub fn propose_freeze_token_account(
+        ctx: Context<ProposeTransaction>,
+        token_account: Pubkey,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        create_indexed_proposal(
+            accounts,
+            ctx.bumps.proposal,
+            ProposalAction::FreezeTokenAccount(token_account),
+            options,
+        )
+    }
+
+    pub fn propose_thaw_token_account(
+        ctx: Context<ProposeTransaction>,
+        token_account: Pubkey,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        create_indexed_proposal(
+            accounts,
+            ctx.bumps.proposal,
+            ProposalAction::ThawTokenAccount(token_account),
+            options,
+        )
+    }
+
+    pub fn propose_token_approve(
+        ctx: Context<ProposeTransaction>,
+        token_account: Pubkey,
+        delegate: Pubkey,
+        amount: u64,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        create_indexed_proposal(
+            accounts,
+            ctx.bumps.proposal,
+            ProposalAction::TokenApprove {
+                token_account,
+                delegate,
+                amount,
+            },
+            options,
+        )
+    }
+
+    // 不指定 delegate：撤销时的当前 delegate 是谁都可以
+    pub fn propose_token_revoke(
+        ctx: Context<ProposeTransaction>,
+        token_account: Pubkey,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        create_indexed_proposal(
+            accounts,
+            ctx.bumps.proposal,
+            ProposalAction::TokenRevoke(token_account),
+            options,
+        )
+    }
+
+    // 一次批准固定收款方与金额的周期付款
+    pub fn propose_recurring_payment(
+        ctx: Context<ProposeTransaction>,
+        payment: RecurringPayment,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        require!(
+            payment.amount > 0 && payment.interval_seconds > 0 && payment.max_payments > 0,
+            MultisigError::InvalidRecurringPayment
+        );
+        require!(!options.no_sign, MultisigError::InvalidRecurringPayment);
+
+        let accounts = ctx.accounts;
+        create_indexed_proposal(
+            accounts,
+            ctx.bumps.proposal,
+            ProposalAction::Recurring(payment),
+            options,
+        )
+    }
+
+    // 指令数据来自已 finalize 的缓冲区，提案只保存引用与哈希
+    pub fn propose_buffered(
+        ctx: Context<ProposeBuffered>,
+        instruction: BufferedInstruction,
+        options: ProposalOptions,
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        let buffer = &mut accounts.buffer;
+        require_keys_eq!(instruction.buffer, buffer.key(), MultisigError::AccountMismatch);
+        require!(instruction.hash == buffer.hash, MultisigError::BufferHashMismatch);
+        require!(buffer.refs.len() < MAX_BUFFER_REFS, MultisigError::TooManyBufferRefs);
+        buffer.refs.push(accounts.proposal.key());
+
+        create_proposal(
+            &mut accounts.multisig,
+            &mut accounts.proposal,
+            accounts.member_stats.as_mut(),
+            None, // 缓冲区指令在创建时无法解码，不报价
+            accounts.proposer.key(),
+            ctx.bumps.proposal,
+            ProposalAction::Buffered(instruction),
+            options,
+        )
+    }
+
+    // 复制仍未关闭的提案内容到下一个索引，需重新收集批准
+    pub fn reclone_proposal(ctx: Context<RecloneProposal>, options: ProposalOptions) -> Result<()> {
+        let action = ctx.accounts.source.action.clone();
+        // 新提案需登记到缓冲区的引用列表，只能走 propose_buffered
+        require!(
+            !matches!(action, ProposalAction::Buffered(_)),
+            MultisigError::BufferedReclone
+        );
+        if !matches!(action, ProposalAction::Raw(_) | ProposalAction::Batch(_)) {
+            ctx.accounts
+                .multisig
+                .validate_action(&action, Clock::get()?.unix_timestamp)?;
+        }
+
+        let accounts = ctx.accounts;
+        create_proposal(
+            &mut accounts.multisig,
+            &mut accounts.proposal,
+            accounts.member_stats.as_mut(),
+            load_program_config(&accounts.program_config)?.as_ref(),
+            accounts.proposer.key(),
+            ctx.bumps.proposal,
+            action,
+            options,
+        )
+    }
+
+    // 审批人是按这份文档批准的，因此出现赞成票后不可再换
+    pub fn set_proposal_metadata(
+        ctx: Context<SetProposalMetadata>,
+        metadata_uri: Option<String>,
+        metadata_hash: [u8; 32],
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        require!(proposal.approvals == 0, MultisigError::MetadataLocked);
+        check_metadata_uri(&metadata_uri)?;
+        proposal.metadata_uri = metadata_uri;
+        proposal.metadata_hash = metadata_hash;
+        emit_metadata_set(proposal, Clock::get()?.unix_timestamp);
+        Ok(())
+    }
+
+    pub fn create_member_stats(ctx: Context<CreateMemberStats>, member: Pubkey) -> Result<()> {
+        require!(
+            ctx.accounts.multisig.member_index(&member).is_some(),
+            MultisigError::NotMember
+        );
+        let stats = &mut ctx.accounts.member_stats;
+        stats.multisig = ctx.accounts.multisig.key();
+        stats.member = member;
+        stats.proposals_created = 0;
+        stats.approvals_given = 0;
+        stats.rejections_given = 0;
+        stats.last_vote_at = 0;
+        stats.bump = ctx.bumps.member_stats;
+        Ok(())
+    }
+
+    pub fn create_member_metadata(
+        ctx: Context<CreateMemberMetadata>,
+        member: Pubkey,
+        label: String,
+        notification_key: Option<Pubkey>,
+        contact_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.multisig.member_index(&member).is_some(),
+            MultisigError::NotMember
+        );
+        let metadata = &mut ctx.accounts.metadata;
+        metadata.multisig = ctx.accounts.multisig.key();
+        metadata.member = member;
+        metadata.payer = ctx.accounts.payer.key();
+        metadata.bump = ctx.bumps.metadata;
+        let updated_by = ctx.accounts.authority.key();
+        write_member_metadata(metadata, label, notification_key, contact_hash, updated_by)
+    }
+
+    pub fn update_member_metadata(
+        ctx: Context<UpdateMemberMetadata>,
+        label: String,
+        notification_key: Option<Pubkey>,
+        contact_hash: [u8; 32],
+    ) -> Result<()> {
+        let metadata = &mut ctx.accounts.metadata;
+        require!(
+            ctx.accounts.multisig.member_index(&metadata.member).is_some(),
+            MultisigError::NotMember
+        );
+        let updated_by = ctx.accounts.authority.key();
+        write_member_metadata(metadata, label, notification_key, contact_hash, updated_by)
+    }
+
+    pub fn close_member_metadata(ctx: Context<CloseMemberMetadata>) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let member = ctx.accounts.metadata.member;
+        let closer = ctx.accounts.closer.key();
+        let still_member = multisig.member_index(&member).is_some();
+        require!(
+            !still_member
+                || closer == member
+                || closer == multisig.key()
+                || multisig.config_authority == Some(closer),
+            MultisigError::Unauthorized
+        );
+        emit!(MemberMetadataClosed {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig.key(),
+            member,
+            still_member,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn create_spend_ledger(ctx: Context<CreateSpendLedger>) -> Result<()> {
+        let ledger = &mut ctx.accounts.spend_ledger;
+        ledger.multisig = ctx.accounts.multisig.key();
+        ledger.payer = ctx.accounts.payer.key();
+        ledger.mints = vec![];
+        ledger.bump = ctx.bumps.spend_ledger;
+        Ok(())
+    }
+
+    // 关闭后可重新创建，各 mint 计数从零开始；total_lamports_out 不受影响
+    pub fn close_spend_ledger(_ctx: Context<CloseSpendLedger>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn init_program_config(
+        ctx: Context<InitProgramConfig>,
+        fee_recipient: Pubkey,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.program_config;
+        config.authority = ctx.accounts.authority.key();
+        config.fee_bps = 0;
+        config.flat_fee_lamports = 0;
+        config.fee_recipient = fee_recipient;
+        config.bump = ctx.bumps.program_config;
+        emit_program_config(config)
+    }
+
+    // 只影响之后创建的提案，已报价的提案按原报价收取
+    pub fn update_program_config(
+        ctx: Context<UpdateProgramConfig>,
+        fee_bps: u16,
+        flat_fee_lamports: u64,
+        fee_recipient: Pubkey,
+        new_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        require!(
+            fee_bps <= MAX_PROTOCOL_FEE_BPS && flat_fee_lamports <= MAX_FLAT_FEE_LAMPORTS,
+            MultisigError::InvalidProtocolFee
+        );
+        let config = &mut ctx.accounts.program_config;
+        config.fee_bps = fee_bps;
+        config.flat_fee_lamports = flat_fee_lamports;
+        config.fee_recipient = fee_recipient;
+        if let Some(authority) = new_authority {
+            config.authority = authority;
+        }
+        emit_program_config(config)
+    }
+
+    pub fn opt_out_protocol_fee(ctx: Context<OptOutProtocolFee>) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let data_len = multisig.to_account_info().data_len();
+        multisig.set_extension(extension::TAG_NO_PROTOCOL_FEE, &[1], data_len)
+    }
+
+    // expected_hash 为审批人核对过的 action.hash()，防止批准到内容不同的提案
+    pub fn approve_transaction(
+        ctx: Context<ApproveTransaction>,
+        expected_hash: Option<[u8; 32]>,
+    ) -> Result<()> {
+        if let Some(expected) = expected_hash {
+            require!(
+                ctx.accounts.proposal.action.hash()? == expected,
+                MultisigError::InstructionHashMismatch
+            );
+        }
+        cast_vote(ctx, Vote::Approve, None)
+    }
+
+    // 供自动重试使用：重复批准视为成功，不让同一交易中的其他指令失败
+    pub fn approve_idempotent(ctx: Context<ApproveTransaction>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let member = ctx.accounts.approver.key();
+        let multisig = &ctx.accounts.multisig;
+        let index = multisig.active_member_index(&member, now)?;
+        // 已过期的批准不算存在，照常刷新
+        let fresh = ctx.accounts.proposal.fresh_approvals(multisig.approval_ttl_seconds, now);
+        if fresh & (1u32 << index) != 0 {
+            emit!(ApprovalAlreadyPresent {
+                schema_version: EVENT_SCHEMA_VERSION,
+                multisig: ctx.accounts.multisig.key(),
+                proposal: ctx.accounts.proposal.key(),
+                member,
+                timestamp: now,
+            });
+            return Ok(());
+        }
+        cast_vote(ctx, Vote::Approve, None)
+    }
+
+    // 理由保存在提案上并随 ProposalRejected 事件发出，便于发起人据此修改
+    pub fn reject_transaction(
+        ctx: Context<ApproveTransaction>,
+        code: u8,
+        reason: String,
+    ) -> Result<()> {
+        require!(
+            reason.len() <= MAX_REJECTION_REASON_LEN,
+            MultisigError::InvalidRejectionReason
+        );
+        cast_vote(ctx, Vote::Reject, Some((code, reason)))
+    }
+
+    pub fn abstain_transaction(ctx: Context<ApproveTransaction>) -> Result<()> {
+        cast_vote(ctx, Vote::Abstain, None)
+    }
+
+    // 紧邻的前一条指令须为 secp256k1 预编译验签，消息为 eth_approval_message；任何人可代为提交
+    pub fn approve_with_eth_signature(
+        ctx: Context<ApproveWithEthSignature>,
+        expiry: i64,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        require!(clock.unix_timestamp <= expiry, MultisigError::EthSignatureExpired);
+
+        let instructions = ctx.accounts.instructions.to_account_info();
+        let current = load_current_index_checked(&instructions)?;
+        let secp_index = current.checked_sub(1).ok_or(MultisigError::InvalidEthSignature)?;
+        let secp = load_instruction_at_checked(secp_index as usize, &instructions)?;
+        require!(secp.program_id == SECP256K1_PROGRAM_ID, MultisigError::InvalidEthSignature);
+        let (address, message) = u8::try_from(secp_index)
+            .ok()
+            .and_then(|index| read_secp256k1_signature(&secp.data, index))
+            .ok_or(MultisigError::InvalidEthSignature)?;
+
+        let multisig = &mut ctx.accounts.multisig;
+        let proposal = &mut ctx.accounts.proposal;
+        let expected = eth_approval_message(
+            &multisig.key(),
+            &proposal.key(),
+            &proposal.action.hash()?,
+            expiry,
+        );
+        require!(message == expected.as_slice(), MultisigError::InvalidEthSignature);
+
+        let key = eth_member_key(&address);
+        let now = clock.unix_timestamp;
+        let index = multisig.active_member_of_type(&key, KeyType::Secp256k1, now)?;
+        record_vote(multisig, proposal, index, Vote::Approve, &clock, ctx.remaining_accounts)
+    }
+
+    pub fn execute_transaction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteTransaction<'info>>,
+    ) -> Result<()> {
+        #[cfg(feature = "log-compute-units")]
+        sol_log_compute_units();
+        let accounts = ctx.accounts;
+        let now = Clock::get()?.unix_timestamp;
+        let executor = accounts.executor.as_ref().map(|e| e.key());
+        accounts.proposal.check_executor(executor, &accounts.multisig, now)?;
+        let retain = accounts.multisig.retain_executed;
+        execute_proposal(
+            &mut accounts.multisig,
+            &mut accounts.proposal,
+            accounts.spend_ledger.as_deref_mut(),
+            ctx.remaining_accounts,
+            now,
+        )?;
+        retire_executed(&accounts.multisig, &mut accounts.proposal, retain, executor)
+    }
+
+    // 按 execute_transaction 的判定给出状态，keeper 可模拟调用以决定是否尝试执行；
+    // 不检查指定执行人
+    pub fn get_proposal_status<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetProposalStatus<'info>>,
+    ) -> Result<ProposalStatusView> {
+        let now = Clock::get()?.unix_timestamp;
+        let multisig = &ctx.accounts.multisig;
+        let mut proposal = Proposal::clone(&ctx.accounts.proposal);
+        // ready_at 记录的是达到生效阈值的时间；批准过期或配置变化后跌回阈值以下时不再给出 ETA
+        let view = |status, approvals: u32, threshold: u8, proposal: &Proposal| ProposalStatusView {
+            status,
+            approvals: approvals as u8,
+            threshold,
+            ready_at: proposal.ready_at.filter(|_| approvals >= threshold as u32).map(|t| {
+                let executable_at = t + proposal.delay_seconds as i64;
+                proposal.not_before.map_or(executable_at, |n| executable_at.max(n))
+            }),
+            expires_at: proposal.not_after,
+        };
+        if proposal.executed || proposal.cancelled {
+            let status = if proposal.executed {
+                ProposalStatus::Executed
+            } else {
+                ProposalStatus::Cancelled
+            };
+            return Ok(view(status, proposal.approvals.count_ones(), multisig.threshold, &proposal));
+        }
+
+        let account_infos = match &proposal.action {
+            ProposalAction::Buffered(buffered) => {
+                let (_, ix, rest) = read_buffered(buffered, ctx.remaining_accounts)?;
+                proposal.action = ProposalAction::Raw(ix);
+                rest
+            }
+            _ => ctx.remaining_accounts,
+        };
+        let (account_infos, _) = split_memo_accounts(multisig, &proposal, account_infos)?;
+        let verdict = judge(multisig, &proposal, account_infos, now, true)?;
+        let status = match verdict.blocked {
+            None => ProposalStatus::Ready,
+            Some((status, _)) => status,
+        };
+        Ok(view(status, verdict.approvals, verdict.threshold, &proposal))
+    }
+
+    // 执行失败时整笔交易回滚，计数无法在 execute_transaction 中累加，改由本指令单独记录：
+    // 只有提案已可执行、且按当前账户状态执行必然失败时才成功，否则返回对应的错误
+    pub fn record_failed_execution<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RecordFailedExecution<'info>>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let now = clock.unix_timestamp;
+        let multisig = &ctx.accounts.multisig;
+        let mut proposal = Proposal::clone(&ctx.accounts.proposal);
+        let account_infos = match &proposal.action {
+            ProposalAction::Buffered(buffered) => {
+                let (_, ix, rest) = read_buffered(buffered, ctx.remaining_accounts)?;
+                proposal.action = ProposalAction::Raw(ix);
+                rest
+            }
+            _ => ctx.remaining_accounts,
+        };
+        let (account_infos, _) = split_memo_accounts(multisig, &proposal, account_infos)?;
+        // 余额由 execution_failure 检查，其余条件须全部满足
+        if let Some((_, error)) = judge(multisig, &proposal, account_infos, now, false)?.blocked {
+            return Err(error);
+        }
+        if proposal.no_sign {
+            return err!(MultisigError::ExecutionNotFailing);
+        }
+        let reason = match proposal.action.instructions().split_first() {
+            Some((first, rest)) => {
+                let protected = !proposal.allow_dangerous;
+                let mut reason = execution_failure(first, &multisig.key(), account_infos, protected)?;
+                // 后续指令的账户状态可能由前序指令改变（如先创建再初始化），只检查签名标记
+                let mut offset = first.accounts.len();
+                for ix in rest {
+                    if reason.is_some() {
+                        break;
+                    }
+                    let infos = account_infos.get(offset..).unwrap_or_default();
+                    reason = signer_mismatch(ix, &multisig.key(), infos)?;
+                    offset += ix.accounts.len();
+                }
+                reason
+            }
+            None => typed_execution_failure(&proposal.action, &multisig.key(), account_infos)?,
+        };
+        let reason = reason.ok_or(MultisigError::ExecutionNotFailing)?;
+
+        let max_attempts = multisig.max_execution_attempts();
+        let proposal = &mut ctx.accounts.proposal;
+        // 同一笔交易或同一 slot 内重复调用只计一次
+        require!(
+            proposal.last_failed_slot != clock.slot,
+            MultisigError::FailureAlreadyRecorded
+        );
+        proposal.last_failed_slot = clock.slot;
+        proposal.execution_attempts = proposal.execution_attempts.saturating_add(1);
+        emit!(ExecutionFailureRecorded {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig.key(),
+            proposal: proposal.key(),
+            index: proposal.index,
+            reason,
+            attempts: proposal.execution_attempts,
+            cancellable: max_attempts.is_some_and(|max| proposal.execution_attempts > max),
+            reporter: ctx.accounts.reporter.key(),
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    // 向 0 号金库存入 SOL 并记录存入者；不修改多签或提案状态
+    pub fn deposit(ctx: Context<Deposit>, amount: u64, memo: Option<String>) -> Result<()> {
+        check_deposit(amount, &memo)?;
+        let accounts = &ctx.accounts;
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: accounts.depositor.to_account_info(),
+                    to: accounts.vault.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        emit!(DepositReceived {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: accounts.multisig.key(),
+            vault: accounts.vault.key(),
+            depositor: accounts.depositor.key(),
+            mint: None,
+            amount,
+            memo,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    // 只发出事件；retain 时另把摘要写入该金库的快照 PDA（覆盖上一次）
+    pub fn snapshot_balances<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SnapshotBalances<'info>>,
+        vault_index: u8,
+        retain: bool,
+    ) -> Result<()> {
+        let accounts = &ctx.accounts;
+        let vault = accounts.vault.key();
+        require!(
+            ctx.remaining_accounts.len() <= MAX_SNAPSHOT_ACCOUNTS,
+            MultisigError::TooManySnapshotAccounts
+        );
+        let mut seen: Vec<&Pubkey> = Vec::with_capacity(ctx.remaining_accounts.len());
+        let mut balances: Vec<MintBalance> = vec![];
+        for info in ctx.remaining_accounts {
+            // 重复传入同一账户会重复计数；不属于金库或无法解析的账户一律拒绝
+            require!(!seen.contains(&info.key), MultisigError::InvalidSnapshotAccount);
+            seen.push(info.key);
+            let account = read_token_account(info).ok_or(MultisigError::InvalidSnapshotAccount)?;
+            if account.owner != vault {
+                msg!("Token account {} is owned by {}", info.key, account.owner);
+                return err!(MultisigError::InvalidSnapshotAccount);
+            }
+            match balances.iter_mut().find(|b| b.mint == account.mint) {
+                Some(balance) => {
+                    balance.amount = balance.amount.saturating_add(account.amount);
+                    balance.accounts += 1;
+                }
+                None => balances.push(MintBalance {
+                    mint: account.mint,
+                    amount: account.amount,
+                    accounts: 1,
+                }),
+            }
+        }
+        balances.sort_by_key(|b| b.mint);
+
+        let clock = Clock::get()?;
+        let multisig = accounts.multisig.key();
+        let lamports = accounts.vault.lamports();
+        if retain {
+            let (Some(snapshot), Some(payer), Some(system_program)) =
+                (&accounts.snapshot, &accounts.payer, &accounts.system_program)
+            else {
+                return err!(MultisigError::AccountMismatch);
+            };
+            // 保留的记录会覆盖上一次，只允许成员写入，避免他人用不完整的账户列表覆盖
+            accounts
+                .multisig
+                .active_member_index(&payer.key(), clock.unix_timestamp)?;
+            let bump = ctx.bumps.snapshot.ok_or(MultisigError::AccountMismatch)?;
+            if snapshot.data_is_empty() {
+                let seeds: &[&[u8]] = &[SNAPSHOT_SEED, multisig.as_ref(), &[vault_index], &[bump]];
+                create_pda_account(
+                    snapshot,
+                    payer,
+                    system_program,
+                    seeds,
+                    BalanceSnapshotRecord::SPACE,
+                )?;
+            }
+            let record = BalanceSnapshotRecord {
+                multisig,
+                vault_index,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+                lamports,
+                mints: balances.len() as u8,
+                digest: hashv(&[&balances.try_to_vec()?]).to_bytes(),
+                bump,
+            };
+            record.try_serialize(&mut &mut snapshot.try_borrow_mut_data()?[..])?;
+        }
+
+        emit!(BalanceSnapshot {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig,
+            vault,
+            vault_index,
+            lamports,
+            balances,
+            retained: retain,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    // 存入 0 号金库的 ATA；ATA 须已创建
+    pub fn deposit_token(
+        ctx: Context<DepositToken>,
+        amount: u64,
+        memo: Option<String>,
+    ) -> Result<()> {
+        check_deposit(amount, &memo)?;
+        let accounts = &ctx.accounts;
+        let (mint, token_program) = (accounts.mint.key(), accounts.token_program.key());
+        require_keys_eq!(
+            accounts.destination.key(),
+            find_associated_token_address(&accounts.vault.key(), &mint, &token_program),
+            MultisigError::AccountMismatch
+        );
+        let decimals = {
+            require_keys_eq!(*accounts.mint.owner, token_program, MultisigError::AccountMismatch);
+            let data = accounts.mint.try_borrow_data()?;
+            *data.get(44).ok_or(MultisigError::AccountMismatch)?
+        };
+
+        let mut data = vec![12]; // TransferChecked
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.push(decimals);
+        invoke(
+            &Instruction {
+                program_id: token_program,
+                accounts: vec![
+                    AccountMeta::new(accounts.source.key(), false),
+                    AccountMeta::new_readonly(mint, false),
+                    AccountMeta::new(accounts.destination.key(), false),
+                    AccountMeta::new_readonly(accounts.depositor.key(), true),
+                ],
+                data,
+            },
+            &[
+                accounts.source.to_account_info(),
+                accounts.mint.to_account_info(),
+                accounts.destination.to_account_info(),
+                accounts.depositor.to_account_info(),
+            ],
+        )?;
+        emit!(DepositReceived {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: accounts.multisig.key(),
+            vault: accounts.vault.key(),
+            depositor: accounts.depositor.key(),
+            mint: Some(mint),
+            amount,
+            memo,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn close_executed_proposal(ctx: Context<CloseExecutedProposal>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let retention = ctx.accounts.multisig.retention_seconds as i64;
+        if let Some(closable_at) = ctx.accounts.proposal.executed_at.map(|t| t + retention) {
+            if now < closable_at {
+                msg!("Retained until {}, now {}", closable_at, now);
+                return err!(MultisigError::RetentionNotElapsed);
+            }
+        }
+        Ok(())
+    }
+
+    // 达到阈值后激活分期或周期付款，计入已执行
+    // remaining_accounts 为协议费、策略与通知账户（如已设置）
+    pub fn activate_proposal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ActivateProposal<'info>>,
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        let now = Clock::get()?.unix_timestamp;
+        let executor = accounts.executor.as_ref().map(|e| e.key());
+        accounts.proposal.check_executor(executor, &accounts.multisig, now)?;
+        let account_infos = ctx.remaining_accounts;
+        execute_proposal(&mut accounts.multisig, &mut accounts.proposal, None, account_infos, now)
+    }
+
+    // remaining_accounts 为策略与通知账户（如已设置）
+    pub fn execute_vesting_tranche<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteVestingTranche<'info>>,
+        tranche: u8,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let accounts = ctx.accounts;
+        let multisig = &accounts.multisig;
+        require!(multisig.frozen_by.is_none(), MultisigError::Frozen);
+        require!(!multisig.paused, MultisigError::Paused);
+        require!(!multisig.is_vault_paused(0), MultisigError::VaultPaused);
+        multisig.require_not_migrating()?;
+
+        let proposal = &mut accounts.proposal;
+        let ProposalAction::Vesting(schedule) = &proposal.action else {
+            unreachable!()
+        };
+        let t = *schedule
+            .tranches
+            .get(tranche as usize)
+            .ok_or(MultisigError::InvalidTranche)?;
+        if now < t.unlock_time {
+            msg!("Tranche {} unlocks at {}, now {}", tranche, t.unlock_time, now);
+            return err!(MultisigError::TrancheLocked);
+        }
+        let (mint, recipient) = (schedule.mint, schedule.recipient);
+        // 激活后才启用或收紧的白名单同样约束剩余分期
+        require!(
+            accounts.multisig.is_allowed_destination(&recipient),
+            MultisigError::DestinationNotAllowed
+        );
+        require!(
+            !accounts.multisig.denied_destinations.contains(&recipient),
+            MultisigError::DestinationDenied
+        );
+        let total = schedule.tranches.len() as u32;
+        let bit = 1u32 << tranche;
+        require!(proposal.tranches_paid & bit == 0, MultisigError::TranchePaid);
+        proposal.tranches_paid |= bit;
+
+        let token_program = accounts.token_program.key();
+        require_keys_eq!(accounts.mint.key(), mint, MultisigError::AccountMismatch);
+        require_keys_eq!(
+            accounts.source.key(),
+            find_associated_token_address(&accounts.vault.key(), &mint, &token_program),
+            MultisigError::AccountMismatch
+        );
+        // token 账户布局：mint(0..32) + owner(32..64)
+        {
+            let destination = &accounts.destination;
+            require_keys_eq!(*destination.owner, token_program, MultisigError::AccountMismatch);
+            let data = destination.try_borrow_data()?;
+            require!(
+                data.len() >= 64 && data[..32] == mint.to_bytes() && data[32..64] == recipient.to_bytes(),
+                MultisigError::AccountMismatch
+            );
+        }
+        let decimals = {
+            require_keys_eq!(*accounts.mint.owner, token_program, MultisigError::AccountMismatch);
+            let data = accounts.mint.try_borrow_data()?;
+            *data.get(44).ok_or(MultisigError::AccountMismatch)?
+        };
+
+        let mut data = vec![12]; // TransferChecked
+        data.extend_from_slice(&t.amount.to_le_bytes());
+        data.push(decimals);
+        let instruction = Instruction {
+            program_id: token_program,
+            accounts: vec![
+                AccountMeta::new(accounts.source.key(), false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new(accounts.destination.key(), false),
+                AccountMeta::new_readonly(accounts.vault.key(), true),
+            ],
+            data,
+        };
+        // 每次支付同样先经策略程序放行，支付后再通知
+        let action_hash = proposal.action.hash()?;
+        let mut hook_start = 0;
+        if let Some((program, account_count)) = multisig.policy_program() {
+            let metas: Vec<SerializableAccountMeta> =
+                instruction.accounts.iter().cloned().map(Into::into).collect();
+            hook_start = check_policy(
+                multisig,
+                &proposal.to_account_info(),
+                program,
+                account_count,
+                action_hash,
+                &metas,
+                ctx.remaining_accounts,
+            )?;
+        }
+        let multisig_key = multisig.key();
+        let vault_seeds: &[&[u8]] = &[VAULT_SEED, multisig_key.as_ref(), &[0], &[ctx.bumps.vault]];
+        invoke_signed(
+            &instruction,
+            &[
+                accounts.source.to_account_info(),
+                accounts.mint.to_account_info(),
+                accounts.destination.to_account_info(),
+                accounts.vault.to_account_info(),
+                accounts.token_program.to_account_info(),
+            ],
+            &[vault_seeds],
+        )?;
+        notify(multisig, proposal.index, action_hash, ctx.remaining_accounts, hook_start)?;
+
+        let remaining = total - proposal.tranches_paid.count_ones();
+        emit!(VestingTranchePaid {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig_key,
+            proposal: proposal.key(),
+            tranche,
+            recipient,
+            amount: t.amount,
+            remaining: remaining as u8,
+        });
+
+        // 全部支付后关闭提案，租金退还给 multisig
+        if remaining == 0 {
+            proposal.close(accounts.multisig.to_account_info())?;
+        }
+        Ok(())
+    }
+
+    // 停止尚未支付的分期；已支付的不追回
+    pub fn cancel_vesting(ctx: Context<CancelVesting>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let proposal = &ctx.accounts.proposal;
+        let ProposalAction::Vesting(schedule) = &proposal.action else {
+            unreachable!()
+        };
+        let tranches_cancelled = schedule.tranches.len() as u32 - proposal.tranches_paid.count_ones();
+        emit!(VestingCancelled {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: ctx.accounts.multisig.key(),
+            proposal: proposal.key(),
+            tranches_cancelled: tranches_cancelled as u8,
+            timestamp: now,
+        });
+        ctx.accounts.multisig.last_activity = now;
+        Ok(())
+    }
+
+    // 首次付款在激活后即可进行，之后每次须距上次付款满 interval_seconds；
+    // remaining_accounts 为策略与通知账户（如已设置）
+    pub fn execute_recurring_payment<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteRecurringPayment<'info>>,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let accounts = ctx.accounts;
+        let multisig = &accounts.multisig;
+        require!(multisig.frozen_by.is_none(), MultisigError::Frozen);
+        require!(!multisig.paused, MultisigError::Paused);
+        require!(!multisig.is_vault_paused(0), MultisigError::VaultPaused);
+        multisig.require_not_migrating()?;
+
+        let proposal = &mut accounts.proposal;
+        let ProposalAction::Recurring(payment) = proposal.action.clone() else {
+            unreachable!()
+        };
+        require_keys_eq!(
+            accounts.destination.key(),
+            payment.destination,
+            MultisigError::AccountMismatch
+        );
+        require!(
+            multisig.is_allowed_destination(&payment.destination),
+            MultisigError::DestinationNotAllowed
+        );
+        require!(
+            !multisig.denied_destinations.contains(&payment.destination),
+            MultisigError::DestinationDenied
+        );
+        // 同一周期内重复调用会被拒绝
+        if let Some(last_paid_at) = proposal.last_paid_at {
+            let due = last_paid_at.saturating_add(payment.interval_seconds);
+            if now < due {
+                msg!("Next payment due at {}, now {}", due, now);
+                return err!(MultisigError::PaymentNotDue);
+            }
+        }
+        proposal.payments_made += 1;
+        proposal.last_paid_at = Some(now);
+
+        // 每次付款同样先经策略程序放行，付款后再通知
+        let action_hash = proposal.action.hash()?;
+        let mut hook_start = 0;
+        if let Some((program, account_count)) = multisig.policy_program() {
+            let metas = vec![
+                AccountMeta::new(accounts.vault.key(), true).into(),
+                AccountMeta::new(payment.destination, false).into(),
+            ];
+            hook_start = check_policy(
+                multisig,
+                &proposal.to_account_info(),
+                program,
+                account_count,
+                action_hash,
+                &metas,
+                ctx.remaining_accounts,
+            )?;
+        }
+        let multisig_key = multisig.key();
+        let vault_seeds: &[&[u8]] = &[VAULT_SEED, multisig_key.as_ref(), &[0], &[ctx.bumps.vault]];
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
+                accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: accounts.vault.to_account_info(),
+                    to: accounts.destination.to_account_info(),
+                },
+                &[vault_seeds],
+            ),
+            payment.amount,
+        )?;
+        notify(multisig, proposal.index, action_hash, ctx.remaining_accounts, hook_start)?;
+
+        emit!(RecurringPaymentMade {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig_key,
+            proposal: proposal.key(),
+            destination: payment.destination,
+            amount: payment.amount,
+            payments_made: proposal.payments_made,
+            max_payments: payment.max_payments,
+            timestamp: now,
+        });
+
+        // 达到次数上限后关闭提案，租金退还给 multisig
+        if proposal.payments_made >= payment.max_payments {
+            proposal.close(accounts.multisig.to_account_info())?;
+        }
+        Ok(())
+    }
+
+    // 停止之后的付款；已付款项不受影响
+    pub fn cancel_recurring_payment(ctx: Context<CancelRecurringPayment>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        emit!(RecurringPaymentCancelled {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: ctx.accounts.multisig.key(),
+            proposal: ctx.accounts.proposal.key(),
+            payments_made: ctx.accounts.proposal.payments_made,
+            timestamp: now,
+        });
+        ctx.accounts.multisig.last_activity = now;
+        Ok(())
+    }
+
+    // remaining_accounts 依次为 [proposal_0, 其账户 ..., proposal_1, 其账户 ...]，
+    // account_counts[i] 为第 i 个提案所需的账户数。任一提案失败则整笔交易回滚，
+    // 日志中给出失败提案在本批中的位置。
+    pub fn execute_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteMany<'info>>,
+        account_counts: Vec<u8>,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let multisig = &mut ctx.accounts.multisig;
+        let mut ledger = ctx.accounts.spend_ledger.as_deref_mut();
+        let executor = ctx.accounts.executor.as_ref().map(|e| e.key());
+        let mut rest = ctx.remaining_accounts;
+
+        for (position, count) in account_counts.iter().enumerate() {
+            let count = *count as usize;
+            require!(rest.len() > count, MultisigError::AccountMismatch);
+            let (proposal_info, account_infos) = (&rest[0], &rest[1..=count]);
+            rest = &rest[count + 1..];
+
+            let result = Account::<Proposal>::try_from(proposal_info).and_then(|mut proposal| {
+                require!(proposal.multisig == multisig.key(), MultisigError::AccountMismatch);
+                require!(
+                    !proposal.executed && !proposal.cancelled,
+                    MultisigError::AlreadyProcessed
+                );
+                require!(
+                    proposal.config_seqno == multisig.config_seqno,
+                    MultisigError::StaleProposal
+                );
+                require!(!proposal.stays_open(), MultisigError::StandingProposal);
+                proposal.check_executor(executor, multisig, now)?;
+                let retain = multisig.retain_executed;
+                execute_proposal(
+                    multisig,
+                    &mut proposal,
+                    ledger.as_deref_mut(),
+                    account_infos,
+                    now,
+                )?;
+                retire_executed(multisig, &mut proposal, retain, executor)
+            });
+            if let Err(e) = result {
+                msg!("execute_many: proposal at position {} failed", position);
+                return Err(e);
+            }
+        }
+
+        require!(rest.is_empty(), MultisigError::AccountMismatch);
+        Ok(())
+    }
+
+    // 无需许可：把成员变更前的提案迁移到当前成员集合，已移除成员的批准被剔除
+    pub fn prune_approvals(ctx: Context<PruneApprovals>, old_members: Vec<Pubkey>) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let proposal = &mut ctx.accounts.proposal;
+        require!(
+            hash_members(old_members.iter()) == proposal.members_hash,
+            MultisigError::MembersMismatch
+        );
+
+        let rebase = |votes: u32| {
+            let mut rebased = 0u32;
+            for (i, key) in old_members.iter().enumerate() {
+                if votes & (1 << i) == 0 {
+                    continue;
+                }
+                match multisig.member_index(key) {
+                    Some(index) => rebased |= 1 << index,
+                    None => msg!("Pruned vote from removed member {}", key),
+                }
+            }
+            rebased
+        };
+
+        // 批准时间随位一起迁移
+        let mut approved_at = vec![];
+        for (i, key) in old_members.iter().enumerate() {
+            let at = proposal.approved_at.get(i).copied().unwrap_or(0);
+            if let Some(index) = multisig.member_index(key) {
+                if proposal.approvals & (1 << i) != 0 {
+                    approved_at.resize(approved_at.len().max(index + 1), 0);
+                    approved_at[index] = at;
+                }
+            }
+        }
+        proposal.approved_at = approved_at;
+        proposal.approvals = rebase(proposal.approvals);
+        proposal.rejections = rebase(proposal.rejections);
+        proposal.abstentions = rebase(proposal.abstentions);
+        proposal
+            .rejection_reasons
+            .retain(|r| multisig.member_index(&r.member).is_some());
+        proposal.config_seqno = multisig.config_seqno;
+        proposal.members_hash = multisig.members_hash();
+        Ok(())
+    }
+
+    // 无需许可：执行窗口已过、投票截止时仍未达到阈值、必然失败的执行次数超过
+    // max_execution_attempts，或已被否决的乐观提案可由任何人关闭，租金退还给 proposer
+    pub fn close_expired(ctx: Context<CloseExpired>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let proposal = &ctx.accounts.proposal;
+        let max_attempts = ctx.accounts.multisig.max_execution_attempts();
+        let reason = if proposal.is_past_window(now) {
+            CANCEL_REASON_EXPIRED
+        } else if proposal.is_abandoned(now) {
+            CANCEL_REASON_VOTING_CLOSED
+        } else if max_attempts.is_some_and(|max| proposal.execution_attempts > max) {
+            CANCEL_REASON_EXECUTION_FAILED
+        } else if ctx.accounts.multisig.is_vetoed(proposal, now) {
+            CANCEL_REASON_VETOED
+        } else {
+            return err!(MultisigError::NotExpired);
+        };
+        ctx.accounts.multisig.cancelled_count += 1;
+        emit_cancelled(proposal, None, reason, None, now)
+    }
+
+    // 无需许可：发起人被移出多签后，其未结束的提案可由任何人关闭，租金退还给 proposer
+    pub fn cancel_orphaned_proposal(ctx: Context<CancelOrphanedProposal>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts.multisig.cancelled_count += 1;
+        let proposal = &ctx.accounts.proposal;
+        emit_cancelled(proposal, None, CANCEL_REASON_MEMBER_REMOVED, None, now)
+    }
+
+    pub fn cancel_transaction(
+        ctx: Context<CancelTransaction>,
+        reason: u8,
+        note: Option<String>,
+    ) -> Result<()> {
+        ctx.accounts.multisig.require_top_level()?;
+        require!(
+            reason == CANCEL_REASON_UNSPECIFIED || reason >= CANCEL_REASON_CUSTOM,
+            MultisigError::ReservedCancelReason
+        );
+        // 提案账户已在 #[account(close = multisig)] 中自动关闭
+        let now = Clock::get()?.unix_timestamp;
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.cancelled_count += 1;
+        multisig.last_activity = now;
+        let canceller = ctx.accounts.canceller.key();
+        emit_cancelled(&ctx.accounts.proposal, Some(canceller), reason, note, now)
+    }
+
+    // remaining_accounts 为 (proposal, proposer) 成对传入，租金退还给 proposer；
+    // 可分多笔交易调用，已被关闭的提案直接跳过。
+    // 通过提案自治调用时，不要把正在执行的提案本身传入。
+    pub fn cancel_all<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelAll<'info>>,
+        note: Option<String>,
+    ) -> Result<()> {
+        let multisig_key = ctx.accounts.multisig.key();
+        let authority = ctx.accounts.authority.key();
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            ctx.remaining_accounts.len() % 2 == 0,
+            MultisigError::AccountMismatch
+        );
+
+        let mut cancelled = 0;
+        let mut skipped = 0;
+        for pair in ctx.remaining_accounts.chunks(2) {
+            let (proposal_info, proposer_info) = (&pair[0], &pair[1]);
+            if proposal_info.data_is_empty() {
+                msg!("Proposal {} already closed", proposal_info.key);
+                skipped += 1;
+                continue;
+            }
+
+            let proposal = Account::<Proposal>::try_from(proposal_info)?;
+            require!(proposal.multisig == multisig_key, MultisigError::AccountMismatch);
+            require!(
+                !proposal.executed && !proposal.cancelled,
+                MultisigError::AlreadyProcessed
+            );
+            require!(
+                proposal.proposer == *proposer_info.key,
+                MultisigError::AccountMismatch
+            );
+
+            emit_cancelled(
+                &proposal,
+                Some(authority),
+                CANCEL_REASON_BULK_CANCELLED,
+                note.clone(),
+                now,
+            )?;
+            proposal.close(proposer_info.clone())?;
+            cancelled += 1;
+        }
+
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.cancelled_count += cancelled;
+        multisig.last_activity = now;
+        emit!(ProposalsCancelled {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig_key,
+            cancelled,
+            skipped,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    pub fn post_comment(ctx: Context<PostComment>, text: String) -> Result<()> {
+        let author = ctx.accounts.author.key();
+        ctx.accounts
+            .multisig
+            .active_member_index(&author, Clock::get()?.unix_timestamp)?;
+        require!(
+            !text.is_empty() && text.len() <= MAX_COMMENT_LEN,
+            MultisigError::InvalidComment
+        );
+
+        let proposal = &mut ctx.accounts.proposal;
+        let comment = &mut ctx.accounts.comment;
+        comment.proposal = proposal.key();
+        comment.author = author;
+        comment.seq = proposal.comments_count;
+        comment.posted_at = Clock::get()?.unix_timestamp;
+        comment.text = text.clone();
+        comment.bump = ctx.bumps.comment;
+        proposal.comments_count += 1;
+
+        emit!(CommentPosted {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: ctx.accounts.multisig.key(),
+            proposal: proposal.key(),
+            author,
+            seq: comment.seq,
+            text,
+        });
+        Ok(())
+    }
+
+    // 无需许可：提案关闭后回收评论账户
+    pub fn close_comment(_ctx: Context<CloseComment>) -> Result<()> {
+        Ok(())
+    }
+
+    // 成员创建定长缓冲区，之后由创建者分段写入
+    pub fn create_buffer(ctx: Context<CreateBuffer>, id: u32, size: u32) -> Result<()> {
+        let creator = ctx.accounts.creator.key();
+        ctx.accounts
+            .multisig
+            .active_member_index(&creator, Clock::get()?.unix_timestamp)?;
+        require!(
+            size > 0 && size as usize <= MAX_BUFFER_SIZE,
+            MultisigError::InvalidBufferSize
+        );
+
+        let buffer = &mut ctx.accounts.buffer;
+        buffer.multisig = ctx.accounts.multisig.key();
+        buffer.creator = creator;
+        buffer.id = id;
+        buffer.created_at = Clock::get()?.unix_timestamp;
+        buffer.bump = ctx.bumps.buffer;
+        buffer.finalized = false;
+        buffer.hash = [0; 32];
+        buffer.refs = Vec::new();
+        buffer.data = vec![0; size as usize];
+        Ok(())
+    }
+
+    pub fn write_buffer(ctx: Context<WriteBuffer>, offset: u32, bytes: Vec<u8>) -> Result<()> {
+        let data = &mut ctx.accounts.buffer.data;
+        let start = offset as usize;
+        let end = start
+            .checked_add(bytes.len())
+            .filter(|end| *end <= data.len())
+            .ok_or(MultisigError::BufferOverflow)?;
+        data[start..end].copy_from_slice(&bytes);
+        Ok(())
+    }
+
+    // 记录内容哈希，此后缓冲区只读
+    pub fn finalize_buffer(ctx: Context<WriteBuffer>) -> Result<()> {
+        let buffer = &mut ctx.accounts.buffer;
+        buffer.hash = hashv(&[&buffer.data]).to_bytes();
+        buffer.finalized = true;
+        Ok(())
+    }
+
+    // 索引本身已由 #[account(close = multisig)] 关闭
+    pub fn close_instruction_index(_ctx: Context<CloseInstructionIndex>) -> Result<()> {
+        Ok(())
+    }
+
+    // 无需许可：引用缓冲区的提案被取消或过期关闭后解除引用
+    pub fn release_buffer(ctx: Context<ReleaseBuffer>) -> Result<()> {
+        ctx.accounts.buffer.release(ctx.accounts.proposal.key)
+    }
+
+    // 创建者随时可关闭（包括未 finalize 的半成品）；过期后任何人可关闭
+    pub fn close_buffer(ctx: Context<CloseBuffer>) -> Result<()> {
+        if ctx.accounts.closer.key() != ctx.accounts.creator.key() {
+            let expires_at = ctx.accounts.buffer.created_at + BUFFER_EXPIRY_SECONDS;
+            require!(
+                Clock::get()?.unix_timestamp >= expires_at,
+                MultisigError::BufferNotExpired
+            );
+        }
+        Ok(())
+    }
+
+    pub fn request_withdrawal(
+        ctx: Context<RequestWithdrawal>,
+        id: u32,
+        mint: Option<Pubkey>,
+        amount: u64,
+        recipient: Pubkey,
+        memo: Option<String>,
+    ) -> Result<()> {
+        require!(
+            amount > 0
+                && memo
+                    .as_ref()
+                    .is_none_or(|m| !m.is_empty() && m.len() <= MAX_EXECUTION_MEMO_LEN),
+            MultisigError::InvalidWithdrawalRequest
+        );
+        let now = Clock::get()?.unix_timestamp;
+        let request = &mut ctx.accounts.request;
+        request.multisig = ctx.accounts.multisig.key();
+        request.requester = ctx.accounts.requester.key();
+        request.id = id;
+        request.mint = mint;
+        request.amount = amount;
+        request.recipient = recipient;
+        request.memo = memo;
+        request.created_at = now;
+        request.bump = ctx.bumps.request;
+        emit!(WithdrawalRequested {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: request.multisig,
+            request: request.key(),
+            requester: request.requester,
+            mint,
+            amount,
+            recipient,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    // 提案内容完全由请求决定，成员只能选择是否转换
+    pub fn convert_request_to_proposal(ctx: Context<ConvertWithdrawalRequest>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let accounts = ctx.accounts;
+        let request = &accounts.request;
+        require!(!request.is_expired(now), MultisigError::WithdrawalRequestExpired);
+        let mint = accounts.mint.as_ref().map(|m| m.to_account_info());
+        let ix = request.instruction(&accounts.multisig.key(), mint.as_ref())?;
+        let options = ProposalOptions {
+            execution_memo: request.memo.clone(),
+            ..Default::default()
+        };
+        create_proposal(
+            &mut accounts.multisig,
+            &mut accounts.proposal,
+            accounts.member_stats.as_mut(),
+            load_program_config(&accounts.program_config)?.as_ref(),
+            accounts.proposer.key(),
+            ctx.bumps.proposal,
+            ProposalAction::Raw(ix),
+            options,
+        )?;
+        emit!(WithdrawalRequestConverted {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: accounts.multisig.key(),
+            request: accounts.request.key(),
+            proposal: accounts.proposal.key(),
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    pub fn close_withdrawal_request(ctx: Context<CloseWithdrawalRequest>) -> Result<()> {
+        if ctx.accounts.closer.key() != ctx.accounts.requester.key() {
+            require!(
+                ctx.accounts.request.is_expired(Clock::get()?.unix_timestamp),
+                MultisigError::WithdrawalRequestNotExpired
+            );
+        }
+        Ok(())
+    }
+
+    pub fn invite_member(
+        ctx: Context<Auth>,
+        invitee: Pubkey,
+        membership_expires_at: Option<i64>,
+        invite_expires_at: Option<i64>,
+    ) -> Result<()> {
+        ctx.accounts.multisig.invite(
+            invitee,
+            membership_expires_at,
+            invite_expires_at,
+            Clock::get()?.unix_timestamp,
+        )
+    }
+
+    pub fn revoke_invitation(ctx: Context<Auth>, invitee: Pubkey) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let pos = multisig
+            .pending_invites
+            .iter()
+            .position(|i| i.key == invitee)
+            .ok_or(MultisigError::NotInvited)?;
+        multisig.pending_invites.remove(pos);
+        Ok(())
+    }
+
+    pub fn accept_membership(ctx: Context<AcceptMembership>) -> Result<()> {
+        let invitee = ctx.accounts.invitee.key();
+        let multisig = &mut ctx.accounts.multisig;
+        let now = Clock::get()?.unix_timestamp;
+
+        let pos = multisig
+            .pending_invites
+            .iter()
+            .position(|i| i.key == invitee)
+            .ok_or(MultisigError::NotInvited)?;
+        let invitation = multisig.pending_invites.remove(pos);
+        require!(invitation.is_active(now), MultisigError::InvitationExpired);
+
+        let data_len = multisig.to_account_info().data_len();
+        let member = Member {
+            key: invitee,
+            expires_at: invitation.membership_expires_at,
+            last_seen: now,
+            inactive: false,
+            key_type: KeyType::Ed25519,
+        };
+        multisig.insert_member(member, data_len)
+    }
+
+    // 以太坊成员无法签 accept_membership，由多签自身直接加入
+    pub fn add_eth_member(
+        ctx: Context<Auth>,
+        address: [u8; 20],
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let key = eth_member_key(&address);
+        require!(
+            multisig.pending_invites.iter().all(|i| i.key != key),
+            MultisigError::AlreadyInvited
+        );
+        let data_len = multisig.to_account_info().data_len();
+        let member = Member {
+            key,
+            expires_at,
+            last_seen: Clock::get()?.unix_timestamp,
+            inactive: false,
+            key_type: KeyType::Secp256k1,
+        };
+        multisig.insert_member(member, data_len)
+    }
+
+    pub fn remove_member(ctx: Context<Auth>, member: Pubkey) -> Result<()> {
+        ctx.accounts
+            .multisig
+            .remove_member(&member, Clock::get()?.unix_timestamp)
+    }
+
+    pub fn change_threshold(ctx: Context<Auth>, threshold: u8) -> Result<()> {
+        ctx.accounts
+            .multisig
+            .set_threshold(threshold, Clock::get()?.unix_timestamp)
+    }
+
+    pub fn set_quorum(ctx: Context<Auth>, quorum: Option<u8>) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.quorum = quorum;
+        multisig.validate_thresholds(Clock::get()?.unix_timestamp)
+    }
+
+    // 开启后基础阈值取提案创建时的值；成员变更前创建的提案仍须先经 prune_approvals 对应到当前成员
+    pub fn set_execute_against_snapshot(ctx: Context<Auth>, enabled: bool) -> Result<()> {
+        ctx.accounts.multisig.execute_against_snapshot = enabled;
+        Ok(())
+    }
+
+    // 本指令也是配置变更：开启后关闭它同样需要全体批准
+    pub fn set_config_requires_unanimity(ctx: Context<Auth>, enabled: bool) -> Result<()> {
+        ctx.accounts.multisig.config_requires_unanimity = enabled;
+        Ok(())
+    }
+
+    pub fn set_timelock(
+        ctx: Context<Auth>,
+        timelock_seconds: u32,
+        urgent_timelock_seconds: u32,
+        urgent_threshold: Option<u8>,
+    ) -> Result<()> {
+        require!(
+            urgent_timelock_seconds <= timelock_seconds,
+            MultisigError::InvalidTimelock
+        );
+        require!(
+            urgent_threshold.is_none_or(|t| t > 0),
+            MultisigError::InvalidThreshold
+        );
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.timelock_seconds = timelock_seconds;
+        multisig.urgent_timelock_seconds = urgent_timelock_seconds;
+        multisig.urgent_threshold = urgent_threshold;
+        Ok(())
+    }
+
+    pub fn set_min_execution_delay(ctx: Context<Auth>, slots: u16) -> Result<()> {
+        ctx.accounts.multisig.min_execution_delay_slots = slots;
+        Ok(())
+    }
+
+    pub fn set_paused(ctx: Context<Auth>, paused: bool) -> Result<()> {
+        ctx.accounts.multisig.paused = paused;
+        Ok(())
+    }
+
+    pub fn set_threshold_overrides(
+        ctx: Context<Auth>,
+        overrides: Vec<ThresholdOverride>,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        require!(overrides.len() <= MAX_THRESHOLD_OVERRIDES, MultisigError::TooManyOverrides);
+        for (i, o) in overrides.iter().enumerate() {
+            require!(
+                overrides[..i].iter().all(|p| p.program_id != o.program_id),
+                MultisigError::InvalidThreshold
+            );
+        }
+        multisig.threshold_overrides = overrides;
+        multisig.validate_thresholds(Clock::get()?.unix_timestamp)
+    }
+
+    pub fn register_vault(
+        ctx: Context<Auth>,
+        index: u8,
+        name: String,
+        mint_hint: Option<Pubkey>,
+    ) -> Result<()> {
+        require!((index as usize) < MAX_VAULTS, MultisigError::InvalidVaultIndex);
+        check_vault_name(&name)?;
+        let vaults = &mut ctx.accounts.multisig.vaults;
+        let Err(position) = vaults.binary_search_by_key(&index, |v| v.index) else {
+            return err!(MultisigError::VaultAlreadyRegistered);
+        };
+        vaults.insert(
+            position,
+            VaultConfig {
+                index,
+                threshold_override: None,
+                name,
+                mint_hint,
+                paused: false,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn set_vault_metadata(
+        ctx: Context<Auth>,
+        index: u8,
+        name: String,
+        mint_hint: Option<Pubkey>,
+    ) -> Result<()> {
+        check_vault_name(&name)?;
+        let vault = ctx.accounts.multisig.vault_mut(index)?;
+        vault.name = name;
+        vault.mint_hint = mint_hint;
+        Ok(())
+    }
+
+    // 默认（未设置扩展）不允许经 CPI 提案、投票或取消；执行不受影响
+    pub fn set_allow_cpi(ctx: Context<Auth>, allowed: bool) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        if !allowed {
+            multisig.remove_extension(extension::TAG_ALLOW_CPI);
+            return Ok(());
+        }
+        let data_len = multisig.to_account_info().data_len();
+        multisig.set_extension(extension::TAG_ALLOW_CPI, &[1], data_len)
+    }
+
+    // 登记后提案可把该 PDA 标记为签名者，执行时由程序签名
+    pub fn register_app_authority(ctx: Context<SelfAuth>, app_id: [u8; 16]) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let mut entries = multisig.app_authorities();
+        require!(
+            entries.iter().all(|(id, _)| *id != app_id),
+            MultisigError::AppAuthorityRegistered
+        );
+        require!(entries.len() < MAX_APP_AUTHORITIES, MultisigError::TooManyAppAuthorities);
+        let (authority, bump) = find_app_authority_address(&multisig.key(), &app_id);
+        entries.push((app_id, bump));
+        let data_len = multisig.to_account_info().data_len();
+        multisig.set_app_authorities(&entries, data_len)?;
+        emit!(AppAuthoritySet {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig.key(),
+            app_id,
+            authority,
+            registered: true,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    // 立即生效：尚未执行、需要该 authority 签名的提案将无法执行，其他 authority 不受影响
+    pub fn unregister_app_authority(ctx: Context<SelfAuth>, app_id: [u8; 16]) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let mut entries = multisig.app_authorities();
+        let position = entries
+            .iter()
+            .position(|(id, _)| *id == app_id)
+            .ok_or(MultisigError::AppAuthorityNotRegistered)?;
+        entries.remove(position);
+        let data_len = multisig.to_account_info().data_len();
+        multisig.set_app_authorities(&entries, data_len)?;
+        emit!(AppAuthoritySet {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig.key(),
+            app_id,
+            authority: find_app_authority_address(&multisig.key(), &app_id).0,
+            registered: false,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    // 每次执行成功后（含类型化动作与分期、周期付款）以 CPI 通知该程序；required 为 false 时通知程序账户缺失只记录日志
+    pub fn set_notify_program(
+        ctx: Context<SelfAuth>,
+        program: Option<Pubkey>,
+        required: bool,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        match program {
+            Some(program) => {
+                let value = [program.as_ref(), &[required as u8]].concat();
+                let data_len = multisig.to_account_info().data_len();
+                multisig.set_extension(extension::TAG_NOTIFY, &value, data_len)?;
+            }
+            None => {
+                multisig.remove_extension(extension::TAG_NOTIFY);
+            }
+        }
+        emit!(NotifyProgramSet {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig.key(),
+            program,
+            required: program.is_some() && required,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    // 符合形状的提案可跳过批准阈值，因此只能由多签自身设置；None 移除后未执行的乐观提案按普通提案处理
+    pub fn set_optimistic_config(
+        ctx: Context<SelfAuth>,
+        config: Option<OptimisticConfig>,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        match &config {
+            Some(config) => {
+                let valid_shape = |s: &InstructionShape| {
+                    // 乐观通道不能用来调用本程序（改动成员、阈值等）
+                    s.program_id != crate::ID
+                        && (1..=MAX_SHAPE_DISCRIMINATOR_LEN).contains(&s.discriminator.len())
+                        && s.discriminator.len() <= s.data_len as usize
+                };
+                require!(
+                    (1..=MAX_OPTIMISTIC_SHAPES).contains(&config.enabled_for.len())
+                        && config.enabled_for.iter().all(valid_shape)
+                        && config.delay_seconds > 0
+                        && config.veto_threshold > 0
+                        && config.veto_threshold as usize <= multisig.members.len(),
+                    MultisigError::InvalidOptimisticConfig
+                );
+                let data_len = multisig.to_account_info().data_len();
+                let value = config.try_to_vec()?;
+                multisig.set_extension(extension::TAG_OPTIMISTIC, &value, data_len)?;
+            }
+            None => {
+                multisig.remove_extension(extension::TAG_OPTIMISTIC);
+            }
+        }
+        emit!(OptimisticConfigSet {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig.key(),
+            config,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    // 设置后每次执行（含类型化动作与分期、周期付款）须先经策略程序的 check_execution 放行；
+    // 移除或更换策略的提案本身同样要经过当前策略
+    pub fn set_policy_program(
+        ctx: Context<SelfAuth>,
+        program: Option<Pubkey>,
+        account_count: u8,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        match program {
+            Some(program) => {
+                let value = [program.as_ref(), &[account_count]].concat();
+                let data_len = multisig.to_account_info().data_len();
+                multisig.set_extension(extension::TAG_POLICY, &value, data_len)?;
+            }
+            None => {
+                multisig.remove_extension(extension::TAG_POLICY);
+            }
+        }
+        emit!(PolicyProgramSet {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig.key(),
+            program,
+            account_count: if program.is_some() { account_count } else { 0 },
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    // 已记录的失败次数保留，调低后超过新上限的提案立即可被关闭
+    pub fn set_max_execution_attempts(ctx: Context<Auth>, max_attempts: Option<u8>) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        match max_attempts {
+            Some(max) => {
+                let data_len = multisig.to_account_info().data_len();
+                multisig.set_extension(extension::TAG_MAX_EXECUTION_ATTEMPTS, &[max], data_len)
+            }
+            None => {
+                multisig.remove_extension(extension::TAG_MAX_EXECUTION_ATTEMPTS);
+                Ok(())
+            }
+        }
+    }
+
+    // 只影响之后的计票与执行，已记录的批准按各自的时间判断是否过期
+    pub fn set_approval_ttl(ctx: Context<Auth>, ttl_seconds: Option<u32>) -> Result<()> {
+        require!(ttl_seconds != Some(0), MultisigError::InvalidApprovalTtl);
+        ctx.accounts.multisig.approval_ttl_seconds = ttl_seconds;
+        Ok(())
+    }
+
+    pub fn set_executor_grace(ctx: Context<Auth>, grace_seconds: u32) -> Result<()> {
+        ctx.accounts.multisig.executor_grace_seconds = grace_seconds;
+        Ok(())
+    }
+
+    // 只影响之后执行的提案；已保留的提案按关闭时的 retention_seconds 计算
+    pub fn set_retain_executed(
+        ctx: Context<Auth>,
+        retain_executed: bool,
+        retention_seconds: u32,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.retain_executed = retain_executed;
+        multisig.retention_seconds = retention_seconds;
+        Ok(())
+    }
+
+    pub fn set_vault_paused(ctx: Context<SetVaultPaused>, index: u8, paused: bool) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        let multisig = &mut ctx.accounts.multisig;
+        require!(
+            paused
+                || authority == multisig.key()
+                || multisig.config_authority == Some(authority),
+            MultisigError::Unauthorized
+        );
+        multisig.vault_mut(index)?.paused = paused;
+        emit!(VaultPauseSet {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig.key(),
+            index,
+            paused,
+            authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    // 只能抬高：请求多个金库签名时取其中最高者，与 threshold_overrides 同样参与比较
+    pub fn set_vault_threshold(
+        ctx: Context<Auth>,
+        index: u8,
+        threshold_override: Option<u8>,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.vault_mut(index)?.threshold_override = threshold_override;
+        multisig.validate_thresholds(Clock::get()?.unix_timestamp)
+    }
+
+    pub fn emergency_freeze(ctx: Context<EmergencyFreeze>) -> Result<()> {
+        let member = ctx.accounts.member.key();
+        let multisig = &mut ctx.accounts.multisig;
+        let now = Clock::get()?.unix_timestamp;
+        let index = multisig.active_member_index(&member, now)?;
+        require!(multisig.frozen_by.is_none(), MultisigError::Frozen);
+
+        // 上一次的冻结者需等解冻后冷却期结束，防止反复冻结拖垮多签
+        if multisig.last_freezer == Some(member) {
+            require!(
+                now >= multisig.unfrozen_at + FREEZE_COOLDOWN_SECONDS,
+                MultisigError::FreezeCooldown
+            );
+        }
+
+        multisig.frozen_by = Some(member);
+        multisig.frozen_at = now;
+        multisig.last_freezer = Some(member);
+        multisig.touch_member(index, now);
+
+        msg!("MULTISIG FROZEN by {}", member);
+        emit!(MultisigFrozen {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig.key(),
+            frozen_by: member,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    pub fn unfreeze(ctx: Context<Auth>) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let frozen_by = multisig.frozen_by.take().ok_or(MultisigError::NotFrozen)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        multisig.unfrozen_at = now;
+
+        msg!("MULTISIG UNFROZEN (frozen by {})", frozen_by);
+        emit!(MultisigUnfrozen {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig.key(),
+            frozen_by,
+            frozen_at: multisig.frozen_at,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    // 设置/清除 config_authority 必须走本多签自己的提案
+    pub fn set_config_authority(
+        ctx: Context<SelfAuth>,
+        config_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.multisig.config_authority = config_authority;
+        Ok(())
+    }
+
+    pub fn set_guardian(ctx: Context<SelfAuth>, guardian: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.multisig.guardian = guardian;
+        Ok(())
+    }
+
+    // 项目结束时使用：此后只能把资产退还出去，不再承担新的义务
+    // 以相同成员与阈值在新种子下创建多签，并冻结旧多签的提案、投票与执行；
+    // 资金由 migrate_sweep 分批转移，全部清空后 finish_migration
+    pub fn start_migration(
+        ctx: Context<StartMigration>,
+        new_creator: Pubkey,
+        nonce: u8,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.require_not_migrating()?;
+        let new_multisig = &mut ctx.accounts.new_multisig;
+        let keys = multisig.members.iter().map(|m| m.key).collect();
+        init_multisig(
+            new_multisig,
+            new_creator,
+            nonce,
+            keys,
+            multisig.threshold,
+            multisig.max_members,
+            ctx.bumps.new_multisig,
+        )?;
+        // 保留到期时间与密钥类型
+        new_multisig.members = multisig.members.clone();
+        debug_assert!(new_multisig.members_sorted());
+
+        let data_len = multisig.to_account_info().data_len();
+        let (old_key, new_key) = (multisig.key(), new_multisig.key());
+        new_multisig.set_extension(extension::TAG_MIGRATED_FROM, old_key.as_ref(), data_len)?;
+        multisig.set_extension(extension::TAG_MIGRATION, new_key.as_ref(), data_len)?;
+        emit!(MigrationStarted {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: old_key,
+            new_multisig: new_key,
+            new_creator,
+            timestamp: new_multisig.last_activity,
+        });
+        Ok(())
+    }
+
+    // 先清空并关闭列出的 token 账户（租金回到旧金库），再把旧金库的全部 lamports 转入新金库；
+    // 可重复调用，中断后从剩余部分继续
+    pub fn migrate_sweep<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigrateSweep<'info>>,
+        vault_index: u8,
+    ) -> Result<()> {
+        require!((vault_index as usize) < MAX_VAULTS, MultisigError::InvalidVaultIndex);
+        let accounts = &ctx.accounts;
+        let multisig_key = accounts.multisig.key();
+        let vault_seeds: &[&[u8]] =
+            &[VAULT_SEED, multisig_key.as_ref(), &[vault_index], &[ctx.bumps.vault]];
+        let vault = accounts.vault.to_account_info();
+
+        let mut token_accounts = 0u8;
+        if let Some((token_program, rest)) = ctx.remaining_accounts.split_first() {
+            require!(is_token_program(token_program.key), MultisigError::AccountMismatch);
+            require!(rest.len() % 3 == 0, MultisigError::AccountMismatch);
+            for chunk in rest.chunks_exact(3) {
+                let [source, mint, destination] = chunk else {
+                    unreachable!()
+                };
+                require_keys_eq!(
+                    *destination.key,
+                    find_associated_token_address(
+                        accounts.new_vault.key,
+                        mint.key,
+                        token_program.key
+                    ),
+                    MultisigError::AccountMismatch
+                );
+                sweep_token_account(source, mint, destination, &vault, token_program, vault_seeds)?;
+                token_accounts += 1;
+            }
+        }
+
+        let lamports = vault.lamports();
+        if lamports > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new_with_signer(
+                    accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: vault.clone(),
+                        to: accounts.new_vault.to_account_info(),
+                    },
+                    &[vault_seeds],
+                ),
+                lamports,
+            )?;
+        }
+        emit!(MigrationSwept {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig_key,
+            new_multisig: accounts.new_multisig.key(),
+            vault_index,
+            lamports,
+            token_accounts,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    // 无需许可；旧金库须全部为空，之后旧多签处于清退状态且保持冻结
+    pub fn finish_migration(ctx: Context<FinishMigration>) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let multisig_key = multisig.key();
+        require!(
+            ctx.remaining_accounts.len() == MAX_VAULTS,
+            MultisigError::AccountMismatch
+        );
+        for (i, vault) in ctx.remaining_accounts.iter().enumerate() {
+            require_keys_eq!(
+                *vault.key,
+                find_vault_address(&multisig_key, i as u8).0,
+                MultisigError::AccountMismatch
+            );
+            if vault.lamports() > 0 {
+                msg!("Vault {} still holds {} lamports", i, vault.lamports());
+                return err!(MultisigError::MigrationIncomplete);
+            }
+        }
+        let now = Clock::get()?.unix_timestamp;
+        multisig.wound_down_at = Some(now);
+        emit!(MigrationFinished {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig_key,
+            new_multisig: multisig.migrating_to().unwrap(),
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    pub fn wind_down(ctx: Context<SelfAuth>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let multisig = &mut ctx.accounts.multisig;
+        require!(multisig.wound_down_at.is_none(), MultisigError::WindDownRestricted);
+        multisig.wound_down_at = Some(now);
+        emit!(WindDownStarted {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig.key(),
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    pub fn set_amount_rules(ctx: Context<Auth>, rules: Vec<AmountRule>) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        require!(rules.len() <= MAX_AMOUNT_RULES, MultisigError::TooManyAmountRules);
+        multisig.amount_rules = rules;
+        multisig.validate_thresholds(Clock::get()?.unix_timestamp)
+    }
+
+    // 按 key 指定，存储为当前成员列表上的位图；空列表即取消分组要求
+    pub fn set_approval_groups(
+        ctx: Context<Auth>,
+        groups: Vec<ApprovalGroupSpec>,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        require!(
+            groups.len() <= MAX_APPROVAL_GROUPS,
+            MultisigError::TooManyApprovalGroups
+        );
+        let groups = groups
+            .iter()
+            .map(|g| {
+                let mut members = 0u32;
+                for key in &g.members {
+                    members |= 1 << multisig.member_index(key).ok_or(MultisigError::NotMember)?;
+                }
+                Ok(ApprovalGroup {
+                    members,
+                    min_approvals: g.min_approvals,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        multisig.approval_groups = groups;
+        multisig.validate_thresholds(Clock::get()?.unix_timestamp)
+    }
+
+    pub fn set_trusted_destinations(
+        ctx: Context<Auth>,
+        destinations: Vec<Pubkey>,
+    ) -> Result<()> {
+        let mut destinations = destinations;
+        destinations.sort();
+        destinations.dedup();
+        require!(
+            destinations.len() <= MAX_TRUSTED_DESTINATIONS,
+            MultisigError::TooManyTrustedDestinations
+        );
+        ctx.accounts.multisig.trusted_destinations = destinations;
+        Ok(())
+    }
+
+    pub fn set_destination_limits(
+        ctx: Context<Auth>,
+        limits: Vec<DestinationLimit>,
+    ) -> Result<()> {
+        require!(
+            limits.len() <= MAX_DESTINATION_LIMITS
+                && limits
+                    .iter()
+                    .enumerate()
+                    .all(|(i, l)| limits[..i].iter().all(|o| o.mint != l.mint)),
+            MultisigError::InvalidDestinationLimits
+        );
+        ctx.accounts.multisig.destination_limits = limits;
+        Ok(())
+    }
+
+    // 列表为空时立即启用，delay_seconds 为之后修改需等待的时间；
+    // 启用后的增删（含清空与修改等待时间）按当前等待时间排期，由 apply_allowed_destinations 落地。
+    // 再次调用会替换尚未落地的修改并重新计时
+    pub fn set_allowed_destinations(
+        ctx: Context<Auth>,
+        destinations: Vec<Pubkey>,
+        delay_seconds: u32,
+    ) -> Result<()> {
+        let mut destinations = destinations;
+        destinations.sort();
+        destinations.dedup();
+        require!(
+            destinations.len() <= MAX_ALLOWED_DESTINATIONS,
+            MultisigError::TooManyAllowedDestinations
+        );
+        let now = Clock::get()?.unix_timestamp;
+        let multisig = &mut ctx.accounts.multisig;
+        let effective_at = if multisig.allowed_destinations.is_empty() {
+            multisig.allowed_destinations = destinations.clone();
+            multisig.allowlist_delay_seconds = delay_seconds;
+            multisig.pending_allowlist = None;
+            now
+        } else {
+            let effective_at = now.saturating_add(multisig.allowlist_delay_seconds as i64);
+            multisig.pending_allowlist = Some(PendingAllowlist {
+                destinations: destinations.clone(),
+                delay_seconds,
+                effective_at,
+            });
+            effective_at
+        };
+        emit!(AllowedDestinationsChanged {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig.key(),
+            destinations,
+            delay_seconds,
+            effective_at,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    pub fn set_denied_destinations(ctx: Context<Auth>, destinations: Vec<Pubkey>) -> Result<()> {
+        let mut destinations = destinations;
+        destinations.sort();
+        destinations.dedup();
+        require!(
+            destinations.len() <= MAX_DENIED_DESTINATIONS,
+            MultisigError::TooManyDeniedDestinations
+        );
+        ctx.accounts.multisig.denied_destinations = destinations;
+        Ok(())
+    }
+
+    pub fn apply_allowed_destinations(ctx: Context<ApplyAllowedDestinations>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let multisig = &mut ctx.accounts.multisig;
+        let pending = multisig
+            .pending_allowlist
+            .take()
+            .ok_or(MultisigError::NoPendingAllowlistChange)?;
+        if now < pending.effective_at {
+            msg!("Allowlist change takes effect at {}, now {}", pending.effective_at, now);
+            return err!(MultisigError::AllowlistChangeNotReady);
+        }
+        multisig.allowed_destinations = pending.destinations.clone();
+        multisig.allowlist_delay_seconds = pending.delay_seconds;
+        emit!(AllowedDestinationsChanged {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig.key(),
+            destinations: pending.destinations,
+            delay_seconds: pending.delay_seconds,
+            effective_at: now,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    pub fn set_member_expiry(
+        ctx: Context<Auth>,
+        member: Pubkey,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let index = multisig.member_index(&member).ok_or(MultisigError::NotMember)?;
+        multisig.members[index].expires_at = expires_at;
+        multisig.validate_thresholds(Clock::get()?.unix_timestamp)
+    }
+
+    // 启用时所有成员从现在开始计时；关闭时清除全部 inactive 标记
+    pub fn set_liveness(
+        ctx: Context<Auth>,
+        inactivity_timeout: Option<u32>,
+        liveness_floor: u8,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let now = Clock::get()?.unix_timestamp;
+        match (multisig.inactivity_timeout, inactivity_timeout) {
+            (None, Some(_)) => multisig.members.iter_mut().for_each(|m| m.last_seen = now),
+            (_, None) => multisig.members.iter_mut().for_each(|m| m.inactive = false),
+            _ => {}
+        }
+        multisig.inactivity_timeout = inactivity_timeout;
+        multisig.liveness_floor = liveness_floor;
+        multisig.validate_thresholds(now)
+    }
+
+    pub fn heartbeat(ctx: Context<Heartbeat>) -> Result<()> {
+        let member = ctx.accounts.member.key();
+        let multisig = &mut ctx.accounts.multisig;
+        let now = Clock::get()?.unix_timestamp;
+        let index = multisig.active_member_index(&member, now)?;
+        multisig.touch_member(index, now);
+        Ok(())
+    }
+
+    // 超过 inactivity_timeout 未签名的成员不再计入在线成员，阈值随之最多降到 liveness_floor
+    pub fn mark_inactive(ctx: Context<MarkInactive>, member: Pubkey) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let now = Clock::get()?.unix_timestamp;
+        multisig.mark_inactive(&member, now)?;
+
+        let index = multisig.member_index(&member).ok_or(MultisigError::NotMember)?;
+        emit!(MemberMarkedInactive {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig.key(),
+            member,
+            last_seen: multisig.members[index].last_seen,
+            live_members: multisig.live_members(now) as u8,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    // 只读：供客户端模拟调用，检查到期是否会让阈值不可达
+    pub fn validate_config(ctx: Context<ViewMultisig>) -> Result<ConfigStatus> {
+        let status = ctx.accounts.multisig.config_status(Clock::get()?.unix_timestamp);
+        if let Some(t) = status.unreachable_at {
+            msg!("Threshold becomes unreachable at {}", t);
+        }
+        Ok(status)
+    }
+}
+
+fn cast_vote(
+    ctx: Context<ApproveTransaction>,
+    vote: Vote,
+    rejection: Option<(u8, String)>,
+) -> Result<()> {
+    let voter = ctx.accounts.approver.key();
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+    let multisig = &mut ctx.accounts.multisig;
+    multisig.require_top_level()?;
+    multisig.require_not_migrating()?;
+    let index = multisig.active_member_index(&voter, now)?;
+    let proposal = &mut ctx.accounts.proposal;
+    record_vote(multisig, proposal, index, vote, &clock, ctx.remaining_accounts)?;
+
+    // 改投时旧的拒绝理由一并清除
+    proposal.rejection_reasons.retain(|r| r.member != voter);
+    if let Some((code, reason)) = rejection {
+        // 扩容后加入的成员可能超出提案创建时预留的空间
+        let needed = Proposal::BASE_SPACE
+            + 8 * proposal.approved_at.len()
+            + Proposal::REJECTION_SPACE * (proposal.rejection_reasons.len() + 1);
+        require!(
+            needed <= proposal.to_account_info().data_len(),
+            MultisigError::TooManyRejectionReasons
+        );
+        proposal.rejection_reasons.push(RejectionReason {
+            member: voter,
+            code,
+            reason: reason.clone(),
+        });
+        emit!(ProposalRejected {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig.key(),
+            proposal: proposal.key(),
+            member: voter,
+            code,
+            reason,
+            rejections: multisig.count_votes(proposal.rejections, now),
+            timestamp: now,
+        });
+    }
+
+    if let Some(stats) = &mut ctx.accounts.member_stats {
+        match vote {
+            Vote::Approve => stats.approvals_given += 1,
+            Vote::Reject => stats.rejections_given += 1,
+            Vote::Abstain => {}
+        }
+        stats.last_vote_at = now;
+    }
+    Ok(())
+}
+
+// 两种成员共用的计票：冻结与截止检查、记票、心跳及达到阈值时间。
+// account_infos 为投票指令的 remaining_accounts，只用于计算阈值（如普通 SPL transfer 的源账户）
+fn record_vote(
+    multisig: &mut Account<Multisig>,
+    proposal: &mut Account<Proposal>,
+    index: usize,
+    vote: Vote,
+    clock: &Clock,
+    account_infos: &[AccountInfo],
+) -> Result<()> {
+    let now = clock.unix_timestamp;
+    // 冻结期间只能推进 unfreeze 提案
+    require!(
+        multisig.frozen_by.is_none() || proposal.action.is_unfreeze(),
+        MultisigError::Frozen
+    );
+    require!(!proposal.is_voting_closed(now), MultisigError::VotingClosed);
+
+    // 乐观提案自创建起已开始计时，投票只用于否决；作废后不能再改票
+    if multisig.optimistic_lane(proposal).is_some() {
+        require!(now < proposal.veto_window_ends_at(), MultisigError::VotingClosed);
+        require!(!multisig.is_vetoed(proposal, now), MultisigError::ProposalVetoed);
+        proposal.cast_vote(index, vote)?;
+        if vote == Vote::Approve {
+            proposal.set_approved_at(index, now);
+        }
+        multisig.touch_member(index, now);
+        multisig.last_activity = now;
+        return Ok(());
+    }
+
+    let ttl = multisig.approval_ttl_seconds;
+    // 与 judge 使用同一阈值（含档位、金额规则与金库 override），否则 timelock 会提前或永不起算
+    let (threshold, _) = multisig.proposal_threshold(&multisig.key(), proposal, account_infos, now);
+    let threshold = threshold as u32;
+    // 批准过期使提案跌回阈值以下后，再次达到阈值时 timelock 从头计算
+    if multisig.count_votes(proposal.fresh_approvals(ttl, now), now) < threshold {
+        proposal.ready_at = None;
+        proposal.ready_slot = None;
+    }
+    let bit = 1u32 << index;
+    let expired = proposal.approvals & bit != 0 && proposal.fresh_approvals(ttl, now) & bit == 0;
+    // 重新确认过期的批准只刷新时间，不报 AlreadyApproved
+    if !(vote == Vote::Approve && expired) {
+        proposal.cast_vote(index, vote)?;
+    }
+    if vote == Vote::Approve {
+        proposal.set_approved_at(index, now);
+    }
+    // 投票即心跳，须在计票前恢复该成员
+    multisig.touch_member(index, now);
+
+    // 记录（或在改票后撤销）达到阈值的时间
+    let approvals = multisig.count_votes(proposal.fresh_approvals(ttl, now), now);
+    // 时长在链上算好，索引方无需关联创建交易
+    if vote == Vote::Approve {
+        emit!(ApprovalAdded {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig.key(),
+            proposal: proposal.key(),
+            member: multisig.members[index].key,
+            approvals: approvals as u8,
+            seconds_since_creation: now.saturating_sub(proposal.created_at),
+            timestamp: now,
+        });
+    }
+    match (approvals >= threshold, proposal.ready_at) {
+        (true, None) => {
+            proposal.ready_at = Some(now);
+            proposal.ready_slot = Some(clock.slot);
+            emit!(ProposalReady {
+                schema_version: EVENT_SCHEMA_VERSION,
+                multisig: multisig.key(),
+                proposal: proposal.key(),
+                index: proposal.index,
+                approvals: approvals as u8,
+                time_to_ready: now.saturating_sub(proposal.created_at),
+                timestamp: now,
+            });
+        }
+        (false, Some(_)) => {
+            proposal.ready_at = None;
+            proposal.ready_slot = None;
+        }
+        _ => {}
+    }
+    multisig.last_activity = now;
+    Ok(())
+}
+
+fn check_metadata_uri(uri: &Option<String>) -> Result<()> {
+    if let Some(uri) = uri {
+        require!(
+            !uri.is_empty() && uri.len() <= MAX_METADATA_URI_LEN,
+            MultisigError::UriTooLong
+        );
+    }
+    Ok(())
+}
+
+fn write_member_metadata(
+    metadata: &mut Account<MemberMetadata>,
+    label: String,
+    notification_key: Option<Pubkey>,
+    contact_hash: [u8; 32],
+    updated_by: Pubkey,
+) -> Result<()> {
+    // 允许空 label，表示只登记通知地址
+    require!(label.len() <= MAX_MEMBER_LABEL_LEN, MultisigError::InvalidMemberLabel);
+    let now = Clock::get()?.unix_timestamp;
+    metadata.label = label;
+    metadata.notification_key = notification_key;
+    metadata.contact_hash = contact_hash;
+    metadata.updated_at = now;
+    emit!(MemberMetadataSet {
+        schema_version: EVENT_SCHEMA_VERSION,
+        multisig: metadata.multisig,
+        member: metadata.member,
+        label: metadata.label.clone(),
+        notification_key,
+        contact_hash,
+        updated_by,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+fn check_vault_name(name: &str) -> Result<()> {
+    require!(
+        !name.is_empty() && name.len() <= MAX_VAULT_NAME_LEN,
+        MultisigError::InvalidVaultName
+    );
+    Ok(())
+}
+
+fn emit_metadata_set(proposal: &Account<Proposal>, now: i64) {
+    emit!(ProposalMetadataSet {
+        schema_version: EVENT_SCHEMA_VERSION,
+        multisig: proposal.multisig,
+        proposal: proposal.key(),
+        metadata_uri: proposal.metadata_uri.clone(),
+        metadata_hash: proposal.metadata_hash,
+        timestamp: now,
+    });
+}
+
+fn emit_cancelled(
+    proposal: &Account<Proposal>,
+    canceller: Option<Pubkey>,
+    reason: u8,
+    note: Option<String>,
+    now: i64,
+) -> Result<()> {
+    if let Some(note) = &note {
+        require!(
+            !note.is_empty() && note.len() <= MAX_CANCEL_NOTE_LEN,
+            MultisigError::InvalidCancelNote
+        );
+    }
+    emit!(ProposalCancelled {
+        schema_version: EVENT_SCHEMA_VERSION,
+        multisig: proposal.multisig,
+        proposal: proposal.key(),
+        index: proposal.index,
+        canceller,
+        reason,
+        note,
+        action_hash: proposal.action.hash()?,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+// 验证成员：排序 + 唯一 + 非空，且阈值与容量有效
+fn initial_members(
+    mut members: Vec<Pubkey>,
+    threshold: u8,
+    max_members: u8,
+    now: i64,
+) -> Result<Vec<Member>> {
+    members.sort();
+    members.dedup();
+    require!(!members.is_empty(), MultisigError::InvalidMembers);
+    require!(
+        max_members as usize <= MAX_MEMBERS && members.len() <= max_members as usize,
+        MultisigError::TooManyMembers
+    );
+    require!(threshold > 0 && threshold <= members.len() as u8, MultisigError::InvalidThreshold);
+    Ok(members
+        .into_iter()
+        .map(|key| Member {
+            key,
+            expires_at: None,
+            last_seen: now,
+            inactive: false,
+            key_type: KeyType::Ed25519,
+        })
+        .collect())
+}
+
+// ProposeTransaction 系列指令共用：创建提案，传入内容索引时一并登记
+fn create_indexed_proposal<'info>(
+    accounts: &mut ProposeTransaction<'info>,
+    bump: u8,
+    action: ProposalAction,
+    options: ProposalOptions,
+) -> Result<()> {
+    let allow_duplicate = options.allow_duplicate;
+    let hash = match accounts.instruction_index {
+        Some(_) => Some(action.hash()?),
+        None => None,
+    };
+    create_proposal(
+        &mut accounts.multisig,
+        &mut accounts.proposal,
+        accounts.member_stats.as_mut(),
+        load_program_config(&accounts.program_config)?.as_ref(),
+        accounts.proposer.key(),
+        bump,
+        action,
+        options,
+    )?;
+    match hash {
+        Some(hash) => index_proposal(accounts, hash, allow_duplicate),
+        None => Ok(()),
+    }
+}
+
+// 未被索引或原提案已结束时（重新）指向新提案；原提案仍活跃时拒绝，除非 allow_duplicate
+fn index_proposal(
+    accounts: &ProposeTransaction,
+    hash: [u8; 32],
+    allow_duplicate: bool,
+) -> Result<()> {
+    let Some(index_info) = accounts.instruction_index.as_ref() else {
+        return Ok(());
+    };
+    let multisig = accounts.multisig.key();
+    let proposal = accounts.proposal.key();
+    let (address, bump) = find_instruction_index_address(&multisig, &hash);
+    require_keys_eq!(index_info.key(), address, MultisigError::AccountMismatch);
+
+    if index_info.owner == &crate::ID && !index_info.data_is_empty() {
+        let mut index = InstructionIndex::try_deserialize(&mut &index_info.try_borrow_data()?[..])?;
+        let indexed = accounts
+            .indexed_proposal
+            .as_ref()
+            .ok_or(MultisigError::AccountMismatch)?;
+        require_keys_eq!(indexed.key(), index.proposal, MultisigError::AccountMismatch);
+        if proposal_is_active(indexed) {
+            require!(allow_duplicate, MultisigError::DuplicateProposal);
+            msg!("Duplicate of active proposal {}", index.proposal);
+            return Ok(());
+        }
+        index.proposal = proposal;
+        return index.try_serialize(&mut &mut index_info.try_borrow_mut_data()?[..]);
+    }
+
+    let seeds: &[&[u8]] = &[INSTRUCTION_INDEX_SEED, multisig.as_ref(), &hash, &[bump]];
+    create_pda_account(
+        index_info,
+        &accounts.proposer,
+        &accounts.system_program,
+        seeds,
+        InstructionIndex::SPACE,
+    )?;
+    let index = InstructionIndex {
+        multisig,
+        proposal,
+        hash,
+        bump,
+    };
+    index.try_serialize(&mut &mut index_info.try_borrow_mut_data()?[..])
+}
+
+// 已关闭（owner 不再是本程序或数据为空）或已执行、取消的提案不算活跃
+fn proposal_is_active(info: &AccountInfo) -> bool {
+    if info.owner != &crate::ID {
+        return false;
+    }
+    let Ok(data) = info.try_borrow_data() else {
+        return false;
+    };
+    Proposal::try_deserialize(&mut &data[..]).is_ok_and(|p| !p.executed && !p.cancelled)
+}
+
+// 与 Anchor init 相同：地址已被预先转入 lamports 时无法 create_account，改为补足租金后 allocate + assign
+fn create_pda_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    space: usize,
+) -> Result<()> {
+    use anchor_lang::system_program::{allocate, assign, create_account, transfer};
+    use anchor_lang::system_program::{Allocate, Assign, CreateAccount, Transfer};
+
+    let signer = &[seeds];
+    let rent = Rent::get()?.minimum_balance(space);
+    let funded = account.lamports();
+    if funded == 0 {
+        let accounts = CreateAccount {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        let ctx = CpiContext::new_with_signer(system_program.clone(), accounts, signer);
+        return create_account(ctx, rent, space as u64, &crate::ID);
+    }
+    if funded < rent {
+        let accounts = Transfer {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        transfer(CpiContext::new(system_program.clone(), accounts), rent - funded)?;
+    }
+    let accounts = Allocate {
+        account_to_allocate: account.clone(),
+    };
+    allocate(
+        CpiContext::new_with_signer(system_program.clone(), accounts, signer),
+        space as u64,
+    )?;
+    let accounts = Assign {
+        account_to_assign: account.clone(),
+    };
+    assign(CpiContext::new_with_signer(system_program.clone(), accounts, signer), &crate::ID)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create_proposal(
+    multisig: &mut Account<Multisig>,
+    proposal: &mut Account<Proposal>,
+    stats: Option<&mut Account<MemberStats>>,
+    config: Option<&ProgramConfig>,
+    proposer: Pubkey,
+    bump: u8,
+    action: ProposalAction,
+    options: ProposalOptions,
+) -> Result<()> {
+    let created =
+        init_proposal(multisig, proposal, stats, config, proposer, bump, action, options)?;
+    emit!(created);
+    if proposal.metadata_uri.is_some() {
+        emit_metadata_set(proposal, created.timestamp);
+    }
+    Ok(())
+}
+
+// 校验并写入提案、递增计数器；返回的 ProposalCreated 由调用方发出
+#[allow(clippy::too_many_arguments)]
+fn init_proposal(
+    multisig: &mut Account<Multisig>,
+    proposal: &mut Account<Proposal>,
+    stats: Option<&mut Account<MemberStats>>,
+    config: Option<&ProgramConfig>,
+    proposer: Pubkey,
+    bump: u8,
+    action: ProposalAction,
+    options: ProposalOptions,
+) -> Result<ProposalCreated> {
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+    let proposer_index = multisig.active_member_index(&proposer, now)?;
+    multisig.require_top_level()?;
+    multisig.require_not_migrating()?;
+
+    if let (Some(not_before), Some(not_after)) = (options.not_before, options.not_after) {
+        require!(not_before < not_after, MultisigError::InvalidWindow);
+    }
+    // 投票截止须在未来，且不晚于执行截止
+    if let Some(voting_ends_at) = options.voting_ends_at {
+        require!(
+            voting_ends_at > now && options.not_after.is_none_or(|t| voting_ends_at <= t),
+            MultisigError::InvalidWindow
+        );
+    }
+
+    if let ProposalAction::Batch(ixs) = &action {
+        require!(
+            !ixs.is_empty() && ixs.len() <= MAX_BATCH_INSTRUCTIONS,
+            MultisigError::InvalidBatch
+        );
+    }
+
+    // 仅记录的提案不会调用任何程序，无需检查
+    if !options.allow_dangerous && !options.no_sign {
+        let multisig_key = multisig.key();
+        let multisig_info = multisig.to_account_info();
+        multisig.check_protected(&action, &multisig_key, |key| {
+            (*key == multisig_key).then(|| (multisig_info.lamports(), multisig_info.data_len()))
+        })?;
+    }
+
+    // 仅记录的提案不签名；分期与周期付款在激活时并无转账
+    if let Some(memo) = &options.execution_memo {
+        require!(
+            !memo.is_empty()
+                && memo.len() <= MAX_EXECUTION_MEMO_LEN
+                && !options.no_sign
+                && !matches!(action, ProposalAction::Vesting(_) | ProposalAction::Recurring(_)),
+            MultisigError::InvalidExecutionMemo
+        );
+    }
+
+    // 只能延长：紧急提案以 urgent_timelock_seconds 为下限
+    let default_delay = multisig.default_delay(options.urgent);
+    if let Some(delay) = options.delay_override {
+        if delay < default_delay {
+            msg!("Delay override {} is shorter than the default {}", delay, default_delay);
+            return err!(MultisigError::DelayOverrideTooShort);
+        }
+    }
+
+    check_metadata_uri(&options.metadata_uri)?;
+
+    // 跳过批准的通道不能与需要更多批准的选项组合
+    let optimistic_delay = if options.optimistic {
+        let config = multisig
+            .optimistic_config()
+            .filter(|c| c.allows(&action) && !options.urgent && !options.allow_dangerous)
+            .ok_or(MultisigError::NotOptimisticEligible)?;
+        config.delay_seconds
+    } else {
+        0
+    };
+
+    if multisig.wound_down_at.is_some() && !action.is_wind_down_allowed(&multisig.key()) {
+        return err!(MultisigError::WindDownRestricted);
+    }
+    if !options.no_sign {
+        multisig.check_allowed_destinations(&action, &multisig.key(), None)?;
+    }
+
+    // 只能依赖更早的提案，因此不会出现自依赖或循环
+    if let Some(index) = options.depends_on {
+        require!(
+            multisig.is_executed(index).is_some(),
+            MultisigError::InvalidDependency
+        );
+    }
+
+    // 仅记录的提案不会转出资金
+    proposal.protocol_fee = match config {
+        Some(config) if !options.no_sign && !multisig.protocol_fee_opted_out() => {
+            config.quote(&action, &multisig.key())
+        }
+        _ => None,
+    };
+    proposal.multisig = multisig.key();
+    proposal.proposer = proposer;
+    proposal.action = action;
+    proposal.approvals = 0;
+    proposal.rejections = 0;
+    proposal.abstentions = 0;
+    proposal.comments_count = 0;
+    proposal.index = multisig.proposals_count;
+    proposal.depends_on = options.depends_on;
+    proposal.not_before = options.not_before;
+    proposal.not_after = options.not_after;
+    proposal.voting_ends_at = options.voting_ends_at;
+    proposal.metadata_uri = options.metadata_uri;
+    proposal.metadata_hash = options.metadata_hash;
+    proposal.urgent = options.urgent;
+    proposal.allow_dangerous = options.allow_dangerous;
+    proposal.no_sign = options.no_sign;
+    // 乐观提案的 timelock 自创建起算
+    proposal.optimistic = options.optimistic;
+    proposal.ready_at = options.optimistic.then_some(now);
+    proposal.ready_slot = options.optimistic.then_some(clock.slot);
+    proposal.tranches_paid = 0;
+    proposal.payments_made = 0;
+    proposal.last_paid_at = None;
+    proposal.executed_at = None;
+    proposal.executed_by = None;
+    proposal.designated_executor = options.designated_executor;
+    proposal.approved_at = vec![];
+    proposal.created_at = now;
+    proposal.execution_attempts = 0;
+    proposal.last_failed_slot = 0;
+    proposal.execution_memo = options.execution_memo;
+    proposal.rejection_reasons = vec![];
+    proposal.delay_seconds = options
+        .delay_override
+        .unwrap_or(default_delay)
+        .max(optimistic_delay);
+    proposal.threshold_at_creation = multisig.threshold;
+    proposal.executed = false;
+    proposal.cancelled = false;
+    proposal.bump = bump;
+    proposal.version = PROPOSAL_VERSION;
+    proposal.config_seqno = multisig.config_seqno;
+    proposal.members_hash = multisig.members_hash();
+
+    let created = ProposalCreated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        multisig: multisig.key(),
+        proposal: proposal.key(),
+        index: multisig.proposals_count,
+        proposer,
+        urgent: options.urgent,
+        timestamp: now,
+        vaults: multisig.signing_vaults(&proposal.action),
+        protocol_fee: proposal.protocol_fee.clone(),
+    };
+
+    // 递增计数器（防重放）
+    multisig.record_proposal();
+    multisig.touch_member(proposer_index, now);
+    multisig.last_activity = now;
+    if let Some(stats) = stats {
+        stats.proposals_created += 1;
+    }
+    Ok(created)
+}
+
+// 把金库持有的 token 账户余额转入 destination 后关闭，租金退回金库
+fn sweep_token_account<'info>(
+    source: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    vault_seeds: &[&[u8]],
+) -> Result<()> {
+    require_keys_eq!(*source.owner, *token_program.key, MultisigError::AccountMismatch);
+    let amount = {
+        let data = source.try_borrow_data()?;
+        require!(
+            data.get(..32) == Some(mint.key.as_ref())
+                && data.get(32..64) == Some(vault.key.as_ref()),
+            MultisigError::AccountMismatch
+        );
+        decode::read_u64(&data, 64).ok_or(MultisigError::AccountMismatch)?
+    };
+    if amount > 0 {
+        let decimals = *mint
+            .try_borrow_data()?
+            .get(44)
+            .ok_or(MultisigError::AccountMismatch)?;
+        let mut data = vec![12]; // TransferChecked
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.push(decimals);
+        invoke_signed(
+            &Instruction {
+                program_id: *token_program.key,
+                accounts: vec![
+                    AccountMeta::new(*source.key, false),
+                    AccountMeta::new_readonly(*mint.key, false),
+                    AccountMeta::new(*destination.key, false),
+                    AccountMeta::new_readonly(*vault.key, true),
+                ],
+                data,
+            },
+            &[source.clone(), mint.clone(), destination.clone(), vault.clone()],
+            &[vault_seeds],
+        )?;
+    }
+    // TokenInstruction::CloseAccount
+    invoke_signed(
+        &Instruction {
+            program_id: *token_program.key,
+            accounts: vec![
+                AccountMeta::new(*source.key, false),
+                AccountMeta::new(*vault.key, false),
+                AccountMeta::new_readonly(*vault.key, true),
+            ],
+            data: vec![9],
+        },
+        &[source.clone(), vault.clone()],
+        &[vault_seeds],
+    )?;
+    Ok(())
+}
+
+fn check_deposit(amount: u64, memo: &Option<String>) -> Result<()> {
+    require!(amount > 0, MultisigError::InvalidDepositAmount);
+    if let Some(memo) = memo {
+        require!(
+            !memo.is_empty() && memo.len() <= MAX_DEPOSIT_MEMO_LEN,
+            MultisigError::InvalidDepositMemo
+        );
+    }
+    Ok(())
+}
+
+// 未初始化时为空账户，不报价；已初始化则必须按其报价，调用方无法通过不传账户绕过
+fn load_program_config(info: &AccountInfo) -> Result<Option<ProgramConfig>> {
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(None);
+    }
+    let data = info.try_borrow_data()?;
+    Ok(Some(ProgramConfig::try_deserialize(&mut &data[..])?))
+}
+
+fn emit_program_config(config: &ProgramConfig) -> Result<()> {
+    emit!(ProgramConfigUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        authority: config.authority,
+        fee_bps: config.fee_bps,
+        flat_fee_lamports: config.flat_fee_lamports,
+        fee_recipient: config.fee_recipient,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// memo 账户固定在末尾，拆出后其余账户按动作原样校验
+fn split_memo_accounts<'a, 'info>(
+    multisig: &Account<'info, Multisig>,
+    proposal: &Proposal,
+    account_infos: &'a [AccountInfo<'info>],
+) -> Result<(&'a [AccountInfo<'info>], Option<&'a [AccountInfo<'info>]>)> {
+    if proposal.execution_memo.is_none() {
+        return Ok((account_infos, None));
+    }
+    let split = account_infos
+        .len()
+        .checked_sub(2)
+        .ok_or(MultisigError::MemoProgramMissing)?;
+    let (rest, memo_accounts) = account_infos.split_at(split);
+    require_keys_eq!(
+        *memo_accounts[0].key,
+        find_vault_address(&multisig.key(), 0).0,
+        MultisigError::MemoProgramMissing
+    );
+    require_keys_eq!(
+        *memo_accounts[1].key,
+        MEMO_PROGRAM_ID,
+        MultisigError::MemoProgramMissing
+    );
+    Ok((rest, Some(memo_accounts)))
+}
+
+// 按当前账户状态执行必然失败的原因；传入的账户与提案不一致是调用方的问题，返回 AccountMismatch
+fn execution_failure(
+    ix: &InstructionData,
+    multisig_key: &Pubkey,
+    account_infos: &[AccountInfo],
+    protected: bool,
+) -> Result<Option<ExecutionFailure>> {
+    if let Some(failure) = signer_mismatch(ix, multisig_key, account_infos)? {
+        return Ok(Some(failure));
+    }
+    let infos = &account_infos[..ix.accounts.len()];
+    let signers = ix.signer_pdas(multisig_key);
+    if is_token_program(&ix.program_id) && infos.iter().any(|a| a.lamports() == 0) {
+        return Ok(Some(ExecutionFailure::MissingAccount));
+    }
+    let find = |key: &Pubkey| infos.iter().find(|a| a.key == key);
+    if let Some((info, amount)) = system_debit(ix).and_then(|(from, a)| Some((find(&from)?, a))) {
+        let floor = Rent::get()?.minimum_balance(info.data_len());
+        let fails = match info.lamports().checked_sub(amount) {
+            None => true,
+            // 与 check_protected 一致：签名 PDA 转出后不得低于免租下限
+            Some(rest) if protected && signers.contains(info.key) => rest < floor,
+            // 运行时不允许转出后留下不足免租的非零余额
+            Some(rest) => rest != 0 && rest < floor,
+        };
+        if fails {
+            return Ok(Some(ExecutionFailure::InsufficientBalance));
+        }
+    }
+    if let Some(Transfer { source, mint: Some(_), amount, .. }) = decode_transfer(ix, infos) {
+        let balance = find(&source).and_then(read_token_account);
+        if balance.is_some_and(|b| b.amount < amount) {
+            return Ok(Some(ExecutionFailure::InsufficientBalance));
+        }
+    }
+    Ok(None)
+}
+
+// 类型化动作按当前账户状态执行必然失败的原因：动作引用的账户已关闭，或余额不足以提取、拆分或销毁；
+// 所需账户未传入是调用方的问题，返回 AccountMismatch
+fn typed_execution_failure(
+    action: &ProposalAction,
+    multisig_key: &Pubkey,
+    account_infos: &[AccountInfo],
+) -> Result<Option<ExecutionFailure>> {
+    let find = |key: &Pubkey| {
+        account_infos
+            .iter()
+            .find(|a| a.key == key)
+            .ok_or(error!(MultisigError::AccountMismatch))
+    };
+    // (账户, 须从中转出的 lamports)
+    let (account, lamports) = match action {
+        ProposalAction::StakeWithdraw {
+            stake_account: account,
+            lamports,
+            ..
+        }
+        | ProposalAction::StakeSplit {
+            stake_account: account,
+            lamports,
+        }
+        | ProposalAction::VoteWithdraw {
+            vote_account: account,
+            lamports,
+            ..
+        } => (account, *lamports),
+        ProposalAction::StakeDeactivate(account)
+        | ProposalAction::FreezeTokenAccount(account)
+        | ProposalAction::ThawTokenAccount(account)
+        | ProposalAction::TokenRevoke(account)
+        | ProposalAction::TokenApprove {
+            token_account: account,
+            ..
+        }
+        | ProposalAction::SetTokenAuthority { target: account, .. }
+        | ProposalAction::MintTo { mint: account, .. }
+        | ProposalAction::TakeCustody { account, .. }
+        | ProposalAction::ReleaseCustody { account, .. } => (account, 0),
+        ProposalAction::Burn { mint, amount } => {
+            let vault = find_vault_address(multisig_key, 0).0;
+            let source = [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID]
+                .iter()
+                .map(|p| find_associated_token_address(&vault, mint, p))
+                .find_map(|key| account_infos.iter().find(|a| *a.key == key))
+                .ok_or(MultisigError::AccountMismatch)?;
+            if source.lamports() == 0 {
+                return Ok(Some(ExecutionFailure::MissingAccount));
+            }
+            let short = read_token_account(source).is_some_and(|t| t.amount < *amount);
+            return Ok(short.then_some(ExecutionFailure::InsufficientBalance));
+        }
+        _ => return Ok(None),
+    };
+    let info = find(account)?;
+    if info.lamports() == 0 {
+        return Ok(Some(ExecutionFailure::MissingAccount));
+    }
+    Ok((info.lamports() < lamports).then_some(ExecutionFailure::InsufficientBalance))
+}
+
+// 只看提案本身：标记为签名者的多签或金库 PDA 未被 signers 选中时必然失败
+fn signer_mismatch(
+    ix: &InstructionData,
+    multisig_key: &Pubkey,
+    account_infos: &[AccountInfo],
+) -> Result<Option<ExecutionFailure>> {
+    let infos = account_infos
+        .get(..ix.accounts.len())
+        .ok_or(MultisigError::AccountMismatch)?;
+    require!(
+        ix.accounts.iter().zip(infos).all(|(m, a)| m.pubkey == *a.key),
+        MultisigError::AccountMismatch
+    );
+
+    // 多签与金库 PDA 只能由本程序按 signers 签名
+    let signers = ix.signer_pdas(multisig_key);
+    let unsignable = |key: &Pubkey| {
+        !signers.contains(key)
+            && (key == multisig_key
+                || (0..MAX_VAULTS as u8).any(|i| find_vault_address(multisig_key, i).0 == *key))
+    };
+    if ix.accounts.iter().any(|m| m.is_signer && unsignable(&m.pubkey)) {
+        return Ok(Some(ExecutionFailure::MetaMismatch));
+    }
+    Ok(None)
+}
+
+// 批准数、所需阈值，以及第一个不满足的执行条件（对应状态与执行时返回的错误）
+struct Verdict {
+    approvals: u32,
+    threshold: u8,
+    blocked: Option<(ProposalStatus, Error)>,
+}
+
+// execute_proposal 与 get_proposal_status 共用；检查顺序即执行时报错的优先级
+fn judge<'info>(
+    multisig: &Account<'info, Multisig>,
+    proposal: &Proposal,
+    account_infos: &[AccountInfo<'info>],
+    now: i64,
+    check_balances: bool,
+) -> Result<Verdict> {
+    // approve 已保证位唯一，这里只需计数（忽略已到期成员与过期的批准）
+    let fresh = proposal.fresh_approvals(multisig.approval_ttl_seconds, now);
+    let approvals = multisig.tally(proposal, fresh, now);
+    let multisig_key = multisig.key();
+    let (threshold, tier) =
+        multisig.proposal_threshold(&multisig_key, proposal, account_infos, now);
+    let lane = multisig.optimistic_lane(proposal);
+    let blocked = |status: ProposalStatus, error: Error| -> Result<Verdict> {
+        Ok(Verdict {
+            approvals,
+            threshold,
+            blocked: Some((status, error)),
+        })
+    };
+
+    if multisig.frozen_by.is_some() && !proposal.action.is_unfreeze() {
+        return blocked(ProposalStatus::Blocked, error!(MultisigError::Frozen));
+    }
+    // 清退前创建的提案同样受限
+    if multisig.wound_down_at.is_some() && !proposal.action.is_wind_down_allowed(&multisig_key) {
+        return blocked(ProposalStatus::Blocked, error!(MultisigError::WindDownRestricted));
+    }
+    // 只拦截请求了暂停金库签名的提案
+    if !proposal.no_sign {
+        let signing = proposal.action.signing_vaults();
+        if let Some(index) = signing.into_iter().find(|i| multisig.is_vault_paused(*i)) {
+            msg!("Vault {} is paused", index);
+            return blocked(ProposalStatus::Blocked, error!(MultisigError::VaultPaused));
+        }
+    }
+    // 纵深防御：执行时按实际余额再检查一次
+    if check_balances && !proposal.allow_dangerous && !proposal.no_sign {
+        let protected = multisig.check_protected(&proposal.action, &multisig_key, |key| {
+            account_infos
+                .iter()
+                .find(|i| i.key == key)
+                .map(|i| (i.lamports(), i.data_len()))
+        });
+        if let Err(error) = protected {
+            return blocked(ProposalStatus::Blocked, error);
+        }
+    }
+
+    // 快照模式只沿用创建时的阈值；被移除成员的批准须经 prune_approvals 剔除
+    if proposal.config_seqno != multisig.config_seqno {
+        msg!("Proposal predates a membership change; call prune_approvals first");
+        return blocked(ProposalStatus::Blocked, error!(MultisigError::StaleProposal));
+    }
+    if multisig.is_vetoed(proposal, now) {
+        return blocked(ProposalStatus::Blocked, error!(MultisigError::ProposalVetoed));
+    }
+    if approvals < threshold as u32 {
+        msg!("Approvals: {}, threshold: {}", approvals, threshold);
+        if let Some(program_id) = tier {
+            msg!("Threshold tier for program {} requires {}", program_id, threshold);
+            return blocked(ProposalStatus::Pending, error!(MultisigError::TierThresholdNotMet));
+        }
+        return blocked(ProposalStatus::Pending, error!(MultisigError::NotExecutable));
+    }
+
+    if !proposal.no_sign {
+        let unanimous = approvals >= multisig.active_members(now) as u32;
+        let destinations = multisig
+            .check_destination_limits(&proposal.action, &multisig_key, account_infos, unanimous)
+            .and_then(|_| {
+                multisig.check_allowed_destinations(
+                    &proposal.action,
+                    &multisig_key,
+                    Some(account_infos),
+                )
+            })
+            .and_then(|_| {
+                multisig.check_denied_destinations(&proposal.action, &multisig_key, account_infos)
+            });
+        if let Err(error) = destinations {
+            return blocked(ProposalStatus::Blocked, error);
+        }
+    }
+
+    if lane.is_none() && !multisig.approval_groups.is_empty() {
+        if let Some(group) = multisig.unmet_group(fresh, now) {
+            msg!(
+                "Approval group {} requires {} approvals",
+                group,
+                multisig.approval_groups[group].min_approvals
+            );
+            return blocked(ProposalStatus::Pending, error!(MultisigError::GroupApprovalsNotMet));
+        }
+    }
+
+    if let Some(quorum) = multisig.quorum.filter(|_| lane.is_none()) {
+        let quorum = multisig.liveness_cap(quorum, now);
+        let voted = multisig.tally(proposal, proposal.voted(), now);
+        if voted < quorum as u32 {
+            msg!(
+                "Quorum {} not reached: {} voted ({} approved, {} rejected, {} abstained)",
+                quorum,
+                voted,
+                approvals,
+                multisig.tally(proposal, proposal.rejections, now),
+                multisig.tally(proposal, proposal.abstentions, now)
+            );
+            return blocked(ProposalStatus::Pending, error!(MultisigError::QuorumNotReached));
+        }
+    }
+
+    if let Some(t) = proposal.not_before {
+        if now < t {
+            msg!("Executable from {}, now {}", t, now);
+            return blocked(ProposalStatus::Timelocked, error!(MultisigError::TooEarly));
+        }
+    }
+    if proposal.is_past_window(now) {
+        return blocked(ProposalStatus::Expired, error!(MultisigError::TooLate));
+    }
+
+    let timelock = proposal.delay_seconds as i64;
+    if timelock > 0 {
+        let Some(ready_at) = proposal.ready_at else {
+            return blocked(ProposalStatus::Timelocked, error!(MultisigError::TimelockNotElapsed));
+        };
+        if now < ready_at + timelock {
+            msg!("Executable from {}, now {}", ready_at + timelock, now);
+            return blocked(ProposalStatus::Timelocked, error!(MultisigError::TimelockNotElapsed));
+        }
+    }
+
+    // 防止最后一票与执行被打包进同一笔交易
+    let delay = multisig.min_execution_delay_slots as u64;
+    if delay > 0 {
+        let slot = Clock::get()?.slot;
+        let Some(ready_slot) = proposal.ready_slot else {
+            return blocked(ProposalStatus::Timelocked, error!(MultisigError::ExecutionTooSoon));
+        };
+        if slot < ready_slot + delay {
+            msg!("Executable from slot {}, now {}", ready_slot + delay, slot);
+            return blocked(ProposalStatus::Timelocked, error!(MultisigError::ExecutionTooSoon));
+        }
+    }
+
+    if let Some(index) = proposal.depends_on {
+        if multisig.is_executed(index) != Some(true) {
+            msg!("Proposal {} must be executed first", index);
+            return blocked(ProposalStatus::Blocked, error!(MultisigError::DependencyNotExecuted));
+        }
+    }
+
+    // 仅记录的提案不执行动作，不受暂停影响
+    if multisig.paused && !proposal.no_sign && proposal.action.respects_pause() {
+        return blocked(ProposalStatus::Blocked, error!(MultisigError::Paused));
+    }
+    if multisig.migrating_to().is_some() {
+        return blocked(ProposalStatus::Blocked, error!(MultisigError::MigrationInProgress));
+    }
+
+    Ok(Verdict {
+        approvals,
+        threshold,
+        blocked: None,
+    })
+}
+
+// anchor_lang 未再导出 sol_log_compute_units，与其 sol_log_data 一样直接调用 syscall；链下为空操作
+#[cfg(feature = "log-compute-units")]
+fn sol_log_compute_units() {
+    #[cfg(target_os = "solana")]
+    unsafe {
+        solana_define_syscall::definitions::sol_log_compute_units_();
+    }
+}
+
+// 校验阈值、quorum、执行窗口、timelock 与依赖后执行提案；调用方负责账户归属检查与关闭提案
+fn execute_proposal<'info>(
+    multisig: &mut Account<'info, Multisig>,
+    proposal: &mut Account<'info, Proposal>,
+    mut ledger: Option<&mut SpendLedger>,
+    account_infos: &'info [AccountInfo<'info>],
+    now: i64,
+) -> Result<()> {
+    // 先把缓冲区引用还原为完整指令，之后的阈值与保护检查与普通 Raw 提案一致
+    let account_infos = match &proposal.action {
+        ProposalAction::Buffered(_) => load_buffered(proposal, account_infos)?,
+        _ => account_infos,
+    };
+    let (account_infos, memo_accounts) = split_memo_accounts(multisig, proposal, account_infos)?;
+
+    let verdict = judge(multisig, proposal, account_infos, now, true)?;
+    if let Some((_, error)) = verdict.blocked {
+        return Err(error);
+    }
+    let multisig_key = multisig.key();
+    // CPI 会取走指令数据，策略与通知所需的哈希须先算出
+    let action_hash = proposal.action.hash()?;
+    // 指令账户之后依次为策略账户、协议费账户、通知账户；类型化动作按 key 查找所需账户，不占前缀
+    let mut hook_start = match proposal.no_sign {
+        true => 0,
+        false => proposal.action.instructions().iter().map(|ix| ix.accounts.len()).sum(),
+    };
+    if let Some((program, account_count)) = multisig.policy_program() {
+        let metas: Vec<SerializableAccountMeta> = match proposal.action.instructions() {
+            [] => {
+                let accounts = proposal.action.execute_accounts(&multisig_key);
+                accounts.into_iter().map(Into::into).collect()
+            }
+            ixs => ixs.iter().flat_map(|ix| ix.accounts.iter().cloned()).collect(),
+        };
+        let policy_infos = account_infos.get(hook_start..).unwrap_or_default();
+        hook_start += check_policy(
+            multisig,
+            &proposal.to_account_info(),
+            program,
+            account_count,
+            action_hash,
+            &metas,
+            policy_infos,
+        )?;
+    }
+
+    proposal.executed = true;
+    proposal.executed_at = Some(now);
+    let index = proposal.index;
+    emit!(ProposalExecuted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        multisig: multisig_key,
+        proposal: proposal.key(),
+        index,
+        urgent: proposal.urgent,
+        timestamp: now,
+        vaults: multisig.signing_vaults(&proposal.action),
+    });
+
+    if proposal.no_sign {
+        // 仅记录：不做 CPI、不应用配置变更，事件即为链上存证
+        emit!(ProposalRecorded {
+            schema_version: EVENT_SCHEMA_VERSION,
+            multisig: multisig_key,
+            proposal: proposal.key(),
+            index,
+            action_hash,
+            action: proposal.action.clone(),
+        });
+        notify(multisig, index, action_hash, account_infos, hook_start)?;
+        multisig.mark_executed(index);
+        multisig.last_activity = now;
+        return Ok(());
+    }
+
+    let protocol_fee = proposal.protocol_fee.clone();
+    let proposal_key = proposal.key();
+    match &mut proposal.action {
+        ProposalAction::Raw(_) | ProposalAction::Batch(_) => {
+            // 各指令依次调用，账户按顺序排列；任一失败整笔回滚
+            let mut offset = 0;
+            for ix in proposal.action.instructions_mut() {
+                // CPI 会取走指令数据，须先解码；只统计由本指令签名 PDA 转出的部分
+                let spent = decode_transfer(ix, account_infos)
+                    .filter(|t| ix.signer_pdas(&multisig_key).contains(&t.authority));
+                let ix_len = ix.accounts.len();
+                // 内层指令（包括经其他程序转调回本程序的）会从账户数据重新反序列化 multisig：
+                // 先写回内存中的修改（如 execute_many 中前序提案的执行记录）
+                multisig.exit(&crate::ID)?;
+                invoke_instruction(ix, multisig, account_infos.get(offset..).unwrap_or_default())?;
+
+                // 自治提案会在 CPI 中修改 multisig，重新加载以免 exit 时被旧数据覆盖
+                multisig.reload()?;
+                match spent {
+                    Some(Transfer { mint: None, amount, .. }) => {
+                        multisig.total_lamports_out = multisig
+                            .total_lamports_out
+                            .checked_add(amount)
+                            .ok_or(MultisigError::SpendCounterOverflow)?;
+                    }
+                    Some(Transfer { mint: Some(mint), amount, .. }) => {
+                        if let Some(ledger) = ledger.as_deref_mut() {
+                            ledger.record(mint, amount)?;
+                        }
+                    }
+                    None => {}
+                }
+                offset += ix_len;
+            }
+            if let Some(fee) = &protocol_fee {
+                charge_protocol_fee(multisig, proposal_key, fee, account_infos, now)?;
+            }
+        }
+        action => {
+            let action = action.clone();
+            // 在动作之前收取：SweepVault 会清空金库
+            if let Some(fee) = &protocol_fee {
+                charge_protocol_fee(multisig, proposal_key, fee, account_infos, now)?;
+            }
+            if let ProposalAction::SweepVault { index, destination } = action {
+                sweep_vault(multisig, index, &destination, account_infos)?;
+            }
+            if let ProposalAction::SetTokenAuthority {
+                target,
+                authority_type,
+                new_authority,
+            } = action
+            {
+                set_token_authority(multisig, &target, authority_type, new_authority, account_infos)?;
+            }
+            if let ProposalAction::Burn { mint, amount } = action {
+                burn_from_vault(multisig, &mint, amount, account_infos)?;
+            }
+            if let ProposalAction::FreezeTokenAccount(token_account)
+            | ProposalAction::ThawTokenAccount(token_account) = action
+            {
+                let thaw = matches!(action, ProposalAction::ThawTokenAccount(_));
+                freeze_token_account(multisig, &token_account, thaw, account_infos)?;
+            }
+            if let ProposalAction::TokenApprove {
+                token_account,
+                delegate,
+                amount,
+            } = action
+            {
+                let mint =
+                    approve_delegate(multisig, &token_account, &delegate, amount, account_infos)?;
+                emit!(TokenDelegateApproved {
+                    schema_version: EVENT_SCHEMA_VERSION,
+                    multisig: multisig_key,
+                    proposal: proposal.key(),
+                    token_account,
+                    mint,
+                    delegate,
+                    amount,
+                });
+            }
+            if let ProposalAction::TokenRevoke(token_account) = action {
+                revoke_delegate(multisig, &token_account, account_infos)?;
+            }
+            if let ProposalAction::MintTo {
+                mint,
+                destination_owner,
+                amount,
+            } = action
+            {
+                mint_to(multisig, &mint, &destination_owner, amount, account_infos)?;
+            }
+            if let ProposalAction::VoteAuthorize {
+                vote_account,
+                new_authority,
+                authority_type,
+            } = action
+            {
+                vote_authorize(
+                    multisig,
+                    &vote_account,
+                    &new_authority,
+                    authority_type,
+                    account_infos,
+                )?;
+                // withdrawer 移交出去后不再由本多签控制
+                if authority_type == 1 && new_authority != multisig_key {
+                    multisig.release_custody(&vote_account);
+                }
+            }
+            if let ProposalAction::VoteWithdraw {
+                vote_account,
+                lamports,
+                destination,
+            } = action
+            {
+                vote_withdraw(multisig, &vote_account, lamports, &destination, account_infos)?;
+            }
+            if let ProposalAction::StakeSplit {
+                stake_account,
+                lamports,
+            } = action
+            {
+                let new_stake_account =
+                    stake_split(multisig, &stake_account, lamports, account_infos)?;
+                emit!(StakeAccountSplit {
+                    schema_version: EVENT_SCHEMA_VERSION,
+                    multisig: multisig_key,
+                    proposal: proposal.key(),
+                    stake_account,
+                    new_stake_account,
+                    lamports,
+                });
+            }
+            if let ProposalAction::StakeDeactivate(stake_account) = action {
+                stake_deactivate(multisig, &stake_account, account_infos)?;
+            }
+            if let ProposalAction::StakeWithdraw {
+                stake_account,
+                lamports,
+                destination,
+            } = action
+            {
+                stake_withdraw(multisig, &stake_account, lamports, &destination, account_infos)?;
+            }
+            if let ProposalAction::TakeCustody { account, kind } = action {
+                take_custody(multisig, &account, kind, account_infos)?;
+            }
+            if let ProposalAction::ReleaseCustody {
+                account,
+                kind,
+                new_authority,
+            } = action
+            {
+                release_custody(multisig, &account, kind, &new_authority, account_infos)?;
+            }
+            if let ProposalAction::CreateMint {
+                decimals,
+                freeze_authority,
+            } = action
+            {
+                let mint = create_mint(multisig, decimals, freeze_authority, account_infos)?;
+                emit!(MintCreated {
+                    schema_version: EVENT_SCHEMA_VERSION,
+                    multisig: multisig_key,
+                    proposal: proposal.key(),
+                    mint,
+                    mint_authority: find_vault_address(&multisig_key, 0).0,
+                    freeze_authority,
+                    decimals,
+                });
+            }
+            multisig.apply_action(&action, now)?;
+            emit!(ConfigActionExecuted {
+                schema_version: EVENT_SCHEMA_VERSION,
+                multisig: multisig_key,
+                proposal: proposal.key(),
+                action,
+            });
+        }
+    }
+
+    // 与动作在同一条指令内：memo 失败则整个执行回滚
+    if let (Some(memo), Some(memo_accounts)) = (&proposal.execution_memo, memo_accounts) {
+        let (vault, vault_bump) = find_vault_address(&multisig_key, 0);
+        let vault_seeds: &[&[u8]] = &[VAULT_SEED, multisig_key.as_ref(), &[0], &[vault_bump]];
+        invoke_signed(
+            &Instruction {
+                program_id: MEMO_PROGRAM_ID,
+                accounts: vec![AccountMeta::new_readonly(vault, true)],
+                data: memo.as_bytes().to_vec(),
+            },
+            memo_accounts,
+            &[vault_seeds],
+        )?;
+    }
+
+    notify(multisig, index, action_hash, account_infos, hook_start)?;
+    multisig.mark_executed(index);
+    multisig.last_activity = now;
+    Ok(())
+}
+
+// 设置了通知程序时在执行成功后调用；通知账户位于 hook_start 之后
+fn notify<'info>(
+    multisig: &Account<'info, Multisig>,
+    index: u32,
+    action_hash: [u8; 32],
+    account_infos: &[AccountInfo<'info>],
+    hook_start: usize,
+) -> Result<()> {
+    let Some((program, required)) = multisig.notify_program() else {
+        return Ok(());
+    };
+    let hook_infos = account_infos.get(hook_start..).unwrap_or_default();
+    notify_executed(multisig, program, required, index, action_hash, hook_infos)
+}
+
+// 校验缓冲区内容哈希并解除引用，返回去掉缓冲区后的 remaining_accounts
+// retain 为执行前的设置（执行本身可能修改它），与 invoke_instruction 是否保留指令数据一致
+fn retire_executed<'info>(
+    multisig: &Account<'info, Multisig>,
+    proposal: &mut Account<'info, Proposal>,
+    retain: bool,
+    executor: Option<Pubkey>,
+) -> Result<()> {
+    if !retain {
+        return proposal.close(multisig.to_account_info());
+    }
+    proposal.executed_by = executor;
+    // execute_many 中的提案不由 Anchor 写回
+    proposal.exit(&crate::ID)
+}
+
+fn load_buffered<'info>(
+    proposal: &mut Account<'info, Proposal>,
+    account_infos: &'info [AccountInfo<'info>],
+) -> Result<&'info [AccountInfo<'info>]> {
+    let ProposalAction::Buffered(buffered) = &proposal.action else {
+        unreachable!()
+    };
+    let (mut buffer, ix, rest) = read_buffered(buffered, account_infos)?;
+    // 执行即解除引用，缓冲区账户必须可写
+    buffer.release(&proposal.key())?;
+    buffer.exit(&crate::ID)?;
+
+    proposal.action = ProposalAction::Raw(ix);
+    Ok(rest)
+}
+
+// 校验首个账户为该缓冲区且内容哈希一致，返回缓冲区、还原的指令与其余账户
+fn read_buffered<'info>(
+    buffered: &BufferedInstruction,
+    account_infos: &'info [AccountInfo<'info>],
+) -> Result<(
+    Account<'info, InstructionBuffer>,
+    InstructionData,
+    &'info [AccountInfo<'info>],
+)> {
+    let (buffer_info, rest) = account_infos
+        .split_first()
+        .ok_or(MultisigError::AccountMismatch)?;
+    require_keys_eq!(*buffer_info.key, buffered.buffer, MultisigError::AccountMismatch);
+
+    let buffer = Account::<InstructionBuffer>::try_from(buffer_info)?;
+    require!(
+        hashv(&[&buffer.data]).to_bytes() == buffered.hash,
+        MultisigError::BufferHashMismatch
+    );
+    let ix = InstructionData {
+        program_id: buffered.program_id,
+        accounts: buffered.accounts.clone(),
+        data: buffer.data.clone(),
+        signers: buffered.signers,
+    };
+    Ok((buffer, ix, rest))
+}
+
+// 把金库余额全部转给 destination
+fn sweep_vault<'info>(
+    multisig: &Account<'info, Multisig>,
+    index: u8,
+    destination: &Pubkey,
+    account_infos: &[AccountInfo<'info>],
+) -> Result<()> {
+    let multisig_key = multisig.key();
+    let (vault, vault_bump) = find_vault_address(&multisig_key, index);
+    let find = |key: &Pubkey| {
+        account_infos
+            .iter()
+            .find(|i| i.key == key)
+            .ok_or(error!(MultisigError::AccountMismatch))
+    };
+    let vault_info = find(&vault)?;
+    let destination_info = find(destination)?;
+    let system_program = find(&anchor_lang::system_program::ID)?;
+
+    let amount = vault_info.lamports();
+    msg!("Sweeping {} lamports from vault {}", amount, index);
+    let vault_seeds: &[&[u8]] = &[VAULT_SEED, multisig_key.as_ref(), &[index], &[vault_bump]];
+    anchor_lang::system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            anchor_lang::system_program::Transfer {
+                from: vault_info.clone(),
+                to: destination_info.clone(),
+            },
+            &[vault_seeds],
+        ),
+        amount,
+    )
+}
+
+// 从报价时记录的金库收取，最多收到该金库仍保持免租为止
+fn charge_protocol_fee<'info>(
+    multisig: &Account<'info, Multisig>,
+    proposal: Pubkey,
+    fee: &ProtocolFee,
+    account_infos: &[AccountInfo<'info>],
+    now: i64,
+) -> Result<()> {
+    let multisig_key = multisig.key();
+    let (index, vault_bump) = (0..7)
+        .map(|i| (i, find_vault_address(&multisig_key, i)))
+        .find(|(_, (vault, _))| *vault == fee.payer)
+        .map(|(i, (_, bump))| (i, bump))
+        .ok_or(MultisigError::AccountMismatch)?;
+    let find = |key: &Pubkey| {
+        account_infos
+            .iter()
+            .find(|i| i.key == key)
+            .ok_or(error!(MultisigError::ProtocolFeeAccountMissing))
+    };
+    let vault_info = find(&fee.payer)?;
+    let recipient_info = find(&fee.recipient)?;
+    let system_program = find(&anchor_lang::system_program::ID)?;
+
+    let rent_exempt = Rent::get()?.minimum_balance(vault_info.data_len());
+    let lamports = fee
+        .lamports
+        .min(vault_info.lamports().saturating_sub(rent_exempt));
+    if lamports < fee.lamports {
+        msg!("Protocol fee capped at {} of {} lamports", lamports, fee.lamports);
+    }
+    if lamports == 0 {
+        return Ok(());
+    }
+    let vault_seeds: &[&[u8]] = &[VAULT_SEED, multisig_key.as_ref(), &[index], &[vault_bump]];
+    anchor_lang::system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            anchor_lang::system_program::Transfer {
+                from: vault_info.clone(),
+                to: recipient_info.clone(),
+            },
+            &[vault_seeds],
+        ),
+        lamports,
+    )?;
+    emit!(ProtocolFeeCharged {
+        schema_version: EVENT_SCHEMA_VERSION,
+        multisig: multisig_key,
+        proposal,
+        payer: fee.payer,
+        recipient: fee.recipient,
+        lamports,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+// token 账户须由 0 号金库持有（Account.owner @32），返回金库地址与 bump
+fn vault_owned_token_account(
+    multisig: &Account<Multisig>,
+    account_info: &AccountInfo,
+    token_program: &AccountInfo,
+) -> Result<(Pubkey, u8)> {
+    require!(is_token_program(token_program.key), MultisigError::AccountMismatch);
+    require_keys_eq!(*account_info.owner, *token_program.key, MultisigError::AccountMismatch);
+    let (vault, bump) = find_vault_address(&multisig.key(), 0);
+    if account_info.try_borrow_data()?.get(32..64) != Some(vault.as_ref()) {
+        msg!("Token account {} is not owned by vault 0", account_info.key);
+        return err!(MultisigError::AuthorityMismatch);
+    }
+    Ok((vault, bump))
+}
+
+// remaining_accounts: [token 账户, mint, 0 号金库, Token Program]；decimals 在执行时从 mint 读取
+fn approve_delegate<'info>(
+    multisig: &Account<'info, Multisig>,
+    token_account: &Pubkey,
+    delegate: &Pubkey,
+    amount: u64,
+    account_infos: &[AccountInfo<'info>],
+) -> Result<Pubkey> {
+    let [account_info, mint_info, vault_info, token_program] = account_infos else {
+        return err!(MultisigError::AccountMismatch);
+    };
+    require_keys_eq!(*account_info.key, *token_account, MultisigError::AccountMismatch);
+    let (vault, vault_bump) = vault_owned_token_account(multisig, account_info, token_program)?;
+    require_keys_eq!(*vault_info.key, vault, MultisigError::AccountMismatch);
+    require!(
+        account_info.try_borrow_data()?.get(..32) == Some(mint_info.key.as_ref()),
+        MultisigError::AccountMismatch
+    );
+    let decimals = *mint_info
+        .try_borrow_data()?
+        .get(44)
+        .ok_or(MultisigError::AccountMismatch)?;
+
+    // TokenInstruction::ApproveChecked { amount, decimals }
+    let mut data = vec![13];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(decimals);
+    let multisig_key = multisig.key();
+    let vault_seeds: &[&[u8]] = &[VAULT_SEED, multisig_key.as_ref(), &[0], &[vault_bump]];
+    invoke_signed(
+        &Instruction {
+            program_id: *token_program.key,
+            accounts: vec![
+                AccountMeta::new(*token_account, false),
+                AccountMeta::new_readonly(*mint_info.key, false),
+                AccountMeta::new_readonly(*delegate, false),
+                AccountMeta::new_readonly(vault, true),
+            ],
+            data,
+        },
+        &[
+            account_info.clone(),
+            mint_info.clone(),
+            vault_info.clone(),
+            token_program.clone(),
+        ],
+        &[vault_seeds],
+    )?;
+    Ok(*mint_info.key)
+}
+
+// remaining_accounts: [token 账户, 0 号金库, Token Program]
+fn revoke_delegate<'info>(
+    multisig: &Account<'info, Multisig>,
+    token_account: &Pubkey,
+    account_infos: &[AccountInfo<'info>],
+) -> Result<()> {
+    let [account_info, vault_info, token_program] = account_infos else {
+        return err!(MultisigError::AccountMismatch);
+    };
+    require_keys_eq!(*account_info.key, *token_account, MultisigError::AccountMismatch);
+    let (vault, vault_bump) = vault_owned_token_account(multisig, account_info, token_program)?;
+    require_keys_eq!(*vault_info.key, vault, MultisigError::AccountMismatch);
+
+    let multisig_key = multisig.key();
+    let vault_seeds: &[&[u8]] = &[VAULT_SEED, multisig_key.as_ref(), &[0], &[vault_bump]];
+    // TokenInstruction::Revoke
+    invoke_signed(
+        &Instruction {
+            program_id: *token_program.key,
+            accounts: vec![
+                AccountMeta::new(*token_account, false),
+                AccountMeta::new_readonly(vault, true),
+            ],
+            data: vec![5],
+        },
+        &[account_info.clone(), vault_info.clone(), token_program.clone()],
+        &[vault_seeds],
+    )?;
+    Ok(())
+}
+
+// remaining_accounts: [token 账户, mint, Token Program]。
+// 先确认 mint 的 freeze authority 是 multisig PDA，避免得到难以理解的 token 程序错误
+fn freeze_token_account<'info>(
+    multisig: &Account<'info, Multisig>,
+    token_account: &Pubkey,
+    thaw: bool,
+    account_infos: &[AccountInfo<'info>],
+) -> Result<()> {
+    let [account_info, mint_info, token_program] = account_infos else {
+        return err!(MultisigError::AccountMismatch);
+    };
+    require_keys_eq!(*account_info.key, *token_account, MultisigError::AccountMismatch);
+    require!(is_token_program(token_program.key), MultisigError::AccountMismatch);
+    require_keys_eq!(*account_info.owner, *token_program.key, MultisigError::AccountMismatch);
+    require_keys_eq!(*mint_info.owner, *token_program.key, MultisigError::AccountMismatch);
+
+    // Account.mint @0；Mint.freeze_authority COption @46
+    let multisig_key = multisig.key();
+    require!(
+        account_info.try_borrow_data()?.get(..32) == Some(mint_info.key.as_ref()),
+        MultisigError::AccountMismatch
+    );
+    let freeze_authority = decode::read_coption_pubkey(&mint_info.try_borrow_data()?, 46);
+    if freeze_authority != Some(multisig_key) {
+        msg!("Mint {} is not freezable by {}", mint_info.key, multisig_key);
+        return err!(MultisigError::FreezeAuthorityMismatch);
+    }
+
+    let config_seeds: &[&[u8]] = &[
+        MULTISIG_SEED,
+        multisig.creator.as_ref(),
+        std::slice::from_ref(&multisig.nonce),
+        std::slice::from_ref(&multisig.bump),
+    ];
+    // TokenInstruction::FreezeAccount = 10, ThawAccount = 11
+    invoke_signed(
+        &Instruction {
+            program_id: *token_program.key,
+            accounts: vec![
+                AccountMeta::new(*token_account, false),
+                AccountMeta::new_readonly(*mint_info.key, false),
+                AccountMeta::new_readonly(multisig_key, true),
+            ],
+            data: vec![if thaw { 11 } else { 10 }],
+        },
+        &[
+            account_info.clone(),
+            mint_info.clone(),
+            multisig.to_account_info(),
+            token_program.clone(),
+        ],
+        &[config_seeds],
+    )?;
+    Ok(())
+}
+
+// remaining_accounts: [收款方 ATA, mint, 收款方, 0 号金库, System Program, Token Program, ATA Program]。
+// ATA 不存在时先由金库付费创建（create_idempotent），再以 multisig PDA 签名 mint_to_checked
+fn mint_to<'info>(
+    multisig: &Account<'info, Multisig>,
+    mint: &Pubkey,
+    destination_owner: &Pubkey,
+    amount: u64,
+    account_infos: &[AccountInfo<'info>],
+) -> Result<()> {
+    let [destination, mint_info, owner_info, vault_info, system_program, token_program, ata_program] =
+        account_infos
+    else {
+        return err!(MultisigError::AccountMismatch);
+    };
+    let multisig_key = multisig.key();
+    let (vault, vault_bump) = find_vault_address(&multisig_key, 0);
+    require_keys_eq!(*mint_info.key, *mint, MultisigError::AccountMismatch);
+    require_keys_eq!(*owner_info.key, *destination_owner, MultisigError::AccountMismatch);
+    require_keys_eq!(*vault_info.key, vault, MultisigError::AccountMismatch);
+    require_keys_eq!(
+        *system_program.key,
+        anchor_lang::system_program::ID,
+        MultisigError::AccountMismatch
+    );
+    require_keys_eq!(*ata_program.key, ASSOCIATED_TOKEN_PROGRAM_ID, MultisigError::AccountMismatch);
+    require!(is_token_program(token_program.key), MultisigError::AccountMismatch);
+    require_keys_eq!(*mint_info.owner, *token_program.key, MultisigError::AccountMismatch);
+    require_keys_eq!(
+        *destination.key,
+        find_associated_token_address(destination_owner, mint, token_program.key),
+        MultisigError::AccountMismatch
+    );
+
+    // Mint.mint_authority COption @0，decimals @44
+    let (authority, decimals) = {
+        let data = mint_info.try_borrow_data()?;
+        (decode::read_coption_pubkey(&data, 0), data.get(44).copied())
+    };
+    if authority != Some(multisig_key) {
+        msg!("Mint {} is not mintable by {}", mint, multisig_key);
+        return err!(MultisigError::MintAuthorityMismatch);
+    }
+    let decimals = decimals.ok_or(MultisigError::AccountMismatch)?;
+
+    if destination.data_is_empty() {
+        let vault_seeds: &[&[u8]] = &[VAULT_SEED, multisig_key.as_ref(), &[0], &[vault_bump]];
+        // AssociatedTokenAccountInstruction::CreateIdempotent
+        invoke_signed(
+            &Instruction {
+                program_id: ASSOCIATED_TOKEN_PROGRAM_ID,
+                accounts: vec![
+                    AccountMeta::new(vault, true),
+                    AccountMeta::new(*destination.key, false),
+                    AccountMeta::new_readonly(*destination_owner, false),
+                    AccountMeta::new_readonly(*mint, false),
+                    AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+                    AccountMeta::new_readonly(*token_program.key, false),
+                ],
+                data: vec![1],
+            },
+            &[
+                vault_info.clone(),
+                destination.clone(),
+                owner_info.clone(),
+                mint_info.clone(),
+                system_program.clone(),
+                token_program.clone(),
+                ata_program.clone(),
+            ],
+            &[vault_seeds],
+        )?;
+        msg!("Created associated token account {}", destination.key);
+    }
+
+    // TokenInstruction::MintToChecked { amount, decimals }
+    let mut data = vec![14];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(decimals);
+    let config_seeds: &[&[u8]] = &[
+        MULTISIG_SEED,
+        multisig.creator.as_ref(),
+        std::slice::from_ref(&multisig.nonce),
+        std::slice::from_ref(&multisig.bump),
+    ];
+    invoke_signed(
+        &Instruction {
+            program_id: *token_program.key,
+            accounts: vec![
+                AccountMeta::new(*mint, false),
+                AccountMeta::new(*destination.key, false),
+                AccountMeta::new_readonly(multisig_key, true),
+            ],
+            data,
+        },
+        &[
+            mint_info.clone(),
+            destination.clone(),
+            multisig.to_account_info(),
+            token_program.clone(),
+        ],
+        &[config_seeds],
+    )?;
+    Ok(())
+}
+
+// 以 multisig PDA 签名调用 vote / stake 程序
+fn invoke_as_multisig<'info>(
+    multisig: &Account<'info, Multisig>,
+    program_id: Pubkey,
+    accounts: Vec<AccountMeta>,
+    data: Vec<u8>,
+    account_infos: &[AccountInfo<'info>],
+) -> Result<()> {
+    let config_seeds: &[&[u8]] = &[
+        MULTISIG_SEED,
+        multisig.creator.as_ref(),
+        std::slice::from_ref(&multisig.nonce),
+        std::slice::from_ref(&multisig.bump),
+    ];
+    let mut infos = account_infos.to_vec();
+    infos.push(multisig.to_account_info());
+    invoke_signed(
+        &Instruction {
+            program_id,
+            accounts,
+            data,
+        },
+        &infos,
+        &[config_seeds],
+    )?;
+    Ok(())
+}
+
+fn check_vote_accounts(
+    vote_account: &Pubkey,
+    vote_info: &AccountInfo,
+    vote_program: &AccountInfo,
+) -> Result<()> {
+    require_keys_eq!(*vote_info.key, *vote_account, MultisigError::AccountMismatch);
+    require_keys_eq!(*vote_info.owner, VOTE_PROGRAM_ID, MultisigError::AccountMismatch);
+    require_keys_eq!(*vote_program.key, VOTE_PROGRAM_ID, MultisigError::AccountMismatch);
+    Ok(())
+}
+
+// remaining_accounts: [vote 账户, Clock sysvar, Vote Program]。
+// withdrawer 可以更换任一权限；更换 voter 时也接受当前 epoch 的 authorized voter
+fn vote_authorize<'info>(
+    multisig: &Account<'info, Multisig>,
+    vote_account: &Pubkey,
+    new_authority: &Pubkey,
+    authority_type: u8,
+    account_infos: &[AccountInfo<'info>],
+) -> Result<()> {
+    let [vote_info, clock, vote_program] = account_infos else {
+        return err!(MultisigError::AccountMismatch);
+    };
+    check_vote_accounts(vote_account, vote_info, vote_program)?;
+    require_keys_eq!(
+        *clock.key,
+        anchor_lang::solana_program::sysvar::clock::ID,
+        MultisigError::AccountMismatch
+    );
+
+    let multisig_key = multisig.key();
+    let authorized = {
+        let data = vote_info.try_borrow_data()?;
+        decode::read_vote_withdrawer(&data) == Some(multisig_key)
+            || (authority_type == 0
+                && decode::read_vote_authorized_voter(&data, Clock::get()?.epoch)
+                    == Some(multisig_key))
+    };
+    if !authorized {
+        msg!("{} is not the authority of vote account {}", multisig_key, vote_account);
+        return err!(MultisigError::VoteAuthorityMismatch);
+    }
+
+    // VoteInstruction::Authorize(Pubkey, VoteAuthorize)，bincode 枚举标签为 u32
+    let mut data = 1u32.to_le_bytes().to_vec();
+    data.extend_from_slice(new_authority.as_ref());
+    data.extend_from_slice(&(authority_type as u32).to_le_bytes());
+    invoke_as_multisig(
+        multisig,
+        VOTE_PROGRAM_ID,
+        vec![
+            AccountMeta::new(*vote_account, false),
+            AccountMeta::new_readonly(*clock.key, false),
+            AccountMeta::new_readonly(multisig_key, true),
+        ],
+        data,
+        account_infos,
+    )
+}
+
+// remaining_accounts: [vote 账户, 收款方, Vote Program]
+fn vote_withdraw<'info>(
+    multisig: &Account<'info, Multisig>,
+    vote_account: &Pubkey,
+    lamports: u64,
+    destination: &Pubkey,
+    account_infos: &[AccountInfo<'info>],
+) -> Result<()> {
+    let [vote_info, destination_info, vote_program] = account_infos else {
+        return err!(MultisigError::AccountMismatch);
+    };
+    check_vote_accounts(vote_account, vote_info, vote_program)?;
+    require_keys_eq!(*destination_info.key, *destination, MultisigError::AccountMismatch);
+
+    let multisig_key = multisig.key();
+    if decode::read_vote_withdrawer(&vote_info.try_borrow_data()?) != Some(multisig_key) {
+        msg!("{} is not the withdrawer of vote account {}", multisig_key, vote_account);
+        return err!(MultisigError::VoteAuthorityMismatch);
+    }
+
+    // VoteInstruction::Withdraw(u64)
+    let mut data = 3u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&lamports.to_le_bytes());
+    invoke_as_multisig(
+        multisig,
+        VOTE_PROGRAM_ID,
+        vec![
+            AccountMeta::new(*vote_account, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(multisig_key, true),
+        ],
+        data,
+        account_infos,
+    )
+}
+
+// 按账户类型读取当前 withdraw authority
+fn read_withdrawer(kind: CustodyKind, data: &[u8]) -> Option<Pubkey> {
+    match kind {
+        CustodyKind::Stake => decode::read_stake_authorities(data).map(|(_, w)| w),
+        CustodyKind::Vote => decode::read_vote_withdrawer(data),
+    }
+}
+
+// StakeInstruction::Authorize(Pubkey, StakeAuthorize::Withdrawer) 与
+// VoteInstruction::Authorize(Pubkey, VoteAuthorize::Withdrawer) 的编码与账户顺序相同
+fn authorize_withdrawer_instruction(
+    kind: CustodyKind,
+    account: &Pubkey,
+    authority: &Pubkey,
+    new_authority: &Pubkey,
+) -> Instruction {
+    let mut data = 1u32.to_le_bytes().to_vec();
+    data.extend_from_slice(new_authority.as_ref());
+    data.extend_from_slice(&1u32.to_le_bytes());
+    Instruction {
+        program_id: kind.program_id(),
+        accounts: vec![
+            AccountMeta::new(*account, false),
+            AccountMeta::new_readonly(anchor_lang::solana_program::sysvar::clock::ID, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+        data,
+    }
+}
+
+fn check_custody_accounts(
+    kind: CustodyKind,
+    account: &Pubkey,
+    account_info: &AccountInfo,
+    clock: &AccountInfo,
+    program: &AccountInfo,
+) -> Result<()> {
+    require_keys_eq!(*account_info.key, *account, MultisigError::AccountMismatch);
+    require_keys_eq!(*account_info.owner, kind.program_id(), MultisigError::AccountMismatch);
+    require_keys_eq!(*program.key, kind.program_id(), MultisigError::AccountMismatch);
+    require_keys_eq!(
+        *clock.key,
+        anchor_lang::solana_program::sysvar::clock::ID,
+        MultisigError::AccountMismatch
+    );
+    Ok(())
+}
+
+// remaining_accounts: [stake / vote 账户, Clock sysvar, 当前 withdrawer（签名者）, Stake / Vote Program]。
+// 旧权限的签名随 execute 交易传入，CPI 中沿用
+fn take_custody<'info>(
+    multisig: &Account<'info, Multisig>,
+    account: &Pubkey,
+    kind: CustodyKind,
+    account_infos: &[AccountInfo<'info>],
+) -> Result<()> {
+    let [account_info, clock, authority, program] = account_infos else {
+        return err!(MultisigError::AccountMismatch);
+    };
+    check_custody_accounts(kind, account, account_info, clock, program)?;
+    let withdrawer = read_withdrawer(kind, &account_info.try_borrow_data()?);
+    if !authority.is_signer || withdrawer != Some(*authority.key) {
+        msg!("Withdraw authority of {} is {:?}", account, withdrawer);
+        return err!(MultisigError::CustodySignerMismatch);
+    }
+
+    invoke(
+        &authorize_withdrawer_instruction(kind, account, authority.key, &multisig.key()),
+        account_infos,
+    )?;
+    Ok(())
+}
+
+// remaining_accounts: [stake / vote 账户, Clock sysvar, Stake / Vote Program]
+fn release_custody<'info>(
+    multisig: &Account<'info, Multisig>,
+    account: &Pubkey,
+    kind: CustodyKind,
+    new_authority: &Pubkey,
+    account_infos: &[AccountInfo<'info>],
+) -> Result<()> {
+    let [account_info, clock, program] = account_infos else {
+        return err!(MultisigError::AccountMismatch);
+    };
+    check_custody_accounts(kind, account, account_info, clock, program)?;
+    let multisig_key = multisig.key();
+    let withdrawer = read_withdrawer(kind, &account_info.try_borrow_data()?);
+    if withdrawer != Some(multisig_key) {
+        msg!("Withdraw authority of {} is {:?}", account, withdrawer);
+        return match kind {
+            CustodyKind::Stake => err!(MultisigError::StakeAuthorityMismatch),
+            CustodyKind::Vote => err!(MultisigError::VoteAuthorityMismatch),
+        };
+    }
+
+    let ix = authorize_withdrawer_instruction(kind, account, &multisig_key, new_authority);
+    invoke_as_multisig(multisig, ix.program_id, ix.accounts, ix.data, account_infos)
+}
+
+// 核对 stake 账户并返回其 (staker, withdrawer)
+fn stake_authorities(
+    stake_account: &Pubkey,
+    stake_info: &AccountInfo,
+    stake_program: &AccountInfo,
+) -> Result<(Pubkey, Pubkey)> {
+    require_keys_eq!(*stake_info.key, *stake_account, MultisigError::AccountMismatch);
+    require_keys_eq!(*stake_info.owner, STAKE_PROGRAM_ID, MultisigError::AccountMismatch);
+    require_keys_eq!(*stake_program.key, STAKE_PROGRAM_ID, MultisigError::AccountMismatch);
+    let authorities = decode::read_stake_authorities(&stake_info.try_borrow_data()?);
+    authorities.ok_or(error!(MultisigError::StakeAuthorityMismatch))
+}
+
+// remaining_accounts: [新 stake 账户（签名者）, stake 账户, 0 号金库, System Program, Stake Program]。
+// 由金库付租金创建 stake 程序所有的空账户，再以 multisig PDA 作为 staker 调用 Split
+fn stake_split<'info>(
+    multisig: &Account<'info, Multisig>,
+    stake_account: &Pubkey,
+    lamports: u64,
+    account_infos: &[AccountInfo<'info>],
+) -> Result<Pubkey> {
+    let [new_stake, stake_info, vault_info, system_program, stake_program] = account_infos else {
+        return err!(MultisigError::AccountMismatch);
+    };
+    let multisig_key = multisig.key();
+    let (staker, _) = stake_authorities(stake_account, stake_info, stake_program)?;
+    if staker != multisig_key {
+        msg!("Stake authority of {} is {}", stake_account, staker);
+        return err!(MultisigError::StakeAuthorityMismatch);
+    }
+    let (vault, vault_bump) = find_vault_address(&multisig_key, 0);
+    require_keys_eq!(*vault_info.key, vault, MultisigError::AccountMismatch);
+    require_keys_eq!(
+        *system_program.key,
+        anchor_lang::system_program::ID,
+        MultisigError::AccountMismatch
+    );
+    // 临时密钥对的签名随 execute 交易传入，CPI 中沿用
+    require!(new_stake.is_signer, MultisigError::StakeAccountNotSigner);
+
+    // 拆分目标须预先满足免租
+    let vault_seeds: &[&[u8]] = &[VAULT_SEED, multisig_key.as_ref(), &[0], &[vault_bump]];
+    anchor_lang::system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            anchor_lang::system_program::CreateAccount {
+                from: vault_info.clone(),
+                to: new_stake.clone(),
+            },
+            &[vault_seeds],
+        ),
+        Rent::get()?.minimum_balance(STAKE_STATE_SIZE),
+        STAKE_STATE_SIZE as u64,
+        &STAKE_PROGRAM_ID,
+    )?;
+
+    // StakeInstruction::Split(u64)
+    let mut data = 3u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&lamports.to_le_bytes());
+    invoke_as_multisig(
+        multisig,
+        STAKE_PROGRAM_ID,
+        vec![
+            AccountMeta::new(*stake_account, false),
+            AccountMeta::new(*new_stake.key, false),
+            AccountMeta::new_readonly(multisig_key, true),
+        ],
+        data,
+        account_infos,
+    )?;
+    msg!("Split {} lamports into stake account {}", lamports, new_stake.key);
+    Ok(*new_stake.key)
+}
+
+// remaining_accounts: [stake 账户, Clock sysvar, Stake Program]
+fn stake_deactivate<'info>(
+    multisig: &Account<'info, Multisig>,
+    stake_account: &Pubkey,
+    account_infos: &[AccountInfo<'info>],
+) -> Result<()> {
+    let [stake_info, clock, stake_program] = account_infos else {
+        return err!(MultisigError::AccountMismatch);
+    };
+    let multisig_key = multisig.key();
+    let (staker, _) = stake_authorities(stake_account, stake_info, stake_program)?;
+    if staker != multisig_key {
+        msg!("Stake authority of {} is {}", stake_account, staker);
+        return err!(MultisigError::StakeAuthorityMismatch);
+    }
+    require_keys_eq!(
+        *clock.key,
+        anchor_lang::solana_program::sysvar::clock::ID,
+        MultisigError::AccountMismatch
+    );
+
+    // StakeInstruction::Deactivate
+    invoke_as_multisig(
+        multisig,
+        STAKE_PROGRAM_ID,
+        vec![
+            AccountMeta::new(*stake_account, false),
+            AccountMeta::new_readonly(*clock.key, false),
+            AccountMeta::new_readonly(multisig_key, true),
+        ],
+        5u32.to_le_bytes().to_vec(),
+        account_infos,
+    )
+}
+
+// remaining_accounts: [stake 账户, 收款方, Clock sysvar, StakeHistory sysvar, Stake Program]
+fn stake_withdraw<'info>(
+    multisig: &Account<'info, Multisig>,
+    stake_account: &Pubkey,
+    lamports: u64,
+    destination: &Pubkey,
+    account_infos: &[AccountInfo<'info>],
+) -> Result<()> {
+    let [stake_info, destination_info, clock, stake_history, stake_program] = account_infos else {
+        return err!(MultisigError::AccountMismatch);
+    };
+    let multisig_key = multisig.key();
+    let (_, withdrawer) = stake_authorities(stake_account, stake_info, stake_program)?;
+    if withdrawer != multisig_key {
+        msg!("Withdraw authority of {} is {}", stake_account, withdrawer);
+        return err!(MultisigError::StakeAuthorityMismatch);
+    }
+    require_keys_eq!(*destination_info.key, *destination, MultisigError::AccountMismatch);
+    require_keys_eq!(
+        *clock.key,
+        anchor_lang::solana_program::sysvar::clock::ID,
+        MultisigError::AccountMismatch
+    );
+    require_keys_eq!(
+        *stake_history.key,
+        anchor_lang::solana_program::sysvar::stake_history::ID,
+        MultisigError::AccountMismatch
+    );
+
+    // StakeInstruction::Withdraw(u64)
+    let mut data = 4u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&lamports.to_le_bytes());
+    invoke_as_multisig(
+        multisig,
+        STAKE_PROGRAM_ID,
+        vec![
+            AccountMeta::new(*stake_account, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*clock.key, false),
+            AccountMeta::new_readonly(*stake_history.key, false),
+            AccountMeta::new_readonly(multisig_key, true),
+        ],
+        data,
+        account_infos,
+    )
+}
+
+// remaining_accounts: [0 号金库的 ATA, mint, 0 号金库, Token Program]。
+// burn 不可撤销：CPI 前先核对余额，给出明确错误
+fn burn_from_vault<'info>(
+    multisig: &Account<'info, Multisig>,
+    mint: &Pubkey,
+    amount: u64,
+    account_infos: &[AccountInfo<'info>],
+) -> Result<()> {
+    let [source, mint_info, vault_info, token_program] = account_infos else {
+        return err!(MultisigError::AccountMismatch);
+    };
+    let multisig_key = multisig.key();
+    let (vault, vault_bump) = find_vault_address(&multisig_key, 0);
+    require_keys_eq!(*vault_info.key, vault, MultisigError::AccountMismatch);
+    require_keys_eq!(*mint_info.key, *mint, MultisigError::AccountMismatch);
+    require!(is_token_program(token_program.key), MultisigError::AccountMismatch);
+    require_keys_eq!(*mint_info.owner, *token_program.key, MultisigError::AccountMismatch);
+    require_keys_eq!(
+        *source.key,
+        find_associated_token_address(&vault, mint, token_program.key),
+        MultisigError::AccountMismatch
+    );
+
+    // Mint.decimals @44；Account.amount @64
+    let decimals = *mint_info
+        .try_borrow_data()?
+        .get(44)
+        .ok_or(MultisigError::AccountMismatch)?;
+    let balance = decode::read_u64(&source.try_borrow_data()?, 64)
+        .ok_or(MultisigError::AccountMismatch)?;
+    if balance < amount {
+        msg!("Burn {} exceeds vault balance {}", amount, balance);
+        return err!(MultisigError::InsufficientBalance);
+    }
+
+    // TokenInstruction::BurnChecked { amount, decimals }
+    let mut data = vec![15];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(decimals);
+    let vault_seeds: &[&[u8]] = &[VAULT_SEED, multisig_key.as_ref(), &[0], &[vault_bump]];
+    invoke_signed(
+        &Instruction {
+            program_id: *token_program.key,
+            accounts: vec![
+                AccountMeta::new(*source.key, false),
+                AccountMeta::new(*mint, false),
+                AccountMeta::new_readonly(vault, true),
+            ],
+            data,
+        },
+        &[source.clone(), mint_info.clone(), vault_info.clone(), token_program.clone()],
+        &[vault_seeds],
+    )?;
+    Ok(())
+}
+
+// remaining_accounts: [target, 当前权限 PDA, Token Program]。
+// 从 target 数据中读出当前权限，须为该 PDA（multisig 或某个金库），再以其 seeds 签名
+fn set_token_authority<'info>(
+    multisig: &Account<'info, Multisig>,
+    target: &Pubkey,
+    authority_type: u8,
+    new_authority: Option<Pubkey>,
+    account_infos: &[AccountInfo<'info>],
+) -> Result<()> {
+    let [target_info, authority_info, token_program] = account_infos else {
+        return err!(MultisigError::AccountMismatch);
+    };
+    require_keys_eq!(*target_info.key, *target, MultisigError::AccountMismatch);
+    require_keys_eq!(*target_info.owner, *token_program.key, MultisigError::AccountMismatch);
+    require!(is_token_program(token_program.key), MultisigError::AccountMismatch);
+
+    // Mint: mint_authority COption @0，freeze_authority COption @46；
+    // Account: owner @32，close_authority COption @129
+    let current = {
+        let data = target_info.try_borrow_data()?;
+        let coption = |offset| decode::read_coption_pubkey(&data, offset);
+        match authority_type {
+            0 => coption(0),
+            1 => coption(46),
+            2 => data.get(32..64).and_then(|d| Pubkey::try_from(d).ok()),
+            3 => coption(129),
+            _ => return err!(MultisigError::InvalidAuthorityType),
+        }
+    };
+    let Some(current) = current.filter(|c| c == authority_info.key) else {
+        msg!("Current authority: {:?}", current);
+        return err!(MultisigError::AuthorityMismatch);
+    };
+
+    let multisig_key = multisig.key();
+    let config_seeds: [&[u8]; 4] = [
+        MULTISIG_SEED,
+        multisig.creator.as_ref(),
+        std::slice::from_ref(&multisig.nonce),
+        std::slice::from_ref(&multisig.bump),
+    ];
+    let vault = (0..7u8)
+        .map(|i| (i, find_vault_address(&multisig_key, i)))
+        .find(|(_, (vault, _))| *vault == current)
+        .map(|(i, (_, bump))| [i, bump]);
+    let vault_seeds: Option<[&[u8]; 4]> = vault
+        .as_ref()
+        .map(|v| [VAULT_SEED, multisig_key.as_ref(), &v[..1], &v[1..]]);
+    let seeds: &[&[u8]] = if current == multisig_key {
+        &config_seeds
+    } else if let Some(seeds) = &vault_seeds {
+        seeds
+    } else {
+        return err!(MultisigError::AuthorityMismatch);
+    };
+
+    // TokenInstruction::SetAuthority { authority_type, new_authority: COption }
+    let mut data = vec![6, authority_type];
+    match new_authority {
+        Some(key) => {
+            data.push(1);
+            data.extend_from_slice(key.as_ref());
+        }
+        None => data.push(0),
+    }
+    invoke_signed(
+        &Instruction {
+            program_id: *token_program.key,
+            accounts: vec![
+                AccountMeta::new(*target, false),
+                AccountMeta::new_readonly(current, true),
+            ],
+            data,
+        },
+        &[target_info.clone(), authority_info.clone(), token_program.clone()],
+        &[seeds],
+    )?;
+    Ok(())
+}
+
+// remaining_accounts: [新 mint（签名者）, 0 号金库, System Program, Token Program]。
+// 由金库付租金创建账户，再以金库为 mint authority 调用 initialize_mint2
+fn create_mint<'info>(
+    multisig: &Account<'info, Multisig>,
+    decimals: u8,
+    freeze_authority: Option<Pubkey>,
+    account_infos: &[AccountInfo<'info>],
+) -> Result<Pubkey> {
+    let [mint_info, vault_info, system_program, token_program] = account_infos else {
+        return err!(MultisigError::AccountMismatch);
+    };
+    let multisig_key = multisig.key();
+    let (vault, vault_bump) = find_vault_address(&multisig_key, 0);
+    require_keys_eq!(*vault_info.key, vault, MultisigError::AccountMismatch);
+    require_keys_eq!(
+        *system_program.key,
+        anchor_lang::system_program::ID,
+        MultisigError::AccountMismatch
+    );
+    require_keys_eq!(*token_program.key, TOKEN_PROGRAM_ID, MultisigError::AccountMismatch);
+    // 临时密钥对的签名随 execute 交易传入，CPI 中沿用
+    require!(mint_info.is_signer, MultisigError::MintNotSigner);
+
+    let vault_seeds: &[&[u8]] = &[VAULT_SEED, multisig_key.as_ref(), &[0], &[vault_bump]];
+    anchor_lang::system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            anchor_lang::system_program::CreateAccount {
+                from: vault_info.clone(),
+                to: mint_info.clone(),
+            },
+            &[vault_seeds],
+        ),
+        Rent::get()?.minimum_balance(MINT_SIZE),
+        MINT_SIZE as u64,
+        &TOKEN_PROGRAM_ID,
+    )?;
+
+    // TokenInstruction::InitializeMint2 { decimals, mint_authority, freeze_authority: COption }
+    let mut data = vec![20, decimals];
+    data.extend_from_slice(vault.as_ref());
+    match freeze_authority {
+        Some(key) => {
+            data.push(1);
+            data.extend_from_slice(key.as_ref());
+        }
+        None => data.push(0),
+    }
+    invoke(
+        &Instruction {
+            program_id: TOKEN_PROGRAM_ID,
+            accounts: vec![AccountMeta::new(*mint_info.key, false)],
+            data,
+        },
+        &[mint_info.clone(), token_program.clone()],
+    )?;
+    msg!("Created mint {}", mint_info.key);
+    Ok(*mint_info.key)
+}
+
+// 策略账户位于指令账户之后：策略程序后接 account_count 个账户，按声明的数量切分。
+// 类型化动作的账户按 key 查找、顺序不限，因此按 key 定位策略程序；返回截至策略账户末尾的账户数。
+// 传给策略程序的前两个账户固定为 multisig 与提案（均不签名），其余按原样传入；
+// 策略程序报错即拒绝执行，整笔交易回滚
+fn check_policy<'info>(
+    multisig: &Account<'info, Multisig>,
+    proposal: &AccountInfo<'info>,
+    program: Pubkey,
+    account_count: u8,
+    action_hash: [u8; 32],
+    metas: &[SerializableAccountMeta],
+    account_infos: &[AccountInfo<'info>],
+) -> Result<usize> {
+    let position = account_infos
+        .iter()
+        .position(|a| *a.key == program)
+        .ok_or(MultisigError::PolicyAccountsMismatch)?;
+    let end = position + 1 + account_count as usize;
+    let section = account_infos
+        .get(position..end)
+        .ok_or(MultisigError::PolicyAccountsMismatch)?;
+    let policy_accounts = &section[1..];
+
+    let mut accounts = Vec::with_capacity(policy_accounts.len() + 2);
+    accounts.push(AccountMeta::new_readonly(multisig.key(), false));
+    accounts.push(AccountMeta::new_readonly(*proposal.key, false));
+    accounts.extend(policy_accounts.iter().map(|a| AccountMeta {
+        pubkey: *a.key,
+        is_signer: a.is_signer,
+        is_writable: a.is_writable,
+    }));
+    let mut data = POLICY_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(proposal.key.as_ref());
+    data.extend_from_slice(&action_hash);
+    metas.serialize(&mut data)?;
+
+    let mut infos = Vec::with_capacity(section.len() + 2);
+    infos.push(multisig.to_account_info());
+    infos.push(proposal.clone());
+    infos.extend_from_slice(section);
+    invoke(&Instruction { program_id: program, accounts, data }, &infos)?;
+    Ok(end)
+}
+
+// 通知账户位于指令账户之后：通知程序及其所需账户，后者按原样传入，首个账户固定为 multisig。
+// 不以多签签名，接收方不应据此授权。接收方报错时运行时会回滚整笔交易，无法捕获，
+// 因此 required 为 false 时只容忍通知程序账户缺失或不可执行
+fn notify_executed<'info>(
+    multisig: &Account<'info, Multisig>,
+    program: Pubkey,
+    required: bool,
+    index: u32,
+    action_hash: [u8; 32],
+    account_infos: &[AccountInfo<'info>],
+) -> Result<()> {
+    let Some(position) = account_infos
+        .iter()
+        .position(|a| *a.key == program && a.executable)
+    else {
+        require!(!required, MultisigError::NotifyProgramMissing);
+        msg!("Notify program {} not supplied, skipped", program);
+        return Ok(());
+    };
+    let hook_accounts = &account_infos[position + 1..];
+
+    let mut accounts = Vec::with_capacity(hook_accounts.len() + 1);
+    accounts.push(AccountMeta::new_readonly(multisig.key(), false));
+    accounts.extend(hook_accounts.iter().map(|a| AccountMeta {
+        pubkey: *a.key,
+        is_signer: a.is_signer,
+        is_writable: a.is_writable,
+    }));
+    let mut data = NOTIFY_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(multisig.key().as_ref());
+    data.extend_from_slice(&index.to_le_bytes());
+    data.extend_from_slice(&action_hash);
+
+    let mut infos = Vec::with_capacity(hook_accounts.len() + 2);
+    infos.push(multisig.to_account_info());
+    infos.extend_from_slice(&account_infos[position..]);
+    invoke(&Instruction { program_id: program, accounts, data }, &infos)?;
+    Ok(())
+}
+
+// 校验 remaining_accounts 后以提案选中的 PDA 签名调用
+fn invoke_instruction(
+    ix: &mut InstructionData,
+    multisig: &Account<Multisig>,
+    account_infos: &[AccountInfo],
+) -> Result<()> {
+    // 安全验证 remaining_accounts；逐账户 msg! 的格式化开销随账户数线性增长，只在失败时记录。
+    // 其后的策略、协议费与通知账户不传给目标程序
+    let account_infos = account_infos
+        .get(..ix.accounts.len())
+        .ok_or(MultisigError::AccountMismatch)?;
+    if let Some(i) = ix.accounts.iter().zip(account_infos).position(|(m, a)| m.pubkey != *a.key) {
+        msg!("Account {} does not match the proposal", i);
+        return err!(MultisigError::AccountMismatch);
+    }
+
+    // 提案随后被关闭时直接取走 accounts 与 data 避免复制；CPI 失败时整笔交易回滚，数据不受影响
+    let instruction = if multisig.retain_executed {
+        Instruction {
+            program_id: ix.program_id,
+            accounts: ix.accounts.iter().map(AccountMeta::from).collect(),
+            data: ix.data.clone(),
+        }
+    } else {
+        Instruction {
+            program_id: ix.program_id,
+            accounts: std::mem::take(&mut ix.accounts).into_iter().map(AccountMeta::from).collect(),
+            data: std::mem::take(&mut ix.data),
+        }
+    };
+
+    // 应用 authority 不占 signers 位：已登记且在指令中标记为签名者时才签名
+    let multisig_key = multisig.key();
+    let apps: Vec<([u8; 16], [u8; 1])> = multisig
+        .app_authorities()
+        .into_iter()
+        .filter(|(app_id, bump)| {
+            let seeds: &[&[u8]] = &[APP_AUTHORITY_SEED, multisig_key.as_ref(), app_id, &[*bump]];
+            Pubkey::create_program_address(seeds, &crate::ID).is_ok_and(|pda| {
+                instruction.accounts.iter().any(|a| a.is_signer && a.pubkey == pda)
+            })
+        })
+        .map(|(app_id, bump)| (app_id, [bump]))
+        .collect();
+
+    if ix.signers == 0 && apps.is_empty() {
+        #[cfg(feature = "log-compute-units")]
+        sol_log_compute_units();
+        invoke(&instruction, account_infos)?;
+        return Ok(());
+    }
+
+    // 只为提案中选中的 PDA 构造 seeds
+    let config_seeds: [&[u8]; 4] = [
+        MULTISIG_SEED,
+        multisig.creator.as_ref(),
+        std::slice::from_ref(&multisig.nonce),
+        std::slice::from_ref(&multisig.bump),
+    ];
+    let vaults: Vec<[u8; 2]> = ix
+        .signer_vaults()
+        .map(|i| [i, find_vault_address(&multisig_key, i).1])
+        .collect();
+    let vault_seeds: Vec<[&[u8]; 4]> = vaults
+        .iter()
+        .map(|v| [VAULT_SEED, multisig_key.as_ref(), &v[..1], &v[1..]])
+        .collect();
+
+    let app_seeds: Vec<[&[u8]; 4]> = apps
+        .iter()
+        .map(|(app_id, bump)| [APP_AUTHORITY_SEED, multisig_key.as_ref(), app_id, bump])
+        .collect();
+
+    let mut signer_seeds: Vec<&[&[u8]]> =
+        Vec::with_capacity(vault_seeds.len() + app_seeds.len() + 1);
+    if ix.signs_as_config() {
+        signer_seeds.push(&config_seeds);
+    }
+    signer_seeds.extend(vault_seeds.iter().map(|s| s.as_slice()));
+    signer_seeds.extend(app_seeds.iter().map(|s| s.as_slice()));
+
+    #[cfg(feature = "log-compute-units")]
+    sol_log_compute_units();
+    invoke_signed(&instruction, account_infos, &signer_seeds)?;
+    Ok(())
+}
@@ -0,0 +1,388 @@
+// 下游集成测试用的 ProgramTest 辅助工具（`testing` 特性，仅链下）。
+// 多签程序以 BPF 形式加载（anchor 生成的 CPI 在原生 builtin 中不可用），
+// 需先 anchor build，并让 SBF_OUT_DIR 指向 target/deploy。
+//
+//     let mut pt = TestMultisig::program_test();
+//     let mut ms = TestMultisig::new(pt.start_with_context().await, 3, 2).await?;
+//     let record = fixture::create_record(&mut ms.context).await?;
+//     ms.propose(fixture::instruction(record, ms.multisig)).await?;
+//     ms.approve_all_until(2).await?;
+//     ms.execute().await?;
+//     assert!(fixture::was_signed(&mut ms.context, record).await?);
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::system_instruction;
+use anchor_lang::InstructionData as _;
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+
+use crate::compiled::signer_bits;
+use crate::{
+    accounts, find_multisig_address, find_program_config_address, find_proposal_address,
+    find_vault_address, instruction, InstructionData, ProposalOptions, ProposalStatusView, SerializableAccountMeta,
+};
+
+const MEMBER_LAMPORTS: u64 = 1_000_000_000;
+
+pub struct TestMultisig {
+    pub context: ProgramTestContext,
+    pub members: Vec<Keypair>, // members[0] 为创建者
+    pub multisig: Pubkey,
+    pub proposal: Option<Pubkey>, // 最近一次 propose 的提案
+}
+
+impl TestMultisig {
+    // 注册多签程序与 fixture 目标程序；调用方可继续 add_program / add_account 后再 start
+    pub fn program_test() -> ProgramTest {
+        let mut program_test = ProgramTest::new("multisig", crate::ID, None);
+        program_test.add_program(
+            "multisig_fixture",
+            fixture::ID,
+            processor!(fixture::process_instruction),
+        );
+        program_test
+    }
+
+    pub async fn new(
+        mut context: ProgramTestContext,
+        n_members: usize,
+        threshold: u8,
+    ) -> std::result::Result<Self, BanksClientError> {
+        let members: Vec<Keypair> = (0..n_members).map(|_| Keypair::new()).collect();
+        for member in &members {
+            let ix = system_instruction::transfer(
+                &context.payer.pubkey(),
+                &member.pubkey(),
+                MEMBER_LAMPORTS,
+            );
+            send(&mut context, ix, &[]).await?;
+        }
+
+        let creator = &members[0];
+        let multisig = find_multisig_address(&creator.pubkey(), 0).0;
+        let ix = Instruction {
+            program_id: crate::ID,
+            accounts: accounts::CreateMultisig {
+                multisig,
+                creator: creator.pubkey(),
+                system_program: anchor_lang::system_program::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::CreateMultisig {
+                nonce: 0,
+                members: members.iter().map(|m| m.pubkey()).collect(),
+                threshold,
+                max_members: n_members as u8,
+            }
+            .data(),
+        };
+        send(&mut context, ix, &[creator]).await?;
+
+        Ok(Self {
+            context,
+            members,
+            multisig,
+            proposal: None,
+        })
+    }
+
+    pub fn vault(&self, index: u8) -> Pubkey {
+        find_vault_address(&self.multisig, index).0
+    }
+
+    pub async fn fund(
+        &mut self,
+        to: Pubkey,
+        lamports: u64,
+    ) -> std::result::Result<(), BanksClientError> {
+        let ix = system_instruction::transfer(&self.context.payer.pubkey(), &to, lamports);
+        send(&mut self.context, ix, &[]).await
+    }
+
+    // 由 members[0] 提交 Raw 提案；signers 位按指令中的 PDA 签名者推导
+    pub async fn propose(
+        &mut self,
+        ix: Instruction,
+    ) -> std::result::Result<Pubkey, BanksClientError> {
+        let multisig: crate::Multisig = self.fetch(self.multisig).await?;
+        let proposal = find_proposal_address(&self.multisig, multisig.proposals_count).0;
+
+        let mut metas: Vec<SerializableAccountMeta> =
+            ix.accounts.into_iter().map(Into::into).collect();
+        let signers = signer_bits(&metas, &self.multisig);
+        metas.push(SerializableAccountMeta {
+            pubkey: ix.program_id,
+            is_signer: false,
+            is_writable: false,
+        });
+        let proposer = &self.members[0];
+        let ix = Instruction {
+            program_id: crate::ID,
+            accounts: accounts::ProposeTransaction {
+                multisig: self.multisig,
+                proposal,
+                proposer: proposer.pubkey(),
+                system_program: anchor_lang::system_program::ID,
+                member_stats: None,
+                program_config: find_program_config_address().0,
+                instruction_index: None,
+                indexed_proposal: None,
+            }
+            .to_account_metas(None),
+            data: instruction::ProposeTransaction {
+                instruction_data: InstructionData {
+                    program_id: ix.program_id,
+                    accounts: metas,
+                    data: ix.data,
+                    signers,
+                },
+                options: ProposalOptions::default(),
+            }
+            .data(),
+        };
+        send(&mut self.context, ix, &[proposer]).await?;
+        self.proposal = Some(proposal);
+        Ok(proposal)
+    }
+
+    pub async fn approve(&mut self, member: usize) -> std::result::Result<(), BanksClientError> {
+        let ix = self.approve_instruction(member);
+        send(&mut self.context, ix, &[&self.members[member]]).await
+    }
+
+    // 与 approve 相同，但返回程序日志（可交给 client::parse_logs 解码事件）
+    pub async fn approve_with_logs(
+        &mut self,
+        member: usize,
+    ) -> std::result::Result<Vec<String>, BanksClientError> {
+        let ix = self.approve_instruction(member);
+        let blockhash = self.context.banks_client.get_latest_blockhash().await?;
+        let payer = &self.context.payer;
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer.pubkey()),
+            &[payer, &self.members[member]],
+            blockhash,
+        );
+        let outcome = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(tx)
+            .await?;
+        outcome.result?;
+        Ok(outcome.metadata.map(|m| m.log_messages).unwrap_or_default())
+    }
+
+    // 把 Clock 的 unix_timestamp 向前拨 seconds 秒（slot 不变）
+    pub async fn advance_clock(
+        &mut self,
+        seconds: i64,
+    ) -> std::result::Result<(), BanksClientError> {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await?;
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+        Ok(())
+    }
+
+    fn approve_instruction(&self, member: usize) -> Instruction {
+        let approver = &self.members[member];
+        Instruction {
+            program_id: crate::ID,
+            accounts: accounts::ApproveTransaction {
+                multisig: self.multisig,
+                proposal: self.current_proposal(),
+                approver: approver.pubkey(),
+                member_stats: None,
+            }
+            .to_account_metas(None),
+            data: instruction::ApproveTransaction {
+                expected_hash: None,
+            }
+            .data(),
+        }
+    }
+
+    // 依次由 members[0..threshold] 批准最近的提案
+    pub async fn approve_all_until(
+        &mut self,
+        threshold: u8,
+    ) -> std::result::Result<(), BanksClientError> {
+        for member in 0..threshold as usize {
+            self.approve(member).await?;
+        }
+        Ok(())
+    }
+
+    // 执行最近的提案，remaining accounts 由提案内容推导
+    pub async fn execute(&mut self) -> std::result::Result<(), BanksClientError> {
+        let ix = self.execute_instruction().await?;
+        send(&mut self.context, ix, &[]).await
+    }
+
+    // 模拟执行最近的提案，返回消耗的 CU 与程序日志；不改变链上状态
+    pub async fn simulate_execute(
+        &mut self,
+    ) -> std::result::Result<(u64, Vec<String>), BanksClientError> {
+        let ix = self.execute_instruction().await?;
+        let blockhash = self.context.banks_client.get_latest_blockhash().await?;
+        let payer = &self.context.payer;
+        let tx =
+            Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[payer], blockhash);
+        let simulation = self.context.banks_client.simulate_transaction(tx).await?;
+        if let Some(Err(err)) = simulation.result {
+            return Err(err.into());
+        }
+        let details = simulation
+            .simulation_details
+            .ok_or(BanksClientError::ClientError("no simulation details"))?;
+        Ok((details.units_consumed, details.logs))
+    }
+
+    async fn execute_instruction(&mut self) -> std::result::Result<Instruction, BanksClientError> {
+        let proposal = self.current_proposal();
+        let account: crate::Proposal = self.fetch(proposal).await?;
+        let mut accounts = accounts::ExecuteTransaction {
+            multisig: self.multisig,
+            proposal,
+            spend_ledger: None,
+            executor: None,
+            instruction_index: None,
+        }
+        .to_account_metas(None);
+        accounts.extend(account.execute_accounts());
+        Ok(Instruction {
+            program_id: crate::ID,
+            accounts,
+            data: instruction::ExecuteTransaction {}.data(),
+        })
+    }
+
+    // 模拟 get_proposal_status 并解码返回数据，remaining accounts 与 execute 相同
+    pub async fn status(&mut self) -> std::result::Result<ProposalStatusView, BanksClientError> {
+        let proposal = self.current_proposal();
+        let account: crate::Proposal = self.fetch(proposal).await?;
+        let mut accounts = accounts::GetProposalStatus {
+            multisig: self.multisig,
+            proposal,
+        }
+        .to_account_metas(None);
+        accounts.extend(account.execute_accounts());
+        let ix = Instruction {
+            program_id: crate::ID,
+            accounts,
+            data: instruction::GetProposalStatus {}.data(),
+        };
+        let blockhash = self.context.banks_client.get_latest_blockhash().await?;
+        let payer = &self.context.payer;
+        let tx =
+            Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[payer], blockhash);
+        let simulation = self.context.banks_client.simulate_transaction(tx).await?;
+        if let Some(Err(err)) = simulation.result {
+            return Err(err.into());
+        }
+        let data = simulation
+            .simulation_details
+            .and_then(|d| d.return_data)
+            .ok_or(BanksClientError::ClientError("no return data"))?;
+        ProposalStatusView::try_from_slice(&data.data)
+            .map_err(|_| BanksClientError::ClientError("failed to deserialize return data"))
+    }
+
+    pub async fn fetch<T: AccountDeserialize>(
+        &mut self,
+        address: Pubkey,
+    ) -> std::result::Result<T, BanksClientError> {
+        let account = self
+            .context
+            .banks_client
+            .get_account(address)
+            .await?
+            .ok_or(BanksClientError::ClientError("account not found"))?;
+        T::try_deserialize(&mut account.data.as_slice())
+            .map_err(|_| BanksClientError::ClientError("failed to deserialize account"))
+    }
+
+    fn current_proposal(&self) -> Pubkey {
+        self.proposal.expect("no proposal: call propose first")
+    }
+}
+
+// 由 context.payer 付费并签名，附加 signers
+async fn send(
+    context: &mut ProgramTestContext,
+    ix: Instruction,
+    signers: &[&Keypair],
+) -> std::result::Result<(), BanksClientError> {
+    let blockhash = context.banks_client.get_latest_blockhash().await?;
+    let mut all: Vec<&Keypair> = vec![&context.payer];
+    all.extend_from_slice(signers);
+    let tx =
+        Transaction::new_signed_with_payer(&[ix], Some(&context.payer.pubkey()), &all, blockhash);
+    context.banks_client.process_transaction(tx).await
+}
+
+// 目标程序：把 accounts[1] 是否以签名者身份调用记录到 accounts[0]（record 账户）
+pub mod fixture {
+    use super::*;
+
+    pub const ID: Pubkey = Pubkey::new_from_array(*b"multisig-test-fixture-target-prg");
+    // data[0] = 是否签名，data[1..33] = 被检查的账户
+    pub const RECORD_SPACE: usize = 33;
+
+    pub fn process_instruction(
+        _program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        _data: &[u8],
+    ) -> ProgramResult {
+        let [record, signer, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        let mut data = record.try_borrow_mut_data()?;
+        data[0] = signer.is_signer as u8;
+        data[1..33].copy_from_slice(signer.key.as_ref());
+        Ok(())
+    }
+
+    // signer 通常为 multisig PDA 或金库 PDA
+    pub fn instruction(record: Pubkey, signer: Pubkey) -> Instruction {
+        Instruction {
+            program_id: ID,
+            accounts: vec![
+                AccountMeta::new(record, false),
+                AccountMeta::new_readonly(signer, true),
+            ],
+            data: vec![],
+        }
+    }
+
+    pub async fn create_record(
+        context: &mut ProgramTestContext,
+    ) -> std::result::Result<Pubkey, BanksClientError> {
+        let record = Keypair::new();
+        let rent = context.banks_client.get_rent().await?;
+        let ix = system_instruction::create_account(
+            &context.payer.pubkey(),
+            &record.pubkey(),
+            rent.minimum_balance(RECORD_SPACE),
+            RECORD_SPACE as u64,
+            &ID,
+        );
+        send(context, ix, &[&record]).await?;
+        Ok(record.pubkey())
+    }
+
+    // 返回最近一次调用时记录的账户是否为签名者；从未被调用则为 false
+    pub async fn was_signed(
+        context: &mut ProgramTestContext,
+        record: Pubkey,
+    ) -> std::result::Result<bool, BanksClientError> {
+        let account = context
+            .banks_client
+            .get_account(record)
+            .await?
+            .ok_or(BanksClientError::ClientError("record not found"))?;
+        Ok(account.data[0] == 1)
+    }
+}
@@ -0,0 +1,53 @@
+#![cfg(all(feature = "testing", feature = "client"))]
+// 需要 anchor build 产出的 multisig.so：
+//   SBF_OUT_DIR=$PWD/target/deploy cargo test -p multisig --features testing,client \
+//     --test approval_latency -- --ignored
+
+use multisig::client::{parse_logs, MultisigEvent};
+use multisig::testing::{fixture, TestMultisig};
+use multisig::Proposal;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+#[ignore = "requires target/deploy/multisig.so"]
+async fn reports_seconds_since_creation_and_time_to_ready() {
+    let context = TestMultisig::program_test().start_with_context().await;
+    let mut ms = TestMultisig::new(context, 3, 2).await.unwrap();
+    let record = fixture::create_record(&mut ms.context).await.unwrap();
+    let proposal = ms
+        .propose(fixture::instruction(record, ms.multisig))
+        .await
+        .unwrap();
+    let created_at = ms.fetch::<Proposal>(proposal).await.unwrap().created_at;
+
+    ms.advance_clock(60).await.unwrap();
+    let events = parse_logs(&ms.approve_with_logs(0).await.unwrap());
+    let [MultisigEvent::ApprovalAdded(first)] = events.as_slice() else {
+        panic!("expected a single ApprovalAdded, got {events:?}");
+    };
+    assert_eq!(first.proposal, proposal);
+    assert_eq!(first.member, ms.members[0].pubkey());
+    assert_eq!(first.approvals, 1);
+    assert_eq!(first.seconds_since_creation, first.timestamp - created_at);
+    assert!(first.seconds_since_creation >= 60);
+
+    ms.advance_clock(120).await.unwrap();
+    let events = parse_logs(&ms.approve_with_logs(1).await.unwrap());
+    let [MultisigEvent::ApprovalAdded(second), MultisigEvent::ProposalReady(ready)] =
+        events.as_slice()
+    else {
+        panic!("expected ApprovalAdded then ProposalReady, got {events:?}");
+    };
+    assert_eq!(second.approvals, 2);
+    assert!(second.seconds_since_creation >= first.seconds_since_creation + 120);
+    assert_eq!(ready.proposal, proposal);
+    assert_eq!(ready.approvals, 2);
+    assert_eq!(ready.time_to_ready, second.seconds_since_creation);
+
+    // 已就绪后的批准不再发出 ProposalReady
+    let events = parse_logs(&ms.approve_with_logs(2).await.unwrap());
+    assert!(matches!(
+        events.as_slice(),
+        [MultisigEvent::ApprovalAdded(_)]
+    ));
+}
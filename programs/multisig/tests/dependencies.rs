@@ -0,0 +1,28 @@
+use anchor_lang::AnchorDeserialize;
+use multisig::Multisig;
+
+// 全零字节反序列化出空多签，只填依赖相关字段
+fn multisig_with_proposals(count: u32) -> Multisig {
+    let data = vec![0u8; Multisig::space(1)];
+    let mut multisig = Multisig::deserialize(&mut data.as_slice()).unwrap();
+    for _ in 0..count {
+        multisig.record_proposal();
+    }
+    multisig
+}
+
+#[test]
+fn out_of_range_dependencies_are_rejected_without_overflow() {
+    let multisig = multisig_with_proposals(3);
+    assert_eq!(multisig.is_executed(3), None);
+    assert_eq!(multisig.is_executed(u32::MAX), None);
+}
+
+#[test]
+fn executed_dependencies_are_tracked_in_the_window() {
+    let mut multisig = multisig_with_proposals(3);
+    multisig.mark_executed(1);
+    assert_eq!(multisig.is_executed(0), Some(false));
+    assert_eq!(multisig.is_executed(1), Some(true));
+    assert_eq!(multisig.is_executed(2), Some(false));
+}
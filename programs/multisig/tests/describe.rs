@@ -0,0 +1,714 @@
+#![cfg(feature = "client")]
+
+use anchor_lang::prelude::{pubkey, Pubkey};
+use multisig::client::describe::{
+    format_amount, MultisigPda, ASSOCIATED_TOKEN_PROGRAM_ID, BPF_LOADER_UPGRADEABLE_ID,
+};
+use multisig::client::{describe, describe_action_hash, describe_metadata, describe_protocol_fee};
+use multisig::decode::TOKEN_PROGRAM_ID;
+use multisig::{
+    find_vault_address, CustodyKind, InstructionData, Proposal, ProposalAction, ProtocolFee,
+    RecurringPayment, SerializableAccountMeta, VestingSchedule, VestingTranche,
+};
+
+const USDC: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+
+fn proposal(multisig: Pubkey, action: ProposalAction) -> Proposal {
+    Proposal {
+        multisig,
+        proposer: Pubkey::new_unique(),
+        action,
+        approvals: 0,
+        rejections: 0,
+        abstentions: 0,
+        executed: false,
+        cancelled: false,
+        bump: 255,
+        version: 2,
+        config_seqno: 0,
+        members_hash: [0; 32],
+        comments_count: 0,
+        index: 0,
+        depends_on: None,
+        threshold_at_creation: 2,
+        not_before: None,
+        not_after: None,
+        urgent: false,
+        ready_at: None,
+        allow_dangerous: false,
+        no_sign: false,
+        ready_slot: None,
+        tranches_paid: 0,
+        payments_made: 0,
+        last_paid_at: None,
+        execution_memo: None,
+        delay_seconds: 0,
+        voting_ends_at: None,
+        metadata_uri: None,
+        metadata_hash: [0; 32],
+        executed_at: None,
+        executed_by: None,
+        designated_executor: None,
+        approved_at: vec![],
+        protocol_fee: None,
+        created_at: 0,
+        execution_attempts: 0,
+        last_failed_slot: 0,
+        optimistic: false,
+        rejection_reasons: vec![],
+    }
+}
+
+fn meta(pubkey: Pubkey, is_signer: bool, is_writable: bool) -> SerializableAccountMeta {
+    SerializableAccountMeta {
+        pubkey,
+        is_signer,
+        is_writable,
+    }
+}
+
+// 与客户端一致：末尾附上目标程序账户
+fn raw(
+    multisig: Pubkey,
+    program_id: Pubkey,
+    mut accounts: Vec<SerializableAccountMeta>,
+    data: Vec<u8>,
+) -> Proposal {
+    accounts.push(meta(program_id, false, false));
+    proposal(
+        multisig,
+        ProposalAction::Raw(InstructionData {
+            program_id,
+            accounts,
+            data,
+            signers: 0,
+        }),
+    )
+}
+
+fn short(key: &Pubkey) -> String {
+    format!("{}…", &key.to_string()[..4])
+}
+
+#[test]
+fn formats_amounts_with_decimals() {
+    assert_eq!(format_amount(12_500_000, 6), "12.5");
+    assert_eq!(format_amount(1_000_000_000, 9), "1");
+    assert_eq!(format_amount(5, 6), "0.000005");
+    assert_eq!(format_amount(42, 0), "42");
+}
+
+#[test]
+fn describes_system_transfer_from_vault() {
+    let multisig = Pubkey::new_unique();
+    let vault = find_vault_address(&multisig, 0).0;
+    let to = Pubkey::new_unique();
+    let mut data = 2u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&1_500_000_000u64.to_le_bytes());
+    let summary = describe(&raw(
+        multisig,
+        anchor_lang::system_program::ID,
+        vec![meta(vault, true, true), meta(to, false, true)],
+        data,
+    ));
+
+    assert_eq!(
+        summary.description,
+        format!("Transfer 1.5 SOL from vault 0 to {}", short(&to))
+    );
+    assert_eq!(summary.accounts.len(), 2);
+    assert_eq!(summary.accounts[0].pda, Some(MultisigPda::Vault(0)));
+    assert!(summary.accounts[0].is_signer && summary.accounts[0].is_writable);
+    assert_eq!(summary.accounts[1].pda, None);
+    assert!(summary.touches_multisig());
+}
+
+#[test]
+fn describes_token_transfers() {
+    let multisig = Pubkey::new_unique();
+    let vault = find_vault_address(&multisig, 1).0;
+    let (source, destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    let mut data = vec![3];
+    data.extend_from_slice(&700u64.to_le_bytes());
+    let summary = describe(&raw(
+        multisig,
+        TOKEN_PROGRAM_ID,
+        vec![
+            meta(source, false, true),
+            meta(destination, false, true),
+            meta(vault, true, false),
+        ],
+        data,
+    ));
+    assert_eq!(
+        summary.description,
+        format!(
+            "Transfer 700 base units from {} to {} (authority vault 1)",
+            short(&source),
+            short(&destination)
+        )
+    );
+
+    let mut data = vec![12];
+    data.extend_from_slice(&12_500_000u64.to_le_bytes());
+    data.push(6);
+    let summary = describe(&raw(
+        multisig,
+        TOKEN_PROGRAM_ID,
+        vec![
+            meta(source, false, true),
+            meta(USDC, false, false),
+            meta(destination, false, true),
+            meta(vault, true, false),
+        ],
+        data,
+    ));
+    assert_eq!(
+        summary.description,
+        format!(
+            "Transfer 12.5 USDC from {} to {} (authority vault 1)",
+            short(&source),
+            short(&destination)
+        )
+    );
+}
+
+#[test]
+fn describes_token_approvals() {
+    let multisig = Pubkey::new_unique();
+    let (source, delegate, mint) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+
+    let mut data = vec![4];
+    data.extend_from_slice(&10u64.to_le_bytes());
+    let summary = describe(&raw(
+        multisig,
+        TOKEN_PROGRAM_ID,
+        vec![
+            meta(source, false, true),
+            meta(delegate, false, false),
+            meta(multisig, true, false),
+        ],
+        data,
+    ));
+    assert_eq!(
+        summary.description,
+        format!(
+            "Approve {} to spend 10 base units from {} (owner multisig)",
+            short(&delegate),
+            short(&source)
+        )
+    );
+    assert_eq!(summary.accounts[2].pda, Some(MultisigPda::Multisig));
+
+    let mut data = vec![13];
+    data.extend_from_slice(&2_000u64.to_le_bytes());
+    data.push(3);
+    let summary = describe(&raw(
+        multisig,
+        TOKEN_PROGRAM_ID,
+        vec![
+            meta(source, false, true),
+            meta(mint, false, false),
+            meta(delegate, false, false),
+            meta(multisig, true, false),
+        ],
+        data,
+    ));
+    assert_eq!(
+        summary.description,
+        format!(
+            "Approve {} to spend 2 tokens of mint {} from {} (owner multisig)",
+            short(&delegate),
+            short(&mint),
+            short(&source)
+        )
+    );
+}
+
+#[test]
+fn describes_ata_creation() {
+    let multisig = Pubkey::new_unique();
+    let vault = find_vault_address(&multisig, 0).0;
+    let (payer, ata) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = vec![
+        meta(payer, true, true),
+        meta(ata, false, true),
+        meta(vault, false, false),
+        meta(USDC, false, false),
+        meta(anchor_lang::system_program::ID, false, false),
+        meta(TOKEN_PROGRAM_ID, false, false),
+    ];
+
+    let summary = describe(&raw(
+        multisig,
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        accounts.clone(),
+        vec![],
+    ));
+    assert_eq!(
+        summary.description,
+        format!(
+            "Create associated token account {} for vault 0 (mint USDC, paid by {})",
+            short(&ata),
+            short(&payer)
+        )
+    );
+
+    let summary = describe(&raw(
+        multisig,
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        accounts,
+        vec![1],
+    ));
+    assert!(summary
+        .description
+        .starts_with("Create (if missing) associated token account"));
+}
+
+#[test]
+fn describes_program_upgrade() {
+    let multisig = Pubkey::new_unique();
+    let vault = find_vault_address(&multisig, 0).0;
+    let (programdata, program, buffer) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let summary = describe(&raw(
+        multisig,
+        BPF_LOADER_UPGRADEABLE_ID,
+        vec![
+            meta(programdata, false, true),
+            meta(program, false, true),
+            meta(buffer, false, true),
+            meta(vault, false, true),
+            meta(anchor_lang::solana_program::sysvar::rent::ID, false, false),
+            meta(anchor_lang::solana_program::sysvar::clock::ID, false, false),
+            meta(vault, true, false),
+        ],
+        3u32.to_le_bytes().to_vec(),
+    ));
+    assert_eq!(
+        summary.description,
+        format!(
+            "Upgrade program {} from buffer {} (authority vault 0, refund to vault 0)",
+            short(&program),
+            short(&buffer)
+        )
+    );
+}
+
+#[test]
+fn describes_config_actions() {
+    let multisig = Pubkey::new_unique();
+    let member = Pubkey::new_unique();
+
+    let summary = describe(&proposal(multisig, ProposalAction::AddMember(member)));
+    assert_eq!(
+        summary.description,
+        format!("Invite {} as a member", member)
+    );
+    assert_eq!(summary.program_id, None);
+    assert!(summary.touches_multisig());
+
+    let summary = describe(&proposal(multisig, ProposalAction::RemoveMember(member)));
+    assert_eq!(summary.description, format!("Remove member {}", member));
+
+    let summary = describe(&proposal(multisig, ProposalAction::ChangeThreshold(3)));
+    assert_eq!(summary.description, "Change threshold to 3");
+
+    let summary = describe(&proposal(multisig, ProposalAction::SetPaused(true)));
+    assert_eq!(summary.description, "Pause execution");
+
+    let destination = Pubkey::new_unique();
+    let summary = describe(&proposal(
+        multisig,
+        ProposalAction::SweepVault {
+            index: 2,
+            destination,
+        },
+    ));
+    assert_eq!(
+        summary.description,
+        format!("Sweep all SOL from vault 2 to {}", short(&destination))
+    );
+    assert_eq!(summary.accounts[0].pda, Some(MultisigPda::Vault(2)));
+}
+
+#[test]
+fn falls_back_for_unknown_instructions() {
+    let multisig = Pubkey::new_unique();
+    let program = Pubkey::new_unique();
+    let summary = describe(&raw(
+        multisig,
+        program,
+        vec![
+            meta(Pubkey::new_unique(), false, true),
+            meta(Pubkey::new_unique(), false, false),
+        ],
+        vec![9, 9, 9],
+    ));
+    assert_eq!(
+        summary.description,
+        format!("Unknown instruction to {} with 2 accounts", short(&program))
+    );
+    assert!(!summary.touches_multisig());
+
+    // 判别值匹配但长度不符的同样不做猜测
+    let summary = describe(&raw(
+        multisig,
+        anchor_lang::system_program::ID,
+        vec![meta(Pubkey::new_unique(), true, true)],
+        2u32.to_le_bytes().to_vec(),
+    ));
+    assert!(summary
+        .description
+        .starts_with("Unknown instruction to 1111…"));
+}
+
+#[test]
+fn describes_vesting_schedules() {
+    let multisig = Pubkey::new_unique();
+    let recipient = Pubkey::new_unique();
+    let tranche = |unlock_time, amount| VestingTranche {
+        unlock_time,
+        amount,
+    };
+    let summary = describe(&proposal(
+        multisig,
+        ProposalAction::Vesting(VestingSchedule {
+            mint: USDC,
+            recipient,
+            tranches: vec![tranche(100, 1_000), tranche(200, 2_500)],
+        }),
+    ));
+    assert_eq!(
+        summary.description,
+        format!(
+            "Vest 3500 base units of USDC to {} in 2 tranches from vault 0",
+            short(&recipient)
+        )
+    );
+    assert_eq!(summary.program_id, None);
+    assert_eq!(summary.accounts[0].pda, Some(MultisigPda::Vault(0)));
+}
+
+#[test]
+fn describes_recurring_payments() {
+    let multisig = Pubkey::new_unique();
+    let destination = Pubkey::new_unique();
+    let summary = describe(&proposal(
+        multisig,
+        ProposalAction::Recurring(RecurringPayment {
+            destination,
+            amount: 2_000_000_000,
+            interval_seconds: 2_592_000,
+            max_payments: 12,
+        }),
+    ));
+    assert_eq!(
+        summary.description,
+        format!(
+            "Pay 2 SOL from vault 0 to {} every 2592000 seconds, up to 12 times",
+            short(&destination)
+        )
+    );
+    assert_eq!(summary.accounts[1].pubkey, destination);
+}
+
+#[test]
+fn describes_mint_creation() {
+    let multisig = Pubkey::new_unique();
+    let summary = describe(&proposal(
+        multisig,
+        ProposalAction::CreateMint {
+            decimals: 6,
+            freeze_authority: Some(multisig),
+        },
+    ));
+    assert_eq!(
+        summary.description,
+        "Create a mint with 6 decimals, mint authority vault 0, freeze authority multisig"
+    );
+    assert_eq!(summary.accounts[0].pda, Some(MultisigPda::Vault(0)));
+}
+
+#[test]
+fn describes_token_authority_changes() {
+    let multisig = Pubkey::new_unique();
+    let target = Pubkey::new_unique();
+    let set = |authority_type, new_authority| {
+        describe(&proposal(
+            multisig,
+            ProposalAction::SetTokenAuthority {
+                target,
+                authority_type,
+                new_authority,
+            },
+        ))
+        .description
+    };
+    assert_eq!(
+        set(0, Some(find_vault_address(&multisig, 1).0)),
+        format!("Set mint authority of {} to vault 1", short(&target))
+    );
+    assert_eq!(
+        set(1, None),
+        format!(
+            "Set freeze authority of {} to none (renounce)",
+            short(&target)
+        )
+    );
+}
+
+#[test]
+fn describes_burns() {
+    let summary = describe(&proposal(
+        Pubkey::new_unique(),
+        ProposalAction::Burn {
+            mint: USDC,
+            amount: 5_000,
+        },
+    ));
+    assert_eq!(
+        summary.description,
+        "Burn 5000 base units of USDC from vault 0"
+    );
+}
+
+#[test]
+fn describes_freeze_and_thaw() {
+    let multisig = Pubkey::new_unique();
+    let account = Pubkey::new_unique();
+    let summary = describe(&proposal(
+        multisig,
+        ProposalAction::FreezeTokenAccount(account),
+    ));
+    assert_eq!(
+        summary.description,
+        format!("Freeze token account {}", short(&account))
+    );
+    assert!(summary.touches_multisig());
+    let summary = describe(&proposal(
+        multisig,
+        ProposalAction::ThawTokenAccount(account),
+    ));
+    assert_eq!(
+        summary.description,
+        format!("Thaw token account {}", short(&account))
+    );
+}
+
+#[test]
+fn describes_token_delegates() {
+    let account = Pubkey::new_unique();
+    let delegate = Pubkey::new_unique();
+    let summary = describe(&proposal(
+        Pubkey::new_unique(),
+        ProposalAction::TokenApprove {
+            token_account: account,
+            delegate,
+            amount: 250,
+        },
+    ));
+    assert_eq!(
+        summary.description,
+        format!(
+            "Approve {} to spend 250 base units from token account {}",
+            short(&delegate),
+            short(&account)
+        )
+    );
+    let summary = describe(&proposal(
+        Pubkey::new_unique(),
+        ProposalAction::TokenRevoke(account),
+    ));
+    assert_eq!(
+        summary.description,
+        format!("Revoke the delegate of token account {}", short(&account))
+    );
+}
+
+#[test]
+fn describes_mint_to() {
+    let multisig = Pubkey::new_unique();
+    let summary = describe(&proposal(
+        multisig,
+        ProposalAction::MintTo {
+            mint: USDC,
+            destination_owner: find_vault_address(&multisig, 2).0,
+            amount: 1_000_000,
+        },
+    ));
+    assert_eq!(
+        summary.description,
+        "Mint 1000000 base units of USDC to vault 2"
+    );
+    assert!(summary.touches_multisig());
+}
+
+#[test]
+fn describes_vote_account_actions() {
+    let multisig = Pubkey::new_unique();
+    let vote_account = Pubkey::new_unique();
+    let voter = Pubkey::new_unique();
+    let summary = describe(&proposal(
+        multisig,
+        ProposalAction::VoteAuthorize {
+            vote_account,
+            new_authority: voter,
+            authority_type: 0,
+        },
+    ));
+    assert_eq!(
+        summary.description,
+        format!(
+            "Set voter authority of vote account {} to {}",
+            short(&vote_account),
+            short(&voter)
+        )
+    );
+    assert!(summary.touches_multisig());
+
+    let summary = describe(&proposal(
+        multisig,
+        ProposalAction::VoteWithdraw {
+            vote_account,
+            lamports: 2_500_000_000,
+            destination: find_vault_address(&multisig, 0).0,
+        },
+    ));
+    assert_eq!(
+        summary.description,
+        format!(
+            "Withdraw 2.5 SOL from vote account {} to vault 0",
+            short(&vote_account)
+        )
+    );
+}
+
+#[test]
+fn describes_stake_lifecycle() {
+    let multisig = Pubkey::new_unique();
+    let stake_account = Pubkey::new_unique();
+    let describe_action = |action| describe(&proposal(multisig, action)).description;
+    assert_eq!(
+        describe_action(ProposalAction::StakeSplit {
+            stake_account,
+            lamports: 1_000_000_000,
+        }),
+        format!(
+            "Split 1 SOL from stake account {} into a new stake account",
+            short(&stake_account)
+        )
+    );
+    assert_eq!(
+        describe_action(ProposalAction::StakeDeactivate(stake_account)),
+        format!("Deactivate stake account {}", short(&stake_account))
+    );
+    assert_eq!(
+        describe_action(ProposalAction::StakeWithdraw {
+            stake_account,
+            lamports: 1_000_000_000,
+            destination: find_vault_address(&multisig, 0).0,
+        }),
+        format!(
+            "Withdraw 1 SOL from stake account {} to vault 0",
+            short(&stake_account)
+        )
+    );
+}
+
+#[test]
+fn describes_custody_handover() {
+    let multisig = Pubkey::new_unique();
+    let account = Pubkey::new_unique();
+    let summary = describe(&proposal(
+        multisig,
+        ProposalAction::TakeCustody {
+            account,
+            kind: CustodyKind::Stake,
+        },
+    ));
+    assert_eq!(
+        summary.description,
+        format!(
+            "Take custody of the withdraw authority of stake account {}",
+            short(&account)
+        )
+    );
+    let owner = Pubkey::new_unique();
+    let summary = describe(&proposal(
+        multisig,
+        ProposalAction::ReleaseCustody {
+            account,
+            kind: CustodyKind::Vote,
+            new_authority: owner,
+        },
+    ));
+    assert_eq!(
+        summary.description,
+        format!(
+            "Hand the withdraw authority of vote account {} to {}",
+            short(&account),
+            short(&owner)
+        )
+    );
+}
+
+#[test]
+fn describes_metadata_with_its_hash() {
+    let multisig = Pubkey::new_unique();
+    let mut p = proposal(multisig, ProposalAction::ChangeThreshold(2));
+    assert_eq!(describe_metadata(&p), None);
+
+    p.metadata_uri = Some("https://example.com/invoices/2026-041.pdf".to_string());
+    p.metadata_hash = [0xab; 32];
+    assert_eq!(
+        describe_metadata(&p).unwrap(),
+        format!(
+            "Metadata: https://example.com/invoices/2026-041.pdf (sha256 {})",
+            "ab".repeat(32)
+        )
+    );
+}
+
+#[test]
+fn describes_the_action_hash_for_approval() {
+    let multisig = Pubkey::new_unique();
+    let p = proposal(multisig, ProposalAction::ChangeThreshold(2));
+    let hash = p.action.hash().unwrap();
+    let line = describe_action_hash(&p).unwrap();
+    assert_eq!(line.len(), "Action hash: ".len() + 64);
+    assert!(line.ends_with(&format!("{:02x}", hash[31])));
+
+    let other = proposal(multisig, ProposalAction::ChangeThreshold(3));
+    assert_ne!(describe_action_hash(&other).unwrap(), line);
+}
+
+#[test]
+fn describes_the_protocol_fee() {
+    let multisig = Pubkey::new_unique();
+    let mut p = proposal(multisig, ProposalAction::ChangeThreshold(2));
+    assert_eq!(describe_protocol_fee(&p), None);
+
+    let vault = find_vault_address(&multisig, 0).0;
+    let recipient = Pubkey::new_unique();
+    p.protocol_fee = Some(ProtocolFee {
+        lamports: 2_500_000,
+        payer: vault,
+        recipient,
+    });
+    assert_eq!(
+        describe_protocol_fee(&p).unwrap(),
+        format!(
+            "Protocol fee: up to 0.0025 SOL from {} to {}",
+            vault, recipient
+        )
+    );
+}
@@ -0,0 +1,124 @@
+#![cfg(feature = "client")]
+
+// fixtures/*.log 为真实交易日志（事件数据为 emit! 的原始输出），用于在 Anchor 升级时检测
+// 判别符或编码的变化
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Event;
+use multisig::client::{events_from_transaction, parse_logs, MultisigEvent};
+use multisig::{MultisigCreated, ProposalAction, EVENT_SCHEMA_VERSION, PROGRAM_VERSION};
+use solana_transaction_status_client_types::EncodedConfirmedTransactionWithStatusMeta;
+
+const MULTISIG: &str = "7VqPTUZ9r3kyHC3xSJ8xeSrNKw8kdkSfDBGBvmtUEmcG";
+const PROPOSAL: &str = "4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T";
+const MEMBER: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+
+fn fixture(name: &str) -> Vec<String> {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect()
+}
+
+fn key(s: &str) -> Pubkey {
+    s.parse().unwrap()
+}
+
+#[test]
+fn parses_proposal_created() {
+    let events = parse_logs(&fixture("propose.log"));
+    assert_eq!(events.len(), 1);
+    let MultisigEvent::ProposalCreated(e) = &events[0] else {
+        panic!("unexpected event {:?}", events[0]);
+    };
+    assert_eq!(e.schema_version, EVENT_SCHEMA_VERSION);
+    assert_eq!(e.multisig, key(MULTISIG));
+    assert_eq!(e.proposal, key(PROPOSAL));
+    assert_eq!(e.index, 3);
+    assert_eq!(e.timestamp, 1_760_500_000);
+    assert_eq!(e.proposer, key(MEMBER));
+    assert!(!e.urgent);
+    assert_eq!(e.protocol_fee, None);
+}
+
+#[test]
+fn ignores_data_from_inner_cpis() {
+    // 被执行的 CPI 目标程序写出了与 ConfigActionExecuted 相同的数据，不应计入
+    let events = parse_logs(&fixture("execute_cpi.log"));
+    assert_eq!(events.len(), 1);
+    let MultisigEvent::ProposalExecuted(e) = &events[0] else {
+        panic!("unexpected event {:?}", events[0]);
+    };
+    assert_eq!(e.proposal, key(PROPOSAL));
+    assert_eq!(e.index, 3);
+    assert_eq!(e.timestamp, 1_760_500_000);
+}
+
+#[test]
+fn parses_events_when_invoked_by_another_program() {
+    let events = parse_logs(&fixture("outer_cpi.log"));
+    assert_eq!(events.len(), 1);
+    let MultisigEvent::ConfigActionExecuted(e) = &events[0] else {
+        panic!("unexpected event {:?}", events[0]);
+    };
+    assert!(matches!(e.action, ProposalAction::ChangeThreshold(2)));
+}
+
+#[test]
+fn keeps_events_before_truncation() {
+    let events = parse_logs(&fixture("truncated.log"));
+    assert_eq!(events.len(), 1);
+    let MultisigEvent::CommentPosted(e) = &events[0] else {
+        panic!("unexpected event {:?}", events[0]);
+    };
+    assert_eq!(e.author, key(MEMBER));
+    assert_eq!(e.text, "ship it");
+}
+
+#[test]
+fn extracts_events_from_rpc_transaction() {
+    let path = format!(
+        "{}/tests/fixtures/get_transaction.json",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let mut json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    let tx: EncodedConfirmedTransactionWithStatusMeta =
+        serde_json::from_value(json.clone()).unwrap();
+    let events = events_from_transaction(&tx);
+    assert_eq!(events.len(), 1);
+    assert!(matches!(events[0], MultisigEvent::ProposalCreated(_)));
+
+    // 失败交易的事件已随状态回滚
+    json["meta"]["err"] = serde_json::json!({ "InstructionError": [1, { "Custom": 6000 }] });
+    let tx: EncodedConfirmedTransactionWithStatusMeta = serde_json::from_value(json).unwrap();
+    assert!(events_from_transaction(&tx).is_empty());
+}
+
+#[test]
+fn decodes_only_the_current_schema_version() {
+    let created = MultisigCreated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        multisig: key(MULTISIG),
+        creator: key(MEMBER),
+        members: 3,
+        threshold: 2,
+        program_version: PROGRAM_VERSION.to_string(),
+        timestamp: 1_760_500_000,
+    };
+    let data = created.data();
+    assert_eq!(
+        MultisigEvent::schema_version(&data),
+        Some(EVENT_SCHEMA_VERSION)
+    );
+    let Some(MultisigEvent::MultisigCreated(e)) = MultisigEvent::decode(&data) else {
+        panic!("MultisigCreated not decoded");
+    };
+    assert_eq!(e.program_version, PROGRAM_VERSION);
+
+    // 更新的程序发出的事件布局未知，跳过而不是按旧布局误解码
+    let mut future = data;
+    future[8] = EVENT_SCHEMA_VERSION + 1;
+    assert!(MultisigEvent::decode(&future).is_none());
+}
@@ -0,0 +1,49 @@
+use multisig::extension::{get, remove, set, TAG_RESERVED};
+
+const TIERS: u16 = 0x7f01;
+const LIMITS: u16 = 0x7f02;
+
+#[test]
+fn writes_and_reads_back_multiple_extensions() {
+    let mut data = vec![];
+    set(&mut data, TIERS, &[1, 2, 3], 256).unwrap();
+    set(&mut data, LIMITS, &[9; 8], 256).unwrap();
+    assert_eq!(get(&data, TIERS), Some(&[1, 2, 3][..]));
+    assert_eq!(get(&data, LIMITS), Some(&[9; 8][..]));
+    assert_eq!(get(&data, 0x7f03), None);
+    // 每项 4 字节头
+    assert_eq!(data.len(), 4 + 3 + 4 + 8);
+}
+
+#[test]
+fn overwrites_in_place_of_the_old_value() {
+    let mut data = vec![];
+    set(&mut data, TIERS, &[1, 2, 3], 256).unwrap();
+    set(&mut data, LIMITS, &[7], 256).unwrap();
+    set(&mut data, TIERS, &[4], 256).unwrap();
+    assert_eq!(get(&data, TIERS), Some(&[4][..]));
+    assert_eq!(get(&data, LIMITS), Some(&[7][..]));
+    assert_eq!(data.len(), 2 * (4 + 1));
+
+    assert!(remove(&mut data, TIERS));
+    assert!(!remove(&mut data, TIERS));
+    assert_eq!(get(&data, LIMITS), Some(&[7][..]));
+}
+
+#[test]
+fn enforces_capacity_and_tags() {
+    let mut data = vec![];
+    set(&mut data, TIERS, &[0; 12], 16).unwrap();
+    // 覆盖时按替换后的大小计算
+    set(&mut data, TIERS, &[1; 12], 16).unwrap();
+    assert!(set(&mut data, LIMITS, &[], 16).is_err());
+    assert!(set(&mut data, TAG_RESERVED, &[1], 256).is_err());
+    assert_eq!(get(&data, TIERS), Some(&[1; 12][..]));
+}
+
+#[test]
+fn ignores_truncated_entries() {
+    // 声明 8 字节值但只有 2 字节
+    let data = [0x01, 0x7f, 8, 0, 1, 2];
+    assert_eq!(get(&data, TIERS), None);
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::Pubkey;
+use multisig::legacy::{parse_legacy_multisig, LEGACY_MULTISIG_DISCRIMINATOR};
+use solana_sha256_hasher::hash;
+
+// 按 serum multisig 布局构造的账户数据：3 个 owners、threshold 2、nonce 253、owner_set_seqno 3，
+// 分配空间按 10 个 owners 计算（345 字节），尾部补零
+const FIXTURE: &[u8] = include_bytes!("fixtures/serum_multisig.bin");
+// 判别符 + 长度 + 3 个 owners + threshold + nonce + seqno
+const USED: usize = 8 + 4 + 3 * 32 + 8 + 1 + 4;
+
+fn owner(i: u8) -> Pubkey {
+    Pubkey::new_from_array(hash(format!("legacy-owner-{i}").as_bytes()).to_bytes())
+}
+
+#[test]
+fn parses_the_legacy_layout() {
+    assert_eq!(FIXTURE.len(), 345);
+    let legacy = parse_legacy_multisig(FIXTURE).unwrap();
+    assert_eq!(legacy.owners, vec![owner(0), owner(1), owner(2)]);
+    assert_eq!(legacy.threshold, 2);
+    assert_eq!(legacy.nonce, 253);
+    assert_eq!(legacy.owner_set_seqno, 3);
+    // 尾部多余空间不影响解析
+    assert_eq!(parse_legacy_multisig(&FIXTURE[..USED]), Some(legacy));
+}
+
+#[test]
+fn rejects_truncated_data_at_every_length() {
+    for len in 0..USED {
+        assert_eq!(parse_legacy_multisig(&FIXTURE[..len]), None, "len {len}");
+    }
+}
+
+#[test]
+fn rejects_bad_discriminators_and_length_prefixes() {
+    let mut data = FIXTURE.to_vec();
+    data[0] ^= 1;
+    assert_eq!(parse_legacy_multisig(&data), None);
+
+    // 长度前缀超出账户数据
+    let mut data = FIXTURE.to_vec();
+    data[8..12].copy_from_slice(&11u32.to_le_bytes());
+    assert_eq!(parse_legacy_multisig(&data), None);
+
+    // 乘以 32 后会溢出的长度前缀
+    let mut data = FIXTURE.to_vec();
+    data[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(parse_legacy_multisig(&data), None);
+
+    assert_eq!(parse_legacy_multisig(&LEGACY_MULTISIG_DISCRIMINATOR), None);
+}
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AnchorDeserialize;
+use multisig::{KeyType, Member, Multisig};
+
+const MAX: u8 = 16;
+const POOL: usize = 24;
+
+// 全零字节反序列化出空多签，只填计票相关字段
+fn empty_multisig() -> Multisig {
+    let data = vec![0u8; Multisig::space(MAX)];
+    let mut multisig = Multisig::deserialize(&mut data.as_slice()).unwrap();
+    multisig.max_members = MAX;
+    multisig.threshold = 1;
+    multisig
+}
+
+fn member(key: Pubkey) -> Member {
+    Member {
+        key,
+        expires_at: None,
+        last_seen: 0,
+        inactive: false,
+        key_type: KeyType::Ed25519,
+    }
+}
+
+// xorshift64，固定种子保证可复现
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+fn assert_consistent(multisig: &Multisig, pool: &[Pubkey]) {
+    assert!(multisig.members_sorted());
+    for key in pool {
+        let naive = multisig.members.iter().position(|m| m.key == *key);
+        assert_eq!(multisig.member_index(key), naive, "{key}");
+    }
+}
+
+#[test]
+fn random_inserts_and_removals_keep_members_sorted() {
+    let pool: Vec<Pubkey> = (0..POOL).map(|_| Pubkey::new_unique()).collect();
+    let data_len = Multisig::space(MAX);
+    for seed in 1..=32u64 {
+        let mut rng = Rng(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+        let mut multisig = empty_multisig();
+        multisig.insert_member(member(pool[0]), data_len).unwrap();
+        for _ in 0..200 {
+            let key = pool[rng.next() as usize % POOL];
+            let present = multisig.member_index(&key).is_some();
+            let len = multisig.members.len();
+            match (present, len) {
+                (true, 1) => assert!(multisig.insert_member(member(key), data_len).is_err()),
+                (true, _) => multisig.remove_member(&key, 0).unwrap(),
+                (false, n) if n < MAX as usize => {
+                    multisig.insert_member(member(key), data_len).unwrap()
+                }
+                (false, _) => assert!(multisig.insert_member(member(key), data_len).is_err()),
+            }
+            assert_consistent(&multisig, &pool);
+        }
+    }
+}
+
+#[test]
+fn rejects_duplicates_and_unknown_removals_without_reordering() {
+    let pool: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+    let data_len = Multisig::space(MAX);
+    let mut multisig = empty_multisig();
+    for key in pool.iter().rev() {
+        multisig.insert_member(member(*key), data_len).unwrap();
+    }
+    let before: Vec<Pubkey> = multisig.members.iter().map(|m| m.key).collect();
+
+    assert!(multisig.insert_member(member(pool[2]), data_len).is_err());
+    assert!(multisig.remove_member(&Pubkey::new_unique(), 0).is_err());
+    let after: Vec<Pubkey> = multisig.members.iter().map(|m| m.key).collect();
+    assert_eq!(before, after);
+    assert_consistent(&multisig, &pool);
+}
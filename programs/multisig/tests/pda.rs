@@ -0,0 +1,58 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::system_instruction;
+use multisig::decode::system_debit;
+use multisig::{find_multisig_address, find_proposal_address, find_vault_address, InstructionData};
+
+// 与 tests/utils.ts 中的 findProposalPda 等一致：计数器按小端序编码
+#[test]
+fn proposal_index_is_little_endian() {
+    let multisig = Pubkey::new_unique();
+    let expected = Pubkey::find_program_address(
+        &[b"proposal", multisig.as_ref(), &[2, 1, 0, 0]],
+        &multisig::ID,
+    );
+    assert_eq!(find_proposal_address(&multisig, 258), expected);
+}
+
+#[test]
+fn addresses_are_distinct_per_seed() {
+    let creator = Pubkey::new_unique();
+    let (multisig, _) = find_multisig_address(&creator, 0);
+    assert_ne!(multisig, find_multisig_address(&creator, 1).0);
+    assert_ne!(
+        find_vault_address(&multisig, 0).0,
+        find_vault_address(&multisig, 1).0
+    );
+    assert_ne!(
+        find_proposal_address(&multisig, 0).0,
+        find_vault_address(&multisig, 0).0
+    );
+}
+
+// 金库同时作为出资方与 base，数据布局与 system_instruction 一致，出资额计入 system_debit
+#[test]
+fn create_account_with_seed_is_funded_by_the_vault() {
+    let multisig = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let vault = find_vault_address(&multisig, 1).0;
+    let (address, ix) =
+        InstructionData::create_account_with_seed(&multisig, 1, "config", 1_000, 64, &owner)
+            .unwrap();
+    assert_eq!(
+        address,
+        Pubkey::create_with_seed(&vault, "config", &owner).unwrap()
+    );
+
+    let expected = system_instruction::create_account_with_seed(
+        &vault, &address, &vault, "config", 1_000, 64, &owner,
+    );
+    assert_eq!(ix.data, expected.data);
+    assert_eq!(ix.signer_pdas(&multisig), vec![vault]);
+    assert_eq!(system_debit(&ix), Some((vault, 1_000)));
+
+    let long_seed = "s".repeat(33);
+    assert!(
+        InstructionData::create_account_with_seed(&multisig, 1, &long_seed, 1_000, 64, &owner)
+            .is_err()
+    );
+}
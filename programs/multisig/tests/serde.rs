@@ -0,0 +1,170 @@
+#![cfg(feature = "serde")]
+
+use anchor_lang::prelude::Pubkey;
+use multisig::{
+    ControlledAccount, CustodyKind, InstructionData, KeyType, Member, Multisig, Proposal, ProposalAction,
+    RejectionReason, SerializableAccountMeta, VaultConfig,
+};
+
+fn instruction() -> InstructionData {
+    InstructionData {
+        program_id: Pubkey::new_unique(),
+        accounts: vec![SerializableAccountMeta {
+            pubkey: Pubkey::new_unique(),
+            is_signer: true,
+            is_writable: false,
+        }],
+        data: vec![0, 1, 2, 250, 255],
+        signers: 1,
+    }
+}
+
+#[test]
+fn instruction_data_uses_base58_and_base64() {
+    let ix = instruction();
+    let json = serde_json::to_value(&ix).unwrap();
+    assert_eq!(json["program_id"], ix.program_id.to_string());
+    assert_eq!(json["accounts"][0]["pubkey"], ix.accounts[0].pubkey.to_string());
+    assert_eq!(json["data"], "AAEC+v8=");
+
+    let back: InstructionData = serde_json::from_value(json).unwrap();
+    assert_eq!(back.program_id, ix.program_id);
+    assert_eq!(back.accounts[0].pubkey, ix.accounts[0].pubkey);
+    assert_eq!(back.data, ix.data);
+}
+
+#[test]
+fn multisig_round_trips() {
+    let multisig = Multisig {
+        creator: Pubkey::new_unique(),
+        nonce: 1,
+        members: vec![Member {
+            key: Pubkey::new_unique(),
+            expires_at: Some(1_700_000_000),
+            last_seen: 1_690_000_000,
+            inactive: true,
+            key_type: KeyType::Secp256k1,
+        }],
+        threshold: 1,
+        proposals_count: 3,
+        bump: 254,
+        config_seqno: 2,
+        threshold_overrides: vec![],
+        amount_rules: vec![],
+        frozen_by: None,
+        frozen_at: 0,
+        last_freezer: Some(Pubkey::new_unique()),
+        unfrozen_at: 0,
+        pending_invites: vec![],
+        config_authority: None,
+        paused: false,
+        quorum: Some(1),
+        recent_executed: 0b101,
+        guardian: None,
+        execute_against_snapshot: false,
+        timelock_seconds: 60,
+        urgent_timelock_seconds: 0,
+        urgent_threshold: None,
+        min_execution_delay_slots: 0,
+        max_members: 10,
+        executed_count: 3,
+        cancelled_count: 1,
+        last_activity: 1_760_000_000,
+        controlled_accounts: vec![ControlledAccount {
+            account: Pubkey::new_unique(),
+            kind: CustodyKind::Vote,
+        }],
+        inactivity_timeout: Some(30 * 86_400),
+        liveness_floor: 1,
+        wound_down_at: None,
+        total_lamports_out: 2_500_000_000,
+        approval_groups: vec![],
+        config_requires_unanimity: true,
+        trusted_destinations: vec![],
+        destination_limits: vec![],
+        allowed_destinations: vec![Pubkey::new_unique()],
+        allowlist_delay_seconds: 86_400,
+        pending_allowlist: None,
+        denied_destinations: vec![],
+        retain_executed: true,
+        retention_seconds: 90 * 86_400,
+        executor_grace_seconds: 86_400,
+        approval_ttl_seconds: Some(7 * 86_400),
+        extensions: vec![1, 0, 2, 0, 0xab, 0xcd],
+        vaults: vec![VaultConfig {
+            index: 1,
+            threshold_override: Some(3),
+            name: "cold".to_string(),
+            mint_hint: None,
+            paused: true,
+        }],
+    };
+    let json = serde_json::to_string(&multisig).unwrap();
+    let back: Multisig = serde_json::from_str(&json).unwrap();
+    assert_eq!(serde_json::to_string(&back).unwrap(), json);
+    assert_eq!(back.last_freezer, multisig.last_freezer);
+    assert_eq!(back.controlled_accounts[0].kind, CustodyKind::Vote);
+    assert!(back.members[0].inactive);
+}
+
+#[test]
+fn proposal_round_trips() {
+    let proposal = Proposal {
+        multisig: Pubkey::new_unique(),
+        proposer: Pubkey::new_unique(),
+        action: ProposalAction::Raw(instruction()),
+        approvals: 0b11,
+        rejections: 0,
+        abstentions: 0,
+        executed: false,
+        cancelled: false,
+        bump: 255,
+        version: 2,
+        config_seqno: 0,
+        members_hash: [7; 32],
+        comments_count: 0,
+        index: 0,
+        depends_on: None,
+        threshold_at_creation: 2,
+        not_before: None,
+        not_after: Some(1_800_000_000),
+        urgent: false,
+        ready_at: None,
+        allow_dangerous: false,
+        no_sign: false,
+        ready_slot: Some(42),
+        tranches_paid: 0,
+        payments_made: 0,
+        last_paid_at: None,
+        execution_memo: None,
+        delay_seconds: 86_400,
+        voting_ends_at: Some(1_760_259_200),
+        metadata_uri: Some("ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3".to_string()),
+        metadata_hash: [7; 32],
+        executed_at: Some(1_760_300_000),
+        executed_by: Some(Pubkey::new_unique()),
+        designated_executor: None,
+        approved_at: vec![1_760_000_000, 0, 1_760_100_000],
+        protocol_fee: None,
+        created_at: 0,
+        execution_attempts: 0,
+        last_failed_slot: 0,
+        optimistic: false,
+        rejection_reasons: vec![RejectionReason {
+            member: Pubkey::new_unique(),
+            code: 2,
+            reason: "wrong recipient".to_string(),
+        }],
+    };
+    let json = serde_json::to_string(&proposal).unwrap();
+    let back: Proposal = serde_json::from_str(&json).unwrap();
+    assert_eq!(serde_json::to_string(&back).unwrap(), json);
+    assert_eq!(back.rejection_reasons[0].reason, "wrong recipient");
+
+    let sweep = ProposalAction::SweepVault {
+        index: 0,
+        destination: Pubkey::new_unique(),
+    };
+    let value = serde_json::to_value(&sweep).unwrap();
+    assert!(value["SweepVault"]["destination"].is_string());
+}
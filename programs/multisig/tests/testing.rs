@@ -0,0 +1,107 @@
+#![cfg(feature = "testing")]
+// 需要 anchor build 产出的 multisig.so：
+//   SBF_OUT_DIR=$PWD/target/deploy cargo test -p multisig --features testing -- --ignored
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use multisig::testing::{fixture, TestMultisig};
+use multisig::ProposalStatus;
+
+#[tokio::test]
+#[ignore = "requires target/deploy/multisig.so"]
+async fn executes_with_multisig_pda_as_signer() {
+    let context = TestMultisig::program_test().start_with_context().await;
+    let mut ms = TestMultisig::new(context, 3, 2).await.unwrap();
+    let record = fixture::create_record(&mut ms.context).await.unwrap();
+
+    ms.propose(fixture::instruction(record, ms.multisig))
+        .await
+        .unwrap();
+    ms.approve(0).await.unwrap();
+    // 未达到阈值时不能执行
+    assert!(ms.execute().await.is_err());
+
+    ms.approve(1).await.unwrap();
+    ms.execute().await.unwrap();
+    assert!(fixture::was_signed(&mut ms.context, record).await.unwrap());
+}
+
+#[tokio::test]
+#[ignore = "requires target/deploy/multisig.so"]
+async fn signs_as_vault() {
+    let context = TestMultisig::program_test().start_with_context().await;
+    let mut ms = TestMultisig::new(context, 2, 2).await.unwrap();
+    let record = fixture::create_record(&mut ms.context).await.unwrap();
+    let vault = ms.vault(0);
+
+    ms.propose(fixture::instruction(record, vault))
+        .await
+        .unwrap();
+    ms.approve_all_until(2).await.unwrap();
+    ms.execute().await.unwrap();
+    assert!(fixture::was_signed(&mut ms.context, record).await.unwrap());
+}
+
+#[tokio::test]
+#[ignore = "requires target/deploy/multisig.so"]
+async fn reports_status_as_execute_would_judge_it() {
+    let context = TestMultisig::program_test().start_with_context().await;
+    let mut ms = TestMultisig::new(context, 3, 2).await.unwrap();
+    let record = fixture::create_record(&mut ms.context).await.unwrap();
+
+    ms.propose(fixture::instruction(record, ms.multisig))
+        .await
+        .unwrap();
+    ms.approve(0).await.unwrap();
+    let status = ms.status().await.unwrap();
+    assert_eq!(status.status, ProposalStatus::Pending);
+    assert_eq!((status.approvals, status.threshold), (1, 2));
+
+    ms.approve(1).await.unwrap();
+    let status = ms.status().await.unwrap();
+    assert_eq!(status.status, ProposalStatus::Ready);
+    assert_eq!(status.approvals, 2);
+    assert!(status.ready_at.is_some());
+    assert_eq!(status.expires_at, None);
+    ms.execute().await.unwrap();
+}
+
+// 入口与 CPI 前两次 sol_log_compute_units 的差值即执行前开销；
+// 需以 `anchor build -- --features log-compute-units` 构建，否则只打印总消耗
+#[tokio::test]
+#[ignore = "requires target/deploy/multisig.so"]
+async fn pre_cpi_overhead_stays_flat_per_account() {
+    // 逐账户 msg! 时每个账户要格式化两个 base58 公钥，远超此预算
+    const PER_ACCOUNT_BUDGET: u64 = 300;
+
+    let mut measured = Vec::new();
+    for n_accounts in [2, 20] {
+        let context = TestMultisig::program_test().start_with_context().await;
+        let mut ms = TestMultisig::new(context, 2, 2).await.unwrap();
+        let record = fixture::create_record(&mut ms.context).await.unwrap();
+        let mut ix = fixture::instruction(record, ms.vault(0));
+        ix.accounts.extend(
+            (2..n_accounts).map(|_| AccountMeta::new_readonly(Pubkey::new_unique(), false)),
+        );
+        ms.propose(ix).await.unwrap();
+        ms.approve_all_until(2).await.unwrap();
+
+        let (units, logs) = ms.simulate_execute().await.unwrap();
+        let remaining: Vec<u64> = logs
+            .iter()
+            .filter_map(|l| l.strip_prefix("Program consumption: "))
+            .filter_map(|l| l.strip_suffix(" units remaining")?.parse().ok())
+            .collect();
+        let pre_cpi = match remaining[..] {
+            [entry, before_cpi, ..] => Some(entry - before_cpi),
+            _ => None,
+        };
+        println!("{n_accounts} accounts: {units} CU total, pre-CPI overhead {pre_cpi:?} CU");
+        measured.push(pre_cpi);
+    }
+
+    if let [Some(small), Some(large)] = measured[..] {
+        let per_account = large.saturating_sub(small) / 18;
+        println!("pre-CPI overhead per extra account: {per_account} CU");
+        assert!(per_account <= PER_ACCOUNT_BUDGET);
+    }
+}
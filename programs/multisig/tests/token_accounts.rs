@@ -0,0 +1,76 @@
+use anchor_lang::prelude::{AccountInfo, Pubkey};
+use multisig::decode::{read_token_account, TokenAccount, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+// spl_token::state::Account：mint | owner | amount | delegate | state @108 | ...
+fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64, len: usize) -> Vec<u8> {
+    let mut data = vec![0u8; len];
+    data[0..32].copy_from_slice(mint.as_ref());
+    data[32..64].copy_from_slice(owner.as_ref());
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    data[108] = 1;
+    data
+}
+
+fn read(program: &Pubkey, mut data: Vec<u8>) -> Option<TokenAccount> {
+    let key = Pubkey::new_unique();
+    let mut lamports = 0;
+    let info = AccountInfo::new(
+        &key,
+        false,
+        false,
+        &mut lamports,
+        &mut data,
+        program,
+        false,
+        0,
+    );
+    read_token_account(&info)
+}
+
+#[test]
+fn reads_initialized_token_accounts() {
+    let (mint, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let expected = TokenAccount {
+        mint,
+        owner,
+        amount: 42,
+    };
+    assert_eq!(
+        read(&TOKEN_PROGRAM_ID, token_account(&mint, &owner, 42, 165)),
+        Some(expected.clone())
+    );
+    // 冻结的账户（state 2）仍计入持仓
+    let mut frozen = token_account(&mint, &owner, 42, 165);
+    frozen[108] = 2;
+    assert_eq!(read(&TOKEN_PROGRAM_ID, frozen), Some(expected.clone()));
+    // Token-2022 带扩展的账户：AccountType::Account 位于第 165 字节
+    let mut extended = token_account(&mint, &owner, 42, 200);
+    extended[165] = 2;
+    assert_eq!(read(&TOKEN_2022_PROGRAM_ID, extended), Some(expected));
+}
+
+#[test]
+fn rejects_lookalike_accounts() {
+    let (mint, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let valid = token_account(&mint, &owner, 1, 165);
+
+    // 相同数据但不归 token 程序所有
+    assert_eq!(read(&Pubkey::new_unique(), valid.clone()), None);
+    // 未初始化
+    let mut uninitialized = valid.clone();
+    uninitialized[108] = 0;
+    assert_eq!(read(&TOKEN_PROGRAM_ID, uninitialized), None);
+    // SPL Token 的 multisig 账户与长度不符的数据
+    assert_eq!(
+        read(&TOKEN_PROGRAM_ID, token_account(&mint, &owner, 1, 355)),
+        None
+    );
+    assert_eq!(read(&TOKEN_PROGRAM_ID, valid[..164].to_vec()), None);
+    // Token-2022 的 mint 同样补齐到 165 字节以上，AccountType 为 Mint（1）
+    let mut mint_2022 = token_account(&mint, &owner, 1, 200);
+    mint_2022[165] = 1;
+    assert_eq!(read(&TOKEN_2022_PROGRAM_ID, mint_2022), None);
+    let mut multisig_2022 = token_account(&mint, &owner, 1, 355);
+    multisig_2022[165] = 2;
+    assert_eq!(read(&TOKEN_2022_PROGRAM_ID, multisig_2022), None);
+}
@@ -0,0 +1,28 @@
+// 示例策略插件：放行所有执行。策略程序须实现固定指令 check_execution，
+// 参数与账户顺序见多签程序的 check_policy
+use anchor_lang::prelude::*;
+use multisig::SerializableAccountMeta;
+
+declare_id!("3NmS4WmUoNbUxd9WbEgG7Nrm1z8U4Mdv3GYyZ4ora99J");
+
+#[program]
+pub mod policy_allow_all {
+    use super::*;
+
+    pub fn check_execution(
+        _ctx: Context<CheckExecution>,
+        _proposal: Pubkey,
+        _instruction_hash: [u8; 32],
+        _accounts: Vec<SerializableAccountMeta>,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct CheckExecution<'info> {
+    /// CHECK: 不读取
+    pub multisig: UncheckedAccount<'info>,
+    /// CHECK: 不读取
+    pub proposal: UncheckedAccount<'info>,
+}
@@ -0,0 +1,79 @@
+// 示例策略插件：拒绝超过上限的 SOL 转账，上限按多签存放在 ["limit", multisig]。
+// 按提案中可确定的 SOL 流出合计（多指令提案中的 System transfer、提取等类型化动作），周期付款按每次金额计；
+// 金额无法确定的（清扫、缓冲区提案等）放行
+use anchor_lang::prelude::*;
+use multisig::{Proposal, ProposalAction, SerializableAccountMeta};
+
+declare_id!("9P2BCBw3pEDEM2hZWJk81mxdFQAzcPxeTNEtRBR242mV");
+
+#[program]
+pub mod policy_deny_over_amount {
+    use super::*;
+
+    // 须由多签经提案调用，payer 通常为提案选中签名的金库
+    pub fn init_limit(ctx: Context<InitLimit>, max_lamports: u64) -> Result<()> {
+        ctx.accounts.limit.max_lamports = max_lamports;
+        Ok(())
+    }
+
+    pub fn check_execution(
+        ctx: Context<CheckExecution>,
+        _proposal: Pubkey,
+        _instruction_hash: [u8; 32],
+        _accounts: Vec<SerializableAccountMeta>,
+    ) -> Result<()> {
+        let action = &ctx.accounts.proposal.action;
+        let lamports = match action {
+            ProposalAction::Recurring(payment) => payment.amount,
+            _ => action
+                .outflows(ctx.accounts.multisig.key, &[])
+                .into_iter()
+                .flatten()
+                .filter(|(_, mint, _)| mint.is_none())
+                .fold(0u64, |total, (_, _, amount)| total.saturating_add(amount)),
+        };
+        require!(
+            lamports <= ctx.accounts.limit.max_lamports,
+            PolicyError::AmountOverLimit
+        );
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Limit {
+    pub max_lamports: u64,
+}
+
+#[derive(Accounts)]
+pub struct InitLimit<'info> {
+    #[account(
+        init,
+        seeds = [b"limit", multisig.key().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + 8
+    )]
+    pub limit: Account<'info, Limit>,
+    pub multisig: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// 上限账户由 multisig 推导，执行者无法换成别的账户
+#[derive(Accounts)]
+pub struct CheckExecution<'info> {
+    /// CHECK: 只用于推导上限账户
+    pub multisig: UncheckedAccount<'info>,
+    #[account(constraint = proposal.multisig == multisig.key())]
+    pub proposal: Account<'info, Proposal>,
+    #[account(seeds = [b"limit", multisig.key().as_ref()], bump)]
+    pub limit: Account<'info, Limit>,
+}
+
+#[error_code]
+pub enum PolicyError {
+    #[msg("Transfer exceeds the policy limit")]
+    AmountOverLimit,
+}
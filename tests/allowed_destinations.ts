@@ -0,0 +1,147 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  createApproveInstruction,
+  createAssociatedTokenAccount,
+  createMint,
+  createTransferInstruction,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  governed,
+  MEMO_PROGRAM_ID,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL, TransactionInstruction } = anchor.web3;
+
+describe("transfer destination allowlist", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate()];
+  const [member1] = members;
+  const treasury = Keypair.generate();
+  const stranger = Keypair.generate();
+
+  const DELAY = 5;
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+  let mint: PublicKey;
+  let vaultAta: PublicKey;
+
+  const run = async (ix: anchor.web3.TransactionInstruction) => {
+    const proposal = await propose(program, multisigPda, member1, ix);
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    return execute(program, multisigPda, proposal);
+  };
+
+  const sendSol = (to: PublicKey, lamports = LAMPORTS_PER_SOL / 10) =>
+    SystemProgram.transfer({ fromPubkey: vaultPda, toPubkey: to, lamports });
+
+  const setAllowed = async (destinations: PublicKey[], delaySeconds = DELAY) =>
+    governed(
+      program,
+      multisigPda,
+      members,
+      await program.methods
+        .setAllowedDestinations(destinations, delaySeconds)
+        .accounts({ multisig: multisigPda, authority: multisigPda })
+        .instruction()
+    );
+
+  const applyAllowed = () =>
+    program.methods.applyAllowedDestinations().accounts({ multisig: multisigPda }).rpc();
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vaultPda, 10);
+
+    mint = await createMint(provider.connection, member1, member1.publicKey, null, 6);
+    vaultAta = await createAssociatedTokenAccount(provider.connection, member1, mint, vaultPda, undefined, undefined, undefined, true);
+    await mintTo(provider.connection, member1, mint, vaultAta, member1, 10_000);
+
+    // 列表为空时启用立即生效
+    await setAllowed([treasury.publicKey]);
+  });
+
+  it("Rejects opaque instructions signed by the vault, whatever they do", async () => {
+    // 去向无法识别即拒绝：无论是别的程序、补齐字节的转账，还是 token 委托
+    const opaque = new TransactionInstruction({
+      programId: MEMO_PROGRAM_ID,
+      keys: [{ pubkey: vaultPda, isSigner: true, isWritable: false }],
+      data: Buffer.from("looks harmless"),
+    });
+    await expectError(propose(program, multisigPda, member1, opaque), "DestinationNotAllowed");
+
+    const padded = sendSol(treasury.publicKey);
+    padded.data = Buffer.concat([padded.data, Buffer.alloc(4)]);
+    await expectError(propose(program, multisigPda, member1, padded), "DestinationNotAllowed");
+
+    const delegate = createApproveInstruction(vaultAta, stranger.publicKey, vaultPda, 1_000);
+    await expectError(propose(program, multisigPda, member1, delegate), "DestinationNotAllowed");
+  });
+
+  it("Rejects SOL transfers to unlisted addresses at propose time", async () => {
+    await expectError(propose(program, multisigPda, member1, sendSol(stranger.publicKey)), "DestinationNotAllowed");
+    await run(sendSol(treasury.publicKey));
+    assert.equal(await provider.connection.getBalance(treasury.publicKey), LAMPORTS_PER_SOL / 10);
+  });
+
+  it("Checks the token account owner at execute time", async () => {
+    const treasuryAta = await createAssociatedTokenAccount(provider.connection, member1, mint, treasury.publicKey);
+    await run(createTransferInstruction(vaultAta, treasuryAta, vaultPda, 500));
+    assert.equal((await getAccount(provider.connection, treasuryAta)).amount, 500n);
+
+    const strangerAta = await createAssociatedTokenAccount(provider.connection, member1, mint, stranger.publicKey);
+    await expectError(run(createTransferInstruction(vaultAta, strangerAta, vaultPda, 500)), "DestinationNotAllowed");
+    assert.equal((await getAccount(provider.connection, strangerAta)).amount, 0n);
+  });
+
+  it("Delays additions until the configured period has passed", async () => {
+    await setAllowed([treasury.publicKey, stranger.publicKey]);
+    await expectError(applyAllowed(), "AllowlistChangeNotReady");
+    await expectError(propose(program, multisigPda, member1, sendSol(stranger.publicKey)), "DestinationNotAllowed");
+
+    await sleep((DELAY + 1) * 1000);
+    await applyAllowed();
+    await expectError(applyAllowed(), "NoPendingAllowlistChange");
+    await run(sendSol(stranger.publicKey));
+    assert.equal(await provider.connection.getBalance(stranger.publicKey), LAMPORTS_PER_SOL / 10);
+  });
+
+  it("Delays removals too, and clearing the list lifts the restriction", async () => {
+    await setAllowed([]);
+    const account = await program.account.multisig.fetch(multisigPda);
+    assert.equal(account.allowedDestinations.length, 2);
+
+    await sleep((DELAY + 1) * 1000);
+    await applyAllowed();
+    assert.isEmpty((await program.account.multisig.fetch(multisigPda)).allowedDestinations);
+    await propose(program, multisigPda, member1, sendSol(Keypair.generate().publicKey));
+  });
+});
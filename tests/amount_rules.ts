@@ -0,0 +1,107 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL, TransactionInstruction } =
+  anchor.web3;
+
+describe("amount-based thresholds", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2, member3] = members;
+  const receiver = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  const approveBy = async (proposal: PublicKey, signers: typeof members) => {
+    for (const s of signers) {
+      await approve(program, multisigPda, proposal, s);
+    }
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 3, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vaultPda, 2);
+
+    // 0.5 SOL 以内的 SOL 转账只需 1 个批准
+    const ix = await program.methods
+      .setAmountRules([
+        { mint: null, maxAmount: new anchor.BN(LAMPORTS_PER_SOL / 2), threshold: 1 },
+      ])
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    const proposal = await propose(program, multisigPda, member1, ix);
+    await approveBy(proposal, members);
+    await execute(program, multisigPda, proposal);
+  });
+
+  it("Executes a 0.1 SOL transfer at the reduced threshold", async () => {
+    const ix = SystemProgram.transfer({
+      fromPubkey: vaultPda,
+      toPubkey: receiver.publicKey,
+      lamports: LAMPORTS_PER_SOL / 10,
+    });
+    const proposal = await propose(program, multisigPda, member1, ix);
+    await approveBy(proposal, [member1]);
+    await execute(program, multisigPda, proposal);
+
+    const balance = await provider.connection.getBalance(receiver.publicKey);
+    assert.equal(balance, LAMPORTS_PER_SOL / 10);
+  });
+
+  it("Requires the full threshold above the rule's maximum", async () => {
+    const ix = SystemProgram.transfer({
+      fromPubkey: vaultPda,
+      toPubkey: receiver.publicKey,
+      lamports: LAMPORTS_PER_SOL,
+    });
+    const proposal = await propose(program, multisigPda, member1, ix);
+    await approveBy(proposal, [member1, member2]);
+    await expectError(execute(program, multisigPda, proposal), "NotExecutable");
+
+    await approveBy(proposal, [member3]);
+    await execute(program, multisigPda, proposal);
+  });
+
+  it("Treats a non-canonical transfer encoding as unrecognized", async () => {
+    // 额外尾随字节：系统程序仍会执行转账，但解码器不认，必须全阈值
+    const transfer = SystemProgram.transfer({
+      fromPubkey: vaultPda,
+      toPubkey: receiver.publicKey,
+      lamports: LAMPORTS_PER_SOL / 10,
+    });
+    const disguised = new TransactionInstruction({
+      programId: transfer.programId,
+      keys: transfer.keys,
+      data: Buffer.concat([transfer.data, Buffer.alloc(8, 0xff)]),
+    });
+    const proposal = await propose(program, multisigPda, member1, disguised);
+    await approveBy(proposal, [member1]);
+    await expectError(execute(program, multisigPda, proposal), "NotExecutable");
+  });
+});
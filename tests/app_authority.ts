@@ -0,0 +1,98 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import { CpiCaller } from "../target/types/cpi_caller";
+import {
+  airdrop,
+  approve,
+  executeAccounts,
+  expectError,
+  findAppAuthorityPda,
+  findMultisigPda,
+  findVaultPda,
+  governed,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("app authorities", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+  const caller = anchor.workspace.cpiCaller as Program<CpiCaller>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const appId = Array.from(Buffer.from("fixture-app-0001"));
+
+  let multisigPda: PublicKey;
+  let authority: PublicKey;
+  const [appConfig] = PublicKey.findProgramAddressSync([Buffer.from("app_config")], caller.programId);
+
+  // 管理员调用中 authority 标记为签名者，执行时须作为普通账户传入
+  const proposeSetValue = async (value: number) => {
+    const ix = await caller.methods
+      .setAppValue(new anchor.BN(value))
+      .accounts({ config: appConfig, admin: authority })
+      .instruction();
+    return propose(program, multisigPda, member1, ix);
+  };
+
+  const executeWithAuthority = async (proposal: PublicKey) => {
+    const account = await program.account.proposal.fetch(proposal);
+    const [vault] = findVaultPda(program.programId, multisigPda);
+    return program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal, spendLedger: null })
+      .remainingAccounts(executeAccounts(account.action.raw[0], [multisigPda, vault, authority]))
+      .rpc();
+  };
+
+  const registry = (name: "registerAppAuthority" | "unregisterAppAuthority") =>
+    program.methods[name](appId).accounts({ multisig: multisigPda }).instruction();
+
+  before(async () => {
+    await airdrop(provider, member1.publicKey);
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [authority] = findAppAuthorityPda(program.programId, multisigPda, appId);
+    await program.methods
+      .createMultisig(0, [member1.publicKey, member2.publicKey], 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await caller.methods.initAppConfig(authority).accounts({ config: appConfig }).rpc();
+  });
+
+  it("Never signs for an unregistered app authority", async () => {
+    const proposal = await proposeSetValue(7);
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    await expectError(executeWithAuthority(proposal), "unauthorized signer");
+    assert.equal((await caller.account.appConfig.fetch(appConfig)).value.toNumber(), 0);
+  });
+
+  it("Signs an admin call once the app is registered", async () => {
+    await governed(program, multisigPda, [member1, member2], await registry("registerAppAuthority"));
+    const account = await program.account.multisig.fetch(multisigPda);
+    assert.equal(account.proposalsCount, 2);
+
+    const proposal = await proposeSetValue(42);
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    await executeWithAuthority(proposal);
+    assert.equal((await caller.account.appConfig.fetch(appConfig)).value.toNumber(), 42);
+  });
+
+  it("Stops signing after the app is unregistered", async () => {
+    await governed(program, multisigPda, [member1, member2], await registry("unregisterAppAuthority"));
+
+    const proposal = await proposeSetValue(99);
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    await expectError(executeWithAuthority(proposal), "unauthorized signer");
+    assert.equal((await caller.account.appConfig.fetch(appConfig)).value.toNumber(), 42);
+  });
+});
@@ -0,0 +1,112 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  governed,
+  inviteAndAccept,
+  memoInstruction,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("approval groups", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = Array.from({ length: 5 }, () => Keypair.generate());
+  const [member1, member2, member3, member4] = members;
+  const finance = [member1, member2];
+  const engineering = [member3, member4];
+
+  let multisigPda: PublicKey;
+
+  const groups = (specs: [anchor.web3.Keypair[], number][]) =>
+    specs.map(([keys, minApprovals]) => ({ members: keys.map((k) => k.publicKey), minApprovals }));
+
+  const setGroups = async (specs: [anchor.web3.Keypair[], number][]) =>
+    program.methods
+      .setApprovalGroups(groups(specs))
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+
+  // 按当前成员列表把位图还原为 key
+  const groupKeys = async () => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    return account.approvalGroups.map((g) =>
+      account.members.filter((_, i) => (g.members >>> i) & 1).map((m) => m.key.toBase58()).sort()
+    );
+  };
+  const keys = (kps: anchor.web3.Keypair[]) => kps.map((k) => k.publicKey.toBase58()).sort();
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Validates group definitions", async () => {
+    const approvers = [member1, member2];
+    await expectError(governed(program, multisigPda, approvers, await setGroups([[finance, 3]])), "InvalidApprovalGroup");
+    await expectError(governed(program, multisigPda, approvers, await setGroups([[finance, 0]])), "InvalidApprovalGroup");
+    await expectError(
+      governed(program, multisigPda, approvers, await setGroups([[[member1, Keypair.generate()], 1]])),
+      "NotMember"
+    );
+    await expectError(
+      governed(program, multisigPda, approvers, await setGroups(Array(5).fill([finance, 1]))),
+      "TooManyApprovalGroups"
+    );
+
+    await governed(program, multisigPda, approvers, await setGroups([[finance, 1], [engineering, 1]]));
+    assert.deepEqual(await groupKeys(), [keys(finance), keys(engineering)]);
+  });
+
+  it("Requires one approval from each group regardless of the raw count", async () => {
+    const proposal = await propose(program, multisigPda, member1, memoInstruction("quarterly budget", [multisigPda]));
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    await approve(program, multisigPda, proposal, members[4]);
+    await expectError(execute(program, multisigPda, proposal), "GroupApprovalsNotMet");
+
+    await approve(program, multisigPda, proposal, member3);
+    await execute(program, multisigPda, proposal);
+  });
+
+  it("Keeps groups tied to keys when member indices shift", async () => {
+    // 新成员排在最前，其余成员的下标整体后移
+    let newcomer = Keypair.generate();
+    const first = members.map((m) => m.publicKey.toBuffer()).sort(Buffer.compare)[0];
+    while (Buffer.compare(newcomer.publicKey.toBuffer(), first) >= 0) {
+      newcomer = Keypair.generate();
+    }
+    await airdrop(provider, newcomer.publicKey);
+    await inviteAndAccept(program, multisigPda, [member1, member3], newcomer);
+    assert.deepEqual(await groupKeys(), [keys(finance), keys(engineering)]);
+
+    const remove = async (member: anchor.web3.Keypair) =>
+      program.methods
+        .removeMember(member.publicKey)
+        .accounts({ multisig: multisigPda, authority: multisigPda })
+        .instruction();
+    await governed(program, multisigPda, [member1, member3], await remove(member4));
+    assert.deepEqual(await groupKeys(), [keys(finance), keys([member3])]);
+
+    // 移除后 engineering 组为空，最低批准数不可达
+    await expectError(governed(program, multisigPda, [member1, member3], await remove(member3)), "InvalidApprovalGroup");
+  });
+});
@@ -0,0 +1,72 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { createHash } from "crypto";
+import { Multisig } from "../target/types/multisig";
+import { airdrop, expectError, findMultisigPda, memoInstruction, propose } from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("approval bound to the action hash", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2, member3] = members;
+
+  let multisigPda: PublicKey;
+  let proposal: PublicKey;
+  let lookalike: PublicKey;
+
+  const actionHash = async (address: PublicKey) => {
+    const { action } = await program.account.proposal.fetch(address);
+    const encoded = program.coder.types.encode("proposalAction", action);
+    return Array.from(createHash("sha256").update(encoded).digest());
+  };
+
+  const approveWith = (target: PublicKey, approver: anchor.web3.Keypair, hash: number[] | null) =>
+    program.methods
+      .approveTransaction(hash)
+      .accounts({ multisig: multisigPda, proposal: target, approver: approver.publicKey })
+      .signers([approver])
+      .rpc();
+
+  const bitOf = async (key: PublicKey) => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    return 1 << account.members.findIndex((m: any) => m.key.equals(key));
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 3, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    proposal = await propose(program, multisigPda, member1, memoInstruction("pay invoice 41", []));
+    lookalike = await propose(program, multisigPda, member1, memoInstruction("pay invoice 14", []));
+  });
+
+  it("Approves when the reviewed hash matches", async () => {
+    await approveWith(proposal, member2, await actionHash(proposal));
+    const { approvals } = await program.account.proposal.fetch(proposal);
+    assert.equal(approvals, await bitOf(member2.publicKey));
+  });
+
+  it("Rejects a lookalike proposal with different content", async () => {
+    const reviewed = await actionHash(proposal);
+    await expectError(approveWith(lookalike, member2, reviewed), "InstructionHashMismatch");
+    assert.equal((await program.account.proposal.fetch(lookalike)).approvals, 0);
+  });
+
+  it("Skips the check when no hash is given", async () => {
+    await approveWith(lookalike, member3, null);
+    const { approvals } = await program.account.proposal.fetch(lookalike);
+    assert.equal(approvals, await bitOf(member3.publicKey));
+  });
+});
@@ -0,0 +1,82 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import { airdrop, approve, findMultisigPda, memoInstruction, propose, sleep } from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("approval latency events", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const member3 = Keypair.generate();
+
+  let multisigPda: PublicKey;
+
+  // 收集 run 期间发出的 ApprovalAdded 与 ProposalReady
+  const eventsOf = async (run: () => Promise<unknown>) => {
+    const added: any[] = [];
+    const ready: any[] = [];
+    const listeners = [
+      program.addEventListener("approvalAdded", (e) => added.push(e)),
+      program.addEventListener("proposalReady", (e) => ready.push(e)),
+    ];
+    await run();
+    await sleep(1000);
+    for (const id of listeners) {
+      await program.removeEventListener(id);
+    }
+    return { added, ready };
+  };
+
+  before(async () => {
+    await airdrop(provider, member1.publicKey);
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, [member1.publicKey, member2.publicKey, member3.publicKey], 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Reports seconds since creation and time to ready", async () => {
+    const proposal = await propose(program, multisigPda, member1, memoInstruction("latency", [multisigPda]));
+    const { createdAt, index } = await program.account.proposal.fetch(proposal);
+
+    await sleep(2000);
+    const first = await eventsOf(() => approve(program, multisigPda, proposal, member1));
+    assert.lengthOf(first.added, 1);
+    assert.lengthOf(first.ready, 0);
+    const added = first.added[0];
+    assert.ok(added.proposal.equals(proposal));
+    assert.ok(added.member.equals(member1.publicKey));
+    assert.equal(added.approvals, 1);
+    assert.equal(
+      added.secondsSinceCreation.toNumber(),
+      added.timestamp.toNumber() - createdAt.toNumber()
+    );
+    assert.isAtLeast(added.secondsSinceCreation.toNumber(), 1);
+
+    await sleep(2000);
+    const second = await eventsOf(() => approve(program, multisigPda, proposal, member2));
+    assert.lengthOf(second.ready, 1);
+    const ready = second.ready[0];
+    assert.equal(ready.index, index);
+    assert.equal(ready.approvals, 2);
+    assert.equal(ready.timeToReady.toNumber(), second.added[0].secondsSinceCreation.toNumber());
+    assert.equal(
+      (await program.account.proposal.fetch(proposal)).readyAt.toNumber(),
+      ready.timestamp.toNumber()
+    );
+
+    // 已就绪后的批准只发出 ApprovalAdded
+    const third = await eventsOf(() => approve(program, multisigPda, proposal, member3));
+    assert.lengthOf(third.added, 1);
+    assert.lengthOf(third.ready, 0);
+  });
+});
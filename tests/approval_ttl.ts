@@ -0,0 +1,90 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  governed,
+  memoInstruction,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("approval TTL", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2] = members;
+  const TTL = 4;
+
+  let multisigPda: PublicKey;
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await governed(
+      program,
+      multisigPda,
+      members.slice(0, 2),
+      await program.methods
+        .setApprovalTtl(TTL)
+        .accounts({ multisig: multisigPda, authority: multisigPda })
+        .instruction()
+    );
+  });
+
+  it("Still rejects a duplicate approval while it is fresh", async () => {
+    const proposal = await propose(program, multisigPda, member1, memoInstruction("fresh", []));
+    await approve(program, multisigPda, proposal, member1);
+    await expectError(approve(program, multisigPda, proposal, member1), "AlreadyApproved");
+  });
+
+  it("Drops an expired approval from the count until it is re-confirmed", async () => {
+    const proposal = await propose(program, multisigPda, member1, memoInstruction("stale", []));
+    await approve(program, multisigPda, proposal, member1);
+    // 本地验证器无法调整时钟，等待真实时间让 member1 的批准过期
+    await sleep((TTL + 1) * 1000);
+
+    await approve(program, multisigPda, proposal, member2);
+    assert.isNull((await program.account.proposal.fetch(proposal)).readyAt);
+    await expectError(execute(program, multisigPda, proposal), "NotExecutable");
+
+    // 重新批准刷新时间而不是报 AlreadyApproved
+    await approve(program, multisigPda, proposal, member1);
+    const account = await program.account.proposal.fetch(proposal);
+    assert.isNotNull(account.readyAt);
+    assert.equal(account.approvedAt.filter((t) => t.toNumber() > 0).length, 2);
+    await execute(program, multisigPda, proposal);
+  });
+
+  it("Resets readiness when approvals age out after the threshold was met", async () => {
+    const proposal = await propose(program, multisigPda, member1, memoInstruction("lapsed", []));
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    const firstReady = (await program.account.proposal.fetch(proposal)).readyAt.toNumber();
+
+    await sleep((TTL + 1) * 1000);
+    await expectError(execute(program, multisigPda, proposal), "NotExecutable");
+
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    assert.isAbove((await program.account.proposal.fetch(proposal)).readyAt.toNumber(), firstReady);
+    await execute(program, multisigPda, proposal);
+  });
+});
@@ -0,0 +1,133 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createAccount,
+  createAssociatedTokenAccount,
+  createMint,
+  mintTo,
+} from "@solana/spl-token";
+import { createHash } from "crypto";
+import { Multisig } from "../target/types/multisig";
+import { airdrop, expectError, findMultisigPda, findVaultPda, sleep } from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("vault balance snapshots", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const outsider = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+  let snapshotPda: PublicKey;
+  let usdc: PublicKey;
+  let usdcAta: PublicKey;
+  let usdcSecond: PublicKey;
+  let token22: PublicKey;
+  let token22Ata: PublicKey;
+
+  const readonly = (pubkey: PublicKey) => ({ pubkey, isSigner: false, isWritable: false });
+
+  const snapshot = (accounts: PublicKey[], retain = false, payer: anchor.web3.Keypair | null = null) => {
+    const builder = program.methods
+      .snapshotBalances(0, retain)
+      .accounts({
+        multisig: multisigPda,
+        snapshot: retain ? snapshotPda : null,
+        payer: payer?.publicKey ?? null,
+      })
+      .remainingAccounts(accounts.map(readonly));
+    return payer ? builder.signers([payer]).rpc() : builder.rpc();
+  };
+
+  const eventOf = async (run: () => Promise<unknown>) => {
+    const events: any[] = [];
+    const id = program.addEventListener("balanceSnapshot", (e) => events.push(e));
+    await run();
+    await sleep(1000);
+    await program.removeEventListener(id);
+    assert.lengthOf(events, 1);
+    return events[0];
+  };
+
+  before(async () => {
+    for (const k of [member1, outsider]) {
+      await airdrop(provider, k.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    [snapshotPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("snapshot"), multisigPda.toBuffer(), Buffer.from([0])],
+      program.programId
+    );
+    await program.methods
+      .createMultisig(0, [member1.publicKey, member2.publicKey], 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vaultPda, 1);
+
+    // 同一 mint 下两个金库账户（ATA 与辅助账户），另有一个 Token-2022 mint
+    usdc = await createMint(provider.connection, member1, member1.publicKey, null, 6);
+    usdcAta = await createAssociatedTokenAccount(provider.connection, member1, usdc, vaultPda, undefined, TOKEN_PROGRAM_ID, undefined, true);
+    usdcSecond = await createAccount(provider.connection, member1, usdc, vaultPda, Keypair.generate());
+    await mintTo(provider.connection, member1, usdc, usdcAta, member1, 700);
+    await mintTo(provider.connection, member1, usdc, usdcSecond, member1, 300);
+    token22 = await createMint(provider.connection, member1, member1.publicKey, null, 0, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+    token22Ata = await createAssociatedTokenAccount(provider.connection, member1, token22, vaultPda, undefined, TOKEN_2022_PROGRAM_ID, undefined, true);
+    await mintTo(provider.connection, member1, token22, token22Ata, member1, 5, [], undefined, TOKEN_2022_PROGRAM_ID);
+  });
+
+  it("Emits lamports and per-mint totals for the vault's token accounts", async () => {
+    const event = await eventOf(() => snapshot([usdcAta, token22Ata, usdcSecond]));
+    assert.ok(event.vault.equals(vaultPda));
+    assert.equal(event.lamports.toNumber(), await provider.connection.getBalance(vaultPda));
+    assert.isFalse(event.retained);
+
+    const byMint = new Map(event.balances.map((b: any) => [b.mint.toBase58(), b]));
+    assert.equal((byMint.get(usdc.toBase58()) as any).amount.toNumber(), 1000);
+    assert.equal((byMint.get(usdc.toBase58()) as any).accounts, 2);
+    assert.equal((byMint.get(token22.toBase58()) as any).amount.toNumber(), 5);
+    const mints = event.balances.map((b: any) => b.mint.toBuffer());
+    assert.deepEqual(mints, [...mints].sort(Buffer.compare));
+    assert.isNull(await provider.connection.getAccountInfo(snapshotPda));
+  });
+
+  it("Rejects token accounts the vault does not own, non-token accounts and repeats", async () => {
+    const foreign = await createAccount(provider.connection, outsider, usdc, outsider.publicKey, Keypair.generate());
+    await mintTo(provider.connection, member1, usdc, foreign, member1, 1_000_000);
+    await expectError(snapshot([usdcAta, foreign]), "InvalidSnapshotAccount");
+    // mint 账户与金库本身都不是 token 账户
+    await expectError(snapshot([usdc]), "InvalidSnapshotAccount");
+    await expectError(snapshot([vaultPda]), "InvalidSnapshotAccount");
+    await expectError(snapshot([usdcAta, usdcAta]), "InvalidSnapshotAccount");
+  });
+
+  it("Retains a digest of the snapshot when a member asks for it", async () => {
+    await expectError(snapshot([usdcAta], true, outsider), "NotMember");
+
+    const event = await eventOf(() => snapshot([usdcAta, token22Ata], true, member1));
+    assert.isTrue(event.retained);
+    const record = await program.account.balanceSnapshotRecord.fetch(snapshotPda);
+    assert.equal(record.slot.toNumber(), event.slot.toNumber());
+    assert.equal(record.lamports.toNumber(), event.lamports.toNumber());
+    assert.equal(record.mints, 2);
+    const encoded = Buffer.concat([
+      Buffer.from(new Uint32Array([event.balances.length]).buffer),
+      ...event.balances.map((b: any) => program.coder.types.encode("mintBalance", b)),
+    ]);
+    assert.deepEqual(record.digest, Array.from(createHash("sha256").update(encoded).digest()));
+
+    // 再次保留时覆盖上一次
+    await snapshot([usdcAta], true, member1);
+    assert.equal((await program.account.balanceSnapshotRecord.fetch(snapshotPda)).mints, 1);
+  });
+});
@@ -0,0 +1,68 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import { airdrop, expectError, findMultisigPda, memoInstruction, propose } from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("bootstrap window", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const creator = Keypair.generate();
+  const member2 = Keypair.generate();
+  const typo = Keypair.generate();
+
+  let multisigPda: PublicKey;
+
+  before(async () => {
+    await airdrop(provider, creator.publicKey);
+    [multisigPda] = findMultisigPda(program.programId, creator.publicKey, 0);
+    // 误把 typo 当作第二个成员
+    await program.methods
+      .createMultisig(0, [creator.publicKey, typo.publicKey], 2, 10)
+      .accounts({ multisig: multisigPda, creator: creator.publicKey })
+      .signers([creator])
+      .rpc();
+  });
+
+  it("Lets the creator fix members before the first proposal", async () => {
+    const fixed = [creator.publicKey, member2.publicKey];
+    await program.methods
+      .updateConfigBootstrap(fixed, 1)
+      .accounts({ multisig: multisigPda, creator: creator.publicKey })
+      .signers([creator])
+      .rpc();
+
+    const account = await program.account.multisig.fetch(multisigPda);
+    const keys = account.members.map((m) => m.key.toBase58());
+    assert.sameMembers(keys, fixed.map((k) => k.toBase58()));
+    assert.equal(account.threshold, 1);
+  });
+
+  it("Applies the same validation as create_multisig", async () => {
+    await expectError(
+      program.methods
+        .updateConfigBootstrap([creator.publicKey], 2)
+        .accounts({ multisig: multisigPda, creator: creator.publicKey })
+        .signers([creator])
+        .rpc(),
+      "InvalidThreshold"
+    );
+  });
+
+  it("Closes the window once a proposal exists", async () => {
+    await propose(program, multisigPda, creator, memoInstruction("go live", [multisigPda]));
+    await expectError(
+      program.methods
+        .updateConfigBootstrap([creator.publicKey, typo.publicKey], 2)
+        .accounts({ multisig: multisigPda, creator: creator.publicKey })
+        .signers([creator])
+        .rpc(),
+      "BootstrapClosed"
+    );
+  });
+});
@@ -0,0 +1,163 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findBufferPda,
+  findMultisigPda,
+  memoInstruction,
+  proposeBuffered,
+  uploadBuffer,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("instruction buffers", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+
+  let multisigPda: PublicKey;
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Executes an instruction larger than the proposal account can hold", async () => {
+    // 1500 字节的 memo 放不进提案的 action 字段
+    const ix = memoInstruction("x".repeat(1500), [multisigPda]);
+    const { buffer, hash } = await uploadBuffer(program, multisigPda, member1, 0, Buffer.from(ix.data));
+
+    const proposal = await proposeBuffered(program, multisigPda, member1, ix, buffer, hash);
+    let account = await program.account.instructionBuffer.fetch(buffer);
+    assert.isTrue(account.refs[0].equals(proposal));
+
+    // 仍被引用时不能关闭
+    await expectError(
+      program.methods
+        .closeBuffer()
+        .accounts({ buffer, creator: member1.publicKey, closer: member1.publicKey })
+        .signers([member1])
+        .rpc(),
+      "BufferInUse"
+    );
+
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    await execute(program, multisigPda, proposal);
+
+    account = await program.account.instructionBuffer.fetch(buffer);
+    assert.equal(account.refs.length, 0);
+
+    await program.methods
+      .closeBuffer()
+      .accounts({ buffer, creator: member1.publicKey, closer: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    assert.isNull(await provider.connection.getAccountInfo(buffer));
+  });
+
+  it("Rejects writes after finalization", async () => {
+    const { buffer } = await uploadBuffer(program, multisigPda, member1, 1, Buffer.from("hello"));
+    await expectError(
+      program.methods
+        .writeBuffer(0, Buffer.from("HELLO"))
+        .accounts({ buffer, creator: member1.publicKey })
+        .signers([member1])
+        .rpc(),
+      "BufferFinalized"
+    );
+  });
+
+  it("Rejects a proposal whose hash does not match the buffer", async () => {
+    const ix = memoInstruction("buffered memo", [multisigPda]);
+    const { buffer } = await uploadBuffer(program, multisigPda, member1, 2, Buffer.from(ix.data));
+    await expectError(
+      proposeBuffered(program, multisigPda, member1, ix, buffer, new Array(32).fill(0)),
+      "BufferHashMismatch"
+    );
+  });
+
+  it("Releases the reference of a cancelled proposal", async () => {
+    const ix = memoInstruction("cancelled memo", [multisigPda]);
+    const { buffer, hash } = await uploadBuffer(program, multisigPda, member2, 0, Buffer.from(ix.data));
+    const proposal = await proposeBuffered(program, multisigPda, member2, ix, buffer, hash);
+
+    await program.methods
+      .cancelTransaction(0, null)
+      .accounts({ multisig: multisigPda, proposal, canceller: member2.publicKey })
+      .signers([member2])
+      .rpc();
+
+    // 任何人都可以解除已关闭提案的引用
+    await program.methods.releaseBuffer().accounts({ buffer, proposal }).rpc();
+    await program.methods
+      .closeBuffer()
+      .accounts({ buffer, creator: member2.publicKey, closer: member2.publicKey })
+      .signers([member2])
+      .rpc();
+  });
+
+  it("Lets the creator abandon an unfinished upload", async () => {
+    const [buffer] = findBufferPda(program.programId, multisigPda, member1.publicKey, 3);
+    await program.methods
+      .createBuffer(3, 2000)
+      .accounts({ multisig: multisigPda, buffer, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await program.methods
+      .writeBuffer(0, Buffer.alloc(500, 1))
+      .accounts({ buffer, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+
+    const rent = await provider.connection.getBalance(buffer);
+    const before = await provider.connection.getBalance(member1.publicKey);
+
+    // 未过期前其他人不能关闭
+    await expectError(
+      program.methods
+        .closeBuffer()
+        .accounts({ buffer, creator: member1.publicKey, closer: member2.publicKey })
+        .signers([member2])
+        .rpc(),
+      "BufferNotExpired"
+    );
+
+    // 手续费由 provider 钱包支付，租金全额退还给创建者
+    await program.methods
+      .closeBuffer()
+      .accounts({ buffer, creator: member1.publicKey, closer: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    assert.isNull(await provider.connection.getAccountInfo(buffer));
+    assert.equal(await provider.connection.getBalance(member1.publicKey), before + rent);
+  });
+
+  it("Only lets members create buffers", async () => {
+    const outsider = Keypair.generate();
+    await airdrop(provider, outsider.publicKey);
+    await expectError(
+      uploadBuffer(program, multisigPda, outsider, 0, Buffer.from("nope")),
+      "NotMember"
+    );
+  });
+});
@@ -0,0 +1,113 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccount,
+  createMint,
+  getAccount,
+  getMint,
+  mintTo,
+} from "@solana/spl-token";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  expectError,
+  findMultisigPda,
+  findProposalPda,
+  findVaultPda,
+  proposalOptions,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("burn proposals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  // 创建 mint 与金库 ATA 并铸入 1000
+  const fundVault = async (programId: PublicKey) => {
+    const mint = await createMint(provider.connection, member1, member1.publicKey, null, 6, undefined, undefined, programId);
+    const ata = await createAssociatedTokenAccount(provider.connection, member1, mint, vaultPda, undefined, programId, undefined, true);
+    await mintTo(provider.connection, member1, mint, ata, member1, 1_000, [], undefined, programId);
+    return { mint, ata };
+  };
+
+  const proposeBurn = async (mint: PublicKey, amount: number) => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    const [proposal] = findProposalPda(program.programId, multisigPda, account.proposalsCount);
+    await program.methods
+      .proposeBurn(mint, new anchor.BN(amount), proposalOptions())
+      .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    return proposal;
+  };
+
+  const execute = (proposal: PublicKey, mint: PublicKey, ata: PublicKey, programId: PublicKey) =>
+    program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts([
+        { pubkey: ata, isSigner: false, isWritable: true },
+        { pubkey: mint, isSigner: false, isWritable: true },
+        { pubkey: vaultPda, isSigner: false, isWritable: false },
+        { pubkey: programId, isSigner: false, isWritable: false },
+      ])
+      .rpc();
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Burns part of the vault balance", async () => {
+    const { mint, ata } = await fundVault(TOKEN_PROGRAM_ID);
+    const proposal = await proposeBurn(mint, 400);
+    await execute(proposal, mint, ata, TOKEN_PROGRAM_ID);
+
+    assert.equal((await getAccount(provider.connection, ata)).amount, 600n);
+    assert.equal((await getMint(provider.connection, mint)).supply, 600n);
+  });
+
+  it("Fails before the CPI when burning more than the balance", async () => {
+    await expectError(proposeBurn(Keypair.generate().publicKey, 0), "InvalidBurnAmount");
+
+    const { mint, ata } = await fundVault(TOKEN_PROGRAM_ID);
+    const proposal = await proposeBurn(mint, 1_001);
+    await expectError(execute(proposal, mint, ata, TOKEN_PROGRAM_ID), "InsufficientBalance");
+    assert.equal((await getAccount(provider.connection, ata)).amount, 1_000n);
+  });
+
+  it("Supports Token-2022 mints", async () => {
+    const { mint, ata } = await fundVault(TOKEN_2022_PROGRAM_ID);
+    const proposal = await proposeBurn(mint, 1_000);
+    // ATA 须按 mint 所属的 token 程序推导
+    await expectError(execute(proposal, mint, ata, TOKEN_PROGRAM_ID), "AccountMismatch");
+    await execute(proposal, mint, ata, TOKEN_2022_PROGRAM_ID);
+
+    assert.equal((await getAccount(provider.connection, ata, undefined, TOKEN_2022_PROGRAM_ID)).amount, 0n);
+  });
+});
@@ -0,0 +1,95 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  expectError,
+  findMultisigPda,
+  governed,
+  memoInstruction,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("cancel_all", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const guardian = Keypair.generate();
+  const members = [member1, member2];
+
+  let multisigPda: PublicKey;
+
+  const pairs = (proposals: PublicKey[], proposer: PublicKey) =>
+    proposals.flatMap((pubkey) => [
+      { pubkey, isSigner: false, isWritable: true },
+      { pubkey: proposer, isSigner: false, isWritable: true },
+    ]);
+
+  const cancelAll = (authority: anchor.web3.Keypair, proposals: PublicKey[]) =>
+    program.methods
+      .cancelAll(null)
+      .accounts({ multisig: multisigPda, authority: authority.publicKey })
+      .remainingAccounts(pairs(proposals, member2.publicKey))
+      .signers([authority])
+      .rpc();
+
+  before(async () => {
+    for (const m of [...members, guardian]) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+
+    const ix = await program.methods
+      .setGuardian(guardian.publicKey)
+      .accounts({ multisig: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, members, ix);
+  });
+
+  it("Rejects callers that are not the guardian", async () => {
+    const proposal = await propose(program, multisigPda, member2, memoInstruction("pending", [multisigPda]));
+    await expectError(cancelAll(member1, [proposal]), "Unauthorized");
+  });
+
+  it("Guardian closes pending proposals across transactions and tolerates races", async () => {
+    const proposals: PublicKey[] = [];
+    for (let i = 0; i < 4; i++) {
+      proposals.push(await propose(program, multisigPda, member2, memoInstruction(`p${i}`, [multisigPda])));
+    }
+    const before = await provider.connection.getBalance(member2.publicKey);
+
+    await cancelAll(guardian, proposals.slice(0, 2));
+    // 前两个已关闭，重复传入不报错
+    await cancelAll(guardian, proposals);
+
+    for (const p of proposals) {
+      assert.isNull(await provider.connection.getAccountInfo(p));
+    }
+    assert.isAbove(await provider.connection.getBalance(member2.publicKey), before);
+  });
+
+  it("Rejects a mismatched rent recipient", async () => {
+    const proposal = await propose(program, multisigPda, member2, memoInstruction("other", [multisigPda]));
+    await expectError(
+      program.methods
+        .cancelAll(null)
+        .accounts({ multisig: multisigPda, authority: guardian.publicKey })
+        .remainingAccounts(pairs([proposal], member1.publicKey))
+        .signers([guardian])
+        .rpc(),
+      "AccountMismatch"
+    );
+  });
+});
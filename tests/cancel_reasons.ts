@@ -0,0 +1,138 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { createHash } from "crypto";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  chainTime,
+  expectError,
+  findMultisigPda,
+  governed,
+  memoInstruction,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+// 与程序中的 CANCEL_REASON_* 一致
+const EXPIRED = 1;
+const BULK_CANCELLED = 3;
+const CUSTOM = 16;
+
+describe("cancel reasons", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const guardian = Keypair.generate();
+
+  let multisigPda: PublicKey;
+
+  const memo = (text: string, notAfter: number | null = null) =>
+    propose(program, multisigPda, member1, memoInstruction(text, [multisigPda]), {
+      notAfter: notAfter === null ? null : new anchor.BN(notAfter),
+    });
+
+  const cancel = (proposal: PublicKey, reason: number, note: string | null) =>
+    program.methods
+      .cancelTransaction(reason, note)
+      .accounts({ multisig: multisigPda, proposal, canceller: member1.publicKey })
+      .signers([member1])
+      .rpc();
+
+  // 监听 ProposalCancelled，返回本次操作发出的全部事件
+  const cancelledBy = async (run: () => Promise<unknown>) => {
+    const events: any[] = [];
+    const listener = program.addEventListener("proposalCancelled", (event) => {
+      events.push(event);
+    });
+    await run();
+    await sleep(1000);
+    await program.removeEventListener(listener);
+    return events;
+  };
+
+  const actionHash = async (proposal: PublicKey) => {
+    const { action } = await program.account.proposal.fetch(proposal);
+    const encoded = program.coder.types.encode("proposalAction", action);
+    return createHash("sha256").update(encoded).digest();
+  };
+
+  before(async () => {
+    for (const m of [member1, member2]) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, [member1.publicKey, member2.publicKey], 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    const ix = await program.methods
+      .setGuardian(guardian.publicKey)
+      .accounts({ multisig: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, [member1, member2], ix);
+  });
+
+  it("Rejects reserved codes and oversized notes on manual cancel", async () => {
+    const proposal = await memo("reserved");
+    await expectError(cancel(proposal, EXPIRED, null), "ReservedCancelReason");
+    await expectError(cancel(proposal, CUSTOM, "x".repeat(65)), "InvalidCancelNote");
+  });
+
+  it("Records the canceller, reason, note and action hash on manual cancel", async () => {
+    const proposal = await memo("superseded by v2");
+    const hash = await actionHash(proposal);
+    const [event] = await cancelledBy(() => cancel(proposal, CUSTOM + 1, "superseded by #7"));
+
+    assert.isTrue(event.proposal.equals(proposal));
+    assert.isTrue(event.canceller.equals(member1.publicKey));
+    assert.equal(event.reason, CUSTOM + 1);
+    assert.equal(event.note, "superseded by #7");
+    assert.deepEqual(Buffer.from(event.actionHash), hash);
+  });
+
+  it("Uses the Expired code without a canceller when closing expired proposals", async () => {
+    const notAfter = (await chainTime(provider)) + 4;
+    const proposal = await memo("missed window", notAfter);
+    while ((await chainTime(provider)) <= notAfter) {
+      await sleep(1000);
+    }
+    const [event] = await cancelledBy(() =>
+      program.methods
+        .closeExpired()
+        .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+        .rpc()
+    );
+    assert.equal(event.reason, EXPIRED);
+    assert.isNull(event.canceller);
+    assert.isNull(event.note);
+  });
+
+  it("Emits one BulkCancelled event per proposal for cancel_all", async () => {
+    const proposals = [await memo("veto a"), await memo("veto b")];
+    const events = await cancelledBy(() =>
+      program.methods
+        .cancelAll("guardian veto")
+        .accounts({ multisig: multisigPda, authority: guardian.publicKey })
+        .remainingAccounts(
+          proposals.flatMap((p) => [
+            { pubkey: p, isSigner: false, isWritable: true },
+            { pubkey: member1.publicKey, isSigner: false, isWritable: true },
+          ])
+        )
+        .signers([guardian])
+        .rpc()
+    );
+    assert.equal(events.length, 2);
+    assert.sameMembers(events.map((e) => e.proposal.toBase58()), proposals.map((p) => p.toBase58()));
+    assert.isTrue(events.every((e) => e.reason === BULK_CANCELLED && e.canceller.equals(guardian.publicKey)));
+    assert.isTrue(events.every((e) => e.note === "guardian veto"));
+  });
+});
@@ -0,0 +1,64 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import { airdrop, expectError, findMultisigPda, governed } from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("multisig capacity", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const creator = Keypair.generate();
+  const members = [creator, Keypair.generate(), Keypair.generate()];
+
+  const create = async (nonce: number, keys: anchor.web3.PublicKey[], maxMembers: number) => {
+    const [multisigPda] = findMultisigPda(program.programId, creator.publicKey, nonce);
+    await program.methods
+      .createMultisig(nonce, keys, 2, maxMembers)
+      .accounts({ multisig: multisigPda, creator: creator.publicKey })
+      .signers([creator])
+      .rpc();
+    return multisigPda;
+  };
+
+  before(async () => {
+    await airdrop(provider, creator.publicKey);
+  });
+
+  it("Sizes the account and rent from max_members", async () => {
+    const keys = members.map((m) => m.publicKey);
+    const small = await provider.connection.getAccountInfo(await create(0, keys, 3));
+    const large = await provider.connection.getAccountInfo(await create(1, keys, 10));
+
+    // 每个成员槽 41 字节（key + Option<i64> 到期时间）
+    assert.equal(large.data.length - small.data.length, 41 * 7);
+    const [smallRent, largeRent] = await Promise.all([
+      provider.connection.getMinimumBalanceForRentExemption(small.data.length),
+      provider.connection.getMinimumBalanceForRentExemption(large.data.length),
+    ]);
+    assert.equal(small.lamports, smallRent);
+    assert.equal(large.lamports - small.lamports, largeRent - smallRent);
+  });
+
+  it("Rejects more members than max_members at creation", async () => {
+    const keys = [...members.map((m) => m.publicKey), Keypair.generate().publicKey];
+    await expectError(create(2, keys, 3), "TooManyMembers");
+    await expectError(create(2, keys, 33), "TooManyMembers");
+  });
+
+  it("Enforces the cap on invitations", async () => {
+    const [multisigPda] = findMultisigPda(program.programId, creator.publicKey, 0);
+    const ix = await program.methods
+      .inviteMember(Keypair.generate().publicKey, null, null)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    for (const m of members.slice(1)) {
+      await airdrop(provider, m.publicKey);
+    }
+    await expectError(governed(program, multisigPda, members.slice(0, 2), ix), "TooManyMembers");
+  });
+});
@@ -0,0 +1,89 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findCommentPda,
+  findMultisigPda,
+  memoInstruction,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("proposal comments", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const outsider = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let proposal: PublicKey;
+
+  const post = async (author: anchor.web3.Keypair, text: string) => {
+    const account = await program.account.proposal.fetch(proposal);
+    const [comment] = findCommentPda(program.programId, proposal, author.publicKey, account.commentsCount);
+    await program.methods
+      .postComment(text)
+      .accounts({ multisig: multisigPda, proposal, comment, author: author.publicKey })
+      .signers([author])
+      .rpc();
+    return comment;
+  };
+
+  const close = (comment: PublicKey, author: PublicKey) =>
+    program.methods.closeComment().accounts({ comment, proposal, author }).rpc();
+
+  before(async () => {
+    for (const m of [member1, member2, outsider]) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, [member1.publicKey, member2.publicKey], 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    proposal = await propose(program, multisigPda, member1, memoInstruction("invoice", [multisigPda]));
+  });
+
+  it("Members post comments and events carry the text", async () => {
+    let seen: any = null;
+    const listener = program.addEventListener("commentPosted", (event) => {
+      seen = event;
+    });
+    const comment = await post(member2, "rejecting until invoice attached");
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.removeEventListener(listener);
+
+    const account = await program.account.comment.fetch(comment);
+    assert.equal(account.text, "rejecting until invoice attached");
+    assert.isTrue(account.author.equals(member2.publicKey));
+    assert.equal(seen?.text, "rejecting until invoice attached");
+  });
+
+  it("Rejects outsiders and oversized text", async () => {
+    await expectError(post(outsider, "hello"), "NotMember");
+    await expectError(post(member1, "x".repeat(201)), "InvalidComment");
+  });
+
+  it("Comments can be reclaimed once the proposal closes", async () => {
+    const comment = await post(member1, "invoice attached");
+    await expectError(close(comment, member1.publicKey), "ProposalStillOpen");
+
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    await execute(program, multisigPda, proposal);
+
+    await close(comment, member1.publicKey);
+    assert.isNull(await provider.connection.getAccountInfo(comment));
+  });
+});
@@ -0,0 +1,153 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findProposalPda,
+  memoInstruction,
+  proposalOptions,
+} from "./utils";
+
+const { PublicKey, Keypair, TransactionInstruction } = anchor.web3;
+
+// 按 Squads VaultTransactionMessage 布局编译指令（与其 SDK 的 transactionMessageBeet 一致）
+function compileVaultMessage(
+  ixs: anchor.web3.TransactionInstruction[],
+  lookups: { accountKey: PublicKey; writableIndexes: number[]; readonlyIndexes: number[] }[] = []
+) {
+  const metas = new Map<string, { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }>();
+  const add = (pubkey: PublicKey, isSigner: boolean, isWritable: boolean) => {
+    const prev = metas.get(pubkey.toBase58());
+    metas.set(pubkey.toBase58(), {
+      pubkey,
+      isSigner: isSigner || (prev?.isSigner ?? false),
+      isWritable: isWritable || (prev?.isWritable ?? false),
+    });
+  };
+  for (const ix of ixs) {
+    ix.keys.forEach((k) => add(k.pubkey, k.isSigner, k.isWritable));
+    add(ix.programId, false, false);
+  }
+  const all = [...metas.values()];
+  const group = (s: boolean, w: boolean) => all.filter((m) => m.isSigner === s && m.isWritable === w);
+  const keys = [...group(true, true), ...group(true, false), ...group(false, true), ...group(false, false)];
+  const indexOf = (k: PublicKey) => keys.findIndex((m) => m.pubkey.equals(k));
+
+  const u16 = (n: number) => Buffer.from([n & 0xff, n >> 8]);
+  const parts: Buffer[] = [
+    Buffer.from([
+      group(true, true).length + group(true, false).length,
+      group(true, true).length,
+      group(false, true).length,
+      keys.length,
+    ]),
+    ...keys.map((k) => k.pubkey.toBuffer()),
+    Buffer.from([ixs.length]),
+  ];
+  for (const ix of ixs) {
+    parts.push(Buffer.from([indexOf(ix.programId), ix.keys.length, ...ix.keys.map((k) => indexOf(k.pubkey))]));
+    parts.push(u16(ix.data.length), Buffer.from(ix.data));
+  }
+  parts.push(Buffer.from([lookups.length]));
+  for (const l of lookups) {
+    parts.push(
+      l.accountKey.toBuffer(),
+      Buffer.from([l.writableIndexes.length, ...l.writableIndexes]),
+      Buffer.from([l.readonlyIndexes.length, ...l.readonlyIndexes])
+    );
+  }
+  return Buffer.concat(parts);
+}
+
+describe("compiled message import", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+
+  let multisigPda: PublicKey;
+
+  const proposeCompiled = async (message: Buffer) => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    const [proposal] = findProposalPda(program.programId, multisigPda, account.proposalsCount);
+    await program.methods
+      .proposeCompiled(message, proposalOptions())
+      .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    return proposal;
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Round-trips account flags and data through a compiled message", async () => {
+    const writable = Keypair.generate().publicKey;
+    const readonly = Keypair.generate().publicKey;
+    const memo = memoInstruction("compiled", [multisigPda]);
+    const ix = new TransactionInstruction({
+      programId: memo.programId,
+      keys: [
+        ...memo.keys,
+        { pubkey: writable, isSigner: false, isWritable: true },
+        { pubkey: readonly, isSigner: false, isWritable: false },
+      ],
+      data: memo.data,
+    });
+
+    const proposal = await proposeCompiled(compileVaultMessage([ix]));
+    const raw = (await program.account.proposal.fetch(proposal)).action.raw[0];
+
+    assert.isTrue(raw.programId.equals(ix.programId));
+    assert.equal(raw.signers, 1);
+    assert.deepEqual(Buffer.from(raw.data), Buffer.from(ix.data));
+    const expected = [...ix.keys, { pubkey: ix.programId, isSigner: false, isWritable: false }];
+    assert.equal(raw.accounts.length, expected.length);
+    raw.accounts.forEach((a: any, i: number) => {
+      assert.isTrue(a.pubkey.equals(expected[i].pubkey));
+      assert.equal(a.isSigner, expected[i].isSigner);
+      assert.equal(a.isWritable, expected[i].isWritable);
+    });
+  });
+
+  it("Executes an imported instruction", async () => {
+    const proposal = await proposeCompiled(compileVaultMessage([memoInstruction("imported", [multisigPda])]));
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    await execute(program, multisigPda, proposal);
+  });
+
+  it("Rejects messages that use address lookup tables", async () => {
+    const message = compileVaultMessage([memoInstruction("alt", [multisigPda])], [
+      { accountKey: Keypair.generate().publicKey, writableIndexes: [0], readonlyIndexes: [] },
+    ]);
+    await expectError(proposeCompiled(message), "UnresolvedLookupTable");
+  });
+
+  it("Rejects multi-instruction messages", async () => {
+    const message = compileVaultMessage([
+      memoInstruction("first", [multisigPda]),
+      memoInstruction("second", [multisigPda]),
+    ]);
+    await expectError(proposeCompiled(message), "CompiledInstructionCount");
+  });
+});
@@ -0,0 +1,108 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  memoInstruction,
+  propose,
+  proposeAction,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("typed config actions", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const member3 = Keypair.generate();
+  const newcomer = Keypair.generate();
+  const members = [member1, member2, member3];
+
+  let multisigPda: PublicKey;
+
+  before(async () => {
+    for (const m of [...members, newcomer]) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Rejects invalid actions at propose time", async () => {
+    await expectError(
+      proposeAction(program, multisigPda, member1, { changeThreshold: { 0: 4 } }),
+      "InvalidThreshold"
+    );
+    await expectError(
+      proposeAction(program, multisigPda, member1, { addMember: { 0: member2.publicKey } }),
+      "AlreadyMember"
+    );
+    await expectError(
+      proposeAction(program, multisigPda, member1, { removeMember: { 0: newcomer.publicKey } }),
+      "NotMember"
+    );
+  });
+
+  it("Executes a threshold change natively", async () => {
+    const proposal = await proposeAction(program, multisigPda, member1, { changeThreshold: { 0: 3 } });
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    await execute(program, multisigPda, proposal);
+
+    const account = await program.account.multisig.fetch(multisigPda);
+    assert.equal(account.threshold, 3);
+  });
+
+  it("Invites a member through AddMember", async () => {
+    const proposal = await proposeAction(program, multisigPda, member1, { addMember: { 0: newcomer.publicKey } });
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    await execute(program, multisigPda, proposal);
+
+    let account = await program.account.multisig.fetch(multisigPda);
+    assert.isTrue(account.pendingInvites.some((i: any) => i.key.equals(newcomer.publicKey)));
+
+    await program.methods
+      .acceptMembership()
+      .accounts({ multisig: multisigPda, invitee: newcomer.publicKey })
+      .signers([newcomer])
+      .rpc();
+    account = await program.account.multisig.fetch(multisigPda);
+    assert.isTrue(account.members.some((m: any) => m.key.equals(newcomer.publicKey)));
+  });
+
+  it("Blocks raw execution while paused", async () => {
+    const pause = await proposeAction(program, multisigPda, member1, { setPaused: { 0: true } });
+    for (const m of members) {
+      await approve(program, multisigPda, pause, m);
+    }
+    await execute(program, multisigPda, pause);
+
+    const memo = await propose(program, multisigPda, member1, memoInstruction("paused", [multisigPda]));
+    for (const m of members) {
+      await approve(program, multisigPda, memo, m);
+    }
+    await expectError(execute(program, multisigPda, memo), "Paused");
+
+    const resume = await proposeAction(program, multisigPda, member1, { setPaused: { 0: false } });
+    for (const m of members) {
+      await approve(program, multisigPda, resume, m);
+    }
+    await execute(program, multisigPda, resume);
+    await execute(program, multisigPda, memo);
+  });
+});
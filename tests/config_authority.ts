@@ -0,0 +1,70 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import { airdrop, expectError, findMultisigPda, governed } from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("config authority", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const parentMembers = [Keypair.generate(), Keypair.generate()];
+  const childMembers = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+
+  let parentPda: PublicKey;
+  let childPda: PublicKey;
+
+  before(async () => {
+    for (const m of [...parentMembers, ...childMembers]) {
+      await airdrop(provider, m.publicKey);
+    }
+    [parentPda] = findMultisigPda(program.programId, parentMembers[0].publicKey, 0);
+    [childPda] = findMultisigPda(program.programId, childMembers[0].publicKey, 0);
+
+    await program.methods
+      .createMultisig(0, parentMembers.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: parentPda, creator: parentMembers[0].publicKey })
+      .signers([parentMembers[0]])
+      .rpc();
+    await program.methods
+      .createMultisig(0, childMembers.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: childPda, creator: childMembers[0].publicKey })
+      .signers([childMembers[0]])
+      .rpc();
+  });
+
+  it("Sets the config authority through the child's own proposal", async () => {
+    const ix = await program.methods
+      .setConfigAuthority(parentPda)
+      .accounts({ multisig: childPda })
+      .instruction();
+    await governed(program, childPda, childMembers.slice(0, 2), ix);
+
+    const child = await program.account.multisig.fetch(childPda);
+    assert.ok(child.configAuthority.equals(parentPda));
+  });
+
+  it("Changes the child's threshold entirely from parent proposals", async () => {
+    const ix = await program.methods
+      .changeThreshold(3)
+      .accounts({ multisig: childPda, authority: parentPda })
+      .instruction();
+    await governed(program, parentPda, parentMembers, ix);
+
+    const child = await program.account.multisig.fetch(childPda);
+    assert.equal(child.threshold, 3);
+  });
+
+  it("Cannot clear the config authority from the parent", async () => {
+    // set_config_authority 只接受 child 自身的 PDA 签名
+    const ix = await program.methods
+      .setConfigAuthority(null)
+      .accounts({ multisig: childPda })
+      .instruction();
+    await expectError(governed(program, parentPda, parentMembers, ix), "");
+  });
+});
@@ -0,0 +1,128 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  governed,
+  propose,
+  proposeAction,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } = anchor.web3;
+
+describe("unanimity for configuration proposals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = Array.from({ length: 5 }, () => Keypair.generate());
+  const [member1, member2, member3] = members;
+  const threeOfFive = [member1, member2, member3];
+  const payee = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  const approveBy = async (proposal: PublicKey, approvers: anchor.web3.Keypair[]) => {
+    for (const m of approvers) {
+      await approve(program, multisigPda, proposal, m);
+    }
+  };
+
+  const setUnanimity = (enabled: boolean) =>
+    program.methods
+      .setConfigRequiresUnanimity(enabled)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 3, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vaultPda);
+
+    // 开启本身按普通阈值
+    await governed(program, multisigPda, threeOfFive, await setUnanimity(true));
+    assert.isTrue((await program.account.multisig.fetch(multisigPda)).configRequiresUnanimity);
+  });
+
+  it("Keeps the normal threshold for payments", async () => {
+    const proposal = await propose(
+      program,
+      multisigPda,
+      member1,
+      SystemProgram.transfer({ fromPubkey: vaultPda, toPubkey: payee.publicKey, lamports: LAMPORTS_PER_SOL / 10 })
+    );
+    await approveBy(proposal, threeOfFive);
+    await execute(program, multisigPda, proposal);
+  });
+
+  it("Requires every member for typed and raw config proposals", async () => {
+    const typed = await proposeAction(program, multisigPda, member1, { changeThreshold: { 0: 2 } });
+    await approveBy(typed, threeOfFive);
+    await expectError(execute(program, multisigPda, typed), "NotExecutable");
+
+    const raw = await propose(
+      program,
+      multisigPda,
+      member1,
+      await program.methods.setQuorum(4).accounts({ multisig: multisigPda, authority: multisigPda }).instruction()
+    );
+    await approveBy(raw, threeOfFive);
+    await expectError(execute(program, multisigPda, raw), "NotExecutable");
+
+    await approveBy(typed, members.slice(3));
+    await execute(program, multisigPda, typed);
+    assert.equal((await program.account.multisig.fetch(multisigPda)).threshold, 2);
+  });
+
+  it("Treats turning the flag off as a config change", async () => {
+    const proposal = await propose(program, multisigPda, member1, await setUnanimity(false));
+    await approveBy(proposal, threeOfFive);
+    await expectError(execute(program, multisigPda, proposal), "NotExecutable");
+    assert.isTrue((await program.account.multisig.fetch(multisigPda)).configRequiresUnanimity);
+
+    await approveBy(proposal, members.slice(3));
+    await execute(program, multisigPda, proposal);
+    assert.isFalse((await program.account.multisig.fetch(multisigPda)).configRequiresUnanimity);
+
+    // 关闭后配置提案回到普通阈值（现为 2）
+    await governed(program, multisigPda, [member1, member2], await setUnanimity(true));
+  });
+
+  it("Still requires members marked inactive", async () => {
+    const liveness = await program.methods
+      .setLiveness(1, 2)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, members, liveness);
+    await sleep(2000);
+    const idle = members[4];
+    await program.methods.markInactive(idle.publicKey).accounts({ multisig: multisigPda }).rpc();
+
+    // 在线检测只降低基础阈值，全体同意仍包括失联成员
+    const proposal = await proposeAction(program, multisigPda, member1, { changeThreshold: { 0: 3 } });
+    await approveBy(proposal, members.slice(0, 4));
+    await expectError(execute(program, multisigPda, proposal), "NotExecutable");
+
+    // 投票即恢复在线
+    await approveBy(proposal, [idle]);
+    await execute(program, multisigPda, proposal);
+    assert.equal((await program.account.multisig.fetch(multisigPda)).threshold, 3);
+  });
+});
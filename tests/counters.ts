@@ -0,0 +1,129 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  chainTime,
+  execute,
+  findMultisigPda,
+  governed,
+  memoInstruction,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("lifecycle counters", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const guardian = Keypair.generate();
+  const members = [member1, member2];
+
+  let multisigPda: PublicKey;
+  const open = new Set<string>();
+
+  const proposeMemo = async (text: string, notAfter: number | null = null) => {
+    const proposal = await propose(program, multisigPda, member1, memoInstruction(text, [multisigPda]), {
+      notAfter: notAfter === null ? null : new anchor.BN(notAfter),
+    });
+    open.add(proposal.toBase58());
+    return proposal;
+  };
+
+  const approveAndExecute = async (proposal: PublicKey) => {
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    await execute(program, multisigPda, proposal);
+    open.delete(proposal.toBase58());
+  };
+
+  // created == executed + cancelled + open
+  const assertCounters = async (executed: number, cancelled: number) => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    assert.equal(account.executedCount, executed);
+    assert.equal(account.cancelledCount, cancelled);
+    assert.equal(account.proposalsCount, account.executedCount + account.cancelledCount + open.size);
+  };
+
+  before(async () => {
+    for (const m of [...members, guardian]) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Starts at zero", async () => {
+    await assertCounters(0, 0);
+  });
+
+  it("Keeps the invariant across a mixed sequence of lifecycle paths", async () => {
+    // 自治提案：执行计数一次
+    const ix = await program.methods
+      .setGuardian(guardian.publicKey)
+      .accounts({ multisig: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, members, ix);
+    await assertCounters(1, 0);
+
+    const executed = await proposeMemo("executed");
+    const cancelled = await proposeMemo("cancelled");
+    const swept = [await proposeMemo("swept 1"), await proposeMemo("swept 2")];
+    const pending = await proposeMemo("pending");
+    await assertCounters(1, 0);
+
+    await approveAndExecute(executed);
+    await assertCounters(2, 0);
+
+    await program.methods
+      .cancelTransaction(0, null)
+      .accounts({ multisig: multisigPda, proposal: cancelled, canceller: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    open.delete(cancelled.toBase58());
+    await assertCounters(2, 1);
+
+    // 已关闭的提案被跳过，不重复计数
+    await program.methods
+      .cancelAll(null)
+      .accounts({ multisig: multisigPda, authority: guardian.publicKey })
+      .remainingAccounts(
+        [cancelled, ...swept].flatMap((pubkey) => [
+          { pubkey, isSigner: false, isWritable: true },
+          { pubkey: member1.publicKey, isSigner: false, isWritable: true },
+        ])
+      )
+      .signers([guardian])
+      .rpc();
+    swept.forEach((p) => open.delete(p.toBase58()));
+    await assertCounters(2, 3);
+
+    const notAfter = (await chainTime(provider)) + 4;
+    const expired = await proposeMemo("expired", notAfter);
+    while ((await chainTime(provider)) <= notAfter) {
+      await sleep(1000);
+    }
+    await program.methods
+      .closeExpired()
+      .accounts({ multisig: multisigPda, proposal: expired, proposer: member1.publicKey })
+      .rpc();
+    open.delete(expired.toBase58());
+    await assertCounters(2, 4);
+
+    await approveAndExecute(pending);
+    await assertCounters(3, 4);
+  });
+});
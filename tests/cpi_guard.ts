@@ -0,0 +1,110 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import { CpiCaller } from "../target/types/cpi_caller";
+import {
+  airdrop,
+  approve,
+  expectError,
+  findMultisigPda,
+  findProgramConfigPda,
+  findProposalPda,
+  governed,
+  memoInstruction,
+  propose,
+  signerBits,
+  toInstructionData,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("top-level only propose/approve", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+  const caller = anchor.workspace.cpiCaller as Program<CpiCaller>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2, member3] = members;
+
+  let multisigPda: PublicKey;
+  let proposal: PublicKey;
+
+  const approveViaCpi = (member: anchor.web3.Keypair) =>
+    caller.methods
+      .approveViaCpi()
+      .accounts({
+        multisig: multisigPda,
+        proposal,
+        member: member.publicKey,
+        multisigProgram: program.programId,
+      })
+      .signers([member])
+      .rpc();
+
+  const bitOf = async (key: PublicKey) => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    return 1 << account.members.findIndex((m: any) => m.key.equals(key));
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    proposal = await propose(program, multisigPda, member1, memoInstruction("cpi guard", []));
+  });
+
+  it("Rejects an approval relayed through another program", async () => {
+    await expectError(approveViaCpi(member2), "CpiNotAllowed");
+    assert.equal((await program.account.proposal.fetch(proposal)).approvals, 0);
+  });
+
+  it("Rejects a proposal relayed through another program", async () => {
+    const { proposalsCount } = await program.account.multisig.fetch(multisigPda);
+    const [relayed] = findProposalPda(program.programId, multisigPda, proposalsCount);
+    const ix = memoInstruction("relayed", []);
+    await expectError(
+      caller.methods
+        .proposeViaCpi(toInstructionData(ix, signerBits(program.programId, multisigPda, ix)))
+        .accounts({
+          multisig: multisigPda,
+          proposal: relayed,
+          member: member3.publicKey,
+          multisigProgram: program.programId,
+          programConfig: findProgramConfigPda(program.programId)[0],
+        })
+        .signers([member3])
+        .rpc(),
+      "CpiNotAllowed"
+    );
+  });
+
+  it("Still accepts a direct approval", async () => {
+    await approve(program, multisigPda, proposal, member2);
+    const { approvals } = await program.account.proposal.fetch(proposal);
+    assert.equal(approvals, await bitOf(member2.publicKey));
+  });
+
+  it("Accepts relayed approvals once the multisig opts in", async () => {
+    await governed(
+      program,
+      multisigPda,
+      members.slice(0, 2),
+      await program.methods
+        .setAllowCpi(true)
+        .accounts({ multisig: multisigPda, authority: multisigPda })
+        .instruction()
+    );
+    await approveViaCpi(member3);
+    const { approvals } = await program.account.proposal.fetch(proposal);
+    assert.equal(approvals, (await bitOf(member2.publicKey)) | (await bitOf(member3.publicKey)));
+  });
+});
@@ -0,0 +1,104 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import { CpiCaller } from "../target/types/cpi_caller";
+import {
+  airdrop,
+  approve,
+  createAccountWithSeedInstruction,
+  execute,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  propose,
+  proposeBatch,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("create_account_with_seed from a vault", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+  const caller = anchor.workspace.cpiCaller as Program<CpiCaller>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+  // 8 字节判别符 + authority + value
+  const CONFIG_SPACE = 8 + 32 + 8;
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  const approveAll = async (proposal: PublicKey) => {
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    return proposal;
+  };
+  const ready = async (ix: anchor.web3.TransactionInstruction) =>
+    approveAll(await propose(program, multisigPda, member1, ix));
+
+  before(async () => {
+    await airdrop(provider, member1.publicKey);
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vaultPda, 1);
+  });
+
+  it("Creates a program-owned account funded by the vault and initializes it", async () => {
+    const { address, ix } = await createAccountWithSeedInstruction(
+      program,
+      multisigPda,
+      "protocol-config",
+      CONFIG_SPACE,
+      caller.programId
+    );
+    const init = await caller.methods
+      .initSeededConfig(new anchor.BN(42))
+      .accounts({ config: address, authority: vaultPda })
+      .instruction();
+    // 创建与初始化在同一个提案中依次执行，中间状态不会单独落地
+    const proposal = await approveAll(await proposeBatch(program, multisigPda, member1, [ix, init]));
+    const before = await provider.connection.getBalance(vaultPda);
+
+    await execute(program, multisigPda, proposal);
+
+    const info = await provider.connection.getAccountInfo(address);
+    assert.ok(info.owner.equals(caller.programId));
+    const rent = await provider.connection.getMinimumBalanceForRentExemption(CONFIG_SPACE);
+    assert.equal(info.lamports, rent);
+    assert.equal(await provider.connection.getBalance(vaultPda), before - rent);
+    const config = await caller.account.seededConfig.fetch(address);
+    assert.ok(config.authority.equals(vaultPda));
+    assert.equal(config.value.toNumber(), 42);
+  });
+
+  it("Refuses to fund an account below the vault's rent exemption", async () => {
+    const { connection } = provider;
+    const floor = await connection.getMinimumBalanceForRentExemption(0);
+    const balance = await connection.getBalance(vaultPda);
+    // 余额足够支付，但出资后金库低于自身的免租下限（默认每字节 6960 lamports，另计 128 字节头部）
+    const space = Math.floor((balance - floor / 2) / 6960) - 128;
+    const cost = await connection.getMinimumBalanceForRentExemption(space);
+    assert.isBelow(cost, balance);
+    assert.isAbove(cost, balance - floor);
+    const { ix } = await createAccountWithSeedInstruction(
+      program,
+      multisigPda,
+      "too-large",
+      space,
+      caller.programId
+    );
+    const proposal = await ready(ix);
+    await expectError(execute(program, multisigPda, proposal), "WouldBreakRentExemption");
+  });
+});
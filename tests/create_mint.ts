@@ -0,0 +1,133 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccount,
+  createMintToInstruction,
+  getMint,
+} from "@solana/spl-token";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findProposalPda,
+  findVaultPda,
+  propose,
+  proposalOptions,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram } = anchor.web3;
+
+describe("create mint proposals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  const proposeCreateMint = async (decimals: number, freezeAuthority: PublicKey | null) => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    const [proposal] = findProposalPda(program.programId, multisigPda, account.proposalsCount);
+    await program.methods
+      .proposeCreateMint(decimals, freezeAuthority, proposalOptions())
+      .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    return proposal;
+  };
+
+  // 新 mint 作为临时签名者：放在 remaining_accounts 首位并签名 execute 交易
+  const executeWithMint = (proposal: PublicKey, mint: Keypair, signs = true) =>
+    program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts([
+        { pubkey: mint.publicKey, isSigner: signs, isWritable: true },
+        { pubkey: vaultPda, isSigner: false, isWritable: true },
+        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+        { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      ])
+      .signers(signs ? [mint] : [])
+      .rpc();
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vaultPda);
+  });
+
+  it("Requires the new mint to sign the execute transaction", async () => {
+    const proposal = await proposeCreateMint(6, null);
+    const mint = Keypair.generate();
+    await expectError(executeWithMint(proposal, mint, false), "MintNotSigner");
+    assert.isNull(await provider.connection.getAccountInfo(mint.publicKey));
+  });
+
+  it("Creates a mint with the vault as authority, paid by the vault", async () => {
+    const [proposal] = findProposalPda(program.programId, multisigPda, 0);
+    const mint = Keypair.generate();
+    const vaultBefore = await provider.connection.getBalance(vaultPda);
+
+    let created: any = null;
+    const listener = program.addEventListener("mintCreated", (event) => {
+      created = event;
+    });
+    await executeWithMint(proposal, mint);
+    await sleep(1000);
+    await program.removeEventListener(listener);
+
+    assert.isTrue(created.mint.equals(mint.publicKey));
+    assert.isTrue(created.mintAuthority.equals(vaultPda));
+    const info = await getMint(provider.connection, mint.publicKey);
+    assert.equal(info.decimals, 6);
+    assert.isTrue(info.mintAuthority.equals(vaultPda));
+    assert.isNull(info.freezeAuthority);
+    const rent = await provider.connection.getMinimumBalanceForRentExemption(82);
+    assert.equal(vaultBefore - (await provider.connection.getBalance(vaultPda)), rent);
+
+    // 之后可以由金库签名增发
+    const ata = await createAssociatedTokenAccount(provider.connection, member1, mint.publicKey, member1.publicKey);
+    const mintTo = await propose(
+      program,
+      multisigPda,
+      member1,
+      createMintToInstruction(mint.publicKey, ata, vaultPda, 1_000)
+    );
+    for (const m of members) {
+      await approve(program, multisigPda, mintTo, m);
+    }
+    await execute(program, multisigPda, mintTo);
+    assert.equal((await getMint(provider.connection, mint.publicKey)).supply, 1_000n);
+  });
+
+  it("Sets the freeze authority when given", async () => {
+    const proposal = await proposeCreateMint(0, multisigPda);
+    const mint = Keypair.generate();
+    await executeWithMint(proposal, mint);
+    const info = await getMint(provider.connection, mint.publicKey);
+    assert.equal(info.decimals, 0);
+    assert.isTrue(info.freezeAuthority.equals(multisigPda));
+  });
+});
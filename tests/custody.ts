@@ -0,0 +1,185 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  expectError,
+  findMultisigPda,
+  findProposalPda,
+  proposalOptions,
+} from "./utils";
+
+const {
+  PublicKey,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  SYSVAR_CLOCK_PUBKEY,
+  Authorized,
+  StakeProgram,
+  Transaction,
+  VoteAccount,
+  VoteInit,
+  VoteProgram,
+} = anchor.web3;
+
+describe("withdraw authority custody", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+  // 验证者运营方当前持有的 withdraw authority
+  const operator = Keypair.generate();
+  const identity = Keypair.generate();
+  const voteAccount = Keypair.generate();
+  const stakeAccount = Keypair.generate();
+
+  let multisigPda: PublicKey;
+
+  const nextProposal = async () => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    return findProposalPda(program.programId, multisigPda, account.proposalsCount)[0];
+  };
+
+  const approveAll = async (proposal: PublicKey) => {
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+  };
+
+  const programFor = (kind: any) => (kind.stake ? StakeProgram.programId : VoteProgram.programId);
+
+  const proposeTake = async (account: PublicKey, kind: any) => {
+    const proposal = await nextProposal();
+    await program.methods
+      .proposeTakeCustody(account, kind, proposalOptions())
+      .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await approveAll(proposal);
+    return proposal;
+  };
+
+  // 旧权限在 execute 交易中共同签名
+  const executeTake = (proposal: PublicKey, account: PublicKey, kind: any, authority = operator) =>
+    program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts([
+        { pubkey: account, isSigner: false, isWritable: true },
+        { pubkey: SYSVAR_CLOCK_PUBKEY, isSigner: false, isWritable: false },
+        { pubkey: authority.publicKey, isSigner: true, isWritable: false },
+        { pubkey: programFor(kind), isSigner: false, isWritable: false },
+      ])
+      .signers([authority])
+      .rpc();
+
+  const release = async (account: PublicKey, kind: any, newAuthority: PublicKey) => {
+    const proposal = await nextProposal();
+    await program.methods
+      .proposeReleaseCustody(account, kind, newAuthority, proposalOptions())
+      .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await approveAll(proposal);
+    return program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts([
+        { pubkey: account, isSigner: false, isWritable: true },
+        { pubkey: SYSVAR_CLOCK_PUBKEY, isSigner: false, isWritable: false },
+        { pubkey: programFor(kind), isSigner: false, isWritable: false },
+      ])
+      .rpc();
+  };
+
+  const registry = async () =>
+    (await program.account.multisig.fetch(multisigPda)).controlledAccounts.map((c) => c.account.toBase58());
+
+  before(async () => {
+    for (const m of [...members, operator]) {
+      await airdrop(provider, m.publicKey, 5);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+
+    const voteRent = await provider.connection.getMinimumBalanceForRentExemption(VoteProgram.space);
+    const stakeRent = await provider.connection.getMinimumBalanceForRentExemption(StakeProgram.space);
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        VoteProgram.createAccount({
+          fromPubkey: operator.publicKey,
+          votePubkey: voteAccount.publicKey,
+          voteInit: new VoteInit(identity.publicKey, identity.publicKey, operator.publicKey, 5),
+          lamports: voteRent,
+        }),
+        StakeProgram.createAccount({
+          fromPubkey: operator.publicKey,
+          stakePubkey: stakeAccount.publicKey,
+          authorized: new Authorized(operator.publicKey, operator.publicKey),
+          lamports: stakeRent + LAMPORTS_PER_SOL,
+        })
+      ),
+      [operator, voteAccount, identity, stakeAccount]
+    );
+  });
+
+  it("Requires the current withdrawer to co-sign the handover", async () => {
+    const proposal = await proposeTake(voteAccount.publicKey, { vote: {} });
+    await expectError(executeTake(proposal, voteAccount.publicKey, { vote: {} }, member2), "CustodySignerMismatch");
+    assert.deepEqual(await registry(), []);
+
+    await executeTake(proposal, voteAccount.publicKey, { vote: {} });
+    const vote = VoteAccount.fromAccountData((await provider.connection.getAccountInfo(voteAccount.publicKey)).data);
+    assert.isTrue(vote.authorizedWithdrawer.equals(multisigPda));
+    assert.deepEqual(await registry(), [voteAccount.publicKey.toBase58()]);
+  });
+
+  it("Takes custody of a stake account's withdraw authority", async () => {
+    const proposal = await proposeTake(stakeAccount.publicKey, { stake: {} });
+    await executeTake(proposal, stakeAccount.publicKey, { stake: {} });
+
+    const info = await provider.connection.getParsedAccountInfo(stakeAccount.publicKey);
+    const { authorized } = (info.value.data as any).parsed.info.meta;
+    assert.equal(authorized.withdrawer, multisigPda.toBase58());
+    assert.equal(authorized.staker, operator.publicKey.toBase58());
+    assert.sameMembers(await registry(), [voteAccount.publicKey.toBase58(), stakeAccount.publicKey.toBase58()]);
+  });
+
+  it("Hands authority back out and drops it from the registry", async () => {
+    await release(stakeAccount.publicKey, { stake: {} }, operator.publicKey);
+    const info = await provider.connection.getParsedAccountInfo(stakeAccount.publicKey);
+    assert.equal((info.value.data as any).parsed.info.meta.authorized.withdrawer, operator.publicKey.toBase58());
+    assert.deepEqual(await registry(), [voteAccount.publicKey.toBase58()]);
+    // 已不再持有权限
+    await expectError(release(stakeAccount.publicKey, { stake: {} }, member1.publicKey), "StakeAuthorityMismatch");
+
+    // 通过 withdrawer 类型的 vote authorize 交出同样会移出登记表
+    const proposal = await nextProposal();
+    await program.methods
+      .proposeVoteAuthorize(voteAccount.publicKey, operator.publicKey, 1, proposalOptions())
+      .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await approveAll(proposal);
+    await program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts([
+        { pubkey: voteAccount.publicKey, isSigner: false, isWritable: true },
+        { pubkey: SYSVAR_CLOCK_PUBKEY, isSigner: false, isWritable: false },
+        { pubkey: VoteProgram.programId, isSigner: false, isWritable: false },
+      ])
+      .rpc();
+    assert.deepEqual(await registry(), []);
+  });
+});
@@ -0,0 +1,101 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  governed,
+  memoInstruction,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+// 默认 timelock 为 DEFAULT 秒，紧急提案为 0
+describe("per-proposal delay overrides", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate()];
+  const [member1, member2] = members;
+  const DEFAULT = 3;
+
+  let multisigPda: PublicKey;
+
+  const memo = async (text: string, options: { delayOverride?: number; urgent?: boolean } = {}) => {
+    const proposal = await propose(program, multisigPda, member1, memoInstruction(text, [multisigPda]), {
+      delayOverride: options.delayOverride ?? null,
+      urgent: options.urgent ?? false,
+    });
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    return proposal;
+  };
+
+  const setTimelock = (timelock: number) =>
+    program.methods
+      .setTimelock(timelock, 0, null)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await governed(program, multisigPda, members, await setTimelock(DEFAULT));
+  });
+
+  it("Only accepts overrides at least as long as the default", async () => {
+    await expectError(memo("shorter", { delayOverride: DEFAULT - 1 }), "DelayOverrideTooShort");
+    // 紧急提案的下限是 urgent_timelock_seconds
+    const urgent = await memo("urgent", { urgent: true, delayOverride: 1 });
+    assert.equal((await program.account.proposal.fetch(urgent)).delaySeconds, 1);
+  });
+
+  it("Waits for the override measured from when the threshold was reached", async () => {
+    const regular = await memo("regular");
+    const longer = await memo("longer", { delayOverride: DEFAULT + 3 });
+    assert.equal((await program.account.proposal.fetch(regular)).delaySeconds, DEFAULT);
+    assert.equal((await program.account.proposal.fetch(longer)).delaySeconds, DEFAULT + 3);
+
+    await sleep((DEFAULT + 1) * 1000);
+    await execute(program, multisigPda, regular);
+    await expectError(execute(program, multisigPda, longer), "TimelockNotElapsed");
+
+    await sleep(3000);
+    await execute(program, multisigPda, longer);
+  });
+
+  it("Keeps the delay snapshotted at creation when the default changes", async () => {
+    const before = await memo("created before the change");
+
+    // 治理提案本身也要等待默认 timelock
+    const change = await propose(program, multisigPda, member1, await setTimelock(3600));
+    for (const m of members) {
+      await approve(program, multisigPda, change, m);
+    }
+    await sleep((DEFAULT + 1) * 1000);
+    await execute(program, multisigPda, change);
+    assert.equal((await program.account.multisig.fetch(multisigPda)).timelockSeconds, 3600);
+
+    // 旧提案仍按创建时的 DEFAULT 秒执行，新提案按 3600 秒
+    await execute(program, multisigPda, before);
+    const after = await memo("created after the change");
+    assert.equal((await program.account.proposal.fetch(after)).delaySeconds, 3600);
+    await expectError(execute(program, multisigPda, after), "TimelockNotElapsed");
+  });
+});
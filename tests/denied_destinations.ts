@@ -0,0 +1,102 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  createAssociatedTokenAccount,
+  createMint,
+  createTransferInstruction,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  governed,
+  MEMO_PROGRAM_ID,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL, TransactionInstruction } = anchor.web3;
+
+describe("transfer destination denylist", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate()];
+  const [member1] = members;
+  const sanctioned = Keypair.generate();
+  const payee = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+  let mint: PublicKey;
+  let vaultAta: PublicKey;
+
+  // 全体批准后执行
+  const run = async (ix: anchor.web3.TransactionInstruction) => {
+    const proposal = await propose(program, multisigPda, member1, ix);
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    return execute(program, multisigPda, proposal);
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vaultPda, 10);
+
+    mint = await createMint(provider.connection, member1, member1.publicKey, null, 6);
+    vaultAta = await createAssociatedTokenAccount(provider.connection, member1, mint, vaultPda, undefined, undefined, undefined, true);
+    await mintTo(provider.connection, member1, mint, vaultAta, member1, 10_000);
+
+    await governed(
+      program,
+      multisigPda,
+      members,
+      await program.methods
+        .setDeniedDestinations([sanctioned.publicKey])
+        .accounts({ multisig: multisigPda, authority: multisigPda })
+        .instruction()
+    );
+  });
+
+  it("Blocks a direct SOL transfer to a denied address", async () => {
+    const send = (to: PublicKey) => SystemProgram.transfer({ fromPubkey: vaultPda, toPubkey: to, lamports: LAMPORTS_PER_SOL });
+    await expectError(run(send(sanctioned.publicKey)), "DestinationDenied");
+    assert.equal(await provider.connection.getBalance(sanctioned.publicKey), 0);
+
+    await run(send(payee.publicKey));
+    assert.equal(await provider.connection.getBalance(payee.publicKey), LAMPORTS_PER_SOL);
+  });
+
+  it("Blocks a token transfer into the denied owner's ATA", async () => {
+    const ata = await createAssociatedTokenAccount(provider.connection, member1, mint, sanctioned.publicKey);
+    await expectError(run(createTransferInstruction(vaultAta, ata, vaultPda, 100)), "DestinationDenied");
+    assert.equal((await getAccount(provider.connection, ata)).amount, 0n);
+  });
+
+  it("Lets non-transfer instructions through untouched", async () => {
+    const memo = new TransactionInstruction({
+      programId: MEMO_PROGRAM_ID,
+      keys: [{ pubkey: vaultPda, isSigner: true, isWritable: false }],
+      data: Buffer.from(`screened against ${sanctioned.publicKey.toBase58()}`),
+    });
+    await run(memo);
+  });
+});
@@ -0,0 +1,88 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  memoInstruction,
+  propose,
+  proposeAction,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("proposal dependencies", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+
+  let multisigPda: PublicKey;
+
+  const approveAll = async (proposal: PublicKey) => {
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Rejects self and forward dependencies", async () => {
+    // 下一个提案的索引为 0，不能依赖自身或尚不存在的提案
+    await expectError(
+      propose(program, multisigPda, member1, memoInstruction("self", [multisigPda]), { dependsOn: 0 }),
+      "InvalidDependency"
+    );
+    await expectError(
+      propose(program, multisigPda, member1, memoInstruction("forward", [multisigPda]), { dependsOn: 5 }),
+      "InvalidDependency"
+    );
+    // 最大索引不能因加一溢出而 panic
+    await expectError(
+      propose(program, multisigPda, member1, memoInstruction("max", [multisigPda]), { dependsOn: 0xffffffff }),
+      "InvalidDependency"
+    );
+  });
+
+  it("Enforces pause -> upgrade -> unpause ordering", async () => {
+    const pause = await proposeAction(program, multisigPda, member1, { setPaused: { 0: true } });
+    const pauseIndex = (await program.account.proposal.fetch(pause)).index;
+    // 暂停期间 Raw 提案不可执行，这里用类型化提案代替升级步骤
+    const upgrade = await proposeAction(program, multisigPda, member1, { changeThreshold: { 0: 2 } }, { dependsOn: pauseIndex });
+    const upgradeIndex = (await program.account.proposal.fetch(upgrade)).index;
+    const unpause = await proposeAction(program, multisigPda, member1, { setPaused: { 0: false } }, { dependsOn: upgradeIndex });
+
+    for (const p of [pause, upgrade, unpause]) {
+      await approveAll(p);
+    }
+
+    await expectError(execute(program, multisigPda, unpause), "DependencyNotExecuted");
+    await expectError(execute(program, multisigPda, upgrade), "DependencyNotExecuted");
+
+    await execute(program, multisigPda, pause);
+    await expectError(execute(program, multisigPda, unpause), "DependencyNotExecuted");
+    await execute(program, multisigPda, upgrade);
+    await execute(program, multisigPda, unpause);
+
+    const account = await program.account.multisig.fetch(multisigPda);
+    assert.isFalse(account.paused);
+  });
+});
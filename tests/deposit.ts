@@ -0,0 +1,160 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccount,
+  createMint,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { Multisig } from "../target/types/multisig";
+import { airdrop, expectError, findMultisigPda, findVaultPda, sleep } from "./utils";
+
+const { PublicKey, Keypair, LAMPORTS_PER_SOL } = anchor.web3;
+
+describe("deposit", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const donor = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+  let otherMultisig: PublicKey;
+  let otherVault: PublicKey;
+
+  const capture = async (run: () => Promise<unknown>) => {
+    const seen: any[] = [];
+    const listener = program.addEventListener("depositReceived", (event) => {
+      seen.push(event);
+    });
+    await run();
+    await sleep(1000);
+    await program.removeEventListener(listener);
+    return seen;
+  };
+
+  before(async () => {
+    await airdrop(provider, member1.publicKey);
+    await airdrop(provider, donor.publicKey, 5);
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    [otherMultisig] = findMultisigPda(program.programId, member1.publicKey, 1);
+    [otherVault] = findVaultPda(program.programId, otherMultisig);
+    for (const [nonce, multisig] of [[0, multisigPda], [1, otherMultisig]] as const) {
+      await program.methods
+        .createMultisig(nonce, [member1.publicKey, member2.publicKey], 2, 10)
+        .accounts({ multisig, creator: member1.publicKey })
+        .signers([member1])
+        .rpc();
+    }
+  });
+
+  it("Deposits SOL into vault 0 and attributes it to the depositor", async () => {
+    const before = await provider.connection.getBalance(vaultPda);
+    const amount = LAMPORTS_PER_SOL;
+
+    const events = await capture(() =>
+      program.methods
+        .deposit(new anchor.BN(amount), "grant round 3")
+        .accounts({ multisig: multisigPda, depositor: donor.publicKey })
+        .signers([donor])
+        .rpc()
+    );
+
+    assert.equal(await provider.connection.getBalance(vaultPda), before + amount);
+    assert.equal(events.length, 1);
+    assert.ok(events[0].multisig.equals(multisigPda));
+    assert.ok(events[0].vault.equals(vaultPda));
+    assert.ok(events[0].depositor.equals(donor.publicKey));
+    assert.isNull(events[0].mint);
+    assert.equal(events[0].amount.toNumber(), amount);
+    assert.equal(events[0].memo, "grant round 3");
+
+    // 不影响提案状态
+    const account = await program.account.multisig.fetch(multisigPda);
+    assert.equal(account.proposalsCount, 0);
+  });
+
+  it("Rejects a vault that belongs to another multisig", async () => {
+    await expectError(
+      program.methods
+        .deposit(new anchor.BN(1_000_000), null)
+        .accounts({ multisig: multisigPda, vault: otherVault, depositor: donor.publicKey })
+        .signers([donor])
+        .rpc(),
+      "ConstraintSeeds"
+    );
+  });
+
+  it("Rejects zero amounts and oversized memos", async () => {
+    await expectError(
+      program.methods
+        .deposit(new anchor.BN(0), null)
+        .accounts({ multisig: multisigPda, depositor: donor.publicKey })
+        .signers([donor])
+        .rpc(),
+      "InvalidDepositAmount"
+    );
+    await expectError(
+      program.methods
+        .deposit(new anchor.BN(1_000_000), "x".repeat(65))
+        .accounts({ multisig: multisigPda, depositor: donor.publicKey })
+        .signers([donor])
+        .rpc(),
+      "InvalidDepositMemo"
+    );
+  });
+
+  it("Deposits tokens into the vault's ATA", async () => {
+    const mint = await createMint(provider.connection, donor, donor.publicKey, null, 6);
+    const source = await createAssociatedTokenAccount(provider.connection, donor, mint, donor.publicKey);
+    const vaultAta = await createAssociatedTokenAccount(provider.connection, donor, mint, vaultPda, undefined, undefined, undefined, true);
+    const otherAta = await createAssociatedTokenAccount(provider.connection, donor, mint, otherVault, undefined, undefined, undefined, true);
+    await mintTo(provider.connection, donor, mint, source, donor, 5_000);
+
+    const events = await capture(() =>
+      program.methods
+        .depositToken(new anchor.BN(1_500), null)
+        .accounts({
+          multisig: multisigPda,
+          depositor: donor.publicKey,
+          source,
+          destination: vaultAta,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([donor])
+        .rpc()
+    );
+
+    assert.equal(Number((await getAccount(provider.connection, vaultAta)).amount), 1_500);
+    assert.equal(events.length, 1);
+    assert.ok(events[0].mint.equals(mint));
+    assert.ok(events[0].depositor.equals(donor.publicKey));
+    assert.equal(events[0].amount.toNumber(), 1_500);
+    assert.isNull(events[0].memo);
+
+    // 另一个多签的 ATA 不能记在本多签名下
+    await expectError(
+      program.methods
+        .depositToken(new anchor.BN(1_500), null)
+        .accounts({
+          multisig: multisigPda,
+          depositor: donor.publicKey,
+          source,
+          destination: otherAta,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([donor])
+        .rpc(),
+      "AccountMismatch"
+    );
+  });
+});
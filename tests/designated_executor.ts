@@ -0,0 +1,95 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  executeRemainingAccounts,
+  expectError,
+  findMultisigPda,
+  governed,
+  memoInstruction,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("designated executor", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate()];
+  const [member1] = members;
+  const bot = Keypair.generate();
+  const keeper = Keypair.generate();
+
+  let multisigPda: PublicKey;
+
+  const approvedFor = async (text: string, designatedExecutor: PublicKey | null) => {
+    const proposal = await propose(program, multisigPda, member1, memoInstruction(text, []), { designatedExecutor });
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    return proposal;
+  };
+
+  const executeAs = async (proposal: PublicKey, executor: anchor.web3.Keypair) => {
+    const account = await program.account.proposal.fetch(proposal);
+    return program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal, spendLedger: null, executor: executor.publicKey })
+      .remainingAccounts(executeRemainingAccounts(account.action, [multisigPda]))
+      .signers([executor])
+      .rpc();
+  };
+
+  before(async () => {
+    for (const k of [...members, bot, keeper]) {
+      await airdrop(provider, k.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Lets only the designated executor fire a ready proposal", async () => {
+    const proposal = await approvedFor("deploy step 1", bot.publicKey);
+    assert.isTrue((await program.account.proposal.fetch(proposal)).designatedExecutor.equals(bot.publicKey));
+
+    await expectError(execute(program, multisigPda, proposal), "NotDesignatedExecutor");
+    await expectError(executeAs(proposal, keeper), "NotDesignatedExecutor");
+    await executeAs(proposal, bot);
+    assert.isNull(await provider.connection.getAccountInfo(proposal));
+  });
+
+  it("Leaves proposals without a designation open to anyone", async () => {
+    await executeAs(await approvedFor("anyone", null), keeper);
+  });
+
+  it("Opens execution to everyone once the grace period has passed", async () => {
+    await governed(
+      program,
+      multisigPda,
+      members,
+      await program.methods
+        .setExecutorGrace(2)
+        .accounts({ multisig: multisigPda, authority: multisigPda })
+        .instruction()
+    );
+    const proposal = await approvedFor("deploy step 2", bot.publicKey);
+    await expectError(executeAs(proposal, keeper), "NotDesignatedExecutor");
+
+    // 机器人密钥丢失：宽限期过后由普通 keeper 执行
+    await sleep(3000);
+    await executeAs(proposal, keeper);
+    assert.isNull(await provider.connection.getAccountInfo(proposal));
+  });
+});
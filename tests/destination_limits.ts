@@ -0,0 +1,188 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  createAccount,
+  createAssociatedTokenAccount,
+  createMint,
+  createTransferInstruction,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  mintTo,
+} from "@solana/spl-token";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  governed,
+  propose,
+  proposeAction,
+  proposeBatch,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } = anchor.web3;
+
+describe("per-destination spending limits", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2] = members;
+  const trusted = Keypair.generate();
+  const stranger = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+  let mint: PublicKey;
+  let vaultAta: PublicKey;
+
+  const SOL_CAP = LAMPORTS_PER_SOL / 2;
+
+  const run = async (ix: anchor.web3.TransactionInstruction, approvers = [member1, member2]) => {
+    const proposal = await propose(program, multisigPda, member1, ix);
+    for (const m of approvers) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    return execute(program, multisigPda, proposal);
+  };
+
+  const sendSol = (to: PublicKey, lamports: number) =>
+    SystemProgram.transfer({ fromPubkey: vaultPda, toPubkey: to, lamports });
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vaultPda, 10);
+
+    mint = await createMint(provider.connection, member1, member1.publicKey, null, 6);
+    vaultAta = await createAssociatedTokenAccount(provider.connection, member1, mint, vaultPda, undefined, undefined, undefined, true);
+    await mintTo(provider.connection, member1, mint, vaultAta, member1, 10_000);
+
+    await governed(
+      program,
+      multisigPda,
+      [member1, member2],
+      await program.methods
+        .setTrustedDestinations([trusted.publicKey])
+        .accounts({ multisig: multisigPda, authority: multisigPda })
+        .instruction()
+    );
+    await governed(
+      program,
+      multisigPda,
+      [member1, member2],
+      await program.methods
+        .setDestinationLimits([
+          { mint: null, maxToTrusted: null, maxToOthers: new anchor.BN(SOL_CAP) },
+          { mint, maxToTrusted: new anchor.BN(1_000), maxToOthers: new anchor.BN(100) },
+        ])
+        .accounts({ multisig: multisigPda, authority: multisigPda })
+        .instruction()
+    );
+  });
+
+  it("Rejects duplicate mints in the limit list", async () => {
+    const ix = await program.methods
+      .setDestinationLimits([
+        { mint, maxToTrusted: null, maxToOthers: new anchor.BN(1) },
+        { mint, maxToTrusted: null, maxToOthers: new anchor.BN(2) },
+      ])
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await expectError(governed(program, multisigPda, [member1, member2], ix), "InvalidDestinationLimits");
+  });
+
+  it("Caps SOL sent to untrusted addresses but not to the trusted list", async () => {
+    await run(sendSol(stranger.publicKey, SOL_CAP));
+    await expectError(run(sendSol(stranger.publicKey, SOL_CAP + 1)), "DestinationLimitExceeded");
+    await run(sendSol(trusted.publicKey, 3 * LAMPORTS_PER_SOL));
+    assert.equal(await provider.connection.getBalance(trusted.publicKey), 3 * LAMPORTS_PER_SOL);
+  });
+
+  it("Sums an amount split across two instructions in one proposal", async () => {
+    // 单看每条都在上限内，合计超出
+    const half = SOL_CAP / 2;
+    const runBatch = async (ixs: anchor.web3.TransactionInstruction[]) => {
+      const proposal = await proposeBatch(program, multisigPda, member1, ixs);
+      for (const m of [member1, member2]) {
+        await approve(program, multisigPda, proposal, m);
+      }
+      return execute(program, multisigPda, proposal);
+    };
+    await expectError(
+      runBatch([sendSol(stranger.publicKey, half + 1), sendSol(stranger.publicKey, half)]),
+      "DestinationLimitExceeded"
+    );
+
+    const before = await provider.connection.getBalance(stranger.publicKey);
+    await runBatch([sendSol(stranger.publicKey, half), sendSol(stranger.publicKey, half)]);
+    assert.equal((await provider.connection.getBalance(stranger.publicKey)) - before, SOL_CAP);
+  });
+
+  it("Classifies token transfers by the destination account's owner", async () => {
+    // 可信钱包的 ATA 与其名下的非 ATA token 账户都按可信计
+    const trustedAta = await createAssociatedTokenAccount(provider.connection, member1, mint, trusted.publicKey);
+    const trustedAux = await createAccount(provider.connection, member1, mint, trusted.publicKey, Keypair.generate());
+    await run(createTransferInstruction(vaultAta, trustedAta, vaultPda, 1_000));
+    await run(createTransferInstruction(vaultAta, trustedAux, vaultPda, 1_000));
+    await expectError(run(createTransferInstruction(vaultAta, trustedAta, vaultPda, 1_001)), "DestinationLimitExceeded");
+
+    const strangerAta = await createAssociatedTokenAccount(provider.connection, member1, mint, stranger.publicKey);
+    await run(createTransferInstruction(vaultAta, strangerAta, vaultPda, 100));
+    await expectError(run(createTransferInstruction(vaultAta, strangerAta, vaultPda, 101)), "DestinationLimitExceeded");
+    assert.equal((await getAccount(provider.connection, strangerAta)).amount, 100n);
+  });
+
+  it("Does not treat the trusted wallet's ATA address as a trusted SOL destination", async () => {
+    const trustedAta = getAssociatedTokenAddressSync(mint, trusted.publicKey);
+    await expectError(run(sendSol(trustedAta, SOL_CAP + 1)), "DestinationLimitExceeded");
+  });
+
+  it("Applies the cap to typed sweeps", async () => {
+    const [vault1] = findVaultPda(program.programId, multisigPda, 1);
+    await airdrop(provider, vault1);
+    const proposal = await proposeAction(program, multisigPda, member1, {
+      sweepVault: { index: 1, destination: stranger.publicKey },
+    });
+    for (const m of [member1, member2]) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    await expectError(
+      program.methods
+        .executeTransaction()
+        .accounts({ multisig: multisigPda, proposal })
+        .remainingAccounts([
+          { pubkey: vault1, isSigner: false, isWritable: true },
+          { pubkey: stranger.publicKey, isSigner: false, isWritable: true },
+          { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+        ])
+        .rpc(),
+      "DestinationLimitExceeded"
+    );
+  });
+
+  it("Requires every member for transfers it cannot decode", async () => {
+    // 补齐字节后严格解码不再识别，去向未知
+    const padded = sendSol(stranger.publicKey, LAMPORTS_PER_SOL);
+    padded.data = Buffer.concat([padded.data, Buffer.alloc(4)]);
+    await expectError(run(padded), "DestinationLimitExceeded");
+
+    const before = await provider.connection.getBalance(stranger.publicKey);
+    await run(padded, members);
+    assert.equal((await provider.connection.getBalance(stranger.publicKey)) - before, LAMPORTS_PER_SOL);
+  });
+});
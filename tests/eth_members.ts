@@ -0,0 +1,151 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { createECDH, createHash, randomBytes } from "crypto";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  chainTime,
+  execute,
+  expectError,
+  findMultisigPda,
+  governed,
+  memoInstruction,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair, Secp256k1Program } = anchor.web3;
+
+// 与程序中的 ETH_APPROVAL_DOMAIN / eth_approval_message 一致
+const DOMAIN = Buffer.from("solana-multisig:eth-approval:v1");
+
+class EthSigner {
+  readonly privateKey = randomBytes(32);
+  readonly address: Buffer;
+
+  constructor() {
+    const ecdh = createECDH("secp256k1");
+    ecdh.setPrivateKey(this.privateKey);
+    this.address = Secp256k1Program.publicKeyToEthAddress(ecdh.getPublicKey().subarray(1));
+  }
+
+  // Secp256k1 成员的 key：地址后补 12 个 0
+  get memberKey() {
+    return new PublicKey(Buffer.concat([this.address, Buffer.alloc(12)]));
+  }
+}
+
+describe("secp256k1 (Ethereum) members", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate()];
+  const [member1, member2] = members;
+  const eth = new EthSigner();
+
+  let multisigPda: PublicKey;
+
+  const actionHash = async (proposal: PublicKey) => {
+    const { action } = await program.account.proposal.fetch(proposal);
+    const encoded = program.coder.types.encode("proposalAction", action);
+    return createHash("sha256").update(encoded).digest();
+  };
+
+  const approvalMessage = async (proposal: PublicKey, expiry: number) => {
+    const payload = Buffer.concat([
+      DOMAIN,
+      program.programId.toBuffer(),
+      multisigPda.toBuffer(),
+      proposal.toBuffer(),
+      await actionHash(proposal),
+      new anchor.BN(expiry).toArrayLike(Buffer, "le", 8),
+    ]);
+    // EIP-191 personal_sign，与以太坊硬件钱包签出的内容相同
+    return Buffer.concat([Buffer.from(`\x19Ethereum Signed Message:\n${payload.length}`), payload]);
+  };
+
+  const ethApprove = async (proposal: PublicKey, expiry: number, signer = eth, signedFor = proposal) => {
+    const verify = Secp256k1Program.createInstructionWithPrivateKey({
+      privateKey: signer.privateKey,
+      message: await approvalMessage(signedFor, expiry),
+      instructionIndex: 0,
+    });
+    return program.methods
+      .approveWithEthSignature(new anchor.BN(expiry))
+      .accounts({ multisig: multisigPda, proposal })
+      .preInstructions([verify])
+      .rpc();
+  };
+
+  const inAnHour = async () => (await chainTime(provider)) + 3600;
+
+  const bitOf = async (key: PublicKey) => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    return 1 << account.members.findIndex((m: any) => m.key.equals(key));
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await governed(
+      program,
+      multisigPda,
+      members,
+      await program.methods
+        .addEthMember(Array.from(eth.address), null)
+        .accounts({ multisig: multisigPda, authority: multisigPda })
+        .instruction()
+    );
+  });
+
+  it("Registers the member with its key type", async () => {
+    const { members: entries } = await program.account.multisig.fetch(multisigPda);
+    const entry = entries.find((m: any) => m.key.equals(eth.memberKey));
+    assert.deepEqual(entry.keyType, { secp256k1: {} });
+  });
+
+  it("Counts an Ethereum approval towards the threshold like any other", async () => {
+    const proposal = await propose(program, multisigPda, member1, memoInstruction("eth council", []));
+    await approve(program, multisigPda, proposal, member1);
+    await ethApprove(proposal, await inAnHour());
+
+    const { approvals, readyAt } = await program.account.proposal.fetch(proposal);
+    assert.equal(approvals, (await bitOf(member1.publicKey)) | (await bitOf(eth.memberKey)));
+    assert.isNotNull(readyAt);
+    await execute(program, multisigPda, proposal);
+  });
+
+  it("Rejects replays, signatures for other proposals and expired signatures", async () => {
+    const proposal = await propose(program, multisigPda, member1, memoInstruction("first", []));
+    const other = await propose(program, multisigPda, member1, memoInstruction("second", []));
+    const expiry = await inAnHour();
+
+    await ethApprove(proposal, expiry);
+    await expectError(ethApprove(proposal, expiry), "AlreadyApproved");
+    // 为 proposal 签的名不能用来批准 other
+    await expectError(ethApprove(other, expiry, eth, proposal), "InvalidEthSignature");
+    await expectError(ethApprove(other, (await chainTime(provider)) - 1), "EthSignatureExpired");
+    assert.equal((await program.account.proposal.fetch(other)).approvals, 0);
+  });
+
+  it("Requires the signer to be a registered Ethereum member", async () => {
+    const proposal = await propose(program, multisigPda, member2, memoInstruction("stranger", []));
+    await expectError(ethApprove(proposal, await inAnHour(), new EthSigner()), "NotMember");
+
+    const noVerify = program.methods
+      .approveWithEthSignature(new anchor.BN(await inAnHour()))
+      .accounts({ multisig: multisigPda, proposal })
+      .rpc();
+    await expectError(noVerify, "InvalidEthSignature");
+  });
+});
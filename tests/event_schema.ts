@@ -0,0 +1,59 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import { airdrop, findMultisigPda, memoInstruction, propose, sleep } from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("event schema version", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+
+  // IDL 中导出的 EVENT_SCHEMA_VERSION
+  const schemaVersion = Number(program.idl.constants.find((c) => c.name === "eventSchemaVersion").value);
+
+  const capture = async (name: string, run: () => Promise<unknown>) => {
+    let seen: any = null;
+    const listener = program.addEventListener(name as any, (event) => {
+      seen = event;
+    });
+    await run();
+    await sleep(1000);
+    await program.removeEventListener(listener);
+    return seen;
+  };
+
+  let multisigPda: PublicKey;
+
+  before(async () => {
+    await airdrop(provider, member1.publicKey);
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+  });
+
+  it("Tags MultisigCreated with the schema and program versions", async () => {
+    const created = await capture("multisigCreated", () =>
+      program.methods
+        .createMultisig(0, [member1.publicKey, member2.publicKey], 2, 10)
+        .accounts({ multisig: multisigPda, creator: member1.publicKey })
+        .signers([member1])
+        .rpc()
+    );
+    assert.equal(created.schemaVersion, schemaVersion);
+    assert.match(created.programVersion, /^\d+\.\d+\.\d+/);
+    assert.equal(created.members, 2);
+    assert.equal(created.threshold, 2);
+  });
+
+  it("Tags every other event with the same version", async () => {
+    const proposed = await capture("proposalCreated", () =>
+      propose(program, multisigPda, member1, memoInstruction("tagged", [multisigPda]))
+    );
+    assert.equal(proposed.schemaVersion, schemaVersion);
+  });
+});
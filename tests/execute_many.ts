@@ -0,0 +1,134 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  computeUnits,
+  executeAccounts,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  memoInstruction,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair, ComputeBudgetProgram } = anchor.web3;
+
+describe("execute_many", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  const ready = async (text: string, approvers = members) => {
+    const proposal = await propose(program, multisigPda, member1, memoInstruction(text, [multisigPda]));
+    for (const m of approvers) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    return proposal;
+  };
+
+  // 每个提案后紧跟其执行账户
+  const executeMany = async (proposals: PublicKey[]) => {
+    const counts: number[] = [];
+    const remaining = [];
+    for (const proposal of proposals) {
+      const account = await program.account.proposal.fetch(proposal);
+      const accounts = executeAccounts(account.action.raw[0], [multisigPda, vaultPda]);
+      counts.push(accounts.length);
+      remaining.push({ pubkey: proposal, isSigner: false, isWritable: true }, ...accounts);
+    }
+    return program.methods
+      .executeMany(Buffer.from(counts))
+      .accounts({ multisig: multisigPda })
+      .remainingAccounts(remaining)
+      .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 })])
+      .rpc();
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Executes several ready proposals in one transaction", async () => {
+    const proposals = [];
+    for (let i = 0; i < 4; i++) {
+      proposals.push(await ready(`keeper ${i}`));
+    }
+    const sig = await executeMany(proposals);
+
+    // 批量规模通常先受交易大小（1232 字节内的账户数）限制，CU 见日志
+    console.log("execute_many CU (4 memo proposals):", await computeUnits(provider, program.programId, sig));
+    for (const p of proposals) {
+      assert.isNull(await provider.connection.getAccountInfo(p));
+    }
+  });
+
+  it("Runs consecutive self-CPI config proposals in one batch", async () => {
+    const quorumIx = await program.methods
+      .setQuorum(2)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    const thresholdIx = await program.methods
+      .changeThreshold(1)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    const first = await propose(program, multisigPda, member1, quorumIx);
+    const second = await propose(program, multisigPda, member1, thresholdIx);
+    for (const p of [first, second]) {
+      for (const m of members) {
+        await approve(program, multisigPda, p, m);
+      }
+    }
+    const [a, b] = await Promise.all([first, second].map((p) => program.account.proposal.fetch(p)));
+
+    await executeMany([first, second]);
+
+    // 第一个提案的执行记录在第二个的 CPI 前已写回，不会被 reload 覆盖
+    const account = await program.account.multisig.fetch(multisigPda);
+    assert.equal(account.quorum, 2);
+    assert.equal(account.threshold, 1);
+    const executed = (index: number) =>
+      !account.recentExecuted.shrn(account.proposalsCount - 1 - index).and(new anchor.BN(1)).isZero();
+    assert.isTrue(executed(a.index));
+    assert.isTrue(executed(b.index));
+
+    // 恢复阈值供后续用例使用
+    const restore = await propose(program, multisigPda, member1, await program.methods
+      .changeThreshold(2)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction());
+    for (const m of members) {
+      await approve(program, multisigPda, restore, m);
+    }
+    await executeMany([restore]);
+  });
+
+  it("Aborts the whole batch when one proposal is not ready", async () => {
+    const good = await ready("good");
+    const bad = await ready("bad", [member1]);
+    await expectError(executeMany([good, bad]), "NotExecutable");
+
+    // 整笔回滚：good 仍未执行
+    const account = await program.account.proposal.fetch(good);
+    assert.isFalse(account.executed);
+  });
+});
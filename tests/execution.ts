@@ -0,0 +1,91 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  computeUnits,
+  executeAccounts,
+  findMultisigPda,
+  memoInstruction,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("execution", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const witness = Keypair.generate();
+
+  let multisigPda: PublicKey;
+
+  before(async () => {
+    for (const m of [member1, member2, witness]) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, [member1.publicKey, member2.publicKey], 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Executes a large payload", async () => {
+    // 接近提案空间上限的 memo
+    const ix = memoInstruction("x".repeat(700), [multisigPda]);
+    const proposal = await propose(program, multisigPda, member1, ix);
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+
+    const account = await program.account.proposal.fetch(proposal);
+    const sig = await program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts(executeAccounts(account.action.raw[0], [multisigPda]))
+      .rpc();
+
+    console.log("execute_transaction CU (700 byte payload):", await computeUnits(provider, program.programId, sig));
+    assert.isNull(await provider.connection.getAccountInfo(proposal));
+  });
+
+  it("Keeps the payload intact when the CPI fails and can be retried", async () => {
+    // memo 要求 witness 也签名；第一次执行不带 witness 签名 -> CPI 失败
+    const ix = memoInstruction("retry me", [multisigPda, witness.publicKey]);
+    const proposal = await propose(program, multisigPda, member1, ix);
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+
+    const before = await program.account.proposal.fetch(proposal);
+    const unsigned = executeAccounts(before.action.raw[0], [multisigPda, witness.publicKey]);
+    try {
+      await program.methods
+        .executeTransaction()
+        .accounts({ multisig: multisigPda, proposal })
+        .remainingAccounts(unsigned)
+        .rpc();
+      assert.fail("execution without witness signature should fail");
+    } catch (e) {
+      // 预期失败
+    }
+
+    const after = await program.account.proposal.fetch(proposal);
+    assert.deepEqual(Buffer.from(after.action.raw[0].data), Buffer.from(before.action.raw[0].data));
+    assert.isFalse(after.executed);
+
+    await program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts(executeAccounts(after.action.raw[0], [multisigPda]))
+      .signers([witness])
+      .rpc();
+    assert.isNull(await provider.connection.getAccountInfo(proposal));
+  });
+});
@@ -0,0 +1,118 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  executeRemainingAccounts,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  MEMO_PROGRAM_ID,
+  memoAccounts,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } = anchor.web3;
+
+describe("execution memos", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+  const payee = Keypair.generate();
+
+  const MEMO = "invoice 2026-041 / vendor payout";
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  const transfer = () =>
+    SystemProgram.transfer({ fromPubkey: vaultPda, toPubkey: payee.publicKey, lamports: LAMPORTS_PER_SOL / 10 });
+
+  const proposeTransfer = async (executionMemo: string | null) => {
+    const proposal = await propose(program, multisigPda, member1, transfer(), { executionMemo });
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    return proposal;
+  };
+
+  const memoLogged = async (signature: string) => {
+    await provider.connection.confirmTransaction(signature, "confirmed");
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const invoked = tx.meta.logMessages.some((l) => l.includes(`Program ${MEMO_PROGRAM_ID.toBase58()} invoke`));
+    return invoked && tx.meta.logMessages.some((l) => l.includes(MEMO));
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vaultPda);
+  });
+
+  it("Rejects empty, oversized and record-only memos", async () => {
+    await expectError(proposeTransfer(""), "InvalidExecutionMemo");
+    await expectError(proposeTransfer("x".repeat(129)), "InvalidExecutionMemo");
+    await expectError(
+      propose(program, multisigPda, member1, transfer(), { executionMemo: MEMO, noSign: true }),
+      "InvalidExecutionMemo"
+    );
+  });
+
+  it("Writes the memo signed by the vault in the same instruction", async () => {
+    const proposal = await proposeTransfer(MEMO);
+    assert.equal((await program.account.proposal.fetch(proposal)).executionMemo, MEMO);
+
+    const sig = await execute(program, multisigPda, proposal);
+    assert.isTrue(await memoLogged(sig));
+    assert.equal(await provider.connection.getBalance(payee.publicKey), LAMPORTS_PER_SOL / 10);
+  });
+
+  it("Fails the whole execution when the memo accounts are missing", async () => {
+    const proposal = await proposeTransfer(MEMO);
+    const { action } = await program.account.proposal.fetch(proposal);
+    const actionAccounts = executeRemainingAccounts(action, [multisigPda, vaultPda]);
+
+    await expectError(
+      program.methods
+        .executeTransaction()
+        .accounts({ multisig: multisigPda, proposal })
+        .remainingAccounts(actionAccounts)
+        .rpc(),
+      "MemoProgramMissing"
+    );
+    assert.equal(await provider.connection.getBalance(payee.publicKey), LAMPORTS_PER_SOL / 10);
+    assert.isFalse((await program.account.proposal.fetch(proposal)).executed);
+
+    await program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts([...actionAccounts, ...memoAccounts(MEMO, vaultPda)])
+      .rpc();
+    assert.equal(await provider.connection.getBalance(payee.publicKey), (2 * LAMPORTS_PER_SOL) / 10);
+  });
+
+  it("Does not invoke the Memo program without a memo", async () => {
+    const proposal = await proposeTransfer(null);
+    const sig = await execute(program, multisigPda, proposal);
+    assert.isFalse(await memoLogged(sig));
+  });
+});
@@ -0,0 +1,93 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  chainTime,
+  execute,
+  expectError,
+  findMultisigPda,
+  memoInstruction,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("execution window", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+
+  let multisigPda: PublicKey;
+
+  // 本地验证器无法直接调整时钟，这里等待链上时间越过目标
+  const waitUntil = async (t: number) => {
+    while ((await chainTime(provider)) <= t) {
+      await sleep(1000);
+    }
+  };
+
+  const windowed = async (text: string, notBefore: number, notAfter: number) => {
+    const proposal = await propose(program, multisigPda, member1, memoInstruction(text, [multisigPda]), {
+      notBefore: new anchor.BN(notBefore),
+      notAfter: new anchor.BN(notAfter),
+    });
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    return proposal;
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Rejects an inverted window", async () => {
+    const now = await chainTime(provider);
+    await expectError(windowed("inverted", now + 20, now + 10), "InvalidWindow");
+  });
+
+  it("Executes only inside the window", async () => {
+    const now = await chainTime(provider);
+    const notBefore = now + 8;
+    const proposal = await windowed("payroll", notBefore, notBefore + 600);
+
+    await expectError(execute(program, multisigPda, proposal), "TooEarly");
+    await waitUntil(notBefore);
+    await execute(program, multisigPda, proposal);
+  });
+
+  it("Becomes permissionlessly closable after not_after", async () => {
+    const now = await chainTime(provider);
+    const notAfter = now + 8;
+    const proposal = await windowed("missed", now - 60, notAfter);
+
+    const close = () =>
+      program.methods
+        .closeExpired()
+        .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+        .rpc();
+    await expectError(close(), "NotExpired");
+
+    await waitUntil(notAfter);
+    await expectError(execute(program, multisigPda, proposal), "TooLate");
+    await close();
+    assert.isNull(await provider.connection.getAccountInfo(proposal));
+  });
+});
@@ -0,0 +1,98 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  chainTime,
+  execute,
+  expectError,
+  findMultisigPda,
+  inviteAndAccept,
+  memoInstruction,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("member expiry", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2, member3] = members;
+  const contractor = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let expiresAt: number;
+  let pending: PublicKey;
+
+  before(async () => {
+    for (const m of [...members, contractor]) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+
+    // 合同工签名权 20 秒后到期
+    expiresAt = (await chainTime(provider)) + 20;
+    await inviteAndAccept(
+      program,
+      multisigPda,
+      [member1, member2],
+      contractor,
+      new anchor.BN(expiresAt)
+    );
+  });
+
+  it("Counts the contractor before expiry", async () => {
+    pending = await propose(program, multisigPda, member1, memoInstruction("before", [multisigPda]));
+    await approve(program, multisigPda, pending, contractor);
+    await approve(program, multisigPda, pending, member1);
+
+    const status = await program.methods.validateConfig().accounts({ multisig: multisigPda }).view();
+    assert.equal(status.activeMembers, 4);
+    assert.isNull(status.unreachableAt);
+  });
+
+  it("Ignores the contractor after expiry", async () => {
+    while ((await chainTime(provider)) <= expiresAt) {
+      await sleep(1000);
+    }
+
+    // 到期前的批准不再计入
+    await expectError(execute(program, multisigPda, pending), "NotExecutable");
+
+    const other = await propose(program, multisigPda, member1, memoInstruction("after", [multisigPda]));
+    await expectError(approve(program, multisigPda, other, contractor), "MembershipExpired");
+
+    await approve(program, multisigPda, pending, member3);
+    await execute(program, multisigPda, pending);
+  });
+
+  it("Reports when expirations would make the threshold unreachable", async () => {
+    const soon = (await chainTime(provider)) + 3600;
+    for (const m of [member2, member3]) {
+      const ix = await program.methods
+        .setMemberExpiry(m.publicKey, new anchor.BN(soon))
+        .accounts({ multisig: multisigPda, authority: multisigPda })
+        .instruction();
+      const proposal = await propose(program, multisigPda, member1, ix);
+      await approve(program, multisigPda, proposal, member1);
+      await approve(program, multisigPda, proposal, member2);
+      await execute(program, multisigPda, proposal);
+    }
+
+    const status = await program.methods.validateConfig().accounts({ multisig: multisigPda }).view();
+    assert.equal(status.requiredThreshold, 2);
+    assert.equal(status.unreachableAt.toNumber(), soon);
+  });
+});
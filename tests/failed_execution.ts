@@ -0,0 +1,239 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  closeAccount,
+  createAccount,
+  createAssociatedTokenAccount,
+  createMint,
+  createTransferInstruction,
+  mintTo,
+} from "@solana/spl-token";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  executeRemainingAccounts,
+  expectError,
+  findMultisigPda,
+  findProposalPda,
+  findVaultPda,
+  governed,
+  memoInstruction,
+  proposalOptions,
+  propose,
+  proposeAction,
+  sleep,
+  toInstructionData,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } = anchor.web3;
+
+// 与程序中的 CANCEL_REASON_EXECUTION_FAILED 一致
+const EXECUTION_FAILED = 6;
+
+describe("failed execution attempts", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+  const recipient = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  const ready = async (ix: anchor.web3.TransactionInstruction) => {
+    const proposal = await propose(program, multisigPda, member1, ix);
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    return proposal;
+  };
+
+  // 每次使用新的 reporter，避免相同交易被去重；类型化动作需显式传入其账户
+  const recordInstruction = async (proposal: PublicKey, accounts?: anchor.web3.AccountMeta[]) => {
+    const { action } = await program.account.proposal.fetch(proposal);
+    const reporter = Keypair.generate();
+    const ix = await program.methods
+      .recordFailedExecution()
+      .accounts({ multisig: multisigPda, proposal, reporter: reporter.publicKey })
+      .remainingAccounts(accounts ?? executeRemainingAccounts(action, [multisigPda, vaultPda]))
+      .instruction();
+    return { ix, reporter };
+  };
+  // 同一 slot 内只计一次，记录后等过当前 slot
+  const record = async (proposal: PublicKey, accounts?: anchor.web3.AccountMeta[]) => {
+    const { ix, reporter } = await recordInstruction(proposal, accounts);
+    const signature = await provider.sendAndConfirm(new anchor.web3.Transaction().add(ix), [reporter]);
+    await sleep(500);
+    return signature;
+  };
+
+  const recorded = async (run: () => Promise<unknown>) => {
+    const events: any[] = [];
+    const listener = program.addEventListener("executionFailureRecorded", (e) => events.push(e));
+    await run();
+    await sleep(1000);
+    await program.removeEventListener(listener);
+    assert.lengthOf(events, 1);
+    return events[0];
+  };
+
+  const closeExpired = (proposal: PublicKey) =>
+    program.methods
+      .closeExpired()
+      .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+      .rpc();
+
+  const overdraft = () =>
+    SystemProgram.transfer({
+      fromPubkey: vaultPda,
+      toPubkey: recipient.publicKey,
+      lamports: 1_000 * LAMPORTS_PER_SOL,
+    });
+
+  before(async () => {
+    await airdrop(provider, member1.publicKey);
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vaultPda, 1);
+  });
+
+  it("Counts a deterministic failure without executing the proposal", async () => {
+    const proposal = await propose(program, multisigPda, member1, overdraft());
+    // 未达到阈值时执行本就会失败，不计入
+    await expectError(record(proposal), "NotExecutable");
+    await approve(program, multisigPda, proposal, member2);
+
+    const event = await recorded(() => record(proposal));
+    assert.deepEqual(event.reason, { insufficientBalance: {} });
+    assert.equal(event.attempts, 1);
+    assert.isFalse(event.cancellable);
+    const account = await program.account.proposal.fetch(proposal);
+    assert.equal(account.executionAttempts, 1);
+    assert.isFalse(account.executed);
+    // 未设置上限时不可关闭
+    await expectError(closeExpired(proposal), "NotExpired");
+  });
+
+  it("Refuses to record proposals that would execute", async () => {
+    const proposal = await ready(memoInstruction("fine", [vaultPda]));
+    await expectError(record(proposal), "ExecutionNotFailing");
+    assert.equal((await program.account.proposal.fetch(proposal)).executionAttempts, 0);
+  });
+
+  it("Recognizes closed token accounts and unselected signers", async () => {
+    const mint = await createMint(provider.connection, member1, member1.publicKey, null, 0);
+    const source = await createAssociatedTokenAccount(
+      provider.connection,
+      member1,
+      mint,
+      vaultPda,
+      undefined,
+      undefined,
+      undefined,
+      true
+    );
+    await mintTo(provider.connection, member1, mint, source, member1, 10);
+    const destination = await createAccount(provider.connection, member1, mint, member1.publicKey, Keypair.generate());
+    const transfer = await ready(createTransferInstruction(source, destination, vaultPda, 5));
+    await closeAccount(provider.connection, member1, destination, member1.publicKey, member1);
+    const missing = await recorded(() => record(transfer));
+    assert.deepEqual(missing.reason, { missingAccount: {} });
+
+    // 金库被标记为签名者，但 signers 位未选中
+    const [proposal] = findProposalPda(
+      program.programId,
+      multisigPda,
+      (await program.account.multisig.fetch(multisigPda)).proposalsCount
+    );
+    await program.methods
+      .proposeTransaction(toInstructionData(memoInstruction("unsigned", [vaultPda]), 0), proposalOptions({}))
+      .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    const mismatch = await recorded(() => record(proposal));
+    assert.deepEqual(mismatch.reason, { metaMismatch: {} });
+  });
+
+  it("Counts at most one failure per slot", async () => {
+    const proposal = await ready(overdraft());
+    const first = await recordInstruction(proposal);
+    const second = await recordInstruction(proposal);
+    await expectError(
+      provider.sendAndConfirm(new anchor.web3.Transaction().add(first.ix, second.ix), [
+        first.reporter,
+        second.reporter,
+      ]),
+      "FailureAlreadyRecorded"
+    );
+    assert.equal((await program.account.proposal.fetch(proposal)).executionAttempts, 0);
+    await record(proposal);
+    await record(proposal);
+    assert.equal((await program.account.proposal.fetch(proposal)).executionAttempts, 2);
+  });
+
+  it("Recognizes typed actions that cannot succeed", async () => {
+    const mint = await createMint(provider.connection, member1, member1.publicKey, null, 0);
+    const source = await createAssociatedTokenAccount(
+      provider.connection,
+      member1,
+      mint,
+      vaultPda,
+      undefined,
+      undefined,
+      undefined,
+      true
+    );
+    await mintTo(provider.connection, member1, mint, source, member1, 10);
+    const proposal = await proposeAction(program, multisigPda, member1, {
+      burn: { mint, amount: new anchor.BN(11) },
+    });
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    const accounts = [{ pubkey: source, isSigner: false, isWritable: true }];
+    const event = await recorded(() => record(proposal, accounts));
+    assert.deepEqual(event.reason, { insufficientBalance: {} });
+    // 所需账户未传入是调用方的问题
+    await expectError(record(proposal, []), "AccountMismatch");
+  });
+
+  it("Lets anyone close a proposal once attempts exceed the limit", async () => {
+    const ix = await program.methods
+      .setMaxExecutionAttempts(2)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, members, ix);
+
+    const proposal = await ready(overdraft());
+    await record(proposal);
+    await record(proposal);
+    await expectError(closeExpired(proposal), "NotExpired");
+    const third = await recorded(() => record(proposal));
+    assert.equal(third.attempts, 3);
+    assert.isTrue(third.cancellable);
+
+    const events: any[] = [];
+    const listener = program.addEventListener("proposalCancelled", (e) => events.push(e));
+    await closeExpired(proposal);
+    await sleep(1000);
+    await program.removeEventListener(listener);
+    assert.isNull(await provider.connection.getAccountInfo(proposal));
+    assert.lengthOf(events, 1);
+    assert.equal(events[0].reason, EXECUTION_FAILED);
+    assert.isNull(events[0].canceller);
+  });
+});
@@ -0,0 +1,91 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  memoInstruction,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("emergency freeze", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2, member3] = members;
+  const outsider = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let pending: PublicKey;
+
+  const freeze = (member: typeof member1) =>
+    program.methods
+      .emergencyFreeze()
+      .accounts({ multisig: multisigPda, member: member.publicKey })
+      .signers([member])
+      .rpc();
+
+  const unfreeze = async () => {
+    const ix = await program.methods.unfreeze().accounts({ multisig: multisigPda, authority: multisigPda }).instruction();
+    const proposal = await propose(program, multisigPda, member1, ix);
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    await execute(program, multisigPda, proposal);
+  };
+
+  before(async () => {
+    for (const m of [...members, outsider]) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+
+    pending = await propose(program, multisigPda, member1, memoInstruction("pending", [multisigPda]));
+    await approve(program, multisigPda, pending, member1);
+  });
+
+  it("Lets any single member freeze", async () => {
+    await expectError(freeze(outsider), "NotMember");
+    await freeze(member3);
+
+    const account = await program.account.multisig.fetch(multisigPda);
+    assert.ok(account.frozenBy.equals(member3.publicKey));
+    await expectError(freeze(member2), "Frozen");
+  });
+
+  it("Blocks approve and execute but not propose", async () => {
+    await expectError(approve(program, multisigPda, pending, member2), "Frozen");
+
+    const other = await propose(program, multisigPda, member2, memoInstruction("while frozen", [multisigPda]));
+    assert.isNotNull(await provider.connection.getAccountInfo(other));
+  });
+
+  it("Unfreezes through a self-governed proposal", async () => {
+    await unfreeze();
+
+    const account = await program.account.multisig.fetch(multisigPda);
+    assert.isNull(account.frozenBy);
+    await approve(program, multisigPda, pending, member2);
+    await execute(program, multisigPda, pending);
+  });
+
+  it("Stops the previous freezer from toggling again during the cooldown", async () => {
+    await expectError(freeze(member3), "FreezeCooldown");
+    // 其他成员不受影响
+    await freeze(member2);
+    await unfreeze();
+  });
+});
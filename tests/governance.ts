@@ -0,0 +1,121 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  inviteAndAccept,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("self-governance and approval bitmap", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const member3 = Keypair.generate();
+  const newcomer = Keypair.generate();
+  const members = [member1, member2, member3];
+
+  let multisigPda: PublicKey;
+
+  // 按成员在（排序后）members 中的下标计算位
+  const bitOf = async (key: PublicKey) => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    const index = account.members.findIndex((m: any) => m.key.equals(key));
+    assert.isAtLeast(index, 0);
+    return 1 << index;
+  };
+
+  before(async () => {
+    for (const m of [...members, newcomer]) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Records approvals as member bits", async () => {
+    const ix = await program.methods
+      .changeThreshold(2)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    const proposal = await propose(program, multisigPda, member1, ix);
+
+    await approve(program, multisigPda, proposal, member2);
+    await approve(program, multisigPda, proposal, member3);
+
+    const account = await program.account.proposal.fetch(proposal);
+    const expected = (await bitOf(member2.publicKey)) | (await bitOf(member3.publicKey));
+    assert.equal(account.approvals, expected);
+    assert.equal(account.version, 2);
+
+    await expectError(approve(program, multisigPda, proposal, member2), "AlreadyApproved");
+    await expectError(approve(program, multisigPda, proposal, newcomer), "NotMember");
+  });
+
+  it("Rejects execution below threshold", async () => {
+    const ix = await program.methods
+      .changeThreshold(3)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    const proposal = await propose(program, multisigPda, member1, ix);
+    await approve(program, multisigPda, proposal, member1);
+
+    await expectError(execute(program, multisigPda, proposal), "NotExecutable");
+  });
+
+  it("Invalidates approvals made before a membership change", async () => {
+    // 先建一个已获批准但未执行的提案
+    const pendingIx = await program.methods
+      .changeThreshold(1)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    const pending = await propose(program, multisigPda, member1, pendingIx);
+    await approve(program, multisigPda, pending, member1);
+    await approve(program, multisigPda, pending, member2);
+
+    // 通过自治提案邀请新成员，新成员接受后加入
+    await inviteAndAccept(program, multisigPda, [member1, member3], newcomer);
+
+    const account = await program.account.multisig.fetch(multisigPda);
+    assert.equal(account.members.length, 4);
+    assert.equal(account.configSeqno, 1);
+
+    // 下标已变化，旧位图不能再被解释
+    await expectError(execute(program, multisigPda, pending), "StaleProposal");
+    await expectError(approve(program, multisigPda, pending, member3), "StaleProposal");
+  });
+
+  it("Counts approvals against the new member set", async () => {
+    const ix = await program.methods
+      .changeThreshold(3)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    const proposal = await propose(program, multisigPda, newcomer, ix);
+
+    await approve(program, multisigPda, proposal, newcomer);
+    let account = await program.account.proposal.fetch(proposal);
+    assert.equal(account.approvals, await bitOf(newcomer.publicKey));
+    assert.equal(account.configSeqno, 1);
+
+    await approve(program, multisigPda, proposal, member1);
+    await execute(program, multisigPda, proposal);
+
+    const multisig = await program.account.multisig.fetch(multisigPda);
+    assert.equal(multisig.threshold, 3);
+  });
+});
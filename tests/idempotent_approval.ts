@@ -0,0 +1,76 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import { airdrop, approve, expectError, findMultisigPda, MEMO_PROGRAM_ID, propose, sleep } from "./utils";
+
+const { PublicKey, Keypair, Transaction, TransactionInstruction } = anchor.web3;
+
+describe("idempotent approval", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2] = members;
+
+  let multisigPda: PublicKey;
+  let proposal: PublicKey;
+
+  const approveIdempotent = (approver: anchor.web3.Keypair) =>
+    program.methods
+      .approveIdempotent()
+      .accounts({ multisig: multisigPda, proposal, approver: approver.publicKey })
+      .instruction();
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 3, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    const ix = new TransactionInstruction({ programId: MEMO_PROGRAM_ID, keys: [], data: Buffer.from("retry") });
+    proposal = await propose(program, multisigPda, member1, ix);
+  });
+
+  it("Treats a repeated approval as a no-op and keeps the rest of the transaction", async () => {
+    await provider.sendAndConfirm(new Transaction().add(await approveIdempotent(member2)), [member2]);
+
+    const events: any[] = [];
+    const listener = program.addEventListener("approvalAlreadyPresent", (event) => {
+      events.push(event);
+    });
+    // 重试的批准与其他指令打包在同一笔交易中
+    const memo = new TransactionInstruction({ programId: MEMO_PROGRAM_ID, keys: [], data: Buffer.from("bundled") });
+    await provider.sendAndConfirm(new Transaction().add(await approveIdempotent(member2), memo), [member2]);
+    await sleep(1000);
+    await program.removeEventListener(listener);
+
+    assert.equal(events.length, 1);
+    assert.isTrue(events[0].member.equals(member2.publicKey));
+    // 成员按公钥排序存储，位序须从链上查
+    const index = (await program.account.multisig.fetch(multisigPda)).members.findIndex((m) =>
+      m.key.equals(member2.publicKey)
+    );
+    const { approvals } = await program.account.proposal.fetch(proposal);
+    assert.equal(approvals, 1 << index);
+  });
+
+  it("Keeps approve_transaction strict", async () => {
+    await expectError(approve(program, multisigPda, proposal, member2), "AlreadyApproved");
+  });
+
+  it("Still rejects non-members", async () => {
+    const outsider = Keypair.generate();
+    await airdrop(provider, outsider.publicKey);
+    await expectError(
+      provider.sendAndConfirm(new Transaction().add(await approveIdempotent(outsider)), [outsider]),
+      "NotMember"
+    );
+  });
+});
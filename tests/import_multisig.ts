@@ -0,0 +1,70 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { createHash } from "crypto";
+import { Multisig } from "../target/types/multisig";
+import { airdrop, expectError, findMultisigPda } from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+// Anchor.toml 中预置的旧 serum multisig 账户，见 programs/multisig/tests/fixtures/serum_multisig.json
+const LEGACY = new PublicKey("GcrW2JAP9w9MoT9GdJZFR21TNrHBJgnmpk9xkPjPZRvR");
+const legacyOwner = (i: number) =>
+  new PublicKey(createHash("sha256").update(`legacy-owner-${i}`).digest());
+
+describe("import from a serum multisig", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const creator = Keypair.generate();
+
+  const importFrom = (source: PublicKey, nonce: number) => {
+    const [multisig] = findMultisigPda(program.programId, creator.publicKey, nonce);
+    return {
+      multisig,
+      tx: program.methods
+        .importMultisig(nonce, 10)
+        .accounts({ multisig, source, creator: creator.publicKey })
+        .signers([creator])
+        .rpc(),
+    };
+  };
+
+  before(async () => {
+    await airdrop(provider, creator.publicKey);
+  });
+
+  it("Copies owners and threshold and links the two accounts", async () => {
+    const event = new Promise<any>((resolve) => {
+      const id = program.addEventListener("multisigImported", (e) => {
+        program.removeEventListener(id);
+        resolve(e);
+      });
+    });
+    const { multisig, tx } = importFrom(LEGACY, 0);
+    await tx;
+
+    const account = await program.account.multisig.fetch(multisig);
+    const owners = [0, 1, 2].map(legacyOwner).sort((a, b) => a.toBuffer().compare(b.toBuffer()));
+    assert.deepEqual(
+      account.members.map((m) => m.key.toBase58()),
+      owners.map((o) => o.toBase58())
+    );
+    assert.equal(account.threshold, 2);
+
+    const imported = await event;
+    assert.ok(imported.multisig.equals(multisig));
+    assert.ok(imported.source.equals(LEGACY));
+    assert.equal(imported.members, 3);
+    assert.equal(imported.ownerSetSeqno, 3);
+  });
+
+  it("Rejects accounts not owned by the legacy program", async () => {
+    // 本程序的多签与旧多签判别符相同，只能靠 owner 拒绝
+    const [ours] = findMultisigPda(program.programId, creator.publicKey, 0);
+    await expectError(importFrom(ours, 1).tx, "InvalidLegacyMultisig");
+    await expectError(importFrom(creator.publicKey, 2).tx, "InvalidLegacyMultisig");
+  });
+});
@@ -0,0 +1,151 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  actionHash,
+  airdrop,
+  approve,
+  executeAccounts,
+  expectError,
+  findInstructionIndexPda,
+  findMultisigPda,
+  findProposalPda,
+  memoInstruction,
+  proposalOptions,
+  ProposalOptions,
+  signerBits,
+  toInstructionData,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("instruction index", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+
+  let multisigPda: PublicKey;
+
+  const indexOf = (text: string) => {
+    const ix = memoInstruction(text, [multisigPda]);
+    const instruction = toInstructionData(ix, signerBits(program.programId, multisigPda, ix));
+    const hash = actionHash(program, { raw: [instruction] });
+    const [index] = findInstructionIndexPda(program.programId, multisigPda, hash);
+    return { instruction, index };
+  };
+
+  // 登记内容索引；索引已存在时一并传入它当前指向的提案
+  const proposeIndexed = async (text: string, options: Partial<ProposalOptions> = {}) => {
+    const { instruction, index } = indexOf(text);
+    const existing = await program.account.instructionIndex.fetchNullable(index);
+    const { proposalsCount } = await program.account.multisig.fetch(multisigPda);
+    const [proposal] = findProposalPda(program.programId, multisigPda, proposalsCount);
+    await program.methods
+      .proposeTransaction(instruction, proposalOptions(options))
+      .accounts({
+        multisig: multisigPda,
+        proposal,
+        proposer: member1.publicKey,
+        instructionIndex: index,
+        indexedProposal: existing?.proposal ?? null,
+      })
+      .signers([member1])
+      .rpc();
+    return { proposal, index };
+  };
+
+  const cancel = (proposal: PublicKey, instructionIndex: PublicKey | null) =>
+    program.methods
+      .cancelTransaction(0, null)
+      .accounts({ multisig: multisigPda, proposal, canceller: member1.publicKey, instructionIndex })
+      .signers([member1])
+      .rpc();
+
+  before(async () => {
+    await airdrop(provider, member1.publicKey);
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Rejects a duplicate of an active proposal unless allowed", async () => {
+    const { proposal, index } = await proposeIndexed("pay invoice 17");
+    const account = await program.account.instructionIndex.fetch(index);
+    assert.ok(account.proposal.equals(proposal));
+    assert.ok(account.multisig.equals(multisigPda));
+
+    await expectError(proposeIndexed("pay invoice 17"), "DuplicateProposal");
+
+    // 有意重复时仍可创建，索引继续指向原提案
+    const duplicate = await proposeIndexed("pay invoice 17", { allowDuplicate: true });
+    assert.ok((await program.account.instructionIndex.fetch(index)).proposal.equals(proposal));
+    await cancel(duplicate.proposal, null);
+
+    // 执行时一并关闭索引，之后可再次提交相同内容
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    const { action } = await program.account.proposal.fetch(proposal);
+    await program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal, spendLedger: null, instructionIndex: index })
+      .remainingAccounts(executeAccounts(action.raw[0], [multisigPda]))
+      .rpc();
+    assert.isNull(await provider.connection.getAccountInfo(index));
+
+    const again = await proposeIndexed("pay invoice 17");
+    await cancel(again.proposal, again.index);
+    assert.isNull(await provider.connection.getAccountInfo(again.index));
+  });
+
+  it("Repoints an index whose proposal ended without closing it", async () => {
+    const first = await proposeIndexed("rotate key");
+    await cancel(first.proposal, null);
+    assert.isNotNull(await provider.connection.getAccountInfo(first.index));
+
+    const second = await proposeIndexed("rotate key");
+    assert.ok(second.index.equals(first.index));
+    assert.ok((await program.account.instructionIndex.fetch(second.index)).proposal.equals(second.proposal));
+  });
+
+  it("Lets anyone close the index once its proposal has ended", async () => {
+    const { proposal, index } = await proposeIndexed("close me");
+    const closeIndex = () =>
+      program.methods
+        .closeInstructionIndex()
+        .accounts({ multisig: multisigPda, instructionIndex: index, proposal })
+        .rpc();
+
+    await expectError(closeIndex(), "ProposalStillOpen");
+    await cancel(proposal, null);
+    const before = await provider.connection.getBalance(multisigPda);
+    const rent = await provider.connection.getBalance(index);
+    await closeIndex();
+    assert.isNull(await provider.connection.getAccountInfo(index));
+    assert.equal(await provider.connection.getBalance(multisigPda), before + rent);
+  });
+
+  it("Refuses an index address that does not match the content", async () => {
+    const { index } = indexOf("something else");
+    const { instruction } = indexOf("mismatch");
+    const { proposalsCount } = await program.account.multisig.fetch(multisigPda);
+    const [proposal] = findProposalPda(program.programId, multisigPda, proposalsCount);
+    await expectError(
+      program.methods
+        .proposeTransaction(instruction, proposalOptions())
+        .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey, instructionIndex: index })
+        .signers([member1])
+        .rpc(),
+      "AccountMismatch"
+    );
+  });
+});
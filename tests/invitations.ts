@@ -0,0 +1,98 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  chainTime,
+  expectError,
+  findMultisigPda,
+  governed,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("member invitations", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2] = members;
+  const invitee = Keypair.generate();
+  const revoked = Keypair.generate();
+  const late = Keypair.generate();
+
+  let multisigPda: PublicKey;
+
+  const invite = async (key: PublicKey, inviteExpiresAt: anchor.BN | null = null) => {
+    const ix = await program.methods
+      .inviteMember(key, null, inviteExpiresAt)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, [member1, member2], ix);
+  };
+
+  const accept = (who: typeof invitee) =>
+    program.methods
+      .acceptMembership()
+      .accounts({ multisig: multisigPda, invitee: who.publicKey })
+      .signers([who])
+      .rpc();
+
+  before(async () => {
+    for (const m of [...members, invitee, revoked, late]) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Adds an invitee only after they accept", async () => {
+    await invite(invitee.publicKey);
+    let account = await program.account.multisig.fetch(multisigPda);
+    assert.equal(account.members.length, 3);
+    assert.equal(account.pendingInvites.length, 1);
+
+    await accept(invitee);
+    account = await program.account.multisig.fetch(multisigPda);
+    assert.equal(account.members.length, 4);
+    assert.equal(account.pendingInvites.length, 0);
+    assert.ok(account.members.some((m: any) => m.key.equals(invitee.publicKey)));
+  });
+
+  it("Rejects inviting an existing member", async () => {
+    const ix = await program.methods
+      .inviteMember(member2.publicKey, null, null)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await expectError(governed(program, multisigPda, [member1, member2], ix), "AlreadyMember");
+  });
+
+  it("Cannot accept a revoked invitation", async () => {
+    await invite(revoked.publicKey);
+    const ix = await program.methods
+      .revokeInvitation(revoked.publicKey)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, [member1, member2], ix);
+
+    await expectError(accept(revoked), "NotInvited");
+  });
+
+  it("Cannot accept an expired invitation", async () => {
+    const expiresAt = (await chainTime(provider)) + 15;
+    await invite(late.publicKey, new anchor.BN(expiresAt));
+    while ((await chainTime(provider)) <= expiresAt) {
+      await sleep(1000);
+    }
+
+    await expectError(accept(late), "InvitationExpired");
+  });
+});
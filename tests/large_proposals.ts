@@ -0,0 +1,118 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  findBufferPda,
+  findMultisigPda,
+  memoInstruction,
+  propose,
+  UploadProgress,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("propose falls back to buffers for large payloads", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+
+  let multisigPda: PublicKey;
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Keeps small payloads inline", async () => {
+    const progress: UploadProgress[] = [];
+    const proposal = await propose(
+      program,
+      multisigPda,
+      member1,
+      memoInstruction("small", [multisigPda]),
+      {},
+      { onProgress: (p) => progress.push(p) }
+    );
+    const { action } = await program.account.proposal.fetch(proposal);
+    assert.ok(action.raw);
+    assert.lengthOf(progress, 0);
+  });
+
+  it("Uploads a 6 KB payload through the same helper and executes it", async () => {
+    const text = "abcdefghij".repeat(600);
+    const ix = memoInstruction(text, [multisigPda]);
+    const progress: UploadProgress[] = [];
+    const proposal = await propose(program, multisigPda, member1, ix, {}, {
+      onProgress: (p) => progress.push(p),
+    });
+
+    const { action } = await program.account.proposal.fetch(proposal);
+    const buffer = await program.account.instructionBuffer.fetch(action.buffered[0].buffer);
+    assert.ok(Buffer.from(buffer.data).equals(Buffer.from(ix.data)));
+    assert.isTrue(buffer.finalized);
+
+    const stages = progress.map((p) => p.stage);
+    assert.equal(stages[0], "create");
+    assert.isAtLeast(stages.filter((s) => s === "write").length, 6);
+    assert.deepEqual(stages.slice(-2), ["finalize", "propose"]);
+    assert.equal(progress[progress.length - 1].written, 6000);
+
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    await execute(program, multisigPda, proposal);
+    assert.isNull(await provider.connection.getAccountInfo(proposal));
+  });
+
+  it("Resumes an interrupted upload without rewriting confirmed chunks", async () => {
+    const data = Buffer.from(memoInstruction("0123456789".repeat(300), [multisigPda]).data);
+    const id = 42;
+    const [buffer] = findBufferPda(program.programId, multisigPda, member1.publicKey, id);
+    // 模拟只写完第一段后中断
+    await program.methods
+      .createBuffer(id, data.length)
+      .accounts({ multisig: multisigPda, buffer, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await program.methods
+      .writeBuffer(0, data.subarray(0, 1000))
+      .accounts({ buffer, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+
+    // 手续费由 provider 钱包支付，每笔交易 2 个签名
+    const before = await provider.connection.getBalance(provider.wallet.publicKey);
+    const progress: UploadProgress[] = [];
+    const proposal = await propose(
+      program,
+      multisigPda,
+      member1,
+      memoInstruction("0123456789".repeat(300), [multisigPda]),
+      {},
+      { bufferId: id, chunkSize: 1000, onProgress: (p) => progress.push(p) }
+    );
+    const fees = before - (await provider.connection.getBalance(provider.wallet.publicKey));
+
+    assert.notInclude(progress.map((p) => p.stage), "create");
+    // 2 段待写 + finalize + propose_buffered；已写入的第一段被跳过
+    assert.equal(fees, 4 * 2 * 5000);
+    const { action } = await program.account.proposal.fetch(proposal);
+    assert.ok(action.buffered[0].buffer.equals(buffer));
+  });
+});
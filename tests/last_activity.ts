@@ -0,0 +1,132 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  chainTime,
+  execute,
+  findMultisigPda,
+  governed,
+  memoInstruction,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("last activity", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const guardian = Keypair.generate();
+  const members = [member1, member2];
+
+  let multisigPda: PublicKey;
+
+  const lastActivity = async () =>
+    (await program.account.multisig.fetch(multisigPda)).lastActivity.toNumber();
+
+  // 链上时间以秒为单位：先等到时间越过当前记录，再断言操作把它推进
+  const assertMoves = async <T>(action: () => Promise<T>): Promise<T> => {
+    const before = await lastActivity();
+    while ((await chainTime(provider)) <= before) {
+      await sleep(500);
+    }
+    const result = await action();
+    assert.isAbove(await lastActivity(), before);
+    return result;
+  };
+
+  const proposeMemo = (text: string, notAfter: number | null = null) =>
+    propose(program, multisigPda, member1, memoInstruction(text, [multisigPda]), {
+      notAfter: notAfter === null ? null : new anchor.BN(notAfter),
+    });
+
+  before(async () => {
+    for (const m of [...members, guardian]) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+  });
+
+  it("Is set at creation", async () => {
+    const now = await chainTime(provider);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    assert.isAtLeast(await lastActivity(), now);
+  });
+
+  it("Moves on propose, vote and execute", async () => {
+    const proposal = await assertMoves(() => proposeMemo("activity"));
+    await assertMoves(() => approve(program, multisigPda, proposal, member1));
+    await assertMoves(() =>
+      program.methods
+        .abstainTransaction()
+        .accounts({ multisig: multisigPda, proposal, approver: member2.publicKey })
+        .signers([member2])
+        .rpc()
+    );
+    await assertMoves(() => approve(program, multisigPda, proposal, member2));
+
+    let seen: any = null;
+    const listener = program.addEventListener("proposalExecuted", (event) => {
+      seen = event;
+    });
+    await assertMoves(() => execute(program, multisigPda, proposal));
+    await sleep(1000);
+    await program.removeEventListener(listener);
+    assert.equal(seen?.timestamp.toNumber(), await lastActivity());
+  });
+
+  it("Moves on cancel and cancel_all", async () => {
+    const cancelled = await proposeMemo("cancelled");
+    await assertMoves(() =>
+      program.methods
+        .cancelTransaction(0, null)
+        .accounts({ multisig: multisigPda, proposal: cancelled, canceller: member1.publicKey })
+        .signers([member1])
+        .rpc()
+    );
+
+    const ix = await program.methods
+      .setGuardian(guardian.publicKey)
+      .accounts({ multisig: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, members, ix);
+    const pending = await proposeMemo("pending");
+    await assertMoves(() =>
+      program.methods
+        .cancelAll(null)
+        .accounts({ multisig: multisigPda, authority: guardian.publicKey })
+        .remainingAccounts([
+          { pubkey: pending, isSigner: false, isWritable: true },
+          { pubkey: member1.publicKey, isSigner: false, isWritable: true },
+        ])
+        .signers([guardian])
+        .rpc()
+    );
+  });
+
+  it("Does not move on permissionless expiry cleanup", async () => {
+    const notAfter = (await chainTime(provider)) + 3;
+    const expired = await proposeMemo("expired", notAfter);
+    while ((await chainTime(provider)) <= notAfter) {
+      await sleep(1000);
+    }
+    const before = await lastActivity();
+    await program.methods
+      .closeExpired()
+      .accounts({ multisig: multisigPda, proposal: expired, proposer: member1.publicKey })
+      .rpc();
+    assert.equal(await lastActivity(), before);
+  });
+});
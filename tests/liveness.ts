@@ -0,0 +1,134 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  governed,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } = anchor.web3;
+
+// 4-of-5，失联成员最多把阈值降到 3
+describe("member liveness", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [0, 1, 2, 3, 4].map(() => Keypair.generate());
+  const [m0, m1, m2, m3, m4] = members;
+  const payee = Keypair.generate();
+  const TIMEOUT = 2;
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  const setLiveness = async (timeout: number | null, floor: number) =>
+    program.methods
+      .setLiveness(timeout, floor)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+
+  const heartbeat = (member: anchor.web3.Keypair) =>
+    program.methods.heartbeat().accounts({ multisig: multisigPda, member: member.publicKey }).signers([member]).rpc();
+
+  const markInactive = (member: anchor.web3.Keypair) =>
+    program.methods.markInactive(member.publicKey).accounts({ multisig: multisigPda }).rpc();
+
+  const memberState = async (member: anchor.web3.Keypair) =>
+    (await program.account.multisig.fetch(multisigPda)).members.find((m) => m.key.equals(member.publicKey));
+
+  const proposePayout = async (approvers: anchor.web3.Keypair[]) => {
+    const ix = SystemProgram.transfer({ fromPubkey: vaultPda, toPubkey: payee.publicKey, lamports: LAMPORTS_PER_SOL / 10 });
+    const proposal = await propose(program, multisigPda, approvers[0], ix);
+    for (const a of approvers) {
+      await approve(program, multisigPda, proposal, a);
+    }
+    return proposal;
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, m0.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 4, 10)
+      .accounts({ multisig: multisigPda, creator: m0.publicKey })
+      .signers([m0])
+      .rpc();
+    await airdrop(provider, vaultPda);
+  });
+
+  it("Requires the regime to be enabled with a reachable floor", async () => {
+    await expectError(markInactive(m4), "LivenessDisabled");
+    await expectError(governed(program, multisigPda, [m0, m1, m2, m3], await setLiveness(TIMEOUT, 0)), "InvalidLivenessFloor");
+    await expectError(governed(program, multisigPda, [m0, m1, m2, m3], await setLiveness(TIMEOUT, 6)), "InvalidLivenessFloor");
+
+    await governed(program, multisigPda, [m0, m1, m2, m3], await setLiveness(TIMEOUT, 3));
+    const account = await program.account.multisig.fetch(multisigPda);
+    assert.equal(account.inactivityTimeout, TIMEOUT);
+    assert.equal(account.livenessFloor, 3);
+    // 开启时所有成员重新计时
+    await expectError(markInactive(m4), "MemberNotIdle");
+  });
+
+  it("Drops idle members from the threshold denominator", async () => {
+    await sleep((TIMEOUT + 1) * 1000);
+    for (const m of [m0, m1, m2]) {
+      await heartbeat(m);
+    }
+    await expectError(markInactive(m0), "MemberNotIdle");
+
+    let marked: any = null;
+    const listener = program.addEventListener("memberMarkedInactive", (event) => {
+      marked = event;
+    });
+    await markInactive(m3);
+    await markInactive(m4);
+    await sleep(1000);
+    await program.removeEventListener(listener);
+    assert.isTrue(marked.member.equals(m4.publicKey));
+    assert.equal(marked.liveMembers, 3);
+    await expectError(markInactive(m4), "MemberNotIdle");
+
+    // 4-of-5 在只剩 3 名在线成员时按 3 执行
+    await execute(program, multisigPda, await proposePayout([m0, m1, m2]));
+    assert.equal(await provider.connection.getBalance(payee.publicKey), LAMPORTS_PER_SOL / 10);
+  });
+
+  it("Never lowers the bar below the floor", async () => {
+    await sleep((TIMEOUT + 1) * 1000);
+    // m2 已超时，但标记后在线成员会少于 liveness_floor
+    await expectError(markInactive(m2), "LivenessFloorReached");
+    assert.isFalse((await memberState(m2)).inactive);
+
+    const proposal = await proposePayout([m0, m1]);
+    await expectError(execute(program, multisigPda, proposal), "NotExecutable");
+  });
+
+  it("Reactivates members on their next signature", async () => {
+    await heartbeat(m3);
+    assert.isFalse((await memberState(m3)).inactive);
+
+    // 在线成员恢复为 4，阈值回到 4
+    const proposal = await proposePayout([m0, m1, m2]);
+    await expectError(execute(program, multisigPda, proposal), "NotExecutable");
+
+    // 投票同样算作签名
+    assert.isTrue((await memberState(m4)).inactive);
+    await approve(program, multisigPda, proposal, m4);
+    assert.isFalse((await memberState(m4)).inactive);
+    await execute(program, multisigPda, proposal);
+    assert.equal(await provider.connection.getBalance(payee.publicKey), (2 * LAMPORTS_PER_SOL) / 10);
+  });
+});
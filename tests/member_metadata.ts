@@ -0,0 +1,134 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { createHash } from "crypto";
+import { Multisig } from "../target/types/multisig";
+import { airdrop, expectError, findMultisigPda, findVaultPda, governed } from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("member metadata", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2, member3] = members;
+  const outsider = Keypair.generate();
+  const notifier = Keypair.generate().publicKey;
+  const contact = Array.from(createHash("sha256").update("ops@example.com").digest());
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  const metadataPda = (member: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("member-meta"), multisigPda.toBuffer(), member.toBuffer()],
+      program.programId
+    )[0];
+
+  const create = (member: PublicKey, authority: anchor.web3.Keypair, label: string) =>
+    program.methods
+      .createMemberMetadata(member, label, notifier, contact)
+      .accounts({ multisig: multisigPda, authority: authority.publicKey, payer: authority.publicKey })
+      .signers([authority])
+      .rpc();
+
+  const update = (member: PublicKey, authority: anchor.web3.Keypair, label: string) =>
+    program.methods
+      .updateMemberMetadata(label, null, contact)
+      .accounts({ multisig: multisigPda, metadata: metadataPda(member), authority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+
+  const close = (member: PublicKey, closer: anchor.web3.Keypair, payer: PublicKey) =>
+    program.methods
+      .closeMemberMetadata()
+      .accounts({ multisig: multisigPda, metadata: metadataPda(member), payer, closer: closer.publicKey })
+      .signers([closer])
+      .rpc();
+
+  before(async () => {
+    for (const k of [...members, outsider]) {
+      await airdrop(provider, k.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vaultPda, 1);
+  });
+
+  it("Lets a member create and update their own metadata", async () => {
+    const events: any[] = [];
+    const id = program.addEventListener("memberMetadataSet", (e) => events.push(e));
+    await create(member1.publicKey, member1, "treasury lead");
+    await update(member1.publicKey, member1, "treasury");
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.removeEventListener(id);
+
+    const metadata = await program.account.memberMetadata.fetch(metadataPda(member1.publicKey));
+    assert.ok(metadata.member.equals(member1.publicKey));
+    assert.ok(metadata.payer.equals(member1.publicKey));
+    assert.equal(metadata.label, "treasury");
+    assert.isNull(metadata.notificationKey);
+    assert.deepEqual(metadata.contactHash, contact);
+
+    assert.lengthOf(events, 2);
+    assert.equal(events[0].label, "treasury lead");
+    assert.ok(events[0].notificationKey.equals(notifier));
+    assert.ok(events[1].updatedBy.equals(member1.publicKey));
+  });
+
+  it("Rejects writes by non-members, other members and oversized labels", async () => {
+    await expectError(create(member2.publicKey, member1, "not mine"), "Unauthorized");
+    await expectError(create(member2.publicKey, outsider, "not mine"), "Unauthorized");
+    // 非成员也不能为自己登记
+    await expectError(create(outsider.publicKey, outsider, "outsider"), "NotMember");
+    await expectError(update(member1.publicKey, member2, "hijacked"), "Unauthorized");
+    await expectError(update(member1.publicKey, outsider, "hijacked"), "Unauthorized");
+    await expectError(update(member1.publicKey, member1, "x".repeat(33)), "InvalidMemberLabel");
+    await expectError(close(member1.publicKey, member2, member1.publicKey), "Unauthorized");
+    assert.equal(
+      (await program.account.memberMetadata.fetch(metadataPda(member1.publicKey))).label,
+      "treasury"
+    );
+  });
+
+  it("Lets governance write any member's metadata with the vault paying rent", async () => {
+    const ix = await program.methods
+      .createMemberMetadata(member3.publicKey, "signer 3", notifier, contact)
+      .accounts({ multisig: multisigPda, authority: multisigPda, payer: vaultPda })
+      .instruction();
+    await governed(program, multisigPda, [member1, member2], ix);
+
+    const metadata = await program.account.memberMetadata.fetch(metadataPda(member3.publicKey));
+    assert.equal(metadata.label, "signer 3");
+    assert.ok(metadata.payer.equals(vaultPda));
+  });
+
+  it("Lets anyone clean up once the member is removed", async () => {
+    const metadata = metadataPda(member3.publicKey);
+    await expectError(close(member3.publicKey, outsider, vaultPda), "Unauthorized");
+
+    const ix = await program.methods
+      .removeMember(member3.publicKey)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, [member1, member2], ix);
+
+    const before = await provider.connection.getBalance(vaultPda);
+    const rent = (await provider.connection.getAccountInfo(metadata)).lamports;
+    await close(member3.publicKey, outsider, vaultPda);
+    assert.isNull(await provider.connection.getAccountInfo(metadata));
+    assert.equal(await provider.connection.getBalance(vaultPda), before + rent);
+
+    // 成员本人随时可关闭自己的记录
+    await close(member1.publicKey, member1, member1.publicKey);
+    assert.isNull(await provider.connection.getAccountInfo(metadataPda(member1.publicKey)));
+  });
+});
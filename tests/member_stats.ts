@@ -0,0 +1,129 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  expectError,
+  findMemberStatsPda,
+  findMultisigPda,
+  findProposalPda,
+  memoInstruction,
+  proposalOptions,
+  signerBits,
+  toInstructionData,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("member stats", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const member3 = Keypair.generate();
+  const members = [member1, member2, member3];
+  const outsider = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  const stats = (member: Keypair) => findMemberStatsPda(program.programId, multisigPda, member.publicKey)[0];
+
+  const createStats = (member: PublicKey, payer: Keypair) =>
+    program.methods
+      .createMemberStats(member)
+      .accounts({ multisig: multisigPda, payer: payer.publicKey })
+      .signers([payer])
+      .rpc();
+
+  const proposeWithStats = async (proposer: Keypair, text: string) => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    const [proposal] = findProposalPda(program.programId, multisigPda, account.proposalsCount);
+    const ix = memoInstruction(text, [multisigPda]);
+    await program.methods
+      .proposeTransaction(toInstructionData(ix, signerBits(program.programId, multisigPda, ix)), proposalOptions())
+      .accounts({ multisig: multisigPda, proposal, proposer: proposer.publicKey, memberStats: stats(proposer) })
+      .signers([proposer])
+      .rpc();
+    return proposal;
+  };
+
+  const vote = (method: "approveTransaction" | "rejectTransaction" | "abstainTransaction", proposal: PublicKey, voter: Keypair) =>
+    (method === "rejectTransaction" ? program.methods.rejectTransaction(0, "") : program.methods[method]())
+      .accounts({ multisig: multisigPda, proposal, approver: voter.publicKey, memberStats: stats(voter) })
+      .signers([voter])
+      .rpc();
+
+  before(async () => {
+    for (const m of [...members, outsider]) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Is created permissionlessly, only for members", async () => {
+    // 任何人都可以为成员付费创建
+    await createStats(member1.publicKey, outsider);
+    await createStats(member2.publicKey, member1);
+    await expectError(createStats(outsider.publicKey, outsider), "NotMember");
+
+    const account = await program.account.memberStats.fetch(stats(member1));
+    assert.isTrue(account.multisig.equals(multisigPda));
+    assert.isTrue(account.member.equals(member1.publicKey));
+    assert.equal(account.proposalsCreated, 0);
+  });
+
+  it("Rejects a stats account derived for another member", async () => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    const [proposal] = findProposalPda(program.programId, multisigPda, account.proposalsCount);
+    const ix = memoInstruction("wrong stats", [multisigPda]);
+    await expectError(
+      program.methods
+        .proposeTransaction(toInstructionData(ix, signerBits(program.programId, multisigPda, ix)), proposalOptions())
+        .accounts({ multisig: multisigPda, proposal, proposer: member2.publicKey, memberStats: stats(member1) })
+        .signers([member2])
+        .rpc(),
+      "ConstraintSeeds"
+    );
+  });
+
+  it("Accumulates proposals and votes across several proposals", async () => {
+    const p1 = await proposeWithStats(member1, "one");
+    const p2 = await proposeWithStats(member1, "two");
+    const p3 = await proposeWithStats(member2, "three");
+
+    await vote("approveTransaction", p1, member1);
+    await vote("approveTransaction", p1, member2);
+    await vote("rejectTransaction", p2, member2);
+    await vote("abstainTransaction", p3, member1);
+    await vote("approveTransaction", p3, member2);
+    // 不带统计账户的投票照常进行，但不计入
+    await approve(program, multisigPda, p2, member1);
+
+    const s1 = await program.account.memberStats.fetch(stats(member1));
+    assert.equal(s1.proposalsCreated, 2);
+    assert.equal(s1.approvalsGiven, 1);
+    assert.equal(s1.rejectionsGiven, 0);
+    assert.isAbove(s1.lastVoteAt.toNumber(), 0);
+
+    const s2 = await program.account.memberStats.fetch(stats(member2));
+    assert.equal(s2.proposalsCreated, 1);
+    assert.equal(s2.approvalsGiven, 2);
+    assert.equal(s2.rejectionsGiven, 1);
+    assert.isAtLeast(s2.lastVoteAt.toNumber(), s1.lastVoteAt.toNumber());
+  });
+
+  it("Leaves members without a stats account unaffected", async () => {
+    const p = await proposeWithStats(member1, "four");
+    await approve(program, multisigPda, p, member3);
+    assert.isNull(await provider.connection.getAccountInfo(stats(member3)));
+  });
+});
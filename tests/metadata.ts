@@ -0,0 +1,87 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { createHash } from "crypto";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  expectError,
+  findMultisigPda,
+  memoInstruction,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("proposal metadata", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+
+  const URI = "https://example.com/invoices/2026-041.pdf";
+  const sha256 = (doc: string) => Array.from(createHash("sha256").update(doc).digest());
+
+  let multisigPda: PublicKey;
+
+  const memo = (text: string, metadataUri: string | null, metadataHash = Array(32).fill(0)) =>
+    propose(program, multisigPda, member1, memoInstruction(text, [multisigPda]), { metadataUri, metadataHash });
+
+  const setMetadata = (proposal: PublicKey, signer: anchor.web3.Keypair, uri: string | null, hash: number[]) =>
+    program.methods
+      .setProposalMetadata(uri, hash)
+      .accounts({ multisig: multisigPda, proposal, proposer: signer.publicKey })
+      .signers([signer])
+      .rpc();
+
+  before(async () => {
+    for (const m of [member1, member2]) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, [member1.publicKey, member2.publicKey], 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Stores a bounded URI and document hash", async () => {
+    await expectError(memo("too long", "x".repeat(201)), "UriTooLong");
+    await expectError(memo("empty", ""), "UriTooLong");
+
+    let event: any = null;
+    const listener = program.addEventListener("proposalMetadataSet", (e) => {
+      event = e;
+    });
+    const maxUri = "https://example.com/" + "a".repeat(200 - 20);
+    const proposal = await memo("invoice", maxUri, sha256("invoice body"));
+    await sleep(1000);
+    await program.removeEventListener(listener);
+
+    const account = await program.account.proposal.fetch(proposal);
+    assert.equal(account.metadataUri, maxUri);
+    assert.deepEqual(account.metadataHash, sha256("invoice body"));
+    assert.isTrue(event.proposal.equals(proposal));
+    assert.equal(event.metadataUri, maxUri);
+  });
+
+  it("Lets the proposer change metadata only until the first approval", async () => {
+    const proposal = await memo("draft", null);
+    await expectError(setMetadata(proposal, member2, URI, sha256("v1")), "NotProposer");
+
+    await setMetadata(proposal, member1, URI, sha256("v1"));
+    await setMetadata(proposal, member1, URI, sha256("v2"));
+    assert.deepEqual((await program.account.proposal.fetch(proposal)).metadataHash, sha256("v2"));
+
+    await approve(program, multisigPda, proposal, member2);
+    await expectError(setMetadata(proposal, member1, URI, sha256("v3")), "MetadataLocked");
+    await expectError(setMetadata(proposal, member1, null, Array(32).fill(0)), "MetadataLocked");
+    assert.deepEqual((await program.account.proposal.fetch(proposal)).metadataHash, sha256("v2"));
+  });
+});
@@ -0,0 +1,168 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccount,
+  createMint,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  governed,
+  memoInstruction,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair, LAMPORTS_PER_SOL, SystemProgram } = anchor.web3;
+
+describe("migration", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const newCreator = Keypair.generate();
+
+  let oldMultisig: PublicKey;
+  let newMultisig: PublicKey;
+  let mint: PublicKey;
+  let oldAta: PublicKey;
+  let newAta: PublicKey;
+
+  const oldVault = (i: number) => findVaultPda(program.programId, oldMultisig, i)[0];
+  const newVault = (i: number) => findVaultPda(program.programId, newMultisig, i)[0];
+
+  const fund = (to: PublicKey, lamports: number) =>
+    provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        SystemProgram.transfer({ fromPubkey: provider.wallet.publicKey, toPubkey: to, lamports })
+      )
+    );
+
+  const sweep = (index: number, tokenAccounts: PublicKey[][] = []) =>
+    program.methods
+      .migrateSweep(index)
+      .accounts({
+        multisig: oldMultisig,
+        newMultisig,
+        vault: oldVault(index),
+        newVault: newVault(index),
+      })
+      .remainingAccounts(
+        tokenAccounts.length === 0
+          ? []
+          : [
+              { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+              ...tokenAccounts.flatMap(([source, m, destination]) => [
+                { pubkey: source, isSigner: false, isWritable: true },
+                { pubkey: m, isSigner: false, isWritable: false },
+                { pubkey: destination, isSigner: false, isWritable: true },
+              ]),
+            ]
+      )
+      .rpc();
+
+  const finish = () =>
+    program.methods
+      .finishMigration()
+      .accounts({ multisig: oldMultisig })
+      .remainingAccounts(
+        [0, 1, 2, 3, 4, 5, 6].map((i) => ({ pubkey: oldVault(i), isSigner: false, isWritable: false }))
+      )
+      .rpc();
+
+  before(async () => {
+    await airdrop(provider, member1.publicKey);
+    [oldMultisig] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [newMultisig] = findMultisigPda(program.programId, newCreator.publicKey, 0);
+    await program.methods
+      .createMultisig(0, [member1.publicKey, member2.publicKey], 2, 10)
+      .accounts({ multisig: oldMultisig, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await fund(oldVault(0), 2 * LAMPORTS_PER_SOL);
+    await fund(oldVault(1), LAMPORTS_PER_SOL / 2);
+
+    mint = await createMint(provider.connection, member1, member1.publicKey, null, 6);
+    oldAta = await createAssociatedTokenAccount(provider.connection, member1, mint, oldVault(0), undefined, undefined, undefined, true);
+    await mintTo(provider.connection, member1, mint, oldAta, member1, 1_000);
+  });
+
+  it("Creates the new multisig with the same members and freezes the old one", async () => {
+    const ix = await program.methods
+      .startMigration(newCreator.publicKey, 0)
+      .accounts({ multisig: oldMultisig, newMultisig, payer: oldVault(0) })
+      .instruction();
+    await governed(program, oldMultisig, [member1, member2], ix);
+
+    const [before, after] = await Promise.all([
+      program.account.multisig.fetch(oldMultisig),
+      program.account.multisig.fetch(newMultisig),
+    ]);
+    assert.deepEqual(
+      after.members.map((m: any) => m.key.toBase58()),
+      before.members.map((m: any) => m.key.toBase58())
+    );
+    assert.equal(after.threshold, before.threshold);
+    assert.ok(after.creator.equals(newCreator.publicKey));
+
+    await expectError(
+      propose(program, oldMultisig, member1, memoInstruction("too late", [])),
+      "MigrationInProgress"
+    );
+  });
+
+  it("Resumes a partial sweep and only finishes once the old vaults are empty", async () => {
+    // 第一批只迁移 1 号金库
+    await sweep(1);
+    assert.equal(await provider.connection.getBalance(oldVault(1)), 0);
+    await expectError(finish(), "MigrationIncomplete");
+
+    // 重复调用无副作用
+    await sweep(1);
+    assert.equal(await provider.connection.getBalance(newVault(1)), LAMPORTS_PER_SOL / 2);
+
+    newAta = await createAssociatedTokenAccount(provider.connection, member1, mint, newVault(0), undefined, undefined, undefined, true);
+    const vault0 = await provider.connection.getBalance(oldVault(0));
+    const ataRent = await provider.connection.getBalance(oldAta);
+    await sweep(0, [[oldAta, mint, newAta]]);
+
+    assert.equal(Number((await getAccount(provider.connection, newAta)).amount), 1_000);
+    assert.isNull(await provider.connection.getAccountInfo(oldAta));
+    assert.equal(await provider.connection.getBalance(newVault(0)), vault0 + ataRent);
+
+    await finish();
+    const old = await program.account.multisig.fetch(oldMultisig);
+    assert.isNotNull(old.woundDownAt);
+    await expectError(finish(), "AlreadyProcessed");
+  });
+
+  it("Refuses to sweep into any other multisig", async () => {
+    const [stranger] = findMultisigPda(program.programId, member1.publicKey, 1);
+    await program.methods
+      .createMultisig(1, [member1.publicKey, member2.publicKey], 2, 10)
+      .accounts({ multisig: stranger, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await expectError(
+      program.methods
+        .migrateSweep(0)
+        .accounts({
+          multisig: oldMultisig,
+          newMultisig: stranger,
+          vault: oldVault(0),
+          newVault: findVaultPda(program.programId, stranger, 0)[0],
+        })
+        .rpc(),
+      "NotMigrating"
+    );
+  });
+});
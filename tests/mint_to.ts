@@ -0,0 +1,136 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getMint,
+} from "@solana/spl-token";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  expectError,
+  findMultisigPda,
+  findProposalPda,
+  findVaultPda,
+  governed,
+  proposalOptions,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram } = anchor.web3;
+
+describe("mint_to proposals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2, member3] = members;
+  const recipient = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+  let mint: PublicKey;
+
+  const proposeMintTo = async (mintKey: PublicKey, amount: number) => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    const [proposal] = findProposalPda(program.programId, multisigPda, account.proposalsCount);
+    await program.methods
+      .proposeMintTo(mintKey, recipient.publicKey, new anchor.BN(amount), proposalOptions())
+      .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+      .remainingAccounts([{ pubkey: mintKey, isSigner: false, isWritable: false }])
+      .signers([member1])
+      .rpc();
+    return proposal;
+  };
+
+  const execute = (proposal: PublicKey) =>
+    program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts([
+        { pubkey: getAssociatedTokenAddressSync(mint, recipient.publicKey), isSigner: false, isWritable: true },
+        { pubkey: mint, isSigner: false, isWritable: true },
+        { pubkey: recipient.publicKey, isSigner: false, isWritable: false },
+        { pubkey: vaultPda, isSigner: false, isWritable: true },
+        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+        { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+        { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      ])
+      .rpc();
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vaultPda);
+
+    // 增发属于 Token Program 档位，需要 3-of-3
+    const ix = await program.methods
+      .setThresholdOverrides([{ programId: TOKEN_PROGRAM_ID, minThreshold: 3 }])
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, [member1, member2], ix);
+
+    mint = await createMint(provider.connection, member1, multisigPda, null, 6);
+  });
+
+  it("Validates the amount and mint authority at propose time", async () => {
+    await expectError(proposeMintTo(mint, 0), "InvalidMintAmount");
+    const other = await createMint(provider.connection, member1, member1.publicKey, null, 6);
+    await expectError(proposeMintTo(other, 100), "MintAuthorityMismatch");
+  });
+
+  it("Emits the recipient and estimated supply when proposed", async () => {
+    let proposed: any = null;
+    const listener = program.addEventListener("mintToProposed", (event) => {
+      proposed = event;
+    });
+    await proposeMintTo(mint, 5_000);
+    await sleep(1000);
+    await program.removeEventListener(listener);
+
+    assert.isTrue(proposed.mint.equals(mint));
+    assert.isTrue(proposed.recipient.equals(recipient.publicKey));
+    assert.isTrue(proposed.destination.equals(getAssociatedTokenAddressSync(mint, recipient.publicKey)));
+    assert.equal(proposed.amount.toNumber(), 5_000);
+    assert.equal(proposed.estimatedSupply.toNumber(), 5_000);
+  });
+
+  it("Requires the Token Program tier and creates the recipient's ATA", async () => {
+    const [proposal] = findProposalPda(program.programId, multisigPda, 1);
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    await expectError(execute(proposal), "TierThresholdNotMet");
+
+    await approve(program, multisigPda, proposal, member3);
+    await execute(proposal);
+
+    const ata = getAssociatedTokenAddressSync(mint, recipient.publicKey);
+    assert.equal((await getAccount(provider.connection, ata)).amount, 5_000n);
+    assert.equal((await getMint(provider.connection, mint)).supply, 5_000n);
+  });
+
+  it("Mints into an existing ATA", async () => {
+    const proposal = await proposeMintTo(mint, 1_000);
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    await execute(proposal);
+    const ata = getAssociatedTokenAddressSync(mint, recipient.publicKey);
+    assert.equal((await getAccount(provider.connection, ata)).amount, 6_000n);
+  });
+});
@@ -89,7 +89,7 @@ describe("multisig with SPL Token", () => {
     );
 
     const tx = await program.methods
-      .createMultisig(nonce, memberPubkeys, 2)
+      .createMultisig(nonce, memberPubkeys, 2, 10)
       .accounts({
         multisig: multisigPda,
         creator: member1.publicKey,
@@ -179,7 +179,8 @@ describe("multisig with SPL Token", () => {
         isSigner: k.isSigner,
         isWritable: k.isWritable,
       })),
-      data: Buffer.from(transferIx.data)
+      data: Buffer.from(transferIx.data),
+      signers: 1, // 仅 multisig PDA 签名
     };
 
     // Proposal PDA (index = 0)
@@ -193,7 +194,7 @@ describe("multisig with SPL Token", () => {
     );
 
     const tx = await program.methods
-      .proposeTransaction(instructionData)
+      .proposeTransaction(instructionData, { dependsOn: null, notBefore: null, notAfter: null, urgent: false, allowDangerous: false, noSign: false, executionMemo: null, delayOverride: null, votingEndsAt: null, metadataUri: null, metadataHash: Array(32).fill(0), designatedExecutor: null, allowDuplicate: false, optimistic: false })
       .accounts({
         multisig: multisigPda,
         proposal: proposalPda,
@@ -208,7 +209,7 @@ describe("multisig with SPL Token", () => {
 
   it("Approves the proposal by member2", async () => {
     const tx = await program.methods
-      .approveTransaction()
+      .approveTransaction(null)
       .accounts({
         multisig: multisigPda,
         proposal: proposalPda,
@@ -222,7 +223,7 @@ describe("multisig with SPL Token", () => {
 
   it("Approves the proposal by member3", async () => {
     const tx = await program.methods
-      .approveTransaction()
+      .approveTransaction(null)
       .accounts({
         multisig: multisigPda,
         proposal: proposalPda,
@@ -242,7 +243,7 @@ describe("multisig with SPL Token", () => {
 
   
     // 构造 remainingAccounts：multisigPda 的 isSigner 设为 false
-    const remainingAccounts = proposalAccount.instruction.accounts.map((acc: any) => {
+    const remainingAccounts = proposalAccount.action.raw[0].accounts.map((acc: any) => {
       const pubkey = new PublicKey(acc.pubkey);
       let isWritable = acc.isWritable;
       let isSigner = acc.isSigner;
@@ -0,0 +1,86 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findProposalPda,
+  memoInstruction,
+  proposalOptions,
+  toInstructionData,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("create multisig with proposal", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const creator = Keypair.generate();
+  const member2 = Keypair.generate();
+  const outsider = Keypair.generate();
+
+  before(async () => {
+    await airdrop(provider, creator.publicKey);
+    await airdrop(provider, outsider.publicKey);
+  });
+
+  it("Creates the multisig and proposal #0 in one instruction", async () => {
+    const [multisig] = findMultisigPda(program.programId, creator.publicKey, 0);
+    const [proposal] = findProposalPda(program.programId, multisig, 0);
+
+    await program.methods
+      .createMultisigWithProposal(
+        0,
+        [creator.publicKey, member2.publicKey],
+        2,
+        10,
+        toInstructionData(memoInstruction("bootstrap", []), 0),
+        proposalOptions()
+      )
+      .accounts({ multisig, proposal, creator: creator.publicKey })
+      .signers([creator])
+      .rpc();
+
+    const account = await program.account.multisig.fetch(multisig);
+    assert.equal(account.proposalsCount, 1);
+    const created = await program.account.proposal.fetch(proposal);
+    assert.equal(created.index, 0);
+    assert.ok(created.proposer.equals(creator.publicKey));
+
+    await approve(program, multisig, proposal, creator);
+    await approve(program, multisig, proposal, member2);
+    await execute(program, multisig, proposal);
+    assert.ok((await program.account.proposal.fetch(proposal)).executed);
+  });
+
+  it("Leaves nothing behind when the proposal part fails", async () => {
+    const [multisig] = findMultisigPda(program.programId, outsider.publicKey, 0);
+    const [proposal] = findProposalPda(program.programId, multisig, 0);
+
+    await expectError(
+      program.methods
+        .createMultisigWithProposal(
+          0,
+          [creator.publicKey, member2.publicKey],
+          2,
+          10,
+          toInstructionData(memoInstruction("orphan", []), 0),
+          proposalOptions()
+        )
+        .accounts({ multisig, proposal, creator: outsider.publicKey })
+        .signers([outsider])
+        .rpc(),
+      "NotMember"
+    );
+
+    assert.isNull(await program.account.multisig.fetchNullable(multisig));
+    assert.isNull(await program.account.proposal.fetchNullable(proposal));
+  });
+});
@@ -0,0 +1,151 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { createHash } from "crypto";
+import { Multisig } from "../target/types/multisig";
+import { CpiCaller } from "../target/types/cpi_caller";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  memoInstruction,
+  notifyAccounts,
+  propose,
+  proposeAction,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("execution notify hook", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+  const caller = anchor.workspace.cpiCaller as Program<CpiCaller>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+
+  let multisigPda: PublicKey;
+  let vault: PublicKey;
+  const [log] = PublicKey.findProgramAddressSync([Buffer.from("notifications")], caller.programId);
+  const hook = () => notifyAccounts(caller.programId, [log]);
+
+  const setNotify = async (notifyProgram: PublicKey | null, required: boolean) => {
+    const ix = await program.methods
+      .setNotifyProgram(notifyProgram, required)
+      .accounts({ multisig: multisigPda })
+      .instruction();
+    // 已设置 required 时治理提案本身也需要通知账户
+    const proposal = await propose(program, multisigPda, member1, ix);
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    await execute(program, multisigPda, proposal, null, { notify: hook() });
+  };
+
+  const proposeMemo = async (text: string) => {
+    const proposal = await propose(program, multisigPda, member1, memoInstruction(text, [vault]));
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    const { action, index } = await program.account.proposal.fetch(proposal);
+    const encoded = program.coder.types.encode("proposalAction", action);
+    return { proposal, index, hash: Array.from(createHash("sha256").update(encoded).digest()) };
+  };
+
+  before(async () => {
+    await airdrop(provider, member1.publicKey);
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vault] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await caller.methods.initNotificationLog().accounts({ log }).rpc();
+  });
+
+  it("Sets the notify program through a proposal", async () => {
+    const event = new Promise<any>((resolve) => {
+      const id = program.addEventListener("notifyProgramSet", (e) => {
+        program.removeEventListener(id);
+        resolve(e);
+      });
+    });
+    await setNotify(caller.programId, false);
+    const set = await event;
+    assert.ok(set.program.equals(caller.programId));
+    assert.isFalse(set.required);
+    const account = await program.account.multisig.fetch(multisigPda);
+    assert.equal(account.proposalsCount, 1);
+  });
+
+  it("Notifies the program with the multisig, index and instruction hash", async () => {
+    const before = await caller.account.notificationLog.fetch(log);
+    const { proposal, index, hash } = await proposeMemo("notify me");
+    await execute(program, multisigPda, proposal, null, { notify: hook() });
+
+    const after = await caller.account.notificationLog.fetch(log);
+    assert.ok(after.multisig.equals(multisigPda));
+    assert.equal(after.index, index);
+    assert.deepEqual(after.instructionHash, hash);
+    assert.equal(after.count.toNumber(), before.count.toNumber() + 1);
+  });
+
+  it("Skips a missing notify program unless required", async () => {
+    const before = await caller.account.notificationLog.fetch(log);
+    const { proposal } = await proposeMemo("no hook accounts");
+    await execute(program, multisigPda, proposal);
+    const after = await caller.account.notificationLog.fetch(log);
+    assert.equal(after.count.toNumber(), before.count.toNumber());
+
+    await setNotify(caller.programId, true);
+    const required = await proposeMemo("hook required");
+    await expectError(execute(program, multisigPda, required.proposal), "NotifyProgramMissing");
+    await execute(program, multisigPda, required.proposal, null, { notify: hook() });
+    assert.equal((await caller.account.notificationLog.fetch(log)).index, required.index);
+  });
+
+  it("Rolls back the execution when the receiver fails", async () => {
+    await caller.methods.setNotificationFailure(true).accounts({ log }).rpc();
+    const { proposal } = await proposeMemo("receiver fails");
+    // CPI 失败无法被捕获，整笔执行回滚
+    await expectError(
+      execute(program, multisigPda, proposal, null, { notify: hook() }),
+      "RequireViolated"
+    );
+    assert.isFalse((await program.account.proposal.fetch(proposal)).executed);
+
+    await caller.methods.setNotificationFailure(false).accounts({ log }).rpc();
+    await execute(program, multisigPda, proposal, null, { notify: hook() });
+  });
+
+  it("Notifies typed actions with the hash of the action", async () => {
+    const proposal = await proposeAction(program, multisigPda, member1, { changeThreshold: [1] });
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    const { action, index } = await program.account.proposal.fetch(proposal);
+    const encoded = program.coder.types.encode("proposalAction", action);
+    await execute(program, multisigPda, proposal, null, { notify: hook() });
+
+    const after = await caller.account.notificationLog.fetch(log);
+    assert.equal(after.index, index);
+    assert.deepEqual(after.instructionHash, Array.from(createHash("sha256").update(encoded).digest()));
+    assert.equal((await program.account.multisig.fetch(multisigPda)).threshold, 1);
+  });
+
+  it("Stops notifying once removed", async () => {
+    await setNotify(null, false);
+    const before = await caller.account.notificationLog.fetch(log);
+    const { proposal } = await proposeMemo("hook removed");
+    await execute(program, multisigPda, proposal, null, { notify: hook() });
+    const after = await caller.account.notificationLog.fetch(log);
+    assert.equal(after.count.toNumber(), before.count.toNumber());
+  });
+});
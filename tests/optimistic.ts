@@ -0,0 +1,166 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  chainTime,
+  execute,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  governed,
+  memoInstruction,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } = anchor.web3;
+
+// 与程序中的 CANCEL_REASON_VETOED 一致
+const VETOED = 7;
+const DELAY_SECONDS = 3;
+
+describe("optimistic execution", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2, member3] = members;
+  const payee = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  const payment = () =>
+    SystemProgram.transfer({ fromPubkey: vaultPda, toPubkey: payee.publicKey, lamports: LAMPORTS_PER_SOL / 100 });
+
+  const proposeOptimistic = (ix: anchor.web3.TransactionInstruction, options = {}) =>
+    propose(program, multisigPda, member1, ix, { optimistic: true, ...options });
+
+  const reject = (proposal: PublicKey, member: anchor.web3.Keypair) =>
+    program.methods
+      .rejectTransaction(0, "not this month")
+      .accounts({ multisig: multisigPda, proposal, approver: member.publicKey })
+      .signers([member])
+      .rpc();
+
+  const waitForWindow = async (proposal: PublicKey) => {
+    const { createdAt, delaySeconds } = await program.account.proposal.fetch(proposal);
+    while ((await chainTime(provider)) < createdAt.toNumber() + delaySeconds) {
+      await sleep(1000);
+    }
+  };
+
+  before(async () => {
+    await airdrop(provider, member1.publicKey);
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vaultPda, 1);
+
+    // 只允许 0 号金库发起的 System transfer：判别值 2，data 12 字节，账户含末尾的程序账户共 3 个
+    const config = {
+      enabledFor: [
+        {
+          programId: SystemProgram.programId,
+          discriminator: Buffer.from([2, 0, 0, 0]),
+          dataLen: 12,
+          accounts: 3,
+          signers: 1 << 1,
+        },
+      ],
+      delaySeconds: DELAY_SECONDS,
+      vetoThreshold: 2,
+    };
+    const ix = await program.methods
+      .setOptimisticConfig(config)
+      .accounts({ multisig: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, [member1, member2], ix);
+  });
+
+  it("Executes without approvals once the window closes unvetoed", async () => {
+    const proposal = await proposeOptimistic(payment());
+    const account = await program.account.proposal.fetch(proposal);
+    assert.isTrue(account.optimistic);
+    assert.equal(account.delaySeconds, DELAY_SECONDS);
+    assert.equal(account.approvals, 0);
+    await expectError(execute(program, multisigPda, proposal), "TimelockNotElapsed");
+
+    // 一票否决低于 veto_threshold，不影响执行
+    await reject(proposal, member2);
+    await waitForWindow(proposal);
+    await expectError(reject(proposal, member3), "VotingClosed");
+
+    const before = await provider.connection.getBalance(payee.publicKey);
+    await execute(program, multisigPda, proposal);
+    assert.equal(await provider.connection.getBalance(payee.publicKey), before + LAMPORTS_PER_SOL / 100);
+  });
+
+  it("Dies once the veto threshold is reached within the window", async () => {
+    const proposal = await proposeOptimistic(payment());
+    await reject(proposal, member2);
+    await reject(proposal, member3);
+    // 作废后不能改票挽回
+    await expectError(
+      program.methods
+        .approveTransaction(null)
+        .accounts({ multisig: multisigPda, proposal, approver: member3.publicKey })
+        .signers([member3])
+        .rpc(),
+      "ProposalVetoed"
+    );
+    await waitForWindow(proposal);
+    await expectError(execute(program, multisigPda, proposal), "ProposalVetoed");
+
+    const events: any[] = [];
+    const listener = program.addEventListener("proposalCancelled", (e) => events.push(e));
+    await program.methods
+      .closeExpired()
+      .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+      .rpc();
+    await sleep(1000);
+    await program.removeEventListener(listener);
+    assert.isNull(await provider.connection.getAccountInfo(proposal));
+    assert.equal(events[0].reason, VETOED);
+  });
+
+  it("Keeps instructions outside the allowlist out of the optimistic lane", async () => {
+    await expectError(proposeOptimistic(memoInstruction("sneak", [vaultPda])), "NotOptimisticEligible");
+    // 同一程序与判别值，但改由 1 号金库签名，signers 位不同
+    const [vault1] = findVaultPda(program.programId, multisigPda, 1);
+    const fromVault1 = SystemProgram.transfer({
+      fromPubkey: vault1,
+      toPubkey: payee.publicKey,
+      lamports: LAMPORTS_PER_SOL / 100,
+    });
+    await expectError(proposeOptimistic(fromVault1), "NotOptimisticEligible");
+    // 需要更多批准的选项不能走乐观通道
+    await expectError(proposeOptimistic(payment(), { urgent: true }), "NotOptimisticEligible");
+
+    // 不选择乐观通道的同形状提案仍需达到阈值
+    const proposal = await propose(program, multisigPda, member1, payment());
+    await expectError(execute(program, multisigPda, proposal), "NotExecutable");
+  });
+
+  it("Rejects configs that would open the lane to this program", async () => {
+    const ix = await program.methods
+      .setOptimisticConfig({
+        enabledFor: [
+          { programId: program.programId, discriminator: Buffer.from([1]), dataLen: 9, accounts: 2, signers: 1 },
+        ],
+        delaySeconds: DELAY_SECONDS,
+        vetoThreshold: 1,
+      })
+      .accounts({ multisig: multisigPda })
+      .instruction();
+    await expectError(governed(program, multisigPda, [member1, member2], ix), "InvalidOptimisticConfig");
+  });
+});
@@ -0,0 +1,109 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  expectError,
+  findMultisigPda,
+  governed,
+  memoInstruction,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+// 与程序中的 CANCEL_REASON_MEMBER_REMOVED 一致
+const MEMBER_REMOVED = 4;
+
+describe("cancel orphaned proposals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2, compromised] = members;
+
+  let multisigPda: PublicKey;
+  let ownProposal: PublicKey;
+  let orphans: PublicKey[];
+
+  const memo = (proposer: anchor.web3.Keypair, text: string) =>
+    propose(program, multisigPda, proposer, memoInstruction(text, [multisigPda]));
+
+  // 由 provider 钱包（非成员）付费调用
+  const sweep = (proposal: PublicKey, proposer: PublicKey) =>
+    program.methods
+      .cancelOrphanedProposal()
+      .accounts({ multisig: multisigPda, proposal, proposer })
+      .rpc();
+
+  before(async () => {
+    for (const k of [member1, compromised]) {
+      await airdrop(provider, k.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+
+    ownProposal = await memo(member1, "still trusted");
+    orphans = [await memo(compromised, "drain a"), await memo(compromised, "drain b")];
+    await approve(program, multisigPda, orphans[0], compromised);
+  });
+
+  it("Refuses proposals whose proposer is still a member", async () => {
+    await expectError(sweep(orphans[0], compromised.publicKey), "ProposerStillMember");
+    await expectError(sweep(ownProposal, member1.publicKey), "ProposerStillMember");
+  });
+
+  it("Sweeps the removed member's open proposals and refunds their rent", async () => {
+    const ix = await program.methods
+      .removeMember(compromised.publicKey)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, [member1, member2], ix);
+
+    const before = await provider.connection.getBalance(compromised.publicKey);
+    let rent = 0;
+    for (const orphan of orphans) {
+      rent += (await provider.connection.getAccountInfo(orphan)).lamports;
+    }
+    const { cancelledCount } = await program.account.multisig.fetch(multisigPda);
+
+    const events: any[] = [];
+    const listener = program.addEventListener("proposalCancelled", (e) => events.push(e));
+    for (const orphan of orphans) {
+      await sweep(orphan, compromised.publicKey);
+    }
+    await sleep(1000);
+    await program.removeEventListener(listener);
+
+    for (const orphan of orphans) {
+      assert.isNull(await provider.connection.getAccountInfo(orphan));
+    }
+    assert.equal(await provider.connection.getBalance(compromised.publicKey), before + rent);
+    const account = await program.account.multisig.fetch(multisigPda);
+    assert.equal(account.cancelledCount, cancelledCount + 2);
+
+    assert.lengthOf(events, 2);
+    for (const event of events) {
+      assert.equal(event.reason, MEMBER_REMOVED);
+      assert.isNull(event.canceller);
+    }
+    assert.deepEqual(
+      events.map((e) => e.proposal.toBase58()),
+      orphans.map((p) => p.toBase58())
+    );
+  });
+
+  it("Leaves current members' proposals alone after the removal", async () => {
+    await expectError(sweep(ownProposal, member1.publicKey), "ProposerStillMember");
+    assert.isNotNull(await provider.connection.getAccountInfo(ownProposal));
+  });
+});
@@ -0,0 +1,170 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import { PolicyAllowAll } from "../target/types/policy_allow_all";
+import { PolicyDenyOverAmount } from "../target/types/policy_deny_over_amount";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findProposalPda,
+  findVaultPda,
+  governed,
+  policyAccounts,
+  propose,
+  proposalOptions,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } = anchor.web3;
+
+describe("execution policy plugins", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+  const allowAll = anchor.workspace.policyAllowAll as Program<PolicyAllowAll>;
+  const denyOverAmount = anchor.workspace.policyDenyOverAmount as Program<PolicyDenyOverAmount>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+  const recipient = Keypair.generate().publicKey;
+
+  const createMultisig = async (nonce: number) => {
+    const [multisig] = findMultisigPda(program.programId, member1.publicKey, nonce);
+    await program.methods
+      .createMultisig(nonce, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, findVaultPda(program.programId, multisig)[0], 3);
+    return multisig;
+  };
+
+  const setPolicy = async (multisig: PublicKey, policyProgram: PublicKey, accountCount: number) => {
+    const ix = await program.methods
+      .setPolicyProgram(policyProgram, accountCount)
+      .accounts({ multisig })
+      .instruction();
+    await governed(program, multisig, members, ix);
+  };
+
+  const proposeTransfer = async (multisig: PublicKey, lamports: number) => {
+    const [vault] = findVaultPda(program.programId, multisig);
+    const ix = SystemProgram.transfer({ fromPubkey: vault, toPubkey: recipient, lamports });
+    const proposal = await propose(program, multisig, member1, ix);
+    for (const m of members) {
+      await approve(program, multisig, proposal, m);
+    }
+    return proposal;
+  };
+
+  // 以 deny-over-amount 为策略、上限 0.5 SOL 的多签
+  const createLimited = async (nonce: number) => {
+    const multisig = await createMultisig(nonce);
+    const [vault] = findVaultPda(program.programId, multisig);
+    const [limit] = PublicKey.findProgramAddressSync(
+      [Buffer.from("limit"), multisig.toBuffer()],
+      denyOverAmount.programId
+    );
+    const initLimit = await denyOverAmount.methods
+      .initLimit(new anchor.BN(LAMPORTS_PER_SOL / 2))
+      .accounts({ limit, multisig, payer: vault })
+      .instruction();
+    await governed(program, multisig, members, initLimit);
+    await setPolicy(multisig, denyOverAmount.programId, 1);
+    return { multisig, vault, policy: policyAccounts(denyOverAmount.programId, [limit]) };
+  };
+
+  before(async () => {
+    await airdrop(provider, member1.publicKey);
+  });
+
+  it("Executes once an allow-all policy approves", async () => {
+    const multisig = await createMultisig(0);
+    await setPolicy(multisig, allowAll.programId, 0);
+    const account = await program.account.multisig.fetch(multisig);
+    assert.equal(account.proposalsCount, 1);
+
+    const proposal = await proposeTransfer(multisig, LAMPORTS_PER_SOL / 10);
+    // 缺少策略程序账户时不执行
+    await expectError(execute(program, multisig, proposal), "PolicyAccountsMismatch");
+    const policy = policyAccounts(allowAll.programId);
+    await execute(program, multisig, proposal, null, { policy });
+    assert.equal(await provider.connection.getBalance(recipient), LAMPORTS_PER_SOL / 10);
+  });
+
+  it("Refuses transfers over the limit of a deny-over-amount policy", async () => {
+    const { multisig, policy } = await createLimited(1);
+
+    const over = await proposeTransfer(multisig, LAMPORTS_PER_SOL);
+    await expectError(execute(program, multisig, over, null, { policy }), "AmountOverLimit");
+    assert.isFalse((await program.account.proposal.fetch(over)).executed);
+
+    // 执行者不能以别的账户代替上限账户
+    const [otherLimit] = PublicKey.findProgramAddressSync(
+      [Buffer.from("limit"), recipient.toBuffer()],
+      denyOverAmount.programId
+    );
+    const forged = policyAccounts(denyOverAmount.programId, [otherLimit]);
+    await expectError(
+      execute(program, multisig, over, null, { policy: forged }),
+      "AccountNotInitialized"
+    );
+
+    const before = await provider.connection.getBalance(recipient);
+    const under = await proposeTransfer(multisig, LAMPORTS_PER_SOL / 4);
+    await execute(program, multisig, under, null, { policy });
+    assert.equal(await provider.connection.getBalance(recipient), before + LAMPORTS_PER_SOL / 4);
+  });
+
+  it("Refuses typed payments over the limit", async () => {
+    const { multisig, vault, policy } = await createLimited(2);
+    const proposeRecurring = async (lamports: number) => {
+      const { proposalsCount } = await program.account.multisig.fetch(multisig);
+      const [proposal] = findProposalPda(program.programId, multisig, proposalsCount);
+      const payment = {
+        destination: recipient,
+        amount: new anchor.BN(lamports),
+        intervalSeconds: new anchor.BN(3600),
+        maxPayments: 2,
+      };
+      await program.methods
+        .proposeRecurringPayment(payment, proposalOptions())
+        .accounts({ multisig, proposal, proposer: member1.publicKey })
+        .signers([member1])
+        .rpc();
+      for (const m of members) {
+        await approve(program, multisig, proposal, m);
+      }
+      return proposal;
+    };
+    const activate = (proposal: PublicKey) =>
+      program.methods
+        .activateProposal()
+        .accounts({ multisig, proposal })
+        .remainingAccounts(policy)
+        .rpc();
+    const crank = (proposal: PublicKey, remaining = policy) =>
+      program.methods
+        .executeRecurringPayment()
+        .accounts({ multisig, proposal, vault, destination: recipient })
+        .remainingAccounts(remaining)
+        .rpc();
+
+    const over = await proposeRecurring(LAMPORTS_PER_SOL);
+    await expectError(activate(over), "AmountOverLimit");
+    assert.isFalse((await program.account.proposal.fetch(over)).executed);
+
+    // 每次付款同样经策略程序放行，缺少策略账户时不付款
+    const under = await proposeRecurring(LAMPORTS_PER_SOL / 4);
+    await activate(under);
+    await expectError(crank(under, []), "PolicyAccountsMismatch");
+    const before = await provider.connection.getBalance(recipient);
+    await crank(under);
+    assert.equal(await provider.connection.getBalance(recipient), before + LAMPORTS_PER_SOL / 4);
+  });
+});
@@ -0,0 +1,122 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import { CpiCaller } from "../target/types/cpi_caller";
+import {
+  airdrop,
+  approve,
+  chainTime,
+  executeRemainingAccounts,
+  findMultisigPda,
+  findVaultPda,
+  governed,
+  MEMO_PROGRAM_ID,
+  memoInstruction,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("proposal status view", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+  const caller = anchor.workspace.cpiCaller as Program<CpiCaller>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2, member3] = members;
+
+  let multisigPda: PublicKey;
+
+  const remaining = async (proposal: PublicKey) => {
+    const { action } = await program.account.proposal.fetch(proposal);
+    const [vault] = findVaultPda(program.programId, multisigPda);
+    return executeRemainingAccounts(action, [multisigPda, vault]);
+  };
+
+  // 由 fixture 程序经 CPI 读取，与 keeper 直接模拟调用得到的结果应一致
+  const statusViaCpi = async (proposal: PublicKey) =>
+    caller.methods
+      .proposalStatusViaCpi()
+      .accounts({ multisig: multisigPda, proposal, multisigProgram: program.programId })
+      .remainingAccounts(await remaining(proposal))
+      .view();
+
+  const status = async (proposal: PublicKey) =>
+    program.methods
+      .getProposalStatus()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts(await remaining(proposal))
+      .view();
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Reports pending until the threshold is reached, then ready", async () => {
+    const proposal = await propose(program, multisigPda, member1, memoInstruction("status", []));
+    await approve(program, multisigPda, proposal, member1);
+
+    const pending = await statusViaCpi(proposal);
+    assert.deepEqual(pending.status, { pending: {} });
+    assert.equal(pending.approvals, 1);
+    assert.equal(pending.threshold, 2);
+    assert.isNull(pending.readyAt);
+
+    await approve(program, multisigPda, proposal, member2);
+    const ready = await statusViaCpi(proposal);
+    assert.deepEqual(ready.status, { ready: {} });
+    assert.equal(ready.approvals, 2);
+    assert.isNotNull(ready.readyAt);
+    assert.isNull(ready.expiresAt);
+    assert.deepEqual(await status(proposal), ready);
+  });
+
+  it("Reports expired once the execution window has passed", async () => {
+    const notAfter = new anchor.BN((await chainTime(provider)) + 3);
+    const proposal = await propose(program, multisigPda, member1, memoInstruction("late", []), {
+      notAfter,
+    });
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    await sleep(5000);
+
+    const expired = await statusViaCpi(proposal);
+    assert.deepEqual(expired.status, { expired: {} });
+    assert.equal(expired.expiresAt.toNumber(), notAfter.toNumber());
+  });
+
+  // 放在最后：之后所有 memo 提案都需要 3 个批准
+  it("Reports no ETA until a tiered proposal reaches its tier threshold", async () => {
+    const ix = await program.methods
+      .setThresholdOverrides([{ programId: MEMO_PROGRAM_ID, minThreshold: 3 }])
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, [member1, member2], ix);
+
+    const proposal = await propose(program, multisigPda, member1, memoInstruction("tiered", []));
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    const pending = await status(proposal);
+    assert.deepEqual(pending.status, { pending: {} });
+    assert.equal(pending.threshold, 3);
+    assert.isNull(pending.readyAt);
+
+    await approve(program, multisigPda, proposal, member3);
+    const ready = await status(proposal);
+    assert.deepEqual(ready.status, { ready: {} });
+    const { readyAt } = await program.account.proposal.fetch(proposal);
+    assert.equal(ready.readyAt.toNumber(), readyAt.toNumber());
+  });
+});
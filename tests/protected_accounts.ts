@@ -0,0 +1,89 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL, TransactionInstruction } =
+  anchor.web3;
+
+describe("protected multisig and vault PDAs", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2, member3] = members;
+  const receiver = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vaultPda, 1);
+  });
+
+  it("Rejects a disguised assign of the vault at propose time", async () => {
+    // 在 Assign 数据后追加填充字节，试图绕过按长度匹配的解码
+    const assign = SystemProgram.assign({ accountPubkey: vaultPda, programId: Keypair.generate().publicKey });
+    const disguised = new TransactionInstruction({
+      programId: assign.programId,
+      keys: assign.keys,
+      data: Buffer.concat([assign.data, Buffer.alloc(16, 0xab)]),
+    });
+    await expectError(propose(program, multisigPda, member1, disguised), "ForbiddenConfigMutation");
+
+    const allocate = SystemProgram.allocate({ accountPubkey: multisigPda, space: 10_000 });
+    await expectError(propose(program, multisigPda, member1, allocate), "ForbiddenConfigMutation");
+  });
+
+  it("Rejects a drain-by-transfer below rent exemption at execute time", async () => {
+    const balance = await provider.connection.getBalance(vaultPda);
+    const drain = SystemProgram.transfer({
+      fromPubkey: vaultPda,
+      toPubkey: receiver.publicKey,
+      lamports: balance - 1,
+    });
+    // 提案时金库余额未知，执行时按实际余额拒绝
+    const proposal = await propose(program, multisigPda, member1, drain);
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    await expectError(execute(program, multisigPda, proposal), "WouldBreakRentExemption");
+  });
+
+  it("allow_dangerous requires every member", async () => {
+    const sweep = SystemProgram.transfer({
+      fromPubkey: vaultPda,
+      toPubkey: receiver.publicKey,
+      lamports: LAMPORTS_PER_SOL / 2,
+    });
+    const proposal = await propose(program, multisigPda, member1, sweep, { allowDangerous: true });
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    await expectError(execute(program, multisigPda, proposal), "NotExecutable");
+
+    await approve(program, multisigPda, proposal, member3);
+    await execute(program, multisigPda, proposal);
+    assert.equal(await provider.connection.getBalance(receiver.publicKey), LAMPORTS_PER_SOL / 2);
+  });
+});
@@ -0,0 +1,201 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findProgramConfigPda,
+  findProposalPda,
+  findVaultPda,
+  proposalOptions,
+  proposeAction,
+  protocolFeeAccounts,
+  signerBits,
+  toInstructionData,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } = anchor.web3;
+
+const BPF_LOADER_UPGRADEABLE = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+
+describe("protocol fee", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2] = members;
+  const recipient = Keypair.generate().publicKey;
+  const payee = Keypair.generate().publicKey;
+
+  const [programConfig] = findProgramConfigPda(program.programId);
+  const [programData] = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE
+  );
+
+  let charged: PublicKey;
+  let optedOut: PublicKey;
+
+  const updateConfig = (feeBps: number, flatFee: number) =>
+    program.methods
+      .updateProgramConfig(feeBps, new anchor.BN(flatFee), recipient, null)
+      .accounts({ programConfig, authority: provider.wallet.publicKey })
+      .rpc();
+
+  // 由 0 号金库转出 lamports 的提案，创建时传入全局配置以报价
+  const proposeTransfer = async (multisig: PublicKey, lamports: number) => {
+    const [vault] = findVaultPda(program.programId, multisig, 0);
+    const ix = SystemProgram.transfer({ fromPubkey: vault, toPubkey: payee, lamports });
+    const { proposalsCount } = await program.account.multisig.fetch(multisig);
+    const [proposal] = findProposalPda(program.programId, multisig, proposalsCount);
+    await program.methods
+      .proposeTransaction(
+        toInstructionData(ix, signerBits(program.programId, multisig, ix)),
+        proposalOptions()
+      )
+      .accounts({ multisig, proposal, proposer: member1.publicKey, programConfig })
+      .signers([member1])
+      .rpc();
+    return proposal;
+  };
+
+  const createMultisig = async (nonce: number, optOut: boolean) => {
+    const [multisig] = findMultisigPda(program.programId, member1.publicKey, nonce);
+    const post = optOut
+      ? [
+          await program.methods
+            .optOutProtocolFee()
+            .accounts({ multisig, creator: member1.publicKey })
+            .instruction(),
+        ]
+      : [];
+    await program.methods
+      .createMultisig(nonce, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig, creator: member1.publicKey })
+      .postInstructions(post)
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, findVaultPda(program.programId, multisig, 0)[0], 2);
+    return multisig;
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    charged = await createMultisig(0, false);
+    optedOut = await createMultisig(1, true);
+  });
+
+  it("Only the upgrade authority can initialize the config", async () => {
+    const stranger = Keypair.generate();
+    await airdrop(provider, stranger.publicKey);
+    await expectError(
+      program.methods
+        .initProgramConfig(recipient)
+        .accounts({ programConfig, authority: stranger.publicKey, programData })
+        .signers([stranger])
+        .rpc(),
+      "Unauthorized"
+    );
+
+    await program.methods
+      .initProgramConfig(recipient)
+      .accounts({ programConfig, authority: provider.wallet.publicKey, programData })
+      .rpc();
+    const config = await program.account.programConfig.fetch(programConfig);
+    assert.equal(config.feeBps, 0);
+    assert.equal(config.flatFeeLamports.toNumber(), 0);
+  });
+
+  it("Does not quote a fee while fees are zero", async () => {
+    const proposal = await proposeTransfer(charged, LAMPORTS_PER_SOL / 10);
+    assert.isNull((await program.account.proposal.fetch(proposal)).protocolFee);
+  });
+
+  it("Rejects fees above the maximum", async () => {
+    await expectError(updateConfig(101, 0), "InvalidProtocolFee");
+  });
+
+  it("Quotes the fee at creation and charges it on execution", async () => {
+    await updateConfig(50, 5_000);
+    const proposal = await proposeTransfer(charged, LAMPORTS_PER_SOL);
+    const { protocolFee } = await program.account.proposal.fetch(proposal);
+    const [vault] = findVaultPda(program.programId, charged, 0);
+    assert.equal(protocolFee.lamports.toNumber(), 5_000 + LAMPORTS_PER_SOL / 200);
+    assert.ok(protocolFee.payer.equals(vault));
+    assert.ok(protocolFee.recipient.equals(recipient));
+
+    await approve(program, charged, proposal, member1);
+    await approve(program, charged, proposal, member2);
+    await execute(program, charged, proposal);
+    assert.equal(
+      await provider.connection.getBalance(recipient),
+      protocolFee.lamports.toNumber()
+    );
+  });
+
+  it("Quotes typed outflows and charges before the action runs", async () => {
+    const [vault1] = findVaultPda(program.programId, charged, 1);
+    await airdrop(provider, vault1);
+    // 未显式传入配置账户同样报价
+    const proposal = await proposeAction(program, charged, member1, {
+      sweepVault: { index: 1, destination: payee },
+    });
+    const { protocolFee } = await program.account.proposal.fetch(proposal);
+    // 清扫金额在创建时未知，只收固定部分
+    assert.equal(protocolFee.lamports.toNumber(), 5_000);
+    assert.ok(protocolFee.payer.equals(vault1));
+
+    await approve(program, charged, proposal, member1);
+    await approve(program, charged, proposal, member2);
+    const before = await provider.connection.getBalance(recipient);
+    await program.methods
+      .executeTransaction()
+      .accounts({ multisig: charged, proposal })
+      .remainingAccounts([
+        { pubkey: vault1, isSigner: false, isWritable: true },
+        { pubkey: payee, isSigner: false, isWritable: true },
+        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+        ...protocolFeeAccounts(protocolFee),
+      ])
+      .rpc();
+    assert.equal(await provider.connection.getBalance(recipient), before + 5_000);
+    assert.equal(await provider.connection.getBalance(vault1), 0);
+  });
+
+  it("Never charges a vault below rent exemption", async () => {
+    // 转出全部余额后金库已无可收取的部分
+    const [vault] = findVaultPda(program.programId, charged, 0);
+    const proposal = await proposeTransfer(charged, await provider.connection.getBalance(vault));
+    await approve(program, charged, proposal, member1);
+    await approve(program, charged, proposal, member2);
+    const before = await provider.connection.getBalance(recipient);
+    await execute(program, charged, proposal);
+    assert.equal(await provider.connection.getBalance(recipient), before);
+    assert.equal(await provider.connection.getBalance(vault), 0);
+  });
+
+  it("Skips multisigs that opted out at creation", async () => {
+    const proposal = await proposeTransfer(optedOut, LAMPORTS_PER_SOL);
+    assert.isNull((await program.account.proposal.fetch(proposal)).protocolFee);
+  });
+
+  it("Cannot opt out after the first proposal", async () => {
+    await expectError(
+      program.methods
+        .optOutProtocolFee()
+        .accounts({ multisig: charged, creator: member1.publicKey })
+        .signers([member1])
+        .rpc(),
+      "FeeOptOutClosed"
+    );
+    await updateConfig(0, 0);
+  });
+});
@@ -0,0 +1,84 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  governed,
+  memoInstruction,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("prune approvals of removed members", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2, , compromised] = members;
+
+  let multisigPda: PublicKey;
+  let proposal: PublicKey;
+  let oldMembers: PublicKey[];
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Strips the removed member's approval", async () => {
+    proposal = await propose(program, multisigPda, member1, memoInstruction("pay", [multisigPda]));
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, compromised);
+
+    oldMembers = (await program.account.multisig.fetch(multisigPda)).members.map((m: any) => m.key);
+
+    const ix = await program.methods
+      .removeMember(compromised.publicKey)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, [member1, member2], ix);
+
+    // 未迁移前提案已失效，不能执行
+    await expectError(execute(program, multisigPda, proposal), "StaleProposal");
+
+    await program.methods
+      .pruneApprovals(oldMembers)
+      .accounts({ multisig: multisigPda, proposal })
+      .rpc();
+
+    const account = await program.account.proposal.fetch(proposal);
+    const current = (await program.account.multisig.fetch(multisigPda)).members;
+    const index = current.findIndex((m: any) => m.key.equals(member1.publicKey));
+    assert.equal(account.approvals, 1 << index);
+
+    await expectError(execute(program, multisigPda, proposal), "NotExecutable");
+  });
+
+  it("Rejects a forged member list", async () => {
+    const forged = [...oldMembers].reverse();
+    await expectError(
+      program.methods.pruneApprovals(forged).accounts({ multisig: multisigPda, proposal }).rpc(),
+      "MembersMismatch"
+    );
+  });
+
+  it("Executes once a current member approves", async () => {
+    await approve(program, multisigPda, proposal, member2);
+    await execute(program, multisigPda, proposal);
+  });
+});
@@ -0,0 +1,118 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  governed,
+  memoInstruction,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("abstain votes and participation quorum", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = Array.from({ length: 5 }, () => Keypair.generate());
+  const [member1, member2, member3, member4] = members;
+
+  let multisigPda: PublicKey;
+
+  const vote = (method: "rejectTransaction" | "abstainTransaction", proposal: PublicKey, voter: anchor.web3.Keypair) =>
+    (method === "rejectTransaction" ? program.methods.rejectTransaction(0, "") : program.methods[method]())
+      .accounts({ multisig: multisigPda, proposal, approver: voter.publicKey })
+      .signers([voter])
+      .rpc();
+
+  const memo = (text: string) => propose(program, multisigPda, member1, memoInstruction(text, [multisigPda]));
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+
+    // 5 人中至少 3 人参与（60%）
+    const ix = await program.methods
+      .setQuorum(3)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, [member1, member2], ix);
+  });
+
+  it("Rejects a quorum above the member count", async () => {
+    const ix = await program.methods
+      .setQuorum(6)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await expectError(governed(program, multisigPda, [member1, member2, member3], ix), "InvalidQuorum");
+  });
+
+  it("Threshold met, quorum not met", async () => {
+    const proposal = await memo("threshold only");
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    await expectError(execute(program, multisigPda, proposal), "QuorumNotReached");
+  });
+
+  it("Threshold met, quorum met through an abstention", async () => {
+    const proposal = await memo("abstain");
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    await vote("abstainTransaction", proposal, member3);
+    await execute(program, multisigPda, proposal);
+  });
+
+  it("Threshold not met, quorum met", async () => {
+    const proposal = await memo("rejected");
+    await approve(program, multisigPda, proposal, member1);
+    await vote("rejectTransaction", proposal, member2);
+    await vote("abstainTransaction", proposal, member3);
+    await expectError(execute(program, multisigPda, proposal), "NotExecutable");
+  });
+
+  it("Switching a vote replaces the previous one", async () => {
+    const proposal = await memo("switch");
+    await approve(program, multisigPda, proposal, member1);
+    await vote("rejectTransaction", proposal, member2);
+    await vote("abstainTransaction", proposal, member2);
+    await expectError(vote("abstainTransaction", proposal, member2), "AlreadyVoted");
+
+    // member2 只算一票：1 赞成 + 1 弃权，未达 quorum
+    let account = await program.account.proposal.fetch(proposal);
+    assert.equal(account.rejections, 0);
+    await approve(program, multisigPda, proposal, member2);
+    account = await program.account.proposal.fetch(proposal);
+    assert.equal(account.abstentions, 0);
+    await expectError(execute(program, multisigPda, proposal), "QuorumNotReached");
+
+    await vote("rejectTransaction", proposal, member4);
+    await execute(program, multisigPda, proposal);
+  });
+
+  it("Keeps membership from shrinking below the quorum", async () => {
+    const remove = (member: anchor.web3.Keypair) =>
+      program.methods
+        .removeMember(member.publicKey)
+        .accounts({ multisig: multisigPda, authority: multisigPda })
+        .instruction();
+    const approvers = [member1, member2, member3];
+    await governed(program, multisigPda, approvers, await remove(members[4]));
+    await governed(program, multisigPda, approvers, await remove(member4));
+    await expectError(governed(program, multisigPda, approvers, await remove(member3)), "InvalidQuorum");
+    assert.lengthOf((await program.account.multisig.fetch(multisigPda)).members, 3);
+  });
+});
@@ -0,0 +1,106 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  governed,
+  MEMO_PROGRAM_ID,
+  memoInstruction,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } = anchor.web3;
+
+// timelock 从达到执行时实际要求的阈值起算：3 人、基础阈值 2，
+// memo 程序需要 3 个批准，0.5 SOL 以内的转账只需 1 个
+describe("readiness at the effective threshold", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2, member3] = members;
+  const receiver = Keypair.generate();
+  const TIMELOCK = 3;
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  const readyAt = async (proposal: PublicKey) =>
+    (await program.account.proposal.fetch(proposal)).readyAt;
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vaultPda, 2);
+
+    const config = [
+      await program.methods
+        .setThresholdOverrides([{ programId: MEMO_PROGRAM_ID, minThreshold: 3 }])
+        .accounts({ multisig: multisigPda, authority: multisigPda })
+        .instruction(),
+      await program.methods
+        .setAmountRules([
+          { mint: null, maxAmount: new anchor.BN(LAMPORTS_PER_SOL / 2), threshold: 1 },
+        ])
+        .accounts({ multisig: multisigPda, authority: multisigPda })
+        .instruction(),
+      // timelock 最后设置，之前的治理提案无需等待
+      await program.methods
+        .setTimelock(TIMELOCK, 0, null)
+        .accounts({ multisig: multisigPda, authority: multisigPda })
+        .instruction(),
+    ];
+    for (const ix of config) {
+      await governed(program, multisigPda, members, ix);
+    }
+  });
+
+  it("Starts a tiered proposal's timelock only at the tier threshold", async () => {
+    const proposal = await propose(program, multisigPda, member1, memoInstruction("tiered", [multisigPda]));
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    // 达到基础阈值不算就绪
+    assert.isNull(await readyAt(proposal));
+
+    await sleep((TIMELOCK + 1) * 1000);
+    await approve(program, multisigPda, proposal, member3);
+    assert.isNotNull(await readyAt(proposal));
+    await expectError(execute(program, multisigPda, proposal), "TimelockNotElapsed");
+
+    await sleep((TIMELOCK + 1) * 1000);
+    await execute(program, multisigPda, proposal);
+  });
+
+  it("Starts an amount-rule transfer's timelock at the reduced threshold", async () => {
+    const ix = SystemProgram.transfer({
+      fromPubkey: vaultPda,
+      toPubkey: receiver.publicKey,
+      lamports: LAMPORTS_PER_SOL / 10,
+    });
+    const proposal = await propose(program, multisigPda, member1, ix);
+    await approve(program, multisigPda, proposal, member1);
+    assert.isNotNull(await readyAt(proposal));
+    await expectError(execute(program, multisigPda, proposal), "TimelockNotElapsed");
+
+    await sleep((TIMELOCK + 1) * 1000);
+    await execute(program, multisigPda, proposal);
+    assert.equal(await provider.connection.getBalance(receiver.publicKey), LAMPORTS_PER_SOL / 10);
+  });
+});
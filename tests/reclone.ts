@@ -0,0 +1,84 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findProposalPda,
+  memoInstruction,
+  proposalOptions,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("reclone_proposal", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+
+  let multisigPda: PublicKey;
+
+  const reclone = async (source: PublicKey, proposer: anchor.web3.Keypair) => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    const [proposal] = findProposalPda(program.programId, multisigPda, account.proposalsCount);
+    await program.methods
+      .recloneProposal(proposalOptions())
+      .accounts({ multisig: multisigPda, source, proposal, proposer: proposer.publicKey })
+      .signers([proposer])
+      .rpc();
+    return proposal;
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Copies an open proposal with fresh approvals", async () => {
+    const source = await propose(program, multisigPda, member1, memoInstruction("invoice #42", [multisigPda]));
+    await approve(program, multisigPda, source, member1);
+
+    const copy = await reclone(source, member2);
+    const [src, dst] = await Promise.all([
+      program.account.proposal.fetch(source),
+      program.account.proposal.fetch(copy),
+    ]);
+    assert.deepEqual(Buffer.from(dst.action.raw[0].data), Buffer.from(src.action.raw[0].data));
+    assert.isTrue(dst.proposer.equals(member2.publicKey));
+    assert.equal(dst.approvals, 0);
+    assert.equal(dst.index, src.index + 1);
+
+    // 复制出的提案必须重新批准
+    await expectError(execute(program, multisigPda, copy), "NotExecutable");
+    for (const m of members) {
+      await approve(program, multisigPda, copy, m);
+    }
+    await execute(program, multisigPda, copy);
+  });
+
+  it("Rejects closed sources", async () => {
+    const source = await propose(program, multisigPda, member1, memoInstruction("done", [multisigPda]));
+    for (const m of members) {
+      await approve(program, multisigPda, source, m);
+    }
+    await execute(program, multisigPda, source);
+    await expectError(reclone(source, member1), "AccountNotInitialized");
+  });
+});
@@ -0,0 +1,71 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  findMultisigPda,
+  memoInstruction,
+  MEMO_PROGRAM_ID,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("record-only execution", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+
+  let multisigPda: PublicKey;
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Records an attestation without invoking or signing", async () => {
+    const ix = memoInstruction("board approves document 0xabc123", [multisigPda]);
+    const proposal = await propose(program, multisigPda, member1, ix, { noSign: true });
+    assert.isTrue((await program.account.proposal.fetch(proposal)).noSign);
+
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+
+    let recorded: any = null;
+    const listener = program.addEventListener("proposalRecorded", (event) => {
+      recorded = event;
+    });
+    const sig = await execute(program, multisigPda, proposal);
+    await provider.connection.confirmTransaction(sig, "confirmed");
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.removeEventListener(listener);
+
+    // memo 程序从未被调用
+    const tx = await provider.connection.getTransaction(sig, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    assert.isFalse(tx.meta.logMessages.some((l) => l.includes(`Program ${MEMO_PROGRAM_ID.toBase58()} invoke`)));
+
+    assert.isNotNull(recorded);
+    assert.equal(recorded.actionHash.length, 32);
+    assert.deepEqual(Buffer.from(recorded.action.raw[0].data), ix.data);
+    assert.isNull(await provider.connection.getAccountInfo(proposal));
+  });
+});
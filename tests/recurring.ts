@@ -0,0 +1,156 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  chainTime,
+  expectError,
+  findMultisigPda,
+  findProposalPda,
+  findVaultPda,
+  governed,
+  proposalOptions,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair, LAMPORTS_PER_SOL } = anchor.web3;
+
+describe("recurring payments", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+  const auditor = Keypair.generate();
+
+  const AMOUNT = LAMPORTS_PER_SOL / 10;
+  const INTERVAL = 3;
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  const payment = (maxPayments: number, amount = AMOUNT) => ({
+    destination: auditor.publicKey,
+    amount: new anchor.BN(amount),
+    intervalSeconds: new anchor.BN(INTERVAL),
+    maxPayments,
+  });
+
+  const proposeRecurring = async (p: ReturnType<typeof payment>) => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    const [proposal] = findProposalPda(program.programId, multisigPda, account.proposalsCount);
+    await program.methods
+      .proposeRecurringPayment(p, proposalOptions())
+      .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    return proposal;
+  };
+
+  const activate = async (proposal: PublicKey) => {
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    await program.methods.activateProposal().accounts({ multisig: multisigPda, proposal }).rpc();
+  };
+
+  // 无需许可：由 provider 钱包发起
+  const crank = (proposal: PublicKey, destination = auditor.publicKey) =>
+    program.methods
+      .executeRecurringPayment()
+      .accounts({ multisig: multisigPda, proposal, vault: vaultPda, destination })
+      .rpc();
+
+  const received = () => provider.connection.getBalance(auditor.publicKey);
+
+  const waitInterval = async (proposal: PublicKey) => {
+    const { lastPaidAt } = await program.account.proposal.fetch(proposal);
+    while ((await chainTime(provider)) < lastPaidAt.toNumber() + INTERVAL) {
+      await sleep(1000);
+    }
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vaultPda, 2);
+  });
+
+  it("Rejects zero amounts, intervals or payment counts", async () => {
+    await expectError(proposeRecurring(payment(3, 0)), "InvalidRecurringPayment");
+    await expectError(proposeRecurring(payment(0)), "InvalidRecurringPayment");
+  });
+
+  it("Pays once per interval until max_payments, then closes", async () => {
+    const proposal = await proposeRecurring(payment(3));
+    await expectError(crank(proposal), "RecurringNotActive");
+    await expectError(
+      program.methods.executeTransaction().accounts({ multisig: multisigPda, proposal }).rpc(),
+      "StandingProposal"
+    );
+    await activate(proposal);
+
+    const counts: number[] = [];
+    const listener = program.addEventListener("recurringPaymentMade", (event) => {
+      counts.push(event.paymentsMade);
+    });
+
+    await crank(proposal);
+    assert.equal(await received(), AMOUNT);
+    // 同一周期内再次调用被拒绝
+    await expectError(crank(proposal), "PaymentNotDue");
+    await expectError(crank(proposal, member1.publicKey), "AccountMismatch");
+
+    await waitInterval(proposal);
+    await crank(proposal);
+    assert.equal((await program.account.proposal.fetch(proposal)).paymentsMade, 2);
+
+    await waitInterval(proposal);
+    await crank(proposal);
+    assert.equal(await received(), 3 * AMOUNT);
+    assert.isNull(await provider.connection.getAccountInfo(proposal));
+
+    await sleep(1000);
+    await program.removeEventListener(listener);
+    assert.deepEqual(counts, [1, 2, 3]);
+  });
+
+  it("Stops future payments when governance cancels", async () => {
+    const proposal = await proposeRecurring(payment(12));
+    await activate(proposal);
+    await crank(proposal);
+    const paid = await received();
+
+    await expectError(
+      program.methods
+        .cancelRecurringPayment()
+        .accounts({ multisig: multisigPda, proposal, authority: member1.publicKey })
+        .signers([member1])
+        .rpc(),
+      "Unauthorized"
+    );
+    const ix = await program.methods
+      .cancelRecurringPayment()
+      .accounts({ multisig: multisigPda, proposal, authority: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, members, ix);
+
+    assert.isNull(await provider.connection.getAccountInfo(proposal));
+    await sleep(INTERVAL * 1000);
+    await expectError(crank(proposal), "AccountNotInitialized");
+    assert.equal(await received(), paid);
+  });
+});
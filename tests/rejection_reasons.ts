@@ -0,0 +1,105 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  expectError,
+  findMultisigPda,
+  memoInstruction,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+const MAX_REASON = 80;
+
+describe("rejection reasons", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2, member3] = members;
+
+  let multisigPda: PublicKey;
+
+  const memo = (text: string) => propose(program, multisigPda, member1, memoInstruction(text, [multisigPda]));
+
+  const reject = (proposal: PublicKey, voter: anchor.web3.Keypair, code: number, reason: string) =>
+    program.methods
+      .rejectTransaction(code, reason)
+      .accounts({ multisig: multisigPda, proposal, approver: voter.publicKey })
+      .signers([voter])
+      .rpc();
+
+  const reasons = async (proposal: PublicKey) => (await program.account.proposal.fetch(proposal)).rejectionReasons;
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 3)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Stores a maximum-length reason and emits it", async () => {
+    const proposal = await memo("max length");
+    await expectError(reject(proposal, member2, 1, "x".repeat(MAX_REASON + 1)), "InvalidRejectionReason");
+
+    let rejected: any = null;
+    const listener = program.addEventListener("proposalRejected", (event) => {
+      rejected = event;
+    });
+    const text = "y".repeat(MAX_REASON);
+    await reject(proposal, member2, 1, text);
+    await sleep(1000);
+    await program.removeEventListener(listener);
+
+    assert.isTrue(rejected.member.equals(member2.publicKey));
+    assert.equal(rejected.reason, text);
+    assert.equal(rejected.rejections, 1);
+    const [stored] = await reasons(proposal);
+    assert.isTrue(stored.member.equals(member2.publicKey));
+    assert.equal(stored.code, 1);
+    assert.equal(stored.reason, text);
+  });
+
+  it("Fits a full-length reason from every member", async () => {
+    const proposal = await memo("everyone objects");
+    for (const m of members) {
+      await reject(proposal, m, 2, "z".repeat(MAX_REASON));
+    }
+    assert.equal((await reasons(proposal)).length, members.length);
+  });
+
+  it("Clears the reason when the member changes their vote", async () => {
+    const proposal = await memo("vote changes");
+    await reject(proposal, member2, 3, "wrong recipient");
+    await reject(proposal, member3, 0, "");
+    assert.equal((await reasons(proposal)).length, 2);
+
+    await approve(program, multisigPda, proposal, member2);
+    const remaining = await reasons(proposal);
+    assert.equal(remaining.length, 1);
+    assert.isTrue(remaining[0].member.equals(member3.publicKey));
+
+    await program.methods
+      .abstainTransaction()
+      .accounts({ multisig: multisigPda, proposal, approver: member3.publicKey })
+      .signers([member3])
+      .rpc();
+    assert.deepEqual(await reasons(proposal), []);
+
+    // 再次反对时记录新的理由
+    await reject(proposal, member2, 4, "amount fixed, but memo still wrong");
+    assert.equal((await reasons(proposal))[0].reason, "amount fixed, but memo still wrong");
+  });
+});
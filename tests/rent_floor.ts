@@ -0,0 +1,97 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  propose,
+  proposeAction,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram } = anchor.web3;
+
+describe("vault rent-exempt floor", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+  const receiver = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  const approveAll = async (proposal: PublicKey) => {
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vaultPda, 1);
+  });
+
+  it("Allows transfers down to exactly the rent-exempt minimum", async () => {
+    const floor = await provider.connection.getMinimumBalanceForRentExemption(0);
+    const balance = await provider.connection.getBalance(vaultPda);
+
+    const tooMuch = await propose(
+      program,
+      multisigPda,
+      member1,
+      SystemProgram.transfer({ fromPubkey: vaultPda, toPubkey: receiver.publicKey, lamports: balance - floor + 1 })
+    );
+    await approveAll(tooMuch);
+    await expectError(execute(program, multisigPda, tooMuch), "WouldBreakRentExemption");
+
+    const maximum = await propose(
+      program,
+      multisigPda,
+      member1,
+      SystemProgram.transfer({ fromPubkey: vaultPda, toPubkey: receiver.publicKey, lamports: balance - floor })
+    );
+    await approveAll(maximum);
+    await execute(program, multisigPda, maximum);
+    assert.equal(await provider.connection.getBalance(vaultPda), floor);
+  });
+
+  it("Empties the vault only through an explicit sweep", async () => {
+    const destination = Keypair.generate().publicKey;
+    const proposal = await proposeAction(program, multisigPda, member1, {
+      sweepVault: { index: 0, destination },
+    });
+    await approveAll(proposal);
+
+    const balance = await provider.connection.getBalance(vaultPda);
+    await program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts([
+        { pubkey: vaultPda, isSigner: false, isWritable: true },
+        { pubkey: destination, isSigner: false, isWritable: true },
+        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+      ])
+      .rpc();
+
+    assert.equal(await provider.connection.getBalance(vaultPda), 0);
+    assert.equal(await provider.connection.getBalance(destination), balance);
+  });
+});
@@ -0,0 +1,115 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  governed,
+  inviteAndAccept,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("resize multisig", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+  const funder = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let baseSpace: number;
+
+  // 每个成员槽 51 字节
+  const space = (maxMembers: number) => baseSpace + 51 * maxMembers;
+  const dataLength = async () => (await provider.connection.getAccountInfo(multisigPda)).data.length;
+
+  const resize = (newSize: number, authority: Keypair, payer: Keypair = funder) =>
+    program.methods
+      .resizeMultisig(newSize)
+      .accounts({ multisig: multisigPda, authority: authority.publicKey, payer: payer.publicKey })
+      .signers(authority === payer ? [payer] : [authority, payer])
+      .rpc();
+
+  before(async () => {
+    for (const m of [...members, funder]) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 2)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    baseSpace = (await dataLength()) - 41 * 2;
+  });
+
+  it("Never shrinks the account", async () => {
+    await expectError(resize(space(2) - 1, member1), "InvalidResize");
+    await expectError(resize(space(1), member1), "InvalidResize");
+    assert.equal(await dataLength(), space(2));
+  });
+
+  it("Rejects authorities other than the creator during bootstrap", async () => {
+    await expectError(resize(space(3), member2), "Unauthorized");
+    await expectError(resize(space(3), funder), "Unauthorized");
+  });
+
+  it("Lets the creator grow the account during bootstrap, paid by any funder", async () => {
+    await resize(space(3), member1);
+
+    const info = await provider.connection.getAccountInfo(multisigPda);
+    assert.equal(info.data.length, space(3));
+    assert.equal(info.lamports, await provider.connection.getMinimumBalanceForRentExemption(space(3)));
+
+    // 扩容后仍可正常反序列化，已有数据不变
+    const account = await program.account.multisig.fetch(multisigPda);
+    assert.equal(account.maxMembers, 3);
+    assert.equal(account.members.length, 2);
+    assert.equal(account.threshold, 2);
+  });
+
+  it("Requires self-governance once a proposal exists", async () => {
+    const newcomer = Keypair.generate();
+    await airdrop(provider, newcomer.publicKey);
+    await inviteAndAccept(program, multisigPda, members, newcomer);
+    members.push(newcomer);
+    await expectError(resize(space(4), member1), "Unauthorized");
+
+    // 自治提案由金库出资
+    const [vault] = findVaultPda(program.programId, multisigPda, 0);
+    await airdrop(provider, vault);
+    const ix = await program.methods
+      .resizeMultisig(space(4))
+      .accounts({ multisig: multisigPda, authority: multisigPda, payer: vault })
+      .instruction();
+    await governed(program, multisigPda, members.slice(0, 2), ix);
+
+    assert.equal(await dataLength(), space(4));
+    assert.equal((await program.account.multisig.fetch(multisigPda)).maxMembers, 4);
+  });
+
+  it("Uses the extra space for another member", async () => {
+    const newcomer = Keypair.generate();
+    await airdrop(provider, newcomer.publicKey);
+    await inviteAndAccept(program, multisigPda, members.slice(0, 2), newcomer);
+
+    const account = await program.account.multisig.fetch(multisigPda);
+    assert.equal(account.members.length, 4);
+    assert.isTrue(account.members.some((m: any) => m.key.equals(newcomer.publicKey)));
+
+    const extra = Keypair.generate();
+    const ix = await program.methods
+      .inviteMember(extra.publicKey, null, null)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await expectError(governed(program, multisigPda, members.slice(0, 2), ix), "TooManyMembers");
+  });
+});
@@ -0,0 +1,122 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  executeRemainingAccounts,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  governed,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } = anchor.web3;
+
+describe("retained executed proposals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate()];
+  const [member1, member2] = members;
+  const payee = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let vault: PublicKey;
+
+  const setRetain = async (retain: boolean, seconds: number) =>
+    governed(
+      program,
+      multisigPda,
+      members,
+      await program.methods
+        .setRetainExecuted(retain, seconds)
+        .accounts({ multisig: multisigPda, authority: multisigPda })
+        .instruction()
+    );
+
+  const approvedSend = async () => {
+    const ix = SystemProgram.transfer({ fromPubkey: vault, toPubkey: payee.publicKey, lamports: LAMPORTS_PER_SOL / 10 });
+    const proposal = await propose(program, multisigPda, member1, ix);
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    return proposal;
+  };
+
+  const executeAs = async (proposal: PublicKey, executor: anchor.web3.Keypair) => {
+    const account = await program.account.proposal.fetch(proposal);
+    return program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal, spendLedger: null, executor: executor.publicKey })
+      .remainingAccounts(executeRemainingAccounts(account.action, [multisigPda, vault]))
+      .signers([executor])
+      .rpc();
+  };
+
+  const closeExecuted = (proposal: PublicKey) =>
+    program.methods.closeExecutedProposal().accounts({ multisig: multisigPda, proposal }).rpc();
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vault] = findVaultPda(program.programId, multisigPda, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vault);
+  });
+
+  it("Closes executed proposals by default", async () => {
+    const proposal = await approvedSend();
+    await execute(program, multisigPda, proposal);
+    assert.isNull(await provider.connection.getAccountInfo(proposal));
+  });
+
+  it("Keeps a readable tombstone that cannot be replayed or approved", async () => {
+    await setRetain(true, 3);
+    const proposal = await approvedSend();
+    await executeAs(proposal, member2);
+
+    const tombstone = await program.account.proposal.fetch(proposal);
+    assert.isTrue(tombstone.executed);
+    assert.isTrue(tombstone.executedBy.equals(member2.publicKey));
+    assert.isNotNull(tombstone.executedAt);
+    // 指令数据仍然完整可读
+    assert.isTrue(tombstone.action.raw[0].programId.equals(SystemProgram.programId));
+    assert.equal(tombstone.action.raw[0].data.length, 12);
+
+    await expectError(execute(program, multisigPda, proposal), "AlreadyProcessed");
+    await expectError(executeAs(proposal, member1), "AlreadyProcessed");
+    await expectError(approve(program, multisigPda, proposal, member1), "AlreadyProcessed");
+    assert.equal(await provider.connection.getBalance(payee.publicKey), (2 * LAMPORTS_PER_SOL) / 10);
+  });
+
+  it("Reclaims rent only after the retention period", async () => {
+    const proposal = await approvedSend();
+    await execute(program, multisigPda, proposal);
+    assert.isNull((await program.account.proposal.fetch(proposal)).executedBy);
+
+    await expectError(closeExecuted(proposal), "RetentionNotElapsed");
+    await sleep(4000);
+    const before = await provider.connection.getBalance(multisigPda);
+    await closeExecuted(proposal);
+    assert.isNull(await provider.connection.getAccountInfo(proposal));
+    assert.isAbove(await provider.connection.getBalance(multisigPda), before);
+  });
+
+  it("Refuses to close proposals that have not executed", async () => {
+    const proposal = await approvedSend();
+    await expectError(closeExecuted(proposal), "NotExecuted");
+  });
+});
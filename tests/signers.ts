@@ -0,0 +1,86 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findProposalPda,
+  findVaultPda,
+  memoInstruction,
+  proposalOptions,
+  toInstructionData,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("per-instruction signer selector", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  // 显式指定 signers 位提案
+  const proposeWith = async (ix: anchor.web3.TransactionInstruction, signers: number) => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    const [proposal] = findProposalPda(program.programId, multisigPda, account.proposalsCount);
+    await program.methods
+      .proposeTransaction(toInstructionData(ix, signers), proposalOptions())
+      .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    return proposal;
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Records the selected signers on the proposal", async () => {
+    const proposal = await proposeWith(memoInstruction("vault only", [vaultPda]), 0b10);
+    const account = await program.account.proposal.fetch(proposal);
+    assert.equal(account.action.raw[0].signers, 0b10);
+    await execute(program, multisigPda, proposal);
+  });
+
+  it("Does not sign with the config PDA when only the vault is selected", async () => {
+    // memo 要求所有传入账户都签名：multisig PDA 未被选中，CPI 失败
+    const proposal = await proposeWith(memoInstruction("config needed", [multisigPda]), 0b10);
+    await expectError(execute(program, multisigPda, proposal), "");
+    const account = await program.account.proposal.fetch(proposal);
+    assert.isFalse(account.executed);
+  });
+
+  it("Does not sign with the vault when only the config PDA is selected", async () => {
+    const proposal = await proposeWith(memoInstruction("vault needed", [vaultPda]), 0b01);
+    await expectError(execute(program, multisigPda, proposal), "");
+  });
+
+  it("Invokes without signatures when no signer is selected", async () => {
+    const proposal = await proposeWith(memoInstruction("unsigned", []), 0);
+    await execute(program, multisigPda, proposal);
+    assert.isNull(await provider.connection.getAccountInfo(proposal));
+  });
+});
@@ -0,0 +1,167 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  executeAccounts,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  governed,
+  MEMO_PROGRAM_ID,
+  memoInstruction,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair, Transaction } = anchor.web3;
+
+describe("minimum execution delay in slots", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  // 最后一票与执行打包在同一笔交易中
+  const approveAndExecute = async (proposal: PublicKey, approver: anchor.web3.Keypair) => {
+    const account = await program.account.proposal.fetch(proposal);
+    const approveIx = await program.methods
+      .approveTransaction(null)
+      .accounts({ multisig: multisigPda, proposal, approver: approver.publicKey })
+      .instruction();
+    const executeIx = await program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts(executeAccounts(account.action.raw[0], [multisigPda, vaultPda]))
+      .instruction();
+    return provider.sendAndConfirm(new Transaction().add(approveIx, executeIx), [approver]);
+  };
+
+  const halfApproved = async (text: string) => {
+    const proposal = await propose(program, multisigPda, member1, memoInstruction(text, [multisigPda]));
+    await approve(program, multisigPda, proposal, member1);
+    return proposal;
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Zero delay allows approve-and-execute in one transaction", async () => {
+    await approveAndExecute(await halfApproved("bundled"), member2);
+  });
+
+  it("A configured delay rejects bundled approve-and-execute", async () => {
+    const ix = await program.methods
+      .setMinExecutionDelay(4)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, members, ix);
+
+    const proposal = await halfApproved("monitored");
+    await expectError(approveAndExecute(proposal, member2), "ExecutionTooSoon");
+
+    await approve(program, multisigPda, proposal, member2);
+    await expectError(execute(program, multisigPda, proposal), "ExecutionTooSoon");
+
+    // 等待足够的 slot 后可执行
+    const ready = await provider.connection.getSlot("confirmed");
+    while ((await provider.connection.getSlot("confirmed")) < ready + 4) {
+      await sleep(400);
+    }
+    await execute(program, multisigPda, proposal);
+  });
+});
+
+// 基础阈值 2、memo 程序需要 3 个批准：slot 间隔从达到档位阈值起算
+describe("minimum execution delay for tiered proposals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2, member3] = members;
+  const DELAY = 4;
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  const waitSlots = async (slots: number) => {
+    const start = await provider.connection.getSlot("confirmed");
+    while ((await provider.connection.getSlot("confirmed")) < start + slots) {
+      await sleep(400);
+    }
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+
+    const overrides = await program.methods
+      .setThresholdOverrides([{ programId: MEMO_PROGRAM_ID, minThreshold: 3 }])
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, members, overrides);
+    const delay = await program.methods
+      .setMinExecutionDelay(DELAY)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, members, delay);
+  });
+
+  it("Measures the slot gap from the tier threshold, not the base threshold", async () => {
+    const proposal = await propose(program, multisigPda, member1, memoInstruction("tiered", [multisigPda]));
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    assert.isNull((await program.account.proposal.fetch(proposal)).readySlot);
+    await waitSlots(DELAY);
+
+    // 基础阈值早已达到，最后一个档位批准仍不能与执行打包
+    const account = await program.account.proposal.fetch(proposal);
+    const approveIx = await program.methods
+      .approveTransaction(null)
+      .accounts({ multisig: multisigPda, proposal, approver: member3.publicKey })
+      .instruction();
+    const executeIx = await program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts(executeAccounts(account.action.raw[0], [multisigPda, vaultPda]))
+      .instruction();
+    await expectError(
+      provider.sendAndConfirm(new Transaction().add(approveIx, executeIx), [member3]),
+      "ExecutionTooSoon"
+    );
+
+    await approve(program, multisigPda, proposal, member3);
+    await waitSlots(DELAY);
+    await execute(program, multisigPda, proposal);
+  });
+});
@@ -0,0 +1,107 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  governed,
+  memoInstruction,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("proposal member snapshot", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  // 每个用例使用独立的多签：3 人，阈值 2
+  const setup = async (nonce: number, snapshot: boolean) => {
+    const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    const [multisigPda] = findMultisigPda(program.programId, members[0].publicKey, nonce);
+    await program.methods
+      .createMultisig(nonce, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: members[0].publicKey })
+      .signers([members[0]])
+      .rpc();
+    if (snapshot) {
+      const ix = await program.methods
+        .setExecuteAgainstSnapshot(true)
+        .accounts({ multisig: multisigPda, authority: multisigPda })
+        .instruction();
+      await governed(program, multisigPda, members.slice(0, 2), ix);
+    }
+    return { members, multisigPda };
+  };
+
+  // 创建时的成员列表（按 key 排序），供 prune_approvals 校验
+  const memberKeys = async (multisigPda: PublicKey) =>
+    (await program.account.multisig.fetch(multisigPda)).members.map((m) => m.key);
+
+  const prune = (multisigPda: PublicKey, proposal: PublicKey, oldMembers: PublicKey[]) =>
+    program.methods.pruneApprovals(oldMembers).accounts({ multisig: multisigPda, proposal }).rpc();
+
+  // 提案获得 2 票后移除第三位成员
+  const approveThenRemove = async (
+    multisigPda: PublicKey,
+    members: anchor.web3.Keypair[],
+    approvers = members.slice(0, 2)
+  ) => {
+    const proposal = await propose(program, multisigPda, members[0], memoInstruction("churn", [multisigPda]));
+    for (const m of approvers) {
+      await approve(program, multisigPda, proposal, m);
+    }
+
+    const account = await program.account.proposal.fetch(proposal);
+    assert.equal(account.thresholdAtCreation, 2);
+
+    const ix = await program.methods
+      .removeMember(members[2].publicKey)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, members.slice(0, 2), ix);
+    return proposal;
+  };
+
+  it("Live mode rejects proposals created before a membership change", async () => {
+    const { members, multisigPda } = await setup(0, false);
+    const proposal = await approveThenRemove(multisigPda, members);
+    await expectError(execute(program, multisigPda, proposal), "StaleProposal");
+  });
+
+  it("Snapshot mode executes with the creation threshold after pruning", async () => {
+    const { members, multisigPda } = await setup(0, true);
+    const oldMembers = await memberKeys(multisigPda);
+    const proposal = await approveThenRemove(multisigPda, members);
+    // 旧位图须先对应到当前成员
+    await expectError(execute(program, multisigPda, proposal), "StaleProposal");
+    await prune(multisigPda, proposal, oldMembers);
+    await execute(program, multisigPda, proposal);
+    assert.isNull(await provider.connection.getAccountInfo(proposal));
+  });
+
+  it("Snapshot mode does not count approvals from removed members", async () => {
+    const { members, multisigPda } = await setup(1, true);
+    const oldMembers = await memberKeys(multisigPda);
+    // 被移除成员的批准恰好凑够阈值
+    const proposal = await approveThenRemove(multisigPda, members, [members[0], members[2]]);
+    await expectError(execute(program, multisigPda, proposal), "StaleProposal");
+
+    await prune(multisigPda, proposal, oldMembers);
+    const index = (await memberKeys(multisigPda)).findIndex((k) => k.equals(members[0].publicKey));
+    assert.equal((await program.account.proposal.fetch(proposal)).approvals, 1 << index);
+    await expectError(execute(program, multisigPda, proposal), "NotExecutable");
+
+    await approve(program, multisigPda, proposal, members[1]);
+    await execute(program, multisigPda, proposal);
+  });
+});
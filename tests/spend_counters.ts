@@ -0,0 +1,141 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  createAssociatedTokenAccount,
+  createMint,
+  createTransferCheckedInstruction,
+  createTransferInstruction,
+  mintTo,
+} from "@solana/spl-token";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findSpendLedgerPda,
+  findVaultPda,
+  governed,
+  memoInstruction,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } = anchor.web3;
+
+describe("spend counters", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+  const payee = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+  let ledgerPda: PublicKey;
+  let mint: PublicKey;
+  let vaultAta: PublicKey;
+  let payeeAta: PublicKey;
+
+  // 提案、全员批准并执行；ledger 为 null 时不传支出账本
+  const run = async (ix: anchor.web3.TransactionInstruction, ledger: PublicKey | null = ledgerPda) => {
+    const proposal = await propose(program, multisigPda, member1, ix);
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    await execute(program, multisigPda, proposal, ledger);
+  };
+
+  const lamportsOut = async () => (await program.account.multisig.fetch(multisigPda)).totalLamportsOut.toNumber();
+
+  const tokensOut = async () => {
+    const { mints } = await program.account.spendLedger.fetch(ledgerPda);
+    const entry = mints.find((m) => m.mint.equals(mint));
+    return entry ? entry.amount.toNumber() : 0;
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    [ledgerPda] = findSpendLedgerPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vaultPda);
+
+    mint = await createMint(provider.connection, member1, member1.publicKey, null, 6);
+    vaultAta = await createAssociatedTokenAccount(provider.connection, member1, mint, vaultPda, undefined, undefined, undefined, true);
+    payeeAta = await createAssociatedTokenAccount(provider.connection, member1, mint, payee.publicKey);
+    await mintTo(provider.connection, member1, mint, vaultAta, member1, 10_000);
+  });
+
+  it("Starts at zero and creates the ledger permissionlessly", async () => {
+    assert.equal(await lamportsOut(), 0);
+    await program.methods
+      .createSpendLedger()
+      .accounts({ multisig: multisigPda, payer: member2.publicKey })
+      .signers([member2])
+      .rpc();
+    const ledger = await program.account.spendLedger.fetch(ledgerPda);
+    assert.isTrue(ledger.payer.equals(member2.publicKey));
+    assert.lengthOf(ledger.mints, 0);
+  });
+
+  it("Counts recognized SOL transfers out of the vault", async () => {
+    const lamports = LAMPORTS_PER_SOL / 10;
+    await run(SystemProgram.transfer({ fromPubkey: vaultPda, toPubkey: payee.publicKey, lamports }));
+    await run(SystemProgram.transfer({ fromPubkey: vaultPda, toPubkey: payee.publicKey, lamports }), null);
+    assert.equal(await lamportsOut(), 2 * lamports);
+  });
+
+  it("Counts recognized SPL transfers only when the ledger is passed", async () => {
+    await run(createTransferInstruction(vaultAta, payeeAta, vaultPda, 300));
+    await run(createTransferCheckedInstruction(vaultAta, mint, payeeAta, vaultPda, 200, 6));
+    assert.equal(await tokensOut(), 500);
+
+    await run(createTransferInstruction(vaultAta, payeeAta, vaultPda, 100), null);
+    assert.equal(await tokensOut(), 500);
+  });
+
+  it("Ignores instructions it cannot decode", async () => {
+    const before = await lamportsOut();
+    await run(memoInstruction("not a transfer", [vaultPda]));
+    // 补齐多余字节的转账：System 程序仍接受，但严格解码不再识别
+    const padded = SystemProgram.transfer({ fromPubkey: vaultPda, toPubkey: payee.publicKey, lamports: 1_000 });
+    padded.data = Buffer.concat([padded.data, Buffer.alloc(4)]);
+    await run(padded);
+    assert.equal(await lamportsOut(), before);
+    assert.equal(await tokensOut(), 500);
+  });
+
+  it("Closes the ledger only through governance and refunds the creator", async () => {
+    await expectError(
+      program.methods
+        .closeSpendLedger()
+        .accounts({ multisig: multisigPda, authority: member1.publicKey, payer: member2.publicKey })
+        .signers([member1])
+        .rpc(),
+      "Unauthorized"
+    );
+
+    const before = await provider.connection.getBalance(member2.publicKey);
+    const ix = await program.methods
+      .closeSpendLedger()
+      .accounts({ multisig: multisigPda, authority: multisigPda, payer: member2.publicKey })
+      .instruction();
+    await governed(program, multisigPda, members, ix);
+    assert.isNull(await provider.connection.getAccountInfo(ledgerPda));
+    assert.isAbove(await provider.connection.getBalance(member2.publicKey), before);
+    assert.isAbove(await lamportsOut(), 0);
+  });
+});
@@ -0,0 +1,245 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findProposalPda,
+  findVaultPda,
+  propose,
+  proposalOptions,
+  sleep,
+} from "./utils";
+
+const {
+  PublicKey,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  SystemProgram,
+  SYSVAR_CLOCK_PUBKEY,
+  SYSVAR_STAKE_HISTORY_PUBKEY,
+  Authorized,
+  StakeProgram,
+  Transaction,
+  VoteInit,
+  VoteProgram,
+} = anchor.web3;
+
+// Anchor.toml 中 slots_per_epoch = 32，整个流程需要跨越几个 epoch
+describe("stake lifecycle proposals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+  const identity = Keypair.generate();
+  const voteAccount = Keypair.generate();
+  const stakeAccount = Keypair.generate();
+  const splitAccount = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  const nextEpoch = async () => {
+    const { epoch } = await provider.connection.getEpochInfo();
+    while ((await provider.connection.getEpochInfo()).epoch <= epoch) {
+      await sleep(1000);
+    }
+  };
+
+  const delegation = async (stake: PublicKey) => {
+    const info = await provider.connection.getParsedAccountInfo(stake);
+    return (info.value.data as any).parsed.info.stake?.delegation;
+  };
+
+  // 提交并全员批准，返回提案地址
+  const proposeWith = async (build: (proposal: PublicKey) => Promise<unknown>) => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    const [proposal] = findProposalPda(program.programId, multisigPda, account.proposalsCount);
+    await build(proposal);
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    return proposal;
+  };
+
+  const proposeSplit = (lamports: number) =>
+    proposeWith((proposal) =>
+      program.methods
+        .proposeStakeSplit(stakeAccount.publicKey, new anchor.BN(lamports), proposalOptions())
+        .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+        .signers([member1])
+        .rpc()
+    );
+
+  const executeSplit = (proposal: PublicKey, newStake: anchor.web3.Keypair, signs = true) =>
+    program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts([
+        { pubkey: newStake.publicKey, isSigner: signs, isWritable: true },
+        { pubkey: stakeAccount.publicKey, isSigner: false, isWritable: true },
+        { pubkey: vaultPda, isSigner: false, isWritable: true },
+        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+        { pubkey: StakeProgram.programId, isSigner: false, isWritable: false },
+      ])
+      .signers(signs ? [newStake] : [])
+      .rpc();
+
+  const deactivate = async (stake: PublicKey) => {
+    const proposal = await proposeWith((proposal) =>
+      program.methods
+        .proposeStakeDeactivate(stake, proposalOptions())
+        .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+        .signers([member1])
+        .rpc()
+    );
+    return program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts([
+        { pubkey: stake, isSigner: false, isWritable: true },
+        { pubkey: SYSVAR_CLOCK_PUBKEY, isSigner: false, isWritable: false },
+        { pubkey: StakeProgram.programId, isSigner: false, isWritable: false },
+      ])
+      .rpc();
+  };
+
+  const withdraw = async (stake: PublicKey, lamports: number) => {
+    const proposal = await proposeWith((proposal) =>
+      program.methods
+        .proposeStakeWithdraw(stake, new anchor.BN(lamports), vaultPda, proposalOptions())
+        .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+        .signers([member1])
+        .rpc()
+    );
+    return program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts([
+        { pubkey: stake, isSigner: false, isWritable: true },
+        { pubkey: vaultPda, isSigner: false, isWritable: true },
+        { pubkey: SYSVAR_CLOCK_PUBKEY, isSigner: false, isWritable: false },
+        { pubkey: SYSVAR_STAKE_HISTORY_PUBKEY, isSigner: false, isWritable: false },
+        { pubkey: StakeProgram.programId, isSigner: false, isWritable: false },
+      ])
+      .rpc();
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey, 10);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vaultPda);
+
+    // vote 账户与 staker / withdrawer 均为 multisig PDA 的 stake 账户
+    const voteRent = await provider.connection.getMinimumBalanceForRentExemption(VoteProgram.space);
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        VoteProgram.createAccount({
+          fromPubkey: member1.publicKey,
+          votePubkey: voteAccount.publicKey,
+          voteInit: new VoteInit(identity.publicKey, identity.publicKey, identity.publicKey, 0),
+          lamports: voteRent,
+        })
+      ),
+      [member1, voteAccount, identity]
+    );
+    const stakeRent = await provider.connection.getMinimumBalanceForRentExemption(StakeProgram.space);
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        StakeProgram.createAccount({
+          fromPubkey: member1.publicKey,
+          stakePubkey: stakeAccount.publicKey,
+          authorized: new Authorized(multisigPda, multisigPda),
+          lamports: stakeRent + 4 * LAMPORTS_PER_SOL,
+        })
+      ),
+      [member1, stakeAccount]
+    );
+
+    // 委托沿用普通提案，由 multisig PDA 签名
+    const delegateIx = StakeProgram.delegate({
+      stakePubkey: stakeAccount.publicKey,
+      authorizedPubkey: multisigPda,
+      votePubkey: voteAccount.publicKey,
+    }).instructions[0];
+    const proposal = await propose(program, multisigPda, member1, delegateIx);
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    await execute(program, multisigPda, proposal);
+    await nextEpoch();
+  });
+
+  it("Validates amounts and the stake authority", async () => {
+    await expectError(proposeSplit(0), "InvalidStakeAmount");
+
+    const foreign = Keypair.generate();
+    const rent = await provider.connection.getMinimumBalanceForRentExemption(StakeProgram.space);
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        StakeProgram.createAccount({
+          fromPubkey: member1.publicKey,
+          stakePubkey: foreign.publicKey,
+          authorized: new Authorized(member1.publicKey, member1.publicKey),
+          lamports: rent + LAMPORTS_PER_SOL,
+        })
+      ),
+      [member1, foreign]
+    );
+    await expectError(deactivate(foreign.publicKey), "StakeAuthorityMismatch");
+    await expectError(withdraw(foreign.publicKey, LAMPORTS_PER_SOL), "StakeAuthorityMismatch");
+  });
+
+  it("Splits part of the stake into a vault-funded account", async () => {
+    const proposal = await proposeSplit(LAMPORTS_PER_SOL);
+    await expectError(executeSplit(proposal, splitAccount, false), "StakeAccountNotSigner");
+
+    let split: any = null;
+    const listener = program.addEventListener("stakeAccountSplit", (event) => {
+      split = event;
+    });
+    const vaultBefore = await provider.connection.getBalance(vaultPda);
+    await executeSplit(proposal, splitAccount);
+    await sleep(1000);
+    await program.removeEventListener(listener);
+
+    assert.isTrue(split.newStakeAccount.equals(splitAccount.publicKey));
+    const rent = await provider.connection.getMinimumBalanceForRentExemption(StakeProgram.space);
+    assert.equal(vaultBefore - (await provider.connection.getBalance(vaultPda)), rent);
+    assert.equal(Number((await delegation(splitAccount.publicKey)).stake), LAMPORTS_PER_SOL);
+  });
+
+  it("Deactivates the split stake and withdraws it to the vault after cooldown", async () => {
+    await deactivate(splitAccount.publicKey);
+    const { deactivationEpoch } = await delegation(splitAccount.publicKey);
+    assert.notEqual(deactivationEpoch, "18446744073709551615");
+
+    // 冷却完成前无法取出委托中的部分
+    await expectError(withdraw(splitAccount.publicKey, LAMPORTS_PER_SOL), "insufficient funds");
+
+    await nextEpoch();
+    await nextEpoch();
+    const balance = await provider.connection.getBalance(splitAccount.publicKey);
+    const vaultBefore = await provider.connection.getBalance(vaultPda);
+    await withdraw(splitAccount.publicKey, balance);
+
+    assert.equal((await provider.connection.getBalance(vaultPda)) - vaultBefore, balance);
+    assert.isNull(await provider.connection.getAccountInfo(splitAccount.publicKey));
+  });
+});
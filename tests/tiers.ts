@@ -0,0 +1,82 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  MEMO_PROGRAM_ID,
+  memoInstruction,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("threshold tiers", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2, member3] = members;
+
+  let multisigPda: PublicKey;
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+
+    // memo 程序需要 3-of-3
+    const ix = await program.methods
+      .setThresholdOverrides([{ programId: MEMO_PROGRAM_ID, minThreshold: 3 }])
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    const proposal = await propose(program, multisigPda, member1, ix);
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    await execute(program, multisigPda, proposal);
+
+    const account = await program.account.multisig.fetch(multisigPda);
+    assert.equal(account.thresholdOverrides.length, 1);
+  });
+
+  it("Fails a proposal that meets the base threshold but not its tier", async () => {
+    const proposal = await propose(
+      program,
+      multisigPda,
+      member1,
+      memoInstruction("tiered", [multisigPda])
+    );
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+
+    await expectError(execute(program, multisigPda, proposal), "TierThresholdNotMet");
+
+    await approve(program, multisigPda, proposal, member3);
+    await execute(program, multisigPda, proposal);
+    assert.isNull(await provider.connection.getAccountInfo(proposal));
+  });
+
+  it("Rejects overrides that exceed the member count", async () => {
+    const ix = await program.methods
+      .setThresholdOverrides([{ programId: MEMO_PROGRAM_ID, minThreshold: 4 }])
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    const proposal = await propose(program, multisigPda, member1, ix);
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+
+    await expectError(execute(program, multisigPda, proposal), "InvalidThreshold");
+  });
+});
@@ -0,0 +1,113 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, createMint, getMint } from "@solana/spl-token";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  expectError,
+  findMultisigPda,
+  findProposalPda,
+  findVaultPda,
+  proposalOptions,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+// spl_token AuthorityType
+const MINT_TOKENS = 0;
+const FREEZE_ACCOUNT = 1;
+const ACCOUNT_OWNER = 2;
+
+describe("set token authority proposals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  const proposeSetAuthority = async (target: PublicKey, authorityType: number, newAuthority: PublicKey | null) => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    const [proposal] = findProposalPda(program.programId, multisigPda, account.proposalsCount);
+    await program.methods
+      .proposeSetTokenAuthority(target, authorityType, newAuthority, proposalOptions())
+      .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    return proposal;
+  };
+
+  const approveAll = async (proposal: PublicKey) => {
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+  };
+
+  const execute = (proposal: PublicKey, target: PublicKey, authority: PublicKey) =>
+    program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts([
+        { pubkey: target, isSigner: false, isWritable: true },
+        { pubkey: authority, isSigner: false, isWritable: false },
+        { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      ])
+      .rpc();
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Validates the authority type at propose time", async () => {
+    const target = Keypair.generate().publicKey;
+    await expectError(proposeSetAuthority(target, 4, member1.publicKey), "InvalidAuthorityType");
+    // token 账户 owner 不能放弃
+    await expectError(proposeSetAuthority(target, ACCOUNT_OWNER, null), "InvalidAuthorityType");
+  });
+
+  it("Transfers a mint authority held by the vault", async () => {
+    const mint = await createMint(provider.connection, member1, vaultPda, null, 6);
+    const proposal = await proposeSetAuthority(mint, MINT_TOKENS, member2.publicKey);
+    await approveAll(proposal);
+    await execute(proposal, mint, vaultPda);
+
+    const info = await getMint(provider.connection, mint);
+    assert.isTrue(info.mintAuthority.equals(member2.publicKey));
+  });
+
+  it("Renounces an authority held by the multisig PDA", async () => {
+    const mint = await createMint(provider.connection, member1, member1.publicKey, multisigPda, 0);
+    const proposal = await proposeSetAuthority(mint, FREEZE_ACCOUNT, null);
+    await approveAll(proposal);
+    await execute(proposal, mint, multisigPda);
+
+    const info = await getMint(provider.connection, mint);
+    assert.isNull(info.freezeAuthority);
+    assert.isTrue(info.mintAuthority.equals(member1.publicKey));
+  });
+
+  it("Checks the current authority at execute time", async () => {
+    // 权限不在本多签或其金库手中
+    const mint = await createMint(provider.connection, member1, member1.publicKey, null, 6);
+    const proposal = await proposeSetAuthority(mint, MINT_TOKENS, member2.publicKey);
+    await approveAll(proposal);
+    await expectError(execute(proposal, mint, vaultPda), "AuthorityMismatch");
+    await expectError(execute(proposal, mint, member1.publicKey), "AuthorityMismatch");
+  });
+});
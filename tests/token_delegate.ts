@@ -0,0 +1,170 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  approve as splApprove,
+  createAccount,
+  createAssociatedTokenAccount,
+  createMint,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  expectError,
+  findMultisigPda,
+  findProposalPda,
+  findVaultPda,
+  proposalOptions,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("token delegate proposals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+  const delegate = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  // 创建 mint 与金库 ATA 并铸入 1000
+  const fundVault = async (programId: PublicKey) => {
+    const mint = await createMint(provider.connection, member1, member1.publicKey, null, 6, undefined, undefined, programId);
+    const ata = await createAssociatedTokenAccount(provider.connection, member1, mint, vaultPda, undefined, programId, undefined, true);
+    await mintTo(provider.connection, member1, mint, ata, member1, 1_000, [], undefined, programId);
+    return { mint, ata };
+  };
+
+  const approveAll = async (proposal: PublicKey) => {
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+  };
+
+  const nextProposal = async () => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    return findProposalPda(program.programId, multisigPda, account.proposalsCount)[0];
+  };
+
+  const proposeApprove = async (tokenAccount: PublicKey, amount: number) => {
+    const proposal = await nextProposal();
+    await program.methods
+      .proposeTokenApprove(tokenAccount, delegate.publicKey, new anchor.BN(amount), proposalOptions())
+      .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await approveAll(proposal);
+    return proposal;
+  };
+
+  const proposeRevoke = async (tokenAccount: PublicKey) => {
+    const proposal = await nextProposal();
+    await program.methods
+      .proposeTokenRevoke(tokenAccount, proposalOptions())
+      .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await approveAll(proposal);
+    return proposal;
+  };
+
+  const executeApprove = (proposal: PublicKey, ata: PublicKey, mint: PublicKey, programId: PublicKey) =>
+    program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts([
+        { pubkey: ata, isSigner: false, isWritable: true },
+        { pubkey: mint, isSigner: false, isWritable: false },
+        { pubkey: vaultPda, isSigner: false, isWritable: false },
+        { pubkey: programId, isSigner: false, isWritable: false },
+      ])
+      .rpc();
+
+  const executeRevoke = (proposal: PublicKey, ata: PublicKey, programId: PublicKey) =>
+    program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts([
+        { pubkey: ata, isSigner: false, isWritable: true },
+        { pubkey: vaultPda, isSigner: false, isWritable: false },
+        { pubkey: programId, isSigner: false, isWritable: false },
+      ])
+      .rpc();
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Approves a delegate and records it in the event", async () => {
+    const { mint, ata } = await fundVault(TOKEN_PROGRAM_ID);
+    const proposal = await proposeApprove(ata, 250);
+
+    let approved: any = null;
+    const listener = program.addEventListener("tokenDelegateApproved", (event) => {
+      approved = event;
+    });
+    await executeApprove(proposal, ata, mint, TOKEN_PROGRAM_ID);
+    await sleep(1000);
+    await program.removeEventListener(listener);
+
+    assert.isTrue(approved.delegate.equals(delegate.publicKey));
+    assert.equal(approved.amount.toNumber(), 250);
+    assert.isTrue(approved.mint.equals(mint));
+    const info = await getAccount(provider.connection, ata);
+    assert.isTrue(info.delegate.equals(delegate.publicKey));
+    assert.equal(info.delegatedAmount, 250n);
+
+    await executeRevoke(await proposeRevoke(ata), ata, TOKEN_PROGRAM_ID);
+    assert.isNull((await getAccount(provider.connection, ata)).delegate);
+  });
+
+  it("Revokes whatever delegate is currently set", async () => {
+    const { mint, ata } = await fundVault(TOKEN_PROGRAM_ID);
+    const proposal = await proposeRevoke(ata);
+    // 提案创建后 delegate 被换成了另一个地址
+    await executeApprove(await proposeApprove(ata, 10), ata, mint, TOKEN_PROGRAM_ID);
+    await executeRevoke(proposal, ata, TOKEN_PROGRAM_ID);
+    assert.isNull((await getAccount(provider.connection, ata)).delegate);
+  });
+
+  it("Supports Token-2022 accounts", async () => {
+    const { mint, ata } = await fundVault(TOKEN_2022_PROGRAM_ID);
+    await executeApprove(await proposeApprove(ata, 1_000), ata, mint, TOKEN_2022_PROGRAM_ID);
+    const info = await getAccount(provider.connection, ata, undefined, TOKEN_2022_PROGRAM_ID);
+    assert.equal(info.delegatedAmount, 1_000n);
+
+    await executeRevoke(await proposeRevoke(ata), ata, TOKEN_2022_PROGRAM_ID);
+    assert.isNull((await getAccount(provider.connection, ata, undefined, TOKEN_2022_PROGRAM_ID)).delegate);
+  });
+
+  it("Rejects token accounts not owned by vault 0", async () => {
+    const { mint } = await fundVault(TOKEN_PROGRAM_ID);
+    const other = await createAccount(provider.connection, member1, mint, member1.publicKey);
+    await splApprove(provider.connection, member1, other, delegate.publicKey, member1, 5);
+
+    await expectError(executeApprove(await proposeApprove(other, 5), other, mint, TOKEN_PROGRAM_ID), "AuthorityMismatch");
+    await expectError(executeRevoke(await proposeRevoke(other), other, TOKEN_PROGRAM_ID), "AuthorityMismatch");
+    assert.isTrue((await getAccount(provider.connection, other)).delegate.equals(delegate.publicKey));
+  });
+});
@@ -0,0 +1,88 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { TOKEN_PROGRAM_ID, createAccount, createMint, getAccount } from "@solana/spl-token";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  expectError,
+  findMultisigPda,
+  findProposalPda,
+  proposalOptions,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("freeze and thaw token accounts", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+  const holder = Keypair.generate();
+
+  let multisigPda: PublicKey;
+
+  const propose = async (method: "proposeFreezeTokenAccount" | "proposeThawTokenAccount", tokenAccount: PublicKey) => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    const [proposal] = findProposalPda(program.programId, multisigPda, account.proposalsCount);
+    await program.methods[method](tokenAccount, proposalOptions())
+      .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    return proposal;
+  };
+
+  const execute = (proposal: PublicKey, tokenAccount: PublicKey, mint: PublicKey) =>
+    program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts([
+        { pubkey: tokenAccount, isSigner: false, isWritable: true },
+        { pubkey: mint, isSigner: false, isWritable: false },
+        { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      ])
+      .rpc();
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Freezes and thaws an account of a mint frozen by the multisig", async () => {
+    const mint = await createMint(provider.connection, member1, member1.publicKey, multisigPda, 6);
+    const tokenAccount = await createAccount(provider.connection, member1, mint, holder.publicKey);
+
+    await execute(await propose("proposeFreezeTokenAccount", tokenAccount), tokenAccount, mint);
+    assert.isTrue((await getAccount(provider.connection, tokenAccount)).isFrozen);
+
+    await execute(await propose("proposeThawTokenAccount", tokenAccount), tokenAccount, mint);
+    assert.isFalse((await getAccount(provider.connection, tokenAccount)).isFrozen);
+  });
+
+  it("Fails clearly when the multisig is not the freeze authority", async () => {
+    const mint = await createMint(provider.connection, member1, member1.publicKey, member1.publicKey, 6);
+    const tokenAccount = await createAccount(provider.connection, member1, mint, holder.publicKey);
+    const proposal = await propose("proposeFreezeTokenAccount", tokenAccount);
+
+    await expectError(execute(proposal, tokenAccount, mint), "FreezeAuthorityMismatch");
+    // 传入与 token 账户不符的 mint
+    const other = await createMint(provider.connection, member1, member1.publicKey, multisigPda, 6);
+    await expectError(execute(proposal, tokenAccount, other), "AccountMismatch");
+    assert.isFalse((await getAccount(provider.connection, tokenAccount)).isFrozen);
+  });
+});
@@ -0,0 +1,85 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  governed,
+  memoInstruction,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("urgent proposals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = Array.from({ length: 4 }, () => Keypair.generate());
+  const [member1, member2, member3, member4] = members;
+
+  let multisigPda: PublicKey;
+
+  const memo = (text: string, urgent: boolean) =>
+    propose(program, multisigPda, member1, memoInstruction(text, [multisigPda]), { urgent });
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Rejects an urgent timelock longer than the regular one", async () => {
+    const ix = await program.methods
+      .setTimelock(0, 60, null)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await expectError(governed(program, multisigPda, [member1, member2], ix), "InvalidTimelock");
+  });
+
+  it("Configures the timelock and urgent threshold", async () => {
+    // 常规提案等待 1 小时；紧急提案无 timelock，但需要全体成员批准
+    const ix = await program.methods
+      .setTimelock(3600, 0, null)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await governed(program, multisigPda, [member1, member2], ix);
+  });
+
+  it("Regular proposals wait for the timelock", async () => {
+    const proposal = await memo("regular", false);
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    await expectError(execute(program, multisigPda, proposal), "TimelockNotElapsed");
+  });
+
+  it("Urgent proposals need the urgent threshold even when the base is met", async () => {
+    const proposal = await memo("urgent", true);
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    await approve(program, multisigPda, proposal, member3);
+    await expectError(execute(program, multisigPda, proposal), "NotExecutable");
+
+    await approve(program, multisigPda, proposal, member4);
+    let seen: any = null;
+    const listener = program.addEventListener("proposalExecuted", (event) => {
+      seen = event;
+    });
+    await execute(program, multisigPda, proposal);
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.removeEventListener(listener);
+    assert.isTrue(seen?.urgent);
+  });
+});
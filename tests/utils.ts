@@ -0,0 +1,538 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { createHash } from "crypto";
+import { Multisig } from "../target/types/multisig";
+
+const { PublicKey, Keypair, SystemProgram, TransactionInstruction } = anchor.web3;
+
+export async function airdrop(
+  provider: anchor.AnchorProvider,
+  to: PublicKey,
+  sol = 2
+) {
+  await provider.connection.confirmTransaction(
+    await provider.connection.requestAirdrop(to, sol * anchor.web3.LAMPORTS_PER_SOL),
+    "confirmed"
+  );
+}
+
+export function findMultisigPda(programId: PublicKey, creator: PublicKey, nonce: number) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("multisig"), creator.toBuffer(), Buffer.from([nonce])],
+    programId
+  );
+}
+
+export function findProposalPda(programId: PublicKey, multisig: PublicKey, index: number) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("proposal"), multisig.toBuffer(), new anchor.BN(index).toBuffer("le", 4)],
+    programId
+  );
+}
+
+export function findCommentPda(programId: PublicKey, proposal: PublicKey, author: PublicKey, seq: number) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("comment"), proposal.toBuffer(), author.toBuffer(), new anchor.BN(seq).toBuffer("le", 4)],
+    programId
+  );
+}
+
+export function findVaultPda(programId: PublicKey, multisig: PublicKey, index = 0) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("vault"), multisig.toBuffer(), Buffer.from([index])],
+    programId
+  );
+}
+
+export function findBufferPda(programId: PublicKey, multisig: PublicKey, creator: PublicKey, id: number) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("buffer"), multisig.toBuffer(), creator.toBuffer(), new anchor.BN(id).toBuffer("le", 4)],
+    programId
+  );
+}
+
+// hash 为 ProposalAction 的 borsh 编码的 sha256，见 actionHash
+export function findInstructionIndexPda(programId: PublicKey, multisig: PublicKey, hash: Buffer) {
+  return PublicKey.findProgramAddressSync([Buffer.from("ix-index"), multisig.toBuffer(), hash], programId);
+}
+
+export function actionHash(program: Program<Multisig>, action: any) {
+  return createHash("sha256").update(program.coder.types.encode("proposalAction", action)).digest();
+}
+
+export function findMemberStatsPda(programId: PublicKey, multisig: PublicKey, member: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("member_stats"), multisig.toBuffer(), member.toBuffer()],
+    programId
+  );
+}
+
+export function findSpendLedgerPda(programId: PublicKey, multisig: PublicKey) {
+  return PublicKey.findProgramAddressSync([Buffer.from("spend_ledger"), multisig.toBuffer()], programId);
+}
+
+export function findProgramConfigPda(programId: PublicKey) {
+  return PublicKey.findProgramAddressSync([Buffer.from("program_config")], programId);
+}
+
+export function findAppAuthorityPda(programId: PublicKey, multisig: PublicKey, appId: number[]) {
+  return PublicKey.findProgramAddressSync([Buffer.from("authority"), multisig.toBuffer(), Buffer.from(appId)], programId);
+}
+
+export function findWithdrawalRequestPda(programId: PublicKey, multisig: PublicKey, requester: PublicKey, id: number) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("withdrawal_request"), multisig.toBuffer(), requester.toBuffer(), new anchor.BN(id).toBuffer("le", 4)],
+    programId
+  );
+}
+
+// 按指令中需要签名的 PDA 计算 signers 位：位 0 = multisig PDA，位 i+1 = i 号金库
+export function signerBits(programId: PublicKey, multisig: PublicKey, ix: TransactionInstruction) {
+  const signers = ix.keys.filter((k) => k.isSigner).map((k) => k.pubkey);
+  let bits = signers.some((k) => k.equals(multisig)) ? 1 : 0;
+  for (let i = 0; i < 7; i++) {
+    const [vault] = findVaultPda(programId, multisig, i);
+    if (signers.some((k) => k.equals(vault))) {
+      bits |= 1 << (i + 1);
+    }
+  }
+  return bits;
+}
+
+// TransactionInstruction -> InstructionData，末尾附上目标程序账户（CPI 需要）
+export function toInstructionData(ix: TransactionInstruction, signers: number) {
+  return {
+    programId: ix.programId,
+    accounts: [
+      ...ix.keys.map((k) => ({
+        pubkey: k.pubkey,
+        isSigner: k.isSigner,
+        isWritable: k.isWritable,
+      })),
+      { pubkey: ix.programId, isSigner: false, isWritable: false },
+    ],
+    data: Buffer.from(ix.data),
+    signers,
+  };
+}
+
+// 由提案内容构造 execute 的 remainingAccounts，multisig PDA 由程序 invoke_signed 签名
+export function executeAccounts(instruction: any, signerPdas: PublicKey[]) {
+  return instruction.accounts.map((acc: any) => {
+    const pubkey = new PublicKey(acc.pubkey);
+    const isPda = signerPdas.some((p) => p.equals(pubkey));
+    return {
+      pubkey,
+      isSigner: isPda ? false : acc.isSigner,
+      isWritable: acc.isWritable,
+    };
+  });
+}
+
+export type ProposalOptions = {
+  dependsOn: number | null;
+  notBefore: anchor.BN | null;
+  notAfter: anchor.BN | null;
+  urgent: boolean;
+  allowDangerous: boolean;
+  noSign: boolean;
+  executionMemo: string | null;
+  delayOverride: number | null;
+  votingEndsAt: anchor.BN | null;
+  metadataUri: string | null;
+  metadataHash: number[];
+  designatedExecutor: PublicKey | null;
+  allowDuplicate: boolean;
+  optimistic: boolean;
+};
+
+export function proposalOptions(options: Partial<ProposalOptions> = {}): ProposalOptions {
+  return { dependsOn: null, notBefore: null, notAfter: null, urgent: false, allowDangerous: false, noSign: false, executionMemo: null, delayOverride: null, votingEndsAt: null, metadataUri: null, metadataHash: Array(32).fill(0), designatedExecutor: null, allowDuplicate: false, optimistic: false, ...options };
+}
+
+// 单笔交易的线上大小上限
+export const PACKET_DATA_SIZE = 1232;
+// Proposal 中 action 字段预留的空间（BASE_SPACE 里的 1 + 1000）
+const ACTION_SPACE = 1 + 1000;
+
+export type UploadProgress = {
+  stage: "create" | "write" | "finalize" | "propose";
+  written: number; // 已确认写入的字节数
+  total: number;
+};
+
+export type UploadOptions = {
+  bufferId?: number; // 默认取当前 proposalsCount；失败后以同一 id 重试即可续传
+  chunkSize?: number; // 默认按 PACKET_DATA_SIZE 算出的最大值
+  retries?: number; // 每段写入失败后的重试次数
+  onProgress?: (progress: UploadProgress) => void;
+};
+
+// 放不进单笔交易或提案的 action 空间时，自动改走缓冲区：create_buffer、分段 write_buffer、finalize、propose_buffered
+export async function propose(
+  program: Program<Multisig>,
+  multisig: PublicKey,
+  proposer: Keypair,
+  ix: TransactionInstruction,
+  options: Partial<ProposalOptions> = {},
+  upload: UploadOptions = {}
+): Promise<PublicKey> {
+  const account = await program.account.multisig.fetch(multisig);
+  const [proposal] = findProposalPda(program.programId, multisig, account.proposalsCount);
+  const instruction = toInstructionData(ix, signerBits(program.programId, multisig, ix));
+  const method = program.methods
+    .proposeTransaction(instruction, proposalOptions(options))
+    .accounts({ multisig, proposal, proposer: proposer.publicKey })
+    .signers([proposer]);
+  const action = program.coder.types.encode("proposalAction", { raw: [instruction] });
+  if (action.length <= ACTION_SPACE && transactionSize(program, await method.transaction()) !== null) {
+    await method.rpc();
+    return proposal;
+  }
+
+  const id = upload.bufferId ?? account.proposalsCount;
+  const data = Buffer.from(ix.data);
+  const { buffer, hash } = await uploadBuffer(program, multisig, proposer, id, data, upload);
+  upload.onProgress?.({ stage: "propose", written: data.length, total: data.length });
+  return proposeBuffered(program, multisig, proposer, ix, buffer, hash, options);
+}
+
+// 序列化后的字节数；超过 PACKET_DATA_SIZE 时返回 null
+export function transactionSize(program: Program<Multisig>, tx: anchor.web3.Transaction) {
+  tx.feePayer = program.provider.publicKey;
+  tx.recentBlockhash = PublicKey.default.toBase58();
+  try {
+    return tx.serialize({ requireAllSignatures: false, verifySignatures: false }).length;
+  } catch {
+    return null;
+  }
+}
+
+export async function approve(
+  program: Program<Multisig>,
+  multisig: PublicKey,
+  proposal: PublicKey,
+  approver: Keypair
+) {
+  return program.methods
+    .approveTransaction(null)
+    .accounts({ multisig, proposal, approver: approver.publicKey })
+    .signers([approver])
+    .rpc();
+}
+
+export async function execute(
+  program: Program<Multisig>,
+  multisig: PublicKey,
+  proposal: PublicKey,
+  spendLedger: PublicKey | null = null,
+  { policy = [], notify = [] }: { policy?: anchor.web3.AccountMeta[]; notify?: anchor.web3.AccountMeta[] } = {}
+) {
+  const account = await program.account.proposal.fetch(proposal);
+  const [vault] = findVaultPda(program.programId, multisig);
+  return program.methods
+    .executeTransaction()
+    .accounts({ multisig, proposal, spendLedger })
+    .remainingAccounts([
+      ...executeRemainingAccounts(account.action, [multisig, vault]),
+      ...policy,
+      ...protocolFeeAccounts(account.protocolFee),
+      ...notify,
+      ...memoAccounts(account.executionMemo, vault),
+    ])
+    .rpc();
+}
+
+// 设置了策略程序时，紧随指令账户追加策略程序及其声明数量的账户（不含 multisig 与提案，由程序补上）；
+// 类型化动作与分期、周期付款同样需要
+export function policyAccounts(policyProgram: PublicKey, accounts: PublicKey[] = []) {
+  return [
+    { pubkey: policyProgram, isSigner: false, isWritable: false },
+    ...accounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false })),
+  ];
+}
+
+// 设置了通知程序时，在协议费账户之后追加通知程序及其所需账户（不含 multisig，由程序补上）；
+// 每次执行成功都会通知，包括类型化动作、激活与分期、周期付款
+export function notifyAccounts(notifyProgram: PublicKey, accounts: PublicKey[]) {
+  return [
+    { pubkey: notifyProgram, isSigner: false, isWritable: false },
+    ...accounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })),
+  ];
+}
+
+// 报价了协议费的提案须追加 [付费金库, 收款方, System Program]，位于 memo 账户之前
+export function protocolFeeAccounts(protocolFee: any) {
+  if (protocolFee === null) {
+    return [];
+  }
+  return [
+    { pubkey: protocolFee.payer, isSigner: false, isWritable: true },
+    { pubkey: protocolFee.recipient, isSigner: false, isWritable: true },
+    { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+  ];
+}
+
+// 带 execution_memo 的提案须在末尾追加 0 号金库与 Memo 程序
+export function memoAccounts(executionMemo: string | null, vault: PublicKey) {
+  if (executionMemo === null) {
+    return [];
+  }
+  return [
+    { pubkey: vault, isSigner: false, isWritable: false },
+    { pubkey: MEMO_PROGRAM_ID, isSigner: false, isWritable: false },
+  ];
+}
+
+// 类型化配置提案原生执行，无需 remaining accounts；缓冲区提案需先传入可写的缓冲区；
+// 多指令提案依次传入各指令的账户
+export function executeRemainingAccounts(action: any, signerPdas: PublicKey[]) {
+  if (action.raw) {
+    return executeAccounts(action.raw[0], signerPdas);
+  }
+  if (action.batch) {
+    return action.batch[0].flatMap((ix: any) => executeAccounts(ix, signerPdas));
+  }
+  if (action.buffered) {
+    const buffered = action.buffered[0];
+    return [
+      { pubkey: buffered.buffer, isSigner: false, isWritable: true },
+      ...executeAccounts(buffered, signerPdas),
+    ];
+  }
+  return [];
+}
+
+// 创建缓冲区并分段写入、finalize，返回缓冲区地址与内容哈希。
+// 可续传：缓冲区已存在时跳过创建，链上内容已一致的分段不再写入，已 finalize 且哈希一致时直接返回
+export async function uploadBuffer(
+  program: Program<Multisig>,
+  multisig: PublicKey,
+  creator: Keypair,
+  id: number,
+  data: Buffer,
+  { chunkSize, retries = 2, onProgress }: UploadOptions = {}
+) {
+  const [buffer] = findBufferPda(program.programId, multisig, creator.publicKey, id);
+  const total = data.length;
+  const expected = Array.from(createHash("sha256").update(data).digest());
+  let existing = await program.account.instructionBuffer.fetchNullable(buffer);
+  if (existing === null) {
+    onProgress?.({ stage: "create", written: 0, total });
+    await program.methods
+      .createBuffer(id, total)
+      .accounts({ multisig, buffer, creator: creator.publicKey })
+      .signers([creator])
+      .rpc();
+    existing = await program.account.instructionBuffer.fetch(buffer);
+  }
+  if (existing.data.length !== total) {
+    throw new Error(`buffer ${buffer.toBase58()} holds ${existing.data.length} bytes, expected ${total}`);
+  }
+  if (existing.finalized) {
+    if (!Buffer.from(existing.hash).equals(Buffer.from(expected))) {
+      throw new Error(`buffer ${buffer.toBase58()} was finalized with different contents`);
+    }
+    return { buffer, hash: existing.hash as number[] };
+  }
+
+  const size = chunkSize ?? (await maxWriteChunk(program, buffer, creator));
+  const onChain = Buffer.from(existing.data);
+  for (let offset = 0; offset < total; offset += size) {
+    const chunk = data.subarray(offset, offset + size);
+    if (!onChain.subarray(offset, offset + size).equals(chunk)) {
+      await withRetries(retries, () =>
+        program.methods
+          .writeBuffer(offset, chunk)
+          .accounts({ buffer, creator: creator.publicKey })
+          .signers([creator])
+          .rpc()
+      );
+    }
+    onProgress?.({ stage: "write", written: offset + chunk.length, total });
+  }
+  onProgress?.({ stage: "finalize", written: total, total });
+  await program.methods
+    .finalizeBuffer()
+    .accounts({ buffer, creator: creator.publicKey })
+    .signers([creator])
+    .rpc();
+  const { hash } = await program.account.instructionBuffer.fetch(buffer);
+  return { buffer, hash };
+}
+
+// 单笔 write_buffer 能携带的最大字节数（签名位已计入）；预留 2 字节给指令数据长度前缀的增长
+async function maxWriteChunk(program: Program<Multisig>, buffer: PublicKey, creator: Keypair) {
+  const empty = await program.methods
+    .writeBuffer(0, Buffer.alloc(0))
+    .accounts({ buffer, creator: creator.publicKey })
+    .transaction();
+  return PACKET_DATA_SIZE - transactionSize(program, empty) - 2;
+}
+
+async function withRetries<T>(retries: number, run: () => Promise<T>): Promise<T> {
+  for (let attempt = 0; ; attempt++) {
+    try {
+      return await run();
+    } catch (e) {
+      if (attempt >= retries) {
+        throw e;
+      }
+    }
+  }
+}
+
+export async function proposeBuffered(
+  program: Program<Multisig>,
+  multisig: PublicKey,
+  proposer: Keypair,
+  ix: TransactionInstruction,
+  buffer: PublicKey,
+  hash: number[],
+  options: Partial<ProposalOptions> = {}
+): Promise<PublicKey> {
+  const account = await program.account.multisig.fetch(multisig);
+  const [proposal] = findProposalPda(program.programId, multisig, account.proposalsCount);
+  const { programId, accounts, signers } = toInstructionData(ix, signerBits(program.programId, multisig, ix));
+  await program.methods
+    .proposeBuffered({ programId, accounts, signers, buffer, hash }, proposalOptions(options))
+    .accounts({ multisig, buffer, proposal, proposer: proposer.publicKey })
+    .signers([proposer])
+    .rpc();
+  return proposal;
+}
+
+export async function proposeAction(
+  program: Program<Multisig>,
+  multisig: PublicKey,
+  proposer: Keypair,
+  action: any,
+  options: Partial<ProposalOptions> = {}
+): Promise<PublicKey> {
+  const account = await program.account.multisig.fetch(multisig);
+  const [proposal] = findProposalPda(program.programId, multisig, account.proposalsCount);
+  await program.methods
+    .proposeAction(action, proposalOptions(options))
+    .accounts({ multisig, proposal, proposer: proposer.publicKey })
+    .signers([proposer])
+    .rpc();
+  return proposal;
+}
+
+// 多条指令合为一个提案，执行时依次调用，任一失败整体回滚
+export async function proposeBatch(
+  program: Program<Multisig>,
+  multisig: PublicKey,
+  proposer: Keypair,
+  ixs: TransactionInstruction[],
+  options: Partial<ProposalOptions> = {}
+): Promise<PublicKey> {
+  const batch = ixs.map((ix) => toInstructionData(ix, signerBits(program.programId, multisig, ix)));
+  return proposeAction(program, multisig, proposer, { batch: [batch] }, options);
+}
+
+export async function expectError(promise: Promise<unknown>, code: string) {
+  try {
+    await promise;
+  } catch (e: any) {
+    const actual = e?.error?.errorCode?.code ?? e?.message ?? String(e);
+    if (!String(actual).includes(code)) {
+      throw new Error(`expected ${code}, got ${actual}`);
+    }
+    return;
+  }
+  throw new Error(`expected ${code}, but call succeeded`);
+}
+
+export const MEMO_PROGRAM_ID = new PublicKey("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+export function memoInstruction(text: string | Buffer, signers: PublicKey[]) {
+  return new TransactionInstruction({
+    programId: MEMO_PROGRAM_ID,
+    keys: signers.map((pubkey) => ({ pubkey, isSigner: true, isWritable: false })),
+    data: Buffer.from(text),
+  });
+}
+
+// 从交易日志中读取本程序（最外层）消耗的 CU
+export async function computeUnits(
+  provider: anchor.AnchorProvider,
+  programId: PublicKey,
+  signature: string
+): Promise<number> {
+  await provider.connection.confirmTransaction(signature, "confirmed");
+  const tx = await provider.connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  const pattern = new RegExp(`Program ${programId.toBase58()} consumed (\\d+) of`);
+  const consumed = tx.meta.logMessages
+    .map((line) => line.match(pattern))
+    .filter((m) => m !== null);
+  return Number(consumed[consumed.length - 1][1]);
+}
+
+export const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+export async function chainTime(provider: anchor.AnchorProvider): Promise<number> {
+  const slot = await provider.connection.getSlot("confirmed");
+  return (await provider.connection.getBlockTime(slot)) ?? Math.floor(Date.now() / 1000);
+}
+
+// 提案 -> 批准 -> 执行 一条自治指令
+// 由金库出资并作为 base 创建归 owner 所有的账户；返回新地址与可直接提案的指令，租金按免租最低额从金库扣除
+export async function createAccountWithSeedInstruction(
+  program: Program<Multisig>,
+  multisig: PublicKey,
+  seed: string,
+  space: number,
+  owner: PublicKey,
+  vaultIndex = 0
+) {
+  const [vault] = findVaultPda(program.programId, multisig, vaultIndex);
+  const address = await PublicKey.createWithSeed(vault, seed, owner);
+  const lamports = await program.provider.connection.getMinimumBalanceForRentExemption(space);
+  const ix = SystemProgram.createAccountWithSeed({
+    fromPubkey: vault,
+    newAccountPubkey: address,
+    basePubkey: vault,
+    seed,
+    lamports,
+    space,
+    programId: owner,
+  });
+  return { address, ix };
+}
+
+export async function governed(
+  program: Program<Multisig>,
+  multisig: PublicKey,
+  approvers: Keypair[],
+  ix: TransactionInstruction
+) {
+  const proposal = await propose(program, multisig, approvers[0], ix);
+  for (const a of approvers) {
+    await approve(program, multisig, proposal, a);
+  }
+  await execute(program, multisig, proposal);
+  return proposal;
+}
+
+export async function inviteAndAccept(
+  program: Program<Multisig>,
+  multisig: PublicKey,
+  approvers: Keypair[],
+  invitee: Keypair,
+  membershipExpiresAt: anchor.BN | null = null
+) {
+  const ix = await program.methods
+    .inviteMember(invitee.publicKey, membershipExpiresAt, null)
+    .accounts({ multisig, authority: multisig })
+    .instruction();
+  await governed(program, multisig, approvers, ix);
+  await program.methods
+    .acceptMembership()
+    .accounts({ multisig, invitee: invitee.publicKey })
+    .signers([invitee])
+    .rpc();
+}
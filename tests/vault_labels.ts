@@ -0,0 +1,125 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  governed,
+  MEMO_PROGRAM_ID,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL, TransactionInstruction } = anchor.web3;
+
+describe("vault labels", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate()];
+  const [member1] = members;
+  const usdcHint = Keypair.generate().publicKey;
+  const payee = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let payroll: PublicKey;
+  let insurance: PublicKey;
+
+  const selfCall = async (build: () => any) =>
+    governed(
+      program,
+      multisigPda,
+      members,
+      await build().accounts({ multisig: multisigPda, authority: multisigPda }).instruction()
+    );
+
+  // 收集一次调用期间发出的事件
+  const capture = async <T>(name: string, run: () => Promise<T>) => {
+    const events: any[] = [];
+    const listener = program.addEventListener(name as any, (event) => {
+      events.push(event);
+    });
+    const result = await run();
+    await sleep(1000);
+    await program.removeEventListener(listener);
+    return { events, result };
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [payroll] = findVaultPda(program.programId, multisigPda, 1);
+    [insurance] = findVaultPda(program.programId, multisigPda, 2);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, payroll);
+    await airdrop(provider, insurance);
+
+    await selfCall(() => program.methods.registerVault(1, "payroll", null));
+    await selfCall(() => program.methods.registerVault(2, "insurance fund", usdcHint));
+  });
+
+  it("Validates registrations", async () => {
+    await expectError(selfCall(() => program.methods.registerVault(1, "again", null)), "VaultAlreadyRegistered");
+    await expectError(selfCall(() => program.methods.registerVault(3, "x".repeat(25), null)), "InvalidVaultName");
+    await expectError(selfCall(() => program.methods.registerVault(7, "out of range", null)), "InvalidVaultIndex");
+    await expectError(selfCall(() => program.methods.setVaultMetadata(3, "ops", null)), "VaultNotRegistered");
+
+    const { vaults } = await program.account.multisig.fetch(multisigPda);
+    assert.deepEqual(
+      vaults.map((v) => [v.index, v.name]),
+      [
+        [1, "payroll"],
+        [2, "insurance fund"],
+      ]
+    );
+    assert.isTrue(vaults[1].mintHint.equals(usdcHint));
+  });
+
+  it("Labels the signing vaults in proposal and execution events", async () => {
+    const ix = SystemProgram.transfer({ fromPubkey: insurance, toPubkey: payee.publicKey, lamports: LAMPORTS_PER_SOL });
+    const { events: created, result: proposal } = await capture("proposalCreated", () =>
+      propose(program, multisigPda, member1, ix)
+    );
+    assert.deepEqual(
+      created[0].vaults.map((v) => [v.index, v.name]),
+      [[2, "insurance fund"]]
+    );
+    assert.isTrue(created[0].vaults[0].mintHint.equals(usdcHint));
+
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    const { events: executed } = await capture("proposalExecuted", () => execute(program, multisigPda, proposal));
+    assert.equal(executed[0].vaults[0].name, "insurance fund");
+  });
+
+  it("Carries updated metadata and leaves unregistered vaults out", async () => {
+    await selfCall(() => program.methods.setVaultMetadata(1, "payroll (monthly)", null));
+
+    // 0 号金库与 payroll 都签名，但 0 号未登记
+    const [vault0] = findVaultPda(program.programId, multisigPda, 0);
+    const ix = new TransactionInstruction({
+      programId: MEMO_PROGRAM_ID,
+      keys: [vault0, payroll].map((pubkey) => ({ pubkey, isSigner: true, isWritable: false })),
+      data: Buffer.from("march payroll"),
+    });
+    const { events } = await capture("proposalCreated", () => propose(program, multisigPda, member1, ix));
+    assert.deepEqual(
+      events[0].vaults.map((v) => [v.index, v.name]),
+      [[1, "payroll (monthly)"]]
+    );
+  });
+});
@@ -0,0 +1,109 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  governed,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } = anchor.web3;
+
+describe("per-vault pause", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate()];
+  const [member1] = members;
+  const guardian = Keypair.generate();
+  const payee = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let hotVault: PublicKey;
+  let coldVault: PublicKey;
+
+  const selfCall = async (ix: Promise<anchor.web3.TransactionInstruction>) =>
+    governed(program, multisigPda, members, await ix);
+
+  const setPaused = (index: number, paused: boolean) =>
+    program.methods.setVaultPaused(index, paused).accounts({ multisig: multisigPda, authority: multisigPda }).instruction();
+
+  const guardianSetPaused = (index: number, paused: boolean) =>
+    program.methods
+      .setVaultPaused(index, paused)
+      .accounts({ multisig: multisigPda, authority: guardian.publicKey })
+      .signers([guardian])
+      .rpc();
+
+  const send = (from: PublicKey) =>
+    SystemProgram.transfer({ fromPubkey: from, toPubkey: payee.publicKey, lamports: LAMPORTS_PER_SOL / 10 });
+
+  const approved = async (ix: anchor.web3.TransactionInstruction) => {
+    const proposal = await propose(program, multisigPda, member1, ix);
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    return proposal;
+  };
+
+  before(async () => {
+    for (const m of [...members, guardian]) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [hotVault] = findVaultPda(program.programId, multisigPda, 0);
+    [coldVault] = findVaultPda(program.programId, multisigPda, 1);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, hotVault);
+    await airdrop(provider, coldVault);
+
+    const accounts = { multisig: multisigPda, authority: multisigPda };
+    await selfCall(program.methods.registerVault(0, "hot", null).accounts(accounts).instruction());
+    await selfCall(program.methods.registerVault(1, "cold", null).accounts(accounts).instruction());
+    await selfCall(program.methods.setGuardian(guardian.publicKey).accounts(accounts).instruction());
+  });
+
+  it("Lets the guardian pause but not unpause", async () => {
+    await guardianSetPaused(0, true);
+    const { vaults } = await program.account.multisig.fetch(multisigPda);
+    assert.deepEqual(
+      vaults.map((v) => [v.index, v.paused]),
+      [
+        [0, true],
+        [1, false],
+      ]
+    );
+    await expectError(guardianSetPaused(0, false), "Unauthorized");
+  });
+
+  it("Blocks proposals that request the paused vault, including mixed ones", async () => {
+    // 同一条指令同时请求冷、热金库签名
+    const mixed = send(coldVault);
+    mixed.keys.push({ pubkey: hotVault, isSigner: true, isWritable: false });
+    await expectError(execute(program, multisigPda, await approved(mixed)), "VaultPaused");
+
+    await execute(program, multisigPda, await approved(send(coldVault)));
+    assert.equal(await provider.connection.getBalance(payee.publicKey), LAMPORTS_PER_SOL / 10);
+  });
+
+  it("Resumes the vault once self-governance unpauses it", async () => {
+    const proposal = await approved(send(hotVault));
+    await expectError(execute(program, multisigPda, proposal), "VaultPaused");
+
+    await selfCall(setPaused(0, false));
+    await execute(program, multisigPda, proposal);
+    assert.equal(await provider.connection.getBalance(payee.publicKey), (2 * LAMPORTS_PER_SOL) / 10);
+  });
+});
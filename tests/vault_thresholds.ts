@@ -0,0 +1,110 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  governed,
+  propose,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } = anchor.web3;
+
+describe("per-vault thresholds", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = Array.from({ length: 5 }, () => Keypair.generate());
+  const [member1, member2, member3, member4] = members;
+  const payee = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let hotVault: PublicKey;
+  let coldVault: PublicKey;
+
+  const proposeSend = async (from: PublicKey) => {
+    const ix = SystemProgram.transfer({ fromPubkey: from, toPubkey: payee.publicKey, lamports: LAMPORTS_PER_SOL });
+    const proposal = await propose(program, multisigPda, member1, ix);
+    for (const m of [member1, member2]) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    return proposal;
+  };
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [hotVault] = findVaultPda(program.programId, multisigPda, 0);
+    [coldVault] = findVaultPda(program.programId, multisigPda, 1);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, hotVault);
+    await airdrop(provider, coldVault, 10);
+
+    await governed(
+      program,
+      multisigPda,
+      [member1, member2],
+      await program.methods
+        .registerVault(1, "cold", null)
+        .accounts({ multisig: multisigPda, authority: multisigPda })
+        .instruction()
+    );
+    await governed(
+      program,
+      multisigPda,
+      [member1, member2],
+      await program.methods
+        .setVaultThreshold(1, 4)
+        .accounts({ multisig: multisigPda, authority: multisigPda })
+        .instruction()
+    );
+  });
+
+  it("Rejects overrides above the member count or on unregistered vaults", async () => {
+    const unregistered = await program.methods
+      .setVaultThreshold(0, 3)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await expectError(governed(program, multisigPda, [member1, member2], unregistered), "VaultNotRegistered");
+
+    const ix = await program.methods
+      .setVaultThreshold(1, 6)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+    await expectError(governed(program, multisigPda, [member1, member2], ix), "InvalidThreshold");
+    assert.deepEqual(
+      (await program.account.multisig.fetch(multisigPda)).vaults.map((v) => [v.index, v.thresholdOverride]),
+      [[1, 4]]
+    );
+  });
+
+  it("Executes hot vault transfers at the base threshold", async () => {
+    await execute(program, multisigPda, await proposeSend(hotVault));
+    assert.equal(await provider.connection.getBalance(payee.publicKey), LAMPORTS_PER_SOL);
+  });
+
+  it("Requires the cold vault's threshold when its signature is requested", async () => {
+    const proposal = await proposeSend(coldVault);
+    await expectError(execute(program, multisigPda, proposal), "NotExecutable");
+
+    await approve(program, multisigPda, proposal, member3);
+    await expectError(execute(program, multisigPda, proposal), "NotExecutable");
+
+    await approve(program, multisigPda, proposal, member4);
+    await execute(program, multisigPda, proposal);
+    assert.equal(await provider.connection.getBalance(payee.publicKey), 2 * LAMPORTS_PER_SOL);
+  });
+});
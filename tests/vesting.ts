@@ -0,0 +1,177 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccount,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  mintTo,
+} from "@solana/spl-token";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  chainTime,
+  expectError,
+  findMultisigPda,
+  findProposalPda,
+  findVaultPda,
+  governed,
+  proposalOptions,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+describe("vesting", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+  const recipient = Keypair.generate();
+  const anyone = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+  let mint: PublicKey;
+  let vaultAta: PublicKey;
+  let recipientAta: PublicKey;
+  let anyoneAta: PublicKey;
+
+  const tranche = (unlockTime: number, amount: number) => ({
+    unlockTime: new anchor.BN(unlockTime),
+    amount: new anchor.BN(amount),
+  });
+
+  const proposeVesting = async (tranches: ReturnType<typeof tranche>[]) => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    const [proposal] = findProposalPda(program.programId, multisigPda, account.proposalsCount);
+    await program.methods
+      .proposeVesting(mint, recipient.publicKey, tranches, proposalOptions())
+      .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    return proposal;
+  };
+
+  const activate = async (proposal: PublicKey) => {
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+    await program.methods.activateProposal().accounts({ multisig: multisigPda, proposal }).rpc();
+  };
+
+  // 无需许可：由 provider 钱包发起，不需要成员签名
+  const payTranche = (proposal: PublicKey, index: number, destination = recipientAta) =>
+    program.methods
+      .executeVestingTranche(index)
+      .accounts({
+        multisig: multisigPda,
+        proposal,
+        vault: vaultPda,
+        source: vaultAta,
+        destination,
+        mint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+  const balance = async (ata: PublicKey) => Number((await getAccount(provider.connection, ata)).amount);
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+
+    mint = await createMint(provider.connection, member1, member1.publicKey, null, 6);
+    vaultAta = await createAssociatedTokenAccount(provider.connection, member1, mint, vaultPda, undefined, undefined, undefined, true);
+    recipientAta = await createAssociatedTokenAccount(provider.connection, member1, mint, recipient.publicKey);
+    anyoneAta = await createAssociatedTokenAccount(provider.connection, member1, mint, anyone.publicKey);
+    assert.isTrue(vaultAta.equals(getAssociatedTokenAddressSync(mint, vaultPda, true)));
+    await mintTo(provider.connection, member1, mint, vaultAta, member1, 10_000);
+  });
+
+  it("Rejects empty or zero-amount schedules", async () => {
+    await expectError(proposeVesting([]), "InvalidVestingSchedule");
+    await expectError(proposeVesting([tranche(0, 100), tranche(0, 0)]), "InvalidVestingSchedule");
+  });
+
+  it("Pays each tranche once after unlock, without further votes", async () => {
+    const now = await chainTime(provider);
+    const proposal = await proposeVesting([tranche(now - 10, 100), tranche(now + 4, 200), tranche(now + 3600, 300)]);
+
+    await expectError(payTranche(proposal, 0), "VestingNotActive");
+    await expectError(
+      program.methods.executeTransaction().accounts({ multisig: multisigPda, proposal }).rpc(),
+      "StandingProposal"
+    );
+    await activate(proposal);
+
+    await payTranche(proposal, 0);
+    assert.equal(await balance(recipientAta), 100);
+    await expectError(payTranche(proposal, 0), "TranchePaid");
+    await expectError(payTranche(proposal, 1), "TrancheLocked");
+    await expectError(payTranche(proposal, 3), "InvalidTranche");
+
+    while ((await chainTime(provider)) < now + 4) {
+      await sleep(1000);
+    }
+    // 收款账户必须属于计划中的 recipient
+    await expectError(payTranche(proposal, 1, anyoneAta), "AccountMismatch");
+    await payTranche(proposal, 1);
+    assert.equal(await balance(recipientAta), 300);
+
+    const account = await program.account.proposal.fetch(proposal);
+    assert.equal(account.tranchesPaid, 0b011);
+    assert.isTrue(account.executed);
+  });
+
+  it("Lets governance cancel the remainder without clawing back paid tranches", async () => {
+    const [proposal] = findProposalPda(program.programId, multisigPda, 0);
+    const ix = await program.methods
+      .cancelVesting()
+      .accounts({ multisig: multisigPda, proposal, authority: multisigPda })
+      .instruction();
+    await expectError(
+      program.methods
+        .cancelVesting()
+        .accounts({ multisig: multisigPda, proposal, authority: member1.publicKey })
+        .signers([member1])
+        .rpc(),
+      "Unauthorized"
+    );
+    await governed(program, multisigPda, members, ix);
+
+    assert.isNull(await provider.connection.getAccountInfo(proposal));
+    assert.equal(await balance(recipientAta), 300);
+    await expectError(payTranche(proposal, 2), "AccountNotInitialized");
+  });
+
+  it("Closes the proposal once every tranche is paid", async () => {
+    const now = await chainTime(provider);
+    const proposal = await proposeVesting([tranche(now - 20, 50), tranche(now - 10, 50)]);
+    await activate(proposal);
+    const before = await balance(vaultAta);
+
+    await payTranche(proposal, 1);
+    await payTranche(proposal, 0);
+    assert.equal(before - (await balance(vaultAta)), 100);
+    assert.isNull(await provider.connection.getAccountInfo(proposal));
+
+    const account = await program.account.multisig.fetch(multisigPda);
+    assert.equal(account.executedCount, 3);
+  });
+});
@@ -0,0 +1,154 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  expectError,
+  findMultisigPda,
+  findProposalPda,
+  findVaultPda,
+  proposalOptions,
+} from "./utils";
+
+const {
+  PublicKey,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  SYSVAR_CLOCK_PUBKEY,
+  Transaction,
+  VoteAccount,
+  VoteInit,
+  VoteProgram,
+} = anchor.web3;
+
+// VoteAuthorize
+const VOTER = 0;
+const WITHDRAWER = 1;
+
+describe("vote account authority proposals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+  const identity = Keypair.generate();
+  const voteAccount = Keypair.generate();
+  const newVoter = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  const nextProposal = async () => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    return findProposalPda(program.programId, multisigPda, account.proposalsCount)[0];
+  };
+
+  const approveAll = async (proposal: PublicKey) => {
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+  };
+
+  const authorize = async (newAuthority: PublicKey, authorityType: number) => {
+    const proposal = await nextProposal();
+    await program.methods
+      .proposeVoteAuthorize(voteAccount.publicKey, newAuthority, authorityType, proposalOptions())
+      .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await approveAll(proposal);
+    return program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts([
+        { pubkey: voteAccount.publicKey, isSigner: false, isWritable: true },
+        { pubkey: SYSVAR_CLOCK_PUBKEY, isSigner: false, isWritable: false },
+        { pubkey: VoteProgram.programId, isSigner: false, isWritable: false },
+      ])
+      .rpc();
+  };
+
+  const withdraw = async (lamports: number) => {
+    const proposal = await nextProposal();
+    await program.methods
+      .proposeVoteWithdraw(voteAccount.publicKey, new anchor.BN(lamports), vaultPda, proposalOptions())
+      .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await approveAll(proposal);
+    return program.methods
+      .executeTransaction()
+      .accounts({ multisig: multisigPda, proposal })
+      .remainingAccounts([
+        { pubkey: voteAccount.publicKey, isSigner: false, isWritable: true },
+        { pubkey: vaultPda, isSigner: false, isWritable: true },
+        { pubkey: VoteProgram.programId, isSigner: false, isWritable: false },
+      ])
+      .rpc();
+  };
+
+  const voteState = async () =>
+    VoteAccount.fromAccountData((await provider.connection.getAccountInfo(voteAccount.publicKey)).data);
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+
+    // 真实 vote 账户：voter 与 withdrawer 均为 multisig PDA
+    const rent = await provider.connection.getMinimumBalanceForRentExemption(VoteProgram.space);
+    const tx = new Transaction().add(
+      VoteProgram.createAccount({
+        fromPubkey: member1.publicKey,
+        votePubkey: voteAccount.publicKey,
+        voteInit: new VoteInit(identity.publicKey, multisigPda, multisigPda, 10),
+        lamports: rent + LAMPORTS_PER_SOL,
+      })
+    );
+    await provider.sendAndConfirm(tx, [member1, voteAccount, identity]);
+  });
+
+  it("Rejects unknown authority types at propose time", async () => {
+    await expectError(
+      program.methods
+        .proposeVoteAuthorize(voteAccount.publicKey, newVoter.publicKey, 2, proposalOptions())
+        .accounts({ multisig: multisigPda, proposal: await nextProposal(), proposer: member1.publicKey })
+        .signers([member1])
+        .rpc(),
+      "InvalidAuthorityType"
+    );
+  });
+
+  it("Changes the authorized voter", async () => {
+    await authorize(newVoter.publicKey, VOTER);
+    // 新 voter 自下一个 epoch 起生效
+    const state = await voteState();
+    assert.isTrue(state.authorizedVoters.some((v) => v.authorizedVoter.equals(newVoter.publicKey)));
+  });
+
+  it("Withdraws lamports to the vault", async () => {
+    const before = await provider.connection.getBalance(vaultPda);
+    await withdraw(LAMPORTS_PER_SOL / 2);
+    assert.equal((await provider.connection.getBalance(vaultPda)) - before, LAMPORTS_PER_SOL / 2);
+  });
+
+  it("Surfaces a program error once the withdrawer has moved", async () => {
+    await authorize(member2.publicKey, WITHDRAWER);
+    assert.isTrue((await voteState()).authorizedWithdrawer.equals(member2.publicKey));
+
+    await expectError(withdraw(LAMPORTS_PER_SOL / 4), "VoteAuthorityMismatch");
+    await expectError(authorize(member1.publicKey, WITHDRAWER), "VoteAuthorityMismatch");
+  });
+});
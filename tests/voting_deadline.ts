@@ -0,0 +1,115 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  chainTime,
+  execute,
+  expectError,
+  findMultisigPda,
+  memoInstruction,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair } = anchor.web3;
+
+// 与程序中的 CANCEL_REASON_VOTING_CLOSED 一致
+const VOTING_CLOSED = 5;
+
+describe("voting deadline", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const [member1, member2, member3] = members;
+
+  let multisigPda: PublicKey;
+
+  // 本地验证器无法直接调整时钟，这里等待链上时间越过目标
+  const waitUntil = async (t: number) => {
+    while ((await chainTime(provider)) <= t) {
+      await sleep(1000);
+    }
+  };
+
+  const memo = (text: string, votingEndsAt: number, notAfter: number | null = null) =>
+    propose(program, multisigPda, member1, memoInstruction(text, [multisigPda]), {
+      votingEndsAt: new anchor.BN(votingEndsAt),
+      notAfter: notAfter === null ? null : new anchor.BN(notAfter),
+    });
+
+  const close = (proposal: PublicKey) =>
+    program.methods
+      .closeExpired()
+      .accounts({ multisig: multisigPda, proposal, proposer: member1.publicKey })
+      .rpc();
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+  });
+
+  it("Requires the voting deadline to be in the future and within the execution window", async () => {
+    const now = await chainTime(provider);
+    await expectError(memo("past", now - 10), "InvalidWindow");
+    await expectError(memo("after expiry", now + 60, now + 30), "InvalidWindow");
+    await memo("at expiry", now + 60, now + 60);
+  });
+
+  it("Stops accepting votes after the deadline but still executes a ready proposal", async () => {
+    const now = await chainTime(provider);
+    const votingEndsAt = now + 6;
+    const proposal = await memo("ready in time", votingEndsAt, now + 600);
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+
+    await waitUntil(votingEndsAt);
+    await expectError(approve(program, multisigPda, proposal, member3), "VotingClosed");
+    await expectError(
+      program.methods
+        .rejectTransaction(0, "too late")
+        .accounts({ multisig: multisigPda, proposal, approver: member3.publicKey })
+        .signers([member3])
+        .rpc(),
+      "VotingClosed"
+    );
+    // 已达到阈值的提案不能被当作废弃关闭
+    await expectError(close(proposal), "NotExpired");
+    await execute(program, multisigPda, proposal);
+  });
+
+  it("Makes a proposal that missed the threshold permissionlessly closable", async () => {
+    const now = await chainTime(provider);
+    const votingEndsAt = now + 6;
+    const proposal = await memo("undecided", votingEndsAt);
+    await approve(program, multisigPda, proposal, member1);
+
+    // 截止前仍可投票，也不能关闭
+    await expectError(close(proposal), "NotExpired");
+    await waitUntil(votingEndsAt);
+    await expectError(execute(program, multisigPda, proposal), "NotExecutable");
+
+    let event: any = null;
+    const listener = program.addEventListener("proposalCancelled", (e) => {
+      event = e;
+    });
+    await close(proposal);
+    await sleep(1000);
+    await program.removeEventListener(listener);
+
+    assert.equal(event.reason, VOTING_CLOSED);
+    assert.isNull(await provider.connection.getAccountInfo(proposal));
+  });
+});
@@ -0,0 +1,128 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  createAssociatedTokenAccount,
+  createCloseAccountInstruction,
+  createMint,
+  createTransferInstruction,
+  getAssociatedTokenAddressSync,
+  mintTo,
+} from "@solana/spl-token";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findVaultPda,
+  governed,
+  memoInstruction,
+  propose,
+  sleep,
+} from "./utils";
+
+const { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } = anchor.web3;
+
+describe("wind-down mode", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const members = [member1, member2];
+  const contributor = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+  let mint: PublicKey;
+  let pendingConfig: PublicKey;
+
+  const approveAll = async (proposal: PublicKey) => {
+    for (const m of members) {
+      await approve(program, multisigPda, proposal, m);
+    }
+  };
+
+  const run = async (ix: anchor.web3.TransactionInstruction) => {
+    const proposal = await propose(program, multisigPda, member1, ix);
+    await approveAll(proposal);
+    await execute(program, multisigPda, proposal);
+  };
+
+  const changeThreshold = (threshold: number) =>
+    program.methods
+      .changeThreshold(threshold)
+      .accounts({ multisig: multisigPda, authority: multisigPda })
+      .instruction();
+
+  before(async () => {
+    for (const m of members) {
+      await airdrop(provider, m.publicKey);
+    }
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, members.map((m) => m.publicKey), 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await airdrop(provider, vaultPda);
+
+    mint = await createMint(provider.connection, member1, member1.publicKey, null, 6);
+    const vaultAta = await createAssociatedTokenAccount(provider.connection, member1, mint, vaultPda, undefined, undefined, undefined, true);
+    await createAssociatedTokenAccount(provider.connection, member1, mint, contributor.publicKey);
+    await mintTo(provider.connection, member1, mint, vaultAta, member1, 1_000);
+
+    // 清退前创建、尚未执行的配置提案
+    pendingConfig = await propose(program, multisigPda, member1, await changeThreshold(1));
+  });
+
+  it("Enters wind-down through the multisig's own proposal", async () => {
+    let started: any = null;
+    const listener = program.addEventListener("windDownStarted", (event) => {
+      started = event;
+    });
+    const ix = await program.methods.windDown().accounts({ multisig: multisigPda }).instruction();
+    await governed(program, multisigPda, members, ix);
+    await sleep(1000);
+    await program.removeEventListener(listener);
+
+    assert.isTrue(started.multisig.equals(multisigPda));
+    const account = await program.account.multisig.fetch(multisigPda);
+    assert.equal(account.woundDownAt.toNumber(), started.timestamp.toNumber());
+
+    // 不可撤销，也不能再次进入
+    await expectError(propose(program, multisigPda, member1, ix), "WindDownRestricted");
+  });
+
+  it("Still returns SOL and tokens to contributors", async () => {
+    await run(SystemProgram.transfer({ fromPubkey: vaultPda, toPubkey: contributor.publicKey, lamports: LAMPORTS_PER_SOL / 2 }));
+    assert.equal(await provider.connection.getBalance(contributor.publicKey), LAMPORTS_PER_SOL / 2);
+
+    const vaultAta = getAssociatedTokenAddressSync(mint, vaultPda, true);
+    const contributorAta = getAssociatedTokenAddressSync(mint, contributor.publicKey);
+    await run(createTransferInstruction(vaultAta, contributorAta, vaultPda, 1_000));
+    // 清空后关闭金库 token 账户，租金退还给贡献者
+    await run(createCloseAccountInstruction(vaultAta, contributor.publicKey, vaultPda));
+    assert.isNull(await provider.connection.getAccountInfo(vaultAta));
+  });
+
+  it("Rejects arbitrary CPIs, foreign transfers and config changes", async () => {
+    await expectError(propose(program, multisigPda, member1, memoInstruction("new obligation", [vaultPda])), "WindDownRestricted");
+    // 形状是转账，但不是由多签 PDA 转出
+    await expectError(
+      propose(program, multisigPda, member1, SystemProgram.transfer({ fromPubkey: member1.publicKey, toPubkey: vaultPda, lamports: 1 })),
+      "WindDownRestricted"
+    );
+    await expectError(propose(program, multisigPda, member1, await changeThreshold(1)), "WindDownRestricted");
+
+    // 清退前已创建的提案在执行时同样被拒绝
+    await approveAll(pendingConfig);
+    await expectError(execute(program, multisigPda, pendingConfig), "WindDownRestricted");
+    assert.equal((await program.account.multisig.fetch(multisigPda)).threshold, 2);
+  });
+});
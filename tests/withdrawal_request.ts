@@ -0,0 +1,154 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import {
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccount,
+  createMint,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { Multisig } from "../target/types/multisig";
+import {
+  airdrop,
+  approve,
+  execute,
+  expectError,
+  findMultisigPda,
+  findProposalPda,
+  findVaultPda,
+  findWithdrawalRequestPda,
+} from "./utils";
+
+const { PublicKey, Keypair, LAMPORTS_PER_SOL, SystemProgram } = anchor.web3;
+
+describe("withdrawal requests", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.multisig as Program<Multisig>;
+
+  const member1 = Keypair.generate();
+  const member2 = Keypair.generate();
+  const grantee = Keypair.generate();
+  const recipient = Keypair.generate();
+  const stranger = Keypair.generate();
+
+  let multisigPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  const request = (id: number, mint: PublicKey | null, amount: number, memo: string | null) => {
+    const [pda] = findWithdrawalRequestPda(program.programId, multisigPda, grantee.publicKey, id);
+    return program.methods
+      .requestWithdrawal(id, mint, new anchor.BN(amount), recipient.publicKey, memo)
+      .accounts({ multisig: multisigPda, request: pda, requester: grantee.publicKey })
+      .signers([grantee])
+      .rpc()
+      .then(() => pda);
+  };
+
+  const convert = async (requestPda: PublicKey, proposer: Keypair, mint: PublicKey | null = null) => {
+    const account = await program.account.multisig.fetch(multisigPda);
+    const [proposal] = findProposalPda(program.programId, multisigPda, account.proposalsCount);
+    await program.methods
+      .convertRequestToProposal()
+      .accounts({
+        multisig: multisigPda,
+        request: requestPda,
+        requester: grantee.publicKey,
+        proposal,
+        proposer: proposer.publicKey,
+        mint,
+      })
+      .signers([proposer])
+      .rpc();
+    return proposal;
+  };
+
+  before(async () => {
+    await airdrop(provider, member1.publicKey);
+    await airdrop(provider, grantee.publicKey);
+    await airdrop(provider, stranger.publicKey);
+    [multisigPda] = findMultisigPda(program.programId, member1.publicKey, 0);
+    [vaultPda] = findVaultPda(program.programId, multisigPda);
+    await program.methods
+      .createMultisig(0, [member1.publicKey, member2.publicKey], 2, 10)
+      .accounts({ multisig: multisigPda, creator: member1.publicKey })
+      .signers([member1])
+      .rpc();
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        SystemProgram.transfer({ fromPubkey: provider.wallet.publicKey, toPubkey: vaultPda, lamports: 2 * LAMPORTS_PER_SOL })
+      )
+    );
+  });
+
+  it("Lets anyone file a request and a member convert it verbatim", async () => {
+    const amount = LAMPORTS_PER_SOL / 2;
+    const requestPda = await request(0, null, amount, "grant milestone 1");
+    const stored = await program.account.withdrawalRequest.fetch(requestPda);
+    assert.ok(stored.requester.equals(grantee.publicKey));
+    assert.ok(stored.recipient.equals(recipient.publicKey));
+    assert.equal(stored.amount.toNumber(), amount);
+
+    const granteeBefore = await provider.connection.getBalance(grantee.publicKey);
+    const proposal = await convert(requestPda, member1);
+
+    // 请求关闭，租金退还给请求者
+    assert.isNull(await program.account.withdrawalRequest.fetchNullable(requestPda));
+    assert.isAbove(await provider.connection.getBalance(grantee.publicKey), granteeBefore);
+
+    const created = await program.account.proposal.fetch(proposal);
+    const ix = created.action.raw[0];
+    assert.ok(ix.programId.equals(SystemProgram.programId));
+    assert.ok(ix.accounts[0].pubkey.equals(vaultPda));
+    assert.ok(ix.accounts[1].pubkey.equals(recipient.publicKey));
+    assert.equal(created.executionMemo, "grant milestone 1");
+
+    await approve(program, multisigPda, proposal, member1);
+    await approve(program, multisigPda, proposal, member2);
+    await execute(program, multisigPda, proposal);
+    assert.equal(await provider.connection.getBalance(recipient.publicKey), amount);
+  });
+
+  it("Builds a TransferChecked into the recipient's ATA for token requests", async () => {
+    const mint = await createMint(provider.connection, grantee, grantee.publicKey, null, 6);
+    await createAssociatedTokenAccount(provider.connection, grantee, mint, vaultPda, undefined, undefined, undefined, true);
+    const requestPda = await request(1, mint, 2_500, null);
+
+    // 代币请求必须传入 mint
+    await expectError(convert(requestPda, member1), "AccountMismatch");
+
+    const proposal = await convert(requestPda, member1, mint);
+    const ix = (await program.account.proposal.fetch(proposal)).action.raw[0];
+    assert.ok(ix.programId.equals(TOKEN_PROGRAM_ID));
+    assert.ok(ix.accounts[0].pubkey.equals(getAssociatedTokenAddressSync(mint, vaultPda, true)));
+    assert.ok(ix.accounts[2].pubkey.equals(getAssociatedTokenAddressSync(mint, recipient.publicKey)));
+    assert.deepEqual(Array.from(ix.data), [12, ...new anchor.BN(2_500).toArray("le", 8), 6]);
+  });
+
+  it("Only members convert, and only the requester closes before expiry", async () => {
+    const requestPda = await request(2, null, 1_000, null);
+    await expectError(convert(requestPda, stranger), "NotMember");
+
+    await expectError(
+      program.methods
+        .closeWithdrawalRequest()
+        .accounts({ request: requestPda, requester: grantee.publicKey, closer: stranger.publicKey })
+        .signers([stranger])
+        .rpc(),
+      "WithdrawalRequestNotExpired"
+    );
+
+    await program.methods
+      .closeWithdrawalRequest()
+      .accounts({ request: requestPda, requester: grantee.publicKey, closer: grantee.publicKey })
+      .signers([grantee])
+      .rpc();
+    assert.isNull(await program.account.withdrawalRequest.fetchNullable(requestPda));
+  });
+
+  it("Rejects zero amounts and empty memos", async () => {
+    await expectError(request(3, null, 0, null), "InvalidWithdrawalRequest");
+    await expectError(request(3, null, 1_000, ""), "InvalidWithdrawalRequest");
+  });
+});